@@ -0,0 +1,87 @@
+//! L1 并发读写基准
+//!
+//! 16 个任务同时对同一个仅 L1 的缓存执行读多写少的操作，衡量访问顺序维护的锁竞争。
+//! 运行：cargo bench --bench l1_concurrency
+
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rat_memcache::config::{L1Config, LoggingConfig};
+use rat_memcache::types::EvictionStrategy;
+use rat_memcache::{RatMemCache, RatMemCacheBuilder};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// 并发任务数
+const TASKS: usize = 16;
+/// 每个任务每轮的操作数
+const OPS_PER_TASK: usize = 1_000;
+/// 预先写入的键数量
+const KEYS: usize = 10_000;
+
+async fn build_cache() -> Arc<RatMemCache> {
+    let cache = RatMemCacheBuilder::development()
+        .l1_config(L1Config {
+            max_memory: 256 * 1024 * 1024,
+            max_entries: KEYS * 2,
+            eviction_strategy: EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
+        })
+        .logging_config(LoggingConfig {
+            level: "error".to_string(),
+            enable_colors: false,
+            show_timestamp: false,
+            enable_performance_logs: false,
+            enable_audit_logs: false,
+            enable_cache_logs: false,
+            enable_logging: false,
+            enable_async: false,
+            batch_size: 1000,
+            batch_interval_ms: 100,
+            buffer_size: 8192,
+        })
+        .build()
+        .await
+        .expect("创建缓存失败");
+
+    for i in 0..KEYS {
+        cache.set(format!("key_{}", i), Bytes::from(vec![0u8; 64])).await.unwrap();
+    }
+    Arc::new(cache)
+}
+
+/// 每个任务 90% 读取、10% 写入，键在预写入的范围内分散
+async fn run_round(cache: &Arc<RatMemCache>) {
+    let tasks: Vec<_> = (0..TASKS)
+        .map(|task| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                for i in 0..OPS_PER_TASK {
+                    let key = format!("key_{}", (task * 7_919 + i * 31) % KEYS);
+                    if i % 10 == 0 {
+                        cache.set(key, Bytes::from(vec![1u8; 64])).await.unwrap();
+                    } else {
+                        cache.get(&key).await.unwrap();
+                    }
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+fn l1_concurrency(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let cache = runtime.block_on(build_cache());
+
+    let mut group = c.benchmark_group("l1_concurrency");
+    group.throughput(Throughput::Elements((TASKS * OPS_PER_TASK) as u64));
+    group.sample_size(20);
+    group.bench_function("lru_16_tasks", |b| b.iter(|| runtime.block_on(run_round(&cache))));
+    group.finish();
+}
+
+criterion_group!(benches, l1_concurrency);
+criterion_main!(benches);
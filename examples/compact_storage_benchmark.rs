@@ -0,0 +1,101 @@
+//! L2 紧凑存储写入性能对比
+//!
+//! 对比双键存储（数据键 + 元数据键）与紧凑存储（元数据头内联，单键）
+//! 在小值写入场景下的吞吐量。
+//!
+//! 运行: cargo run --release --example compact_storage_benchmark --features melange-storage
+
+use rat_memcache::{RatMemCacheBuilder, CacheOptions};
+use rat_memcache::config::{L1Config, L2Config, TtlConfig, PerformanceConfig, LoggingConfig};
+use rat_memcache::types::EvictionStrategy;
+use bytes::Bytes;
+use std::time::Instant;
+
+const ENTRY_COUNT: usize = 20_000;
+const VALUE_SIZE: usize = 64;
+
+async fn run_benchmark(compact_storage: bool) -> Result<f64, Box<dyn std::error::Error>> {
+    let temp_dir = tempfile::tempdir()?;
+
+    let cache = RatMemCacheBuilder::new()
+        .l1_config(L1Config {
+            max_memory: 64 * 1024 * 1024, // 64MB
+            max_entries: 100_000,
+            eviction_strategy: EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
+        })
+        .l2_config(L2Config {
+            enable_l2_cache: true,
+            data_dir: Some(temp_dir.path().to_path_buf()),
+            l2_write_strategy: "never".to_string(),
+            compact_storage,
+            ..Default::default()
+        })
+        .ttl_config(TtlConfig {
+            expire_seconds: None, // 无 TTL，紧凑存储生效
+            cleanup_interval: 300,
+            max_cleanup_entries: 1000,
+            lazy_expiration: true,
+            active_expiration: false,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
+        })
+        .performance_config(PerformanceConfig {
+            worker_threads: 4,
+            enable_concurrency: true,
+            read_write_separation: false,
+            batch_size: 100,
+            enable_warmup: false,
+            large_value_threshold: 10240,
+            key_stats_top_n: 0,
+            absolute_max_value_bytes: 0,
+            max_tagged_keys: 100_000,
+        })
+        .logging_config(LoggingConfig {
+            level: "error".to_string(),
+            enable_colors: false,
+            show_timestamp: false,
+            enable_performance_logs: false,
+            enable_audit_logs: false,
+            enable_cache_logs: false,
+            enable_logging: false,
+            enable_async: false,
+            batch_size: 2048,
+            batch_interval_ms: 25,
+            buffer_size: 16384,
+        })
+        .build()
+        .await?;
+
+    // 直接写入 L2
+    let options = CacheOptions {
+        force_l2: true,
+        ..Default::default()
+    };
+    let value = Bytes::from(vec![b'x'; VALUE_SIZE]);
+
+    let start = Instant::now();
+    for i in 0..ENTRY_COUNT {
+        cache.set_with_options(format!("key_{}", i), value.clone(), &options).await?;
+    }
+    let elapsed = start.elapsed();
+
+    cache.shutdown().await?;
+    Ok(ENTRY_COUNT as f64 / elapsed.as_secs_f64())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("🧪 L2 紧凑存储写入性能对比");
+    println!("📋 {} 个条目，每个 {} 字节，无 TTL", ENTRY_COUNT, VALUE_SIZE);
+
+    let two_key = run_benchmark(false).await?;
+    println!("   双键存储: {:.0} ops/s", two_key);
+
+    let compact = run_benchmark(true).await?;
+    println!("   紧凑存储: {:.0} ops/s", compact);
+
+    println!("📈 吞吐量提升: {:.1}%", (compact / two_key - 1.0) * 100.0);
+    Ok(())
+}
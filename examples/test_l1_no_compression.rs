@@ -20,6 +20,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             max_memory: 64 * 1024 * 1024, // 64MB
             max_entries: 1000,
             eviction_strategy: EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
         })
         .ttl_config(TtlConfig {
             expire_seconds: Some(60),
@@ -27,6 +29,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             max_cleanup_entries: 1000,
             lazy_expiration: true,
             active_expiration: true,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
         })
         .performance_config(PerformanceConfig {
             worker_threads: 2,
@@ -35,6 +39,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             batch_size: 100,
             enable_warmup: false,
             large_value_threshold: 10240, // 10KB（默认值）
+            key_stats_top_n: 0,
+            absolute_max_value_bytes: 0,
+            max_tagged_keys: 100_000,
         })
         .logging_config(LoggingConfig {
             level: "debug".to_string(),  // 启用debug日志观察行为
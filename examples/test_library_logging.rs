@@ -18,6 +18,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         max_memory: 1024 * 1024,  // 1MB
         max_entries: 1000,
         eviction_strategy: EvictionStrategy::Lru,
+        l1_min_retention_ms: 0,
+        order_compaction_factor: 2,
     };
 
     let ttl_config = TtlConfig {
@@ -26,6 +28,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         max_cleanup_entries: 1000,
         lazy_expiration: true,
         active_expiration: true,
+        cleanup_channel_capacity: 10_000,
+        sliding_expiration: false,
     };
 
     let performance_config = PerformanceConfig {
@@ -35,6 +39,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         batch_size: 1024,
         enable_warmup: false,
         large_value_threshold: 10240,
+        key_stats_top_n: 0,
+        absolute_max_value_bytes: 0,
+        max_tagged_keys: 100_000,
     };
     // 压缩配置已整合到L2Config中，测试示例不需要压缩功能
 
@@ -0,0 +1,75 @@
+//! TTL 检查吞吐量测试
+//!
+//! 多个任务并发执行过期检查（contains_key）与剩余 TTL 查询（get_ttl），
+//! 同时有一个任务持续刷新 TTL，模拟读多写少的热路径。
+//!
+//! 运行: cargo run --release --example ttl_check_benchmark
+
+use rat_memcache::RatMemCacheBuilder;
+use bytes::Bytes;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+const KEY_COUNT: usize = 10_000;
+const CHECKS_PER_TASK: usize = 200_000;
+
+async fn run_benchmark(tasks: usize) -> Result<f64, Box<dyn std::error::Error>> {
+    let cache = Arc::new(RatMemCacheBuilder::development().build().await?);
+    for i in 0..KEY_COUNT {
+        cache.set_with_ttl(format!("key_{}", i), Bytes::from_static(b"value"), 3600).await?;
+    }
+
+    // 后台持续刷新 TTL，制造写锁竞争
+    let running = Arc::new(AtomicBool::new(true));
+    let writer = {
+        let cache = cache.clone();
+        let running = running.clone();
+        tokio::spawn(async move {
+            let mut i = 0;
+            while running.load(Ordering::Relaxed) {
+                let _ = cache.set_ttl(&format!("key_{}", i % KEY_COUNT), 3600).await;
+                i += 1;
+                tokio::task::yield_now().await;
+            }
+        })
+    };
+
+    let start = Instant::now();
+    let handles: Vec<_> = (0..tasks)
+        .map(|t| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                for i in 0..CHECKS_PER_TASK {
+                    let key = format!("key_{}", (i * 7 + t) % KEY_COUNT);
+                    if i % 2 == 0 {
+                        let _ = cache.contains_key(key.as_str()).await;
+                    } else {
+                        let _ = cache.get_ttl(&key).await;
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await?;
+    }
+    let elapsed = start.elapsed();
+
+    running.store(false, Ordering::Relaxed);
+    writer.await?;
+    cache.shutdown().await?;
+    Ok((tasks * CHECKS_PER_TASK) as f64 / elapsed.as_secs_f64())
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 8)]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("🧪 TTL 检查吞吐量测试");
+    println!("📋 {} 个键，每个任务 {} 次检查，后台持续刷新 TTL", KEY_COUNT, CHECKS_PER_TASK);
+
+    for tasks in [1, 4, 8] {
+        let throughput = run_benchmark(tasks).await?;
+        println!("   {} 个并发任务: {:.0} checks/s", tasks, throughput);
+    }
+    Ok(())
+}
@@ -0,0 +1,220 @@
+//! ARC（自适应替换缓存）驱逐策略
+//!
+//! 常驻键分为 T1（只访问过一次）和 T2（访问过多次）两个列表，另有影子列表 B1、B2
+//! 记录最近从 T1、T2 驱逐的键（只保存键，不保存值）。写入的新键命中影子列表时
+//! 自适应调整 T1 的目标大小 `p`，在近期性与频率之间取得平衡。
+
+use std::collections::{BTreeMap, HashMap};
+
+/// 按访问顺序排列的键列表，队首为最久未访问的键
+#[derive(Debug, Default)]
+struct KeyList {
+    order: BTreeMap<u64, String>,
+    positions: HashMap<String, u64>,
+}
+
+impl KeyList {
+    fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// 将键放到队尾（已存在时移动）
+    fn push_back(&mut self, key: String, seq: u64) {
+        if let Some(old_seq) = self.positions.insert(key.clone(), seq) {
+            self.order.remove(&old_seq);
+        }
+        self.order.insert(seq, key);
+    }
+
+    fn remove(&mut self, key: &str) -> bool {
+        match self.positions.remove(key) {
+            Some(seq) => {
+                self.order.remove(&seq);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn pop_front(&mut self) -> Option<String> {
+        let (_, key) = self.order.pop_first()?;
+        self.positions.remove(&key);
+        Some(key)
+    }
+
+    /// 从队首起第一个满足条件的键
+    fn find_front(&self, mut predicate: impl FnMut(&str) -> bool) -> Option<&str> {
+        self.order.values().map(String::as_str).find(|key| predicate(key))
+    }
+
+    fn clear(&mut self) {
+        self.order.clear();
+        self.positions.clear();
+    }
+}
+
+/// ARC 策略状态
+#[derive(Debug)]
+pub(crate) struct ArcPolicy {
+    /// 缓存容量（条目数）
+    capacity: usize,
+    /// T1 的目标大小 `p`
+    target_t1: usize,
+    t1: KeyList,
+    t2: KeyList,
+    b1: KeyList,
+    b2: KeyList,
+    next_seq: u64,
+}
+
+impl ArcPolicy {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            target_t1: 0,
+            t1: KeyList::default(),
+            t2: KeyList::default(),
+            b1: KeyList::default(),
+            b2: KeyList::default(),
+            next_seq: 0,
+        }
+    }
+
+    /// 写入新键、驱逐之前调用：键在 B1 中时增大 `p`，在 B2 中时减小 `p`
+    pub(crate) fn adapt(&mut self, key: &str) {
+        if self.b1.contains(key) {
+            let delta = (self.b2.len() / self.b1.len()).max(1);
+            self.target_t1 = (self.target_t1 + delta).min(self.capacity);
+        } else if self.b2.contains(key) {
+            let delta = (self.b1.len() / self.b2.len()).max(1);
+            self.target_t1 = self.target_t1.saturating_sub(delta);
+        }
+    }
+
+    /// 新键写入后调用：曾在影子列表中的键进入 T2，否则进入 T1
+    pub(crate) fn admit(&mut self, key: &str) {
+        let seq = self.next_seq();
+        if self.b1.remove(key) || self.b2.remove(key) {
+            self.t2.push_back(key.to_string(), seq);
+        } else {
+            self.t1.push_back(key.to_string(), seq);
+            self.trim_ghosts();
+        }
+    }
+
+    /// 常驻键再次被访问时调用，移到 T2 的队尾
+    pub(crate) fn access(&mut self, key: &str) {
+        if self.t1.remove(key) || self.t2.contains(key) {
+            let seq = self.next_seq();
+            self.t2.push_back(key.to_string(), seq);
+        }
+    }
+
+    /// 常驻键被删除或过期时调用，不进入影子列表
+    pub(crate) fn remove(&mut self, key: &str) {
+        if !self.t1.remove(key) {
+            self.t2.remove(key);
+        }
+    }
+
+    /// 选择驱逐的键并移入对应的影子列表
+    ///
+    /// T1 超过目标大小时从 T1 驱逐，否则从 T2 驱逐；`is_retained` 为 true 的键仅在没有其他候选时被选中
+    pub(crate) fn evict(&mut self, is_retained: impl Fn(&str) -> bool) -> Option<String> {
+        let prefer_t1 = !self.t1.is_empty() && (self.t1.len() > self.target_t1 || self.t2.is_empty());
+        let order = if prefer_t1 { [true, false] } else { [false, true] };
+        let list = |from_t1: bool| if from_t1 { &self.t1 } else { &self.t2 };
+
+        let (from_t1, key) = order.iter()
+            .find_map(|&from_t1| list(from_t1).find_front(|key| !is_retained(key)).map(|key| (from_t1, key.to_string())))
+            .or_else(|| order.iter().find_map(|&from_t1| list(from_t1).find_front(|_| true).map(|key| (from_t1, key.to_string()))))?;
+
+        let seq = self.next_seq();
+        if from_t1 {
+            self.t1.remove(&key);
+            self.b1.push_back(key.clone(), seq);
+        } else {
+            self.t2.remove(&key);
+            self.b2.push_back(key.clone(), seq);
+        }
+        Some(key)
+    }
+
+    /// 清空所有列表并重置 `p`
+    pub(crate) fn clear(&mut self) {
+        self.target_t1 = 0;
+        self.t1.clear();
+        self.t2.clear();
+        self.b1.clear();
+        self.b2.clear();
+    }
+
+    /// 限制影子列表大小：|T1| + |B1| 不超过容量，四个列表合计不超过两倍容量
+    fn trim_ghosts(&mut self) {
+        while self.t1.len() + self.b1.len() > self.capacity && self.b1.pop_front().is_some() {}
+        while self.t1.len() + self.t2.len() + self.b1.len() + self.b2.len() > self.capacity * 2
+            && self.b2.pop_front().is_some()
+        {}
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        self.next_seq += 1;
+        self.next_seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ghost_hit_adapts_target() {
+        let mut arc = ArcPolicy::new(2);
+        arc.admit("a");
+        arc.admit("b");
+        arc.access("b");
+
+        // T1 超过目标大小 0，先驱逐只访问过一次的键
+        assert_eq!(arc.evict(|_| false), Some("a".to_string()));
+        assert!(arc.b1.contains("a"));
+
+        // 重新写入 B1 中的键会增大 p 并直接进入 T2
+        arc.adapt("a");
+        assert_eq!(arc.target_t1, 1);
+        arc.admit("a");
+        assert!(arc.t2.contains("a") && !arc.b1.contains("a"));
+
+        // T1 为空时从 T2 驱逐最久未访问的键
+        assert_eq!(arc.evict(|_| false), Some("b".to_string()));
+        assert!(arc.b2.contains("b"));
+        arc.adapt("b");
+        assert_eq!(arc.target_t1, 0);
+    }
+
+    #[test]
+    fn test_retained_keys_and_ghost_limit() {
+        let mut arc = ArcPolicy::new(2);
+        arc.admit("a");
+        arc.admit("b");
+        assert_eq!(arc.evict(|key| key == "a"), Some("b".to_string()));
+
+        // 删除的键不进入影子列表
+        arc.remove("a");
+        assert!(!arc.b1.contains("a"));
+        assert_eq!(arc.evict(|_| false), None);
+
+        // |T1| + |B1| 不超过容量，超出时丢弃最旧的影子键
+        arc.admit("c");
+        assert!(arc.b1.contains("b"));
+        arc.admit("d");
+        assert!(arc.b1.is_empty());
+    }
+}
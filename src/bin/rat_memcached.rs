@@ -11,14 +11,16 @@ use mimalloc::MiMalloc;
 static GLOBAL: MiMalloc = MiMalloc;
 
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::Notify;
 
 use bytes::Bytes;
-use clap::{Arg, Command};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use clap::{Arg, ArgAction, Command};
+use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::signal;
 use tokio::net::{TcpListener as TokioTcpListener, TcpStream};
 
@@ -26,14 +28,14 @@ use rat_memcache::{
     config::{CacheConfig, LoggingConfig},
     error::{CacheError, CacheResult},
     logging::{LogManager, flush_logs_if_async},
-    RatMemCache,
+    CacheOptions, CasResult, RatMemCache,
 };
 
 // 使用 rat_logger 日志宏
 use rat_logger::{debug, error, info, warn};
 
 // 引入流式协议支持
-use rat_memcache::streaming_protocol::{StreamingCommand, StreamingResponse, StreamingParser, StreamingFormatter};
+use rat_memcache::streaming_protocol::{DumpCodec, DumpEntry, StreamingParser, StreamingFormatter};
 
 /// 服务器配置
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -42,23 +44,124 @@ struct ServerConfig {
     bind_addr: String,
     /// 缓存配置文件路径
     cache_config_path: Option<String>,
-    }
+    /// 绑定地址被占用时的重试次数（0 表示不重试）
+    #[serde(default)]
+    bind_retries: u32,
+    /// 绑定重试的初始等待时间（毫秒），每次重试翻倍
+    #[serde(default = "default_bind_retry_delay_ms")]
+    bind_retry_delay_ms: u64,
+    /// 单条 get 命令允许的最大键数量（0 表示不限制）
+    #[serde(default = "default_max_multiget_keys")]
+    max_multiget_keys: usize,
+    /// 分块 SET 允许声明的最大值大小（字节，0 表示不限制）
+    #[serde(default = "default_max_item_size")]
+    max_item_size: usize,
+    /// 接受连接失败时退避等待的上限（毫秒）
+    #[serde(default = "default_accept_backoff_max_ms")]
+    accept_backoff_max_ms: u64,
+    /// 连续接受连接失败达到该次数时记录严重错误（0 表示不检查）
+    #[serde(default = "default_accept_error_threshold")]
+    accept_error_threshold: u32,
+    /// 连续失败达到阈值时停止服务器
+    #[serde(default)]
+    shutdown_on_accept_errors: bool,
+    /// 收到存储命令后等待数据块的最长时间（毫秒），超时关闭连接（0 表示不限制）
+    #[serde(default = "default_command_assembly_timeout_ms")]
+    command_assembly_timeout_ms: u64,
+    /// 未知命令的 ERROR 响应附带出错的命令，便于调试客户端（默认返回标准的 `ERROR`）
+    #[serde(default)]
+    verbose_errors: bool,
+}
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
             bind_addr: "127.0.0.1:11211".to_string(),
             cache_config_path: None,
+            bind_retries: 0,
+            bind_retry_delay_ms: default_bind_retry_delay_ms(),
+            max_multiget_keys: default_max_multiget_keys(),
+            max_item_size: default_max_item_size(),
+            accept_backoff_max_ms: default_accept_backoff_max_ms(),
+            accept_error_threshold: default_accept_error_threshold(),
+            shutdown_on_accept_errors: false,
+            command_assembly_timeout_ms: default_command_assembly_timeout_ms(),
+            verbose_errors: false,
         }
     }
 }
 
+fn default_bind_retry_delay_ms() -> u64 {
+    500
+}
+
+fn default_max_multiget_keys() -> usize {
+    1024
+}
+
+fn default_max_item_size() -> usize {
+    64 * 1024 * 1024
+}
+
+fn default_accept_backoff_max_ms() -> u64 {
+    5_000
+}
+
+fn default_accept_error_threshold() -> u32 {
+    100
+}
+
+fn default_command_assembly_timeout_ms() -> u64 {
+    10_000
+}
+
+/// 绑定地址被占用且重试耗尽时的退出码
+const EXIT_CODE_ADDR_IN_USE: i32 = 98;
+
+/// 绑定重试的最大等待时间（毫秒）
+const MAX_BIND_RETRY_DELAY_MS: u64 = 30_000;
+
+/// 接受连接失败后的初始等待时间（毫秒）
+const ACCEPT_BACKOFF_INITIAL_MS: u64 = 100;
+
+/// Memcached 键的最大长度（字节）
+const MAX_KEY_LENGTH: usize = 250;
+
+/// 键中无效 UTF-8 字节的映射基址：字节 `b`（0x80..=0xFF）映射为字符 `U+F700 + b`（私有使用区）
+const RAW_BYTE_BASE: u32 = 0xF700;
+
+/// `stats conns` 最多列出的连接数，避免连接数量巨大时输出失控
+const MAX_STATS_CONNS: usize = 1000;
+
+/// `dump` 单个批次的最大条目数
+const DUMP_BATCH_MAX_ENTRIES: usize = 256;
+
+/// `dump` 批次数据达到该大小（字节）时立即发送
+const DUMP_BATCH_MAX_BYTES: usize = 1024 * 1024;
+
+/// `load` 单个数据块的最大字节数
+const MAX_LOAD_BYTES: usize = 128 * 1024 * 1024;
+
 /// Memcached 协议命令
 #[derive(Debug, Clone)]
 enum MemcachedCommand {
     Get {
         keys: Vec<String>,
     },
+    /// 读取值及 CAS 令牌
+    Gets {
+        keys: Vec<String>,
+    },
+    /// 刷新 TTL，不读取值
+    Touch {
+        key: String,
+        exptime: u32,
+    },
+    /// 读取并刷新 TTL
+    Gat {
+        exptime: u32,
+        keys: Vec<String>,
+    },
     Set {
         key: String,
         flags: u32,
@@ -80,6 +183,27 @@ enum MemcachedCommand {
         bytes: usize,
         data: Option<Bytes>,
     },
+    /// 追加到已有值末尾，保留原有标志和 TTL
+    Append {
+        key: String,
+        bytes: usize,
+        data: Option<Bytes>,
+    },
+    /// 插入到已有值开头，保留原有标志和 TTL
+    Prepend {
+        key: String,
+        bytes: usize,
+        data: Option<Bytes>,
+    },
+    /// 令牌匹配时写入
+    Cas {
+        key: String,
+        flags: u32,
+        exptime: u32,
+        bytes: usize,
+        cas_unique: u64,
+        data: Option<Bytes>,
+    },
     Delete {
         key: String,
     },
@@ -91,11 +215,17 @@ enum MemcachedCommand {
         key: String,
         value: u64,
     },
+    /// 解析阶段即被拒绝的命令（返回 CLIENT_ERROR），存储类命令需丢弃随后的数据块
+    Rejected {
+        reason: String,
+        swallow_bytes: usize,
+    },
     // 流式协议命令
     StreamingGet {
         key: String,
         chunk_size: Option<usize>,
     },
+    /// `set_begin <key> <total_size> <chunk_count> <flags> [exptime]`：开始分块SET
     SetBegin {
         key: String,
         total_size: usize,
@@ -103,15 +233,29 @@ enum MemcachedCommand {
         flags: u32,
         exptime: u32,
     },
+    /// `set_data <key> <chunk_number> <bytes>\r\n<data>\r\n`：发送一个数据块
     SetData {
         key: String,
         chunk_number: usize,
-        data: Bytes,
+        bytes: usize,
+        data: Option<Bytes>,
     },
+    /// `set_end <key>`：结束分块SET
     SetEnd {
         key: String,
     },
     Stats,
+    /// `stats conns`：列出活跃连接
+    StatsConns,
+    /// `stats runtime`：编译特性与运行状态
+    StatsRuntime,
+    /// `dump`：分批导出全部条目（非标准扩展，格式见 [`DumpCodec`]）
+    Dump,
+    /// `load <bytes>`：导入 `dump` 批次的数据块（非标准扩展）
+    Load {
+        bytes: usize,
+        data: Option<Bytes>,
+    },
     Flush,
     Version,
     Quit,
@@ -119,15 +263,20 @@ enum MemcachedCommand {
 }
 
 /// Memcached 协议响应
+/// 检索命令返回的单个 VALUE 块
+#[derive(Debug, Clone)]
+struct ValueItem {
+    key: String,
+    flags: u32,
+    data: Bytes,
+    /// `gets` 响应附带的 CAS 令牌
+    cas: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 enum MemcachedResponse {
-    Value {
-        key: String,
-        flags: u32,
-        bytes: usize,
-        data: Bytes,
-    },
-    End,
+    /// 每个命中的键一个 VALUE 块，末尾统一输出一个 END
+    Values(Vec<ValueItem>),
     Stored,
     NotStored,
     Exists,
@@ -139,6 +288,12 @@ enum MemcachedResponse {
     ClientError(String),
     ServerError(String),
     Stats(HashMap<String, String>),
+    /// 保持顺序输出的 STAT 行
+    StatLines(Vec<(String, String)>),
+    /// `load` 导入的条目数
+    Loaded(usize),
+    /// `incr` / `decr` 的新值
+    Number(u64),
     Version(String),
     // 流式协议响应
     StreamBegin {
@@ -157,6 +312,157 @@ enum MemcachedResponse {
     StreamError(String),
 }
 
+/// 单个活跃连接的统计信息
+#[derive(Debug)]
+struct ConnectionInfo {
+    peer: SocketAddr,
+    connected_at: Instant,
+    /// 最近一次活动距建立连接的毫秒数
+    last_activity_ms: AtomicU64,
+    commands: AtomicU64,
+    bytes_read: AtomicU64,
+    bytes_written: AtomicU64,
+}
+
+impl ConnectionInfo {
+    fn touch(&self) {
+        let elapsed = self.connected_at.elapsed().as_millis() as u64;
+        self.last_activity_ms.store(elapsed, Ordering::Relaxed);
+    }
+
+    fn record_read(&self, bytes: usize) {
+        self.bytes_read.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+
+    fn record_command(&self) {
+        self.commands.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_write(&self, bytes: usize) {
+        self.bytes_written.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.touch();
+    }
+
+    /// 空闲时长（秒）
+    fn idle_secs(&self) -> u64 {
+        let age_ms = self.connected_at.elapsed().as_millis() as u64;
+        age_ms.saturating_sub(self.last_activity_ms.load(Ordering::Relaxed)) / 1000
+    }
+}
+
+/// 活跃连接注册表，供 `stats conns` 查询
+#[derive(Debug, Default)]
+struct ConnectionRegistry {
+    next_id: AtomicU64,
+    connections: dashmap::DashMap<u64, Arc<ConnectionInfo>>,
+}
+
+impl ConnectionRegistry {
+    /// 登记新连接，返回的句柄在连接结束（drop）时自动注销
+    fn register(self: &Arc<Self>, peer: SocketAddr) -> ConnectionHandle {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let info = Arc::new(ConnectionInfo {
+            peer,
+            connected_at: Instant::now(),
+            last_activity_ms: AtomicU64::new(0),
+            commands: AtomicU64::new(0),
+            bytes_read: AtomicU64::new(0),
+            bytes_written: AtomicU64::new(0),
+        });
+        self.connections.insert(id, Arc::clone(&info));
+        ConnectionHandle {
+            id,
+            info,
+            registry: Arc::clone(self),
+        }
+    }
+
+    /// 当前活跃连接数
+    fn active(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// 启动以来接受的连接总数
+    fn total(&self) -> u64 {
+        self.next_id.load(Ordering::Relaxed)
+    }
+
+    /// 生成 `stats conns` 输出，空闲最久的连接排在前面，最多列出 `limit` 个
+    fn conn_stats(&self, limit: usize) -> Vec<(String, String)> {
+        let mut conns: Vec<(u64, Arc<ConnectionInfo>)> = self
+            .connections
+            .iter()
+            .map(|entry| (*entry.key(), Arc::clone(entry.value())))
+            .collect();
+        conns.sort_by_key(|(id, info)| (std::cmp::Reverse(info.idle_secs()), *id));
+
+        let mut lines = vec![
+            ("curr_connections".to_string(), conns.len().to_string()),
+            ("listed_connections".to_string(), conns.len().min(limit).to_string()),
+        ];
+        for (id, info) in conns.into_iter().take(limit) {
+            lines.push((format!("{}:addr", id), info.peer.to_string()));
+            lines.push((format!("{}:age_secs", id), info.connected_at.elapsed().as_secs().to_string()));
+            lines.push((format!("{}:idle_secs", id), info.idle_secs().to_string()));
+            lines.push((format!("{}:cmds", id), info.commands.load(Ordering::Relaxed).to_string()));
+            lines.push((format!("{}:bytes_read", id), info.bytes_read.load(Ordering::Relaxed).to_string()));
+            lines.push((format!("{}:bytes_written", id), info.bytes_written.load(Ordering::Relaxed).to_string()));
+        }
+        lines
+    }
+}
+
+/// 已登记连接的句柄
+struct ConnectionHandle {
+    id: u64,
+    info: Arc<ConnectionInfo>,
+    registry: Arc<ConnectionRegistry>,
+}
+
+impl Drop for ConnectionHandle {
+    fn drop(&mut self) {
+        self.registry.connections.remove(&self.id);
+    }
+}
+
+/// 接受连接失败的指数退避状态
+#[derive(Debug)]
+struct AcceptBackoff {
+    max_delay_ms: u64,
+    next_delay_ms: u64,
+    consecutive_errors: u32,
+}
+
+impl AcceptBackoff {
+    fn new(max_delay_ms: u64) -> Self {
+        Self {
+            max_delay_ms,
+            next_delay_ms: ACCEPT_BACKOFF_INITIAL_MS.min(max_delay_ms),
+            consecutive_errors: 0,
+        }
+    }
+
+    /// 记录一次失败并返回本次等待时间，之后每次翻倍直到上限
+    fn next_delay(&mut self) -> Duration {
+        self.consecutive_errors = self.consecutive_errors.saturating_add(1);
+        let delay_ms = self.next_delay_ms;
+        self.next_delay_ms = (delay_ms * 2).min(self.max_delay_ms);
+        Duration::from_millis(delay_ms)
+    }
+
+    /// 连续失败次数
+    fn consecutive_errors(&self) -> u32 {
+        self.consecutive_errors
+    }
+
+    /// 接受成功后重置
+    fn reset(&mut self) {
+        self.next_delay_ms = ACCEPT_BACKOFF_INITIAL_MS.min(self.max_delay_ms);
+        self.consecutive_errors = 0;
+    }
+}
+
 /// Memcached 服务器
 pub struct MemcachedServer {
     cache: Arc<RatMemCache>,
@@ -169,7 +475,9 @@ pub struct MemcachedServer {
     // 流式传输状态管理
     streaming_state: Arc<tokio::sync::RwLock<HashMap<String, StreamingSession>>>,
     // 分块SET状态管理
-    chunked_set_state: Arc<tokio::sync::RwLock<HashMap<String, ChunkedSetSession>>>,
+    chunked_sets: Arc<ChunkedSetRegistry>,
+    // 活跃连接注册表
+    connections: Arc<ConnectionRegistry>,
 }
 
 /// 流式传输会话状态
@@ -206,10 +514,21 @@ struct ChunkedSetSession {
     exptime: u32,
     /// 已接收的数据块
     received_chunks: HashMap<usize, Bytes>,
+    /// 已缓冲的字节数，不超过声明的总大小
+    buffered_bytes: usize,
     /// 创建时间
     created_at: Instant,
 }
 
+/// 分块SET数据块被拒绝的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChunkRejection {
+    /// 块编号超出声明的块数量
+    InvalidChunk,
+    /// 累计大小超过声明的总大小
+    ExceedsTotalSize,
+}
+
 impl ChunkedSetSession {
     pub fn new(key: String, total_size: usize, chunk_count: usize, flags: u32, exptime: u32) -> Self {
         Self {
@@ -219,18 +538,26 @@ impl ChunkedSetSession {
             flags,
             exptime,
             received_chunks: HashMap::new(),
+            buffered_bytes: 0,
             created_at: Instant::now(),
         }
     }
 
-    /// 添加数据块
-    pub fn add_chunk(&mut self, chunk_number: usize, data: Bytes) -> bool {
+    /// 添加数据块，重复发送的块替换旧数据
+    pub fn add_chunk(&mut self, chunk_number: usize, data: Bytes) -> Result<(), ChunkRejection> {
         if chunk_number >= self.chunk_count {
-            return false;
+            return Err(ChunkRejection::InvalidChunk);
+        }
+
+        let replaced = self.received_chunks.get(&chunk_number).map_or(0, Bytes::len);
+        let buffered_bytes = self.buffered_bytes - replaced + data.len();
+        if buffered_bytes > self.total_size {
+            return Err(ChunkRejection::ExceedsTotalSize);
         }
 
+        self.buffered_bytes = buffered_bytes;
         self.received_chunks.insert(chunk_number, data);
-        true
+        Ok(())
     }
 
     /// 检查是否所有块都已接收
@@ -262,6 +589,167 @@ impl ChunkedSetSession {
     }
 }
 
+/// 分块SET会话表，所有连接共享
+#[derive(Debug)]
+struct ChunkedSetRegistry {
+    sessions: tokio::sync::RwLock<HashMap<String, ChunkedSetSession>>,
+    /// 允许声明的最大值大小（字节，0 表示不限制）
+    max_item_size: usize,
+}
+
+impl Default for ChunkedSetRegistry {
+    fn default() -> Self {
+        Self::new(default_max_item_size())
+    }
+}
+
+impl ChunkedSetRegistry {
+    fn new(max_item_size: usize) -> Self {
+        Self {
+            sessions: tokio::sync::RwLock::new(HashMap::new()),
+            max_item_size,
+        }
+    }
+
+    /// 处理分块SET开始命令
+    async fn begin(
+        &self,
+        key: String,
+        total_size: usize,
+        chunk_count: usize,
+        flags: u32,
+        exptime: u32,
+    ) -> MemcachedResponse {
+        info!("处理SET开始: {} ({} bytes, {} chunks)", key, total_size, chunk_count);
+
+        // 在接收任何数据之前校验客户端声明的大小，避免为虚报的总大小缓冲数据
+        let max_item_size = self.max_item_size;
+        if max_item_size > 0 && total_size > max_item_size {
+            warn!("分块SET声明的大小超出限制: {} ({} > {} bytes)", key, total_size, max_item_size);
+            return MemcachedResponse::ClientError("object too large for cache".to_string());
+        }
+        if chunk_count == 0 || chunk_count > total_size.max(1) {
+            warn!("分块SET块数量无效: {} ({} chunks, {} bytes)", key, chunk_count, total_size);
+            return MemcachedResponse::ClientError("invalid chunk count".to_string());
+        }
+
+        /// 会话的最长存活时间，超时未完成的会话可被新的 set_begin 替换
+        const SESSION_TIMEOUT: Duration = Duration::from_secs(300);
+
+        // 创建分块SET会话
+        let session = ChunkedSetSession::new(key.clone(), total_size, chunk_count, flags, exptime);
+
+        // 存储会话状态，同一键已有进行中的会话时拒绝，避免两个会话的数据块互相覆盖
+        {
+            let mut state = self.sessions.write().await;
+            match state.entry(key.clone()) {
+                Entry::Occupied(mut entry) => {
+                    if entry.get().created_at.elapsed() < SESSION_TIMEOUT {
+                        warn!("分块SET已在进行中: {}", key);
+                        return MemcachedResponse::ClientError("set already in progress".to_string());
+                    }
+                    // 超时未完成的会话视为已放弃
+                    entry.insert(session);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(session);
+                }
+            }
+        }
+
+        MemcachedResponse::Stored
+    }
+
+    /// 处理分块SET数据命令，最后一个数据块到达时组装并写入缓存
+    async fn data(
+        &self,
+        cache: &RatMemCache,
+        key: String,
+        chunk_number: usize,
+        data: Bytes,
+    ) -> MemcachedResponse {
+        info!("处理SET数据: {} (chunk {}, {} bytes)", key, chunk_number, data.len());
+
+        let mut state = self.sessions.write().await;
+
+        let Some(session) = state.get_mut(&key) else {
+            warn!("分块SET会话不存在: {}", key);
+            return MemcachedResponse::ClientError("会话不存在".to_string());
+        };
+
+        match session.add_chunk(chunk_number, data) {
+            Ok(()) => {}
+            Err(ChunkRejection::InvalidChunk) => {
+                error!("分块SET数据块无效: {} (chunk {})", key, chunk_number);
+                return MemcachedResponse::ClientError("无效的数据块".to_string());
+            }
+            Err(ChunkRejection::ExceedsTotalSize) => {
+                // 放弃整个会话，释放已缓冲的数据
+                warn!("分块SET数据超出声明的总大小: {} ({} bytes)", key, session.total_size);
+                state.remove(&key);
+                return MemcachedResponse::ClientError("data exceeds total_size".to_string());
+            }
+        }
+
+        let (received, total) = session.progress();
+        info!("SET数据进度: {}/{}", received, total);
+
+        if !session.is_complete() {
+            return MemcachedResponse::Stored;
+        }
+
+        // 已接收所有块，组装数据并存储
+        if session.buffered_bytes != session.total_size {
+            warn!("分块SET数据大小与声明不符: {} ({} != {} bytes)", key, session.buffered_bytes, session.total_size);
+            state.remove(&key);
+            return MemcachedResponse::ClientError("data size does not match total_size".to_string());
+        }
+
+        if let Some(assembled_data) = session.assemble_data() {
+            let ttl = if session.exptime > 0 { session.exptime as u64 } else { 0 };
+            match cache.set_with_ttl(key.clone(), Bytes::from(assembled_data), ttl).await {
+                Ok(_) => {
+                    info!("分块SET完成: {}", key);
+                    state.remove(&key); // 清理会话
+                    MemcachedResponse::Stored
+                }
+                Err(e) => {
+                    error!("分块SET存储失败: {}", e);
+                    state.remove(&key); // 清理会话
+                    MemcachedResponse::ServerError(format!("存储失败: {}", e))
+                }
+            }
+        } else {
+            error!("分块SET数据组装失败: {}", key);
+            state.remove(&key);
+            MemcachedResponse::ServerError("数据组装失败".to_string())
+        }
+    }
+
+    /// 处理分块SET结束命令，放弃未完成的会话（最后一个数据块到达时已写入并结束会话）
+    async fn end(&self, key: String) -> MemcachedResponse {
+        info!("处理SET结束: {}", key);
+
+        let mut state = self.sessions.write().await;
+
+        if let Some(session) = state.get(&key) {
+            if session.is_complete() {
+                // 数据已经在 data 中处理完成
+                state.remove(&key);
+                MemcachedResponse::Stored
+            } else {
+                let (received, total) = session.progress();
+                warn!("分块SET未完成: {} ({}/{})", key, received, total);
+                state.remove(&key);
+                MemcachedResponse::ClientError("数据不完整".to_string())
+            }
+        } else {
+            warn!("分块SET会话不存在: {}", key);
+            MemcachedResponse::ClientError("会话不存在".to_string())
+        }
+    }
+}
+
 impl StreamingSession {
     pub fn new(key: String, data: Bytes, chunk_size: usize) -> Self {
         let total_size = data.len();
@@ -379,102 +867,6 @@ impl MemcachedServer {
         }
     }
 
-    /// 处理分块SET开始命令
-    async fn handle_set_begin(
-        &self,
-        key: String,
-        total_size: usize,
-        chunk_count: usize,
-        flags: u32,
-        exptime: u32,
-    ) -> CacheResult<MemcachedResponse> {
-        info!("处理SET开始: {} ({} bytes, {} chunks)", key, total_size, chunk_count);
-
-        // 创建分块SET会话
-        let session = ChunkedSetSession::new(key.clone(), total_size, chunk_count, flags, exptime);
-
-        // 存储会话状态
-        {
-            let mut state = self.chunked_set_state.write().await;
-            state.insert(key.clone(), session);
-        }
-
-        Ok(MemcachedResponse::Stored)
-    }
-
-    /// 处理分块SET数据命令
-    async fn handle_set_data(
-        &self,
-        key: String,
-        chunk_number: usize,
-        data: Bytes,
-    ) -> CacheResult<MemcachedResponse> {
-        info!("处理SET数据: {} (chunk {}, {} bytes)", key, chunk_number, data.len());
-
-        let mut state = self.chunked_set_state.write().await;
-
-        if let Some(session) = state.get_mut(&key) {
-            if session.add_chunk(chunk_number, data) {
-                let (received, total) = session.progress();
-                info!("SET数据进度: {}/{}", received, total);
-
-                // 如果已接收所有块，组装数据并存储
-                if session.is_complete() {
-                    if let Some(assembled_data) = session.assemble_data() {
-                        let ttl = if session.exptime > 0 { session.exptime as u64 } else { 0 };
-                        match self.cache.set_with_ttl(key.clone(), Bytes::from(assembled_data), ttl).await {
-                            Ok(_) => {
-                                info!("分块SET完成: {}", key);
-                                state.remove(&key); // 清理会话
-                                Ok(MemcachedResponse::Stored)
-                            }
-                            Err(e) => {
-                                error!("分块SET存储失败: {}", e);
-                                state.remove(&key); // 清理会话
-                                Ok(MemcachedResponse::ServerError(format!("存储失败: {}", e)))
-                            }
-                        }
-                    } else {
-                        error!("分块SET数据组装失败: {}", key);
-                        state.remove(&key);
-                        Ok(MemcachedResponse::ServerError("数据组装失败".to_string()))
-                    }
-                } else {
-                    Ok(MemcachedResponse::Stored)
-                }
-            } else {
-                error!("分块SET数据块无效: {} (chunk {})", key, chunk_number);
-                Ok(MemcachedResponse::ClientError("无效的数据块".to_string()))
-            }
-        } else {
-            warn!("分块SET会话不存在: {}", key);
-            Ok(MemcachedResponse::ClientError("会话不存在".to_string()))
-        }
-    }
-
-    /// 处理分块SET结束命令
-    async fn handle_set_end(&self, key: String) -> CacheResult<MemcachedResponse> {
-        info!("处理SET结束: {}", key);
-
-        let mut state = self.chunked_set_state.write().await;
-
-        if let Some(session) = state.get(&key) {
-            if session.is_complete() {
-                // 数据已经在handle_set_data中处理完成
-                state.remove(&key);
-                Ok(MemcachedResponse::Stored)
-            } else {
-                let (received, total) = session.progress();
-                warn!("分块SET未完成: {} ({}/{})", key, received, total);
-                state.remove(&key);
-                Ok(MemcachedResponse::ClientError("数据不完整".to_string()))
-            }
-        } else {
-            warn!("分块SET会话不存在: {}", key);
-            Ok(MemcachedResponse::ClientError("会话不存在".to_string()))
-        }
-    }
-
     /// 创建新的 Memcached 服务器
     pub async fn new(config: ServerConfig) -> CacheResult<Self> {
         let bind_addr: SocketAddr = config
@@ -505,18 +897,23 @@ impl MemcachedServer {
         info!("🚀 初始化 RatMemcached 服务器");
         info!("📍 绑定地址: {}", bind_addr);
 
-        // 显示配置详情
-        Self::print_configuration_details(&cache_config);
-
-        // 如果是异步日志模式，强制刷新启动时的配置信息
-        flush_logs_if_async(&logging_config);
-
         // 创建缓存实例
         let cache = Arc::new(RatMemCache::new(cache_config).await?);
         info!("✅ 缓存实例创建成功");
+        info!("🧩 {}", cache.runtime_info());
 
-        // 创建传统 TCP 监听器
-        let listener = Some(Self::create_tcp_listener(bind_addr).await?);
+        // 显示缓存实际使用的配置
+        Self::print_configuration_details(cache.config());
+
+        // 如果是异步日志模式，强制刷新启动时的配置信息
+        flush_logs_if_async(&logging_config);
+
+        // 创建传统 TCP 监听器
+        let listener = Some(
+            Self::create_tcp_listener(bind_addr, config.bind_retries, config.bind_retry_delay_ms).await?,
+        );
+
+        let chunked_sets = Arc::new(ChunkedSetRegistry::new(config.max_item_size));
 
         Ok(Self {
             cache,
@@ -527,7 +924,8 @@ impl MemcachedServer {
             shutdown_notify: Arc::new(Notify::new()),
             streaming_parser: StreamingParser::new(),
             streaming_state: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
-            chunked_set_state: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            chunked_sets,
+            connections: Arc::new(ConnectionRegistry::default()),
         })
     }
 
@@ -612,10 +1010,7 @@ impl MemcachedServer {
                 .await
                 .map_err(|e| CacheError::io_error(&format!("读取配置文件失败: {}", e)))?;
 
-            let cache_config: CacheConfig = toml::from_str(&config_content)
-                .map_err(|e| CacheError::config_error(&format!("解析配置文件失败: {}", e)))?;
-
-            Ok(cache_config)
+            CacheConfig::from_toml_str(&config_content)
         } else {
             // 预设配置功能已移除，必须使用配置文件
             return Err(CacheError::config_error(
@@ -624,13 +1019,36 @@ impl MemcachedServer {
         }
     }
 
-    async fn create_tcp_listener(bind_addr: SocketAddr) -> CacheResult<TokioTcpListener> {
+    async fn create_tcp_listener(
+        bind_addr: SocketAddr,
+        bind_retries: u32,
+        bind_retry_delay_ms: u64,
+    ) -> CacheResult<TokioTcpListener> {
         info!("🔧 初始化传统 TCP 监听器");
 
-        // 创建 TCP 监听器
-        let listener = TokioTcpListener::bind(bind_addr)
-            .await
-            .map_err(|e| CacheError::io_error(&format!("绑定地址失败: {}", e)))?;
+        // 创建 TCP 监听器，地址被占用时按配置退避重试
+        let mut attempt = 0;
+        let mut delay_ms = bind_retry_delay_ms;
+        let listener = loop {
+            match TokioTcpListener::bind(bind_addr).await {
+                Ok(listener) => break listener,
+                Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                    if attempt >= bind_retries {
+                        error!("绑定地址失败: {} 已被占用（已重试 {} 次）", bind_addr, attempt);
+                        return Err(CacheError::from(e));
+                    }
+                    attempt += 1;
+                    warn!("地址 {} 已被占用，{}ms 后进行第 {}/{} 次重试",
+                        bind_addr, delay_ms, attempt, bind_retries);
+                    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                    delay_ms = (delay_ms * 2).min(MAX_BIND_RETRY_DELAY_MS);
+                }
+                Err(e) => {
+                    error!("绑定地址失败: {} ({})", bind_addr, e);
+                    return Err(CacheError::io_error(format!("绑定地址失败: {}", e)));
+                }
+            }
+        };
 
         // 设置平台特定的优化
         Self::configure_tcp_options(&listener).await?;
@@ -758,26 +1176,46 @@ impl MemcachedServer {
         tokio::select! {
             // 主循环：处理传入的连接
             result = async {
+                let mut backoff = AcceptBackoff::new(self.config.accept_backoff_max_ms);
                 loop {
                     match listener.accept().await {
                         Ok((stream, addr)) => {
                             info!("🔗 新连接来自: {}", addr);
+                            backoff.reset();
 
                             // 为新连接创建处理任务
                             let cache = Arc::clone(&self.cache);
                             let start_time = self.start_time;
+                            let connections = Arc::clone(&self.connections);
+                            let chunked_sets = Arc::clone(&self.chunked_sets);
+                            let max_multiget_keys = self.config.max_multiget_keys;
+                            let assembly_timeout = match self.config.command_assembly_timeout_ms {
+                                0 => None,
+                                ms => Some(Duration::from_millis(ms)),
+                            };
+                            let verbose_errors = self.config.verbose_errors;
 
                             tokio::spawn(async move {
-                                if let Err(e) = Self::handle_tcp_connection(stream, cache, start_time).await
+                                if let Err(e) = Self::handle_tcp_connection(stream, addr, cache, connections, chunked_sets, start_time, max_multiget_keys, assembly_timeout, verbose_errors).await
                                 {
                                     error!("处理 TCP 连接失败: {}", e);
                                 }
                             });
                         }
                         Err(e) => {
-                            error!("接受连接失败: {}", e);
-                            // 短暂休眠避免错误循环
-                            tokio::time::sleep(Duration::from_millis(100)).await;
+                            // 指数退避，避免持续性故障（如文件描述符耗尽）导致错误循环
+                            let delay = backoff.next_delay();
+                            let failures = backoff.consecutive_errors();
+                            error!("接受连接失败（连续 {} 次），{}ms 后重试: {}", failures, delay.as_millis(), e);
+
+                            let threshold = self.config.accept_error_threshold;
+                            if threshold > 0 && failures == threshold {
+                                error!("严重错误: 连续 {} 次接受连接失败，监听器可能处于异常状态", failures);
+                                if self.config.shutdown_on_accept_errors {
+                                    return Err(CacheError::io_error(format!("连续 {} 次接受连接失败: {}", failures, e)));
+                                }
+                            }
+                            tokio::time::sleep(delay).await;
                         }
                     }
                 }
@@ -800,26 +1238,51 @@ impl MemcachedServer {
         self.shutdown_notify.notify_waiters();
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_tcp_connection(
         mut stream: TcpStream,
+        peer: SocketAddr,
         cache: Arc<RatMemCache>,
+        connections: Arc<ConnectionRegistry>,
+        chunked_sets: Arc<ChunkedSetRegistry>,
         start_time: Instant,
+        max_multiget_keys: usize,
+        assembly_timeout: Option<Duration>,
+        verbose_errors: bool,
     ) -> CacheResult<()> {
         info!("🔗 开始处理 TCP 连接");
+        // 连接结束时句柄被 drop，自动从注册表移除
+        let conn = connections.register(peer);
 
         let mut consecutive_errors = 0;
         const MAX_CONSECUTIVE_ERRORS: u32 = 5;
         const MAX_EMPTY_READS: u32 = 3;
+        const READ_TIMEOUT: Duration = Duration::from_secs(30);
         let mut empty_read_count = 0;
-        let mut buffer_accumulator = String::new(); // 累积缓冲区
+        let mut buffer_accumulator: Vec<u8> = Vec::new(); // 累积缓冲区（按字节处理，键与数据可含任意字节）
         let mut pending_command: Option<MemcachedCommand> = None; // 等待数据的命令
         let mut expected_bytes = 0; // 期待的数据字节数
+        let mut pending_deadline: Option<Instant> = None; // 数据块到达的截止时间
 
         loop {
-            // 尝试接收数据，设置超时
+            // 数据块未在截止时间前到齐，防止慢速发送的客户端长期占用连接
+            if pending_command.is_some() && pending_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                warn!("等待数据块超时，关闭连接");
+                let response = MemcachedResponse::ClientError("data block timeout".to_string());
+                let _ = stream.write_all(&Self::format_response(response)).await;
+                break;
+            }
+
+            // 尝试接收数据，设置超时（等待数据块时不超过截止时间）
             let mut buffer = vec![0u8; 4096];
+            let read_timeout = match pending_deadline {
+                Some(deadline) if pending_command.is_some() => {
+                    deadline.saturating_duration_since(Instant::now()).min(READ_TIMEOUT)
+                }
+                _ => READ_TIMEOUT,
+            };
             let receive_result =
-                tokio::time::timeout(Duration::from_secs(30), stream.read(&mut buffer)).await;
+                tokio::time::timeout(read_timeout, stream.read(&mut buffer)).await;
 
             match receive_result {
                 Ok(Ok(bytes_read)) => {
@@ -838,10 +1301,10 @@ impl MemcachedServer {
                     empty_read_count = 0;
 
                     info!("📨 接收到 {} 字节数据", bytes_read);
+                    conn.info.record_read(bytes_read);
 
                     // 将新数据添加到累积缓冲区
-                    let new_data = String::from_utf8_lossy(&buffer[..bytes_read]);
-                    buffer_accumulator.push_str(&new_data);
+                    buffer_accumulator.extend_from_slice(&buffer[..bytes_read]);
 
                     // 处理累积的数据
                     let mut should_quit = false;
@@ -852,17 +1315,11 @@ impl MemcachedServer {
                             // 检查是否有足够的数据，需要考虑数据后的行结束符
                             let data_with_terminator_len = if buffer_accumulator.len()
                                 >= expected_bytes + 2
-                                && buffer_accumulator
-                                    .chars()
-                                    .skip(expected_bytes)
-                                    .take(2)
-                                    .collect::<String>()
-                                    == "\r\n"
+                                && &buffer_accumulator[expected_bytes..expected_bytes + 2] == b"\r\n"
                             {
                                 expected_bytes + 2 // 数据 + \r\n
                             } else if buffer_accumulator.len() >= expected_bytes + 1
-                                && buffer_accumulator.chars().skip(expected_bytes).next()
-                                    == Some('\n')
+                                && buffer_accumulator[expected_bytes] == b'\n'
                             {
                                 expected_bytes + 1 // 数据 + \n
                             } else {
@@ -870,31 +1327,24 @@ impl MemcachedServer {
                             };
 
                             if data_with_terminator_len > 0 {
-                                let data = buffer_accumulator
-                                    .chars()
-                                    .take(expected_bytes)
-                                    .collect::<String>();
-                                buffer_accumulator = buffer_accumulator
-                                    .chars()
-                                    .skip(data_with_terminator_len)
-                                    .collect();
+                                let data = Bytes::copy_from_slice(&buffer_accumulator[..expected_bytes]);
+                                buffer_accumulator.drain(..data_with_terminator_len);
 
                                 // 设置命令数据
                                 match &mut cmd {
-                                    MemcachedCommand::Set { data: d, .. } => {
-                                        *d = Some(Bytes::from(data.into_bytes()))
-                                    }
-                                    MemcachedCommand::Add { data: d, .. } => {
-                                        *d = Some(Bytes::from(data.into_bytes()))
-                                    }
-                                    MemcachedCommand::Replace { data: d, .. } => {
-                                        *d = Some(Bytes::from(data.into_bytes()))
-                                    }
+                                    MemcachedCommand::Set { data: d, .. }
+                                    | MemcachedCommand::Add { data: d, .. }
+                                    | MemcachedCommand::Replace { data: d, .. }
+                                    | MemcachedCommand::Append { data: d, .. }
+                                    | MemcachedCommand::Prepend { data: d, .. }
+                                    | MemcachedCommand::Cas { data: d, .. }
+                                    | MemcachedCommand::Load { data: d, .. }
+                                    | MemcachedCommand::SetData { data: d, .. } => *d = Some(data),
                                     _ => {}
                                 }
 
                                 // 执行命令
-                                let response = Self::execute_command(cmd, &cache, start_time).await;
+                                let response = Self::dispatch_command(cmd, &cache, &connections, &chunked_sets, start_time, verbose_errors).await;
                                 let response_data = Self::format_response(response);
 
                                 if let Err(e) = stream.write_all(&response_data).await {
@@ -903,6 +1353,8 @@ impl MemcachedServer {
                                     if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
                                         return Ok(());
                                     }
+                                } else {
+                                    conn.info.record_write(response_data.len());
                                 }
 
                                 pending_command = None;
@@ -914,25 +1366,22 @@ impl MemcachedServer {
                             }
                         } else {
                             // 查找完整的命令行，支持 \r\n 和 \n 两种结束符
-                            let line_end_pos = if let Some(pos) = buffer_accumulator.find("\r\n") {
-                                Some((pos, 2)) // \r\n 占用 2 个字符
-                            } else if let Some(pos) = buffer_accumulator.find('\n') {
-                                Some((pos, 1)) // \n 占用 1 个字符
-                            } else {
-                                None
-                            };
-
-                            if let Some((line_end, separator_len)) = line_end_pos {
-                                let line = buffer_accumulator[..line_end].to_string();
-                                buffer_accumulator =
-                                    buffer_accumulator[line_end + separator_len..].to_string();
+                            if let Some(newline_pos) = buffer_accumulator.iter().position(|&b| b == b'\n') {
+                                let line_end = if newline_pos > 0 && buffer_accumulator[newline_pos - 1] == b'\r' {
+                                    newline_pos - 1
+                                } else {
+                                    newline_pos
+                                };
+                                let line = Self::decode_command_line(&buffer_accumulator[..line_end]);
+                                buffer_accumulator.drain(..=newline_pos);
 
-                                if line.trim().is_empty() {
+                                if line.trim_matches(Self::is_protocol_whitespace).is_empty() {
                                     continue; // 跳过空行
                                 }
 
                                 debug!("📝 处理命令行: {}", line);
-                                let command = Self::parse_command(&line);
+                                let command = Self::parse_command(&line, max_multiget_keys);
+                                conn.info.record_command();
 
                                 // 检查是否需要等待数据
                                 let needs_data = matches!(
@@ -940,29 +1389,50 @@ impl MemcachedServer {
                                     MemcachedCommand::Set { .. }
                                         | MemcachedCommand::Add { .. }
                                         | MemcachedCommand::Replace { .. }
-                                );
+                                        | MemcachedCommand::Append { .. }
+                                        | MemcachedCommand::Prepend { .. }
+                                        | MemcachedCommand::Cas { .. }
+                                        | MemcachedCommand::Load { .. }
+                                        | MemcachedCommand::SetData { .. }
+                                ) || matches!(command, MemcachedCommand::Rejected { swallow_bytes, .. } if swallow_bytes > 0);
 
                                 if needs_data {
                                     // 获取期待的字节数
                                     let bytes = match &command {
                                         MemcachedCommand::Set { bytes, .. }
                                         | MemcachedCommand::Add { bytes, .. }
-                                        | MemcachedCommand::Replace { bytes, .. } => *bytes,
+                                        | MemcachedCommand::Replace { bytes, .. }
+                                        | MemcachedCommand::Append { bytes, .. }
+                                        | MemcachedCommand::Prepend { bytes, .. }
+                                        | MemcachedCommand::Cas { bytes, .. }
+                                        | MemcachedCommand::Load { bytes, .. }
+                                        | MemcachedCommand::SetData { bytes, .. } => *bytes,
+                                        MemcachedCommand::Rejected { swallow_bytes, .. } => *swallow_bytes,
                                         _ => 0,
                                     };
                                     pending_command = Some(command);
                                     expected_bytes = bytes;
+                                    pending_deadline = assembly_timeout.map(|timeout| Instant::now() + timeout);
+                                } else if matches!(command, MemcachedCommand::Dump) {
+                                    // 导出数据分批直接写入连接
+                                    match Self::stream_dump(&mut stream, &cache).await {
+                                        Ok(bytes) => conn.info.record_write(bytes),
+                                        Err(e) => {
+                                            error!("发送导出数据失败: {}", e);
+                                            return Ok(());
+                                        }
+                                    }
                                 } else if matches!(command, MemcachedCommand::Quit) {
                                     should_quit = true;
                                     let response =
-                                        Self::execute_command(command, &cache, start_time).await;
+                                        Self::dispatch_command(command, &cache, &connections, &chunked_sets, start_time, verbose_errors).await;
                                     let response_data = Self::format_response(response);
                                     let _ = stream.write_all(&response_data).await;
                                     break;
                                 } else {
                                     // 立即执行的命令
                                     let response =
-                                        Self::execute_command(command, &cache, start_time).await;
+                                        Self::dispatch_command(command, &cache, &connections, &chunked_sets, start_time, verbose_errors).await;
                                     let response_data = Self::format_response(response);
 
                                     if let Err(e) = stream.write_all(&response_data).await
@@ -972,6 +1442,8 @@ impl MemcachedServer {
                                         if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
                                             return Ok(());
                                         }
+                                    } else {
+                                        conn.info.record_write(response_data.len());
                                     }
                                 }
                             } else {
@@ -1012,25 +1484,29 @@ impl MemcachedServer {
     /// 格式化响应
     fn format_response(response: MemcachedResponse) -> Vec<u8> {
         match response {
-            MemcachedResponse::Value {
-                key,
-                flags,
-                bytes,
-                data,
-            } => {
-                let header = format!("VALUE {} {} {}\r\n", key, flags, bytes);
+            MemcachedResponse::Values(items) => {
                 let mut response_data = Vec::new();
-                response_data.extend_from_slice(header.as_bytes());
-                response_data.extend_from_slice(&data);
-                response_data.extend_from_slice(b"\r\nEND\r\n");
+                for item in items {
+                    response_data.extend_from_slice(b"VALUE ");
+                    response_data.extend_from_slice(&Self::encode_key(&item.key));
+                    match item.cas {
+                        Some(cas) => response_data.extend_from_slice(format!(" {} {} {}\r\n", item.flags, item.data.len(), cas).as_bytes()),
+                        None => response_data.extend_from_slice(format!(" {} {}\r\n", item.flags, item.data.len()).as_bytes()),
+                    }
+                    response_data.extend_from_slice(&item.data);
+                    response_data.extend_from_slice(b"\r\n");
+                }
+                response_data.extend_from_slice(b"END\r\n");
                 response_data
             }
-            MemcachedResponse::End => b"END\r\n".to_vec(),
             MemcachedResponse::Stored => b"STORED\r\n".to_vec(),
             MemcachedResponse::NotStored => b"NOT_STORED\r\n".to_vec(),
             MemcachedResponse::Deleted => b"DELETED\r\n".to_vec(),
             MemcachedResponse::NotFound => b"NOT_FOUND\r\n".to_vec(),
+            MemcachedResponse::Exists => b"EXISTS\r\n".to_vec(),
+            MemcachedResponse::Touched => b"TOUCHED\r\n".to_vec(),
             MemcachedResponse::Ok => b"OK\r\n".to_vec(),
+            MemcachedResponse::Error(msg) if msg.is_empty() => b"ERROR\r\n".to_vec(),
             MemcachedResponse::Error(msg) => format!("ERROR {}\r\n", msg).into_bytes(),
             MemcachedResponse::ServerError(msg) => format!("SERVER_ERROR {}\r\n", msg).into_bytes(),
             MemcachedResponse::ClientError(msg) => format!("CLIENT_ERROR {}\r\n", msg).into_bytes(),
@@ -1042,6 +1518,16 @@ impl MemcachedServer {
                 result.extend_from_slice(b"END\r\n");
                 result
             }
+            MemcachedResponse::StatLines(lines) => {
+                let mut result = Vec::new();
+                for (key, value) in lines {
+                    result.extend_from_slice(format!("STAT {} {}\r\n", key, value).as_bytes());
+                }
+                result.extend_from_slice(b"END\r\n");
+                result
+            }
+            MemcachedResponse::Loaded(count) => format!("LOADED {}\r\n", count).into_bytes(),
+            MemcachedResponse::Number(value) => format!("{}\r\n", value).into_bytes(),
             MemcachedResponse::Version(version) => format!("VERSION {}\r\n", version).into_bytes(),
             // 流式协议响应处理
             MemcachedResponse::StreamBegin { key, total_size, chunk_count } => {
@@ -1056,24 +1542,197 @@ impl MemcachedServer {
             MemcachedResponse::StreamError(msg) => {
                 StreamingFormatter::format_error(&msg)
             }
-            _ => b"ERROR\r\n".to_vec(),
         }
     }
 
+    /// 分批导出全部条目（`dump` 命令），每个批次写出后让出执行权，避免长时间占用运行时影响其他连接
+    ///
+    /// 返回写出的字节数。读取失败时写出 SERVER_ERROR 并结束，不写出 `DUMP_END`
+    async fn stream_dump<W: AsyncWrite + Unpin>(writer: &mut W, cache: &RatMemCache) -> std::io::Result<usize> {
+        info!("执行 DUMP 命令");
+        let keys = match cache.keys().await {
+            Ok(keys) => keys,
+            Err(e) => return Self::write_dump_error(writer, e).await,
+        };
+
+        // 只读扫描，不把 L2 中的条目提升到 L1
+        let options = CacheOptions {
+            promote_on_hit: Some(false),
+            ..Default::default()
+        };
+        let mut written = 0;
+        let mut total = 0;
+        let mut batch = Vec::new();
+        let mut batch_count = 0;
+        for key in keys {
+            let entry = match cache.get_entry_with_options(&key, &options).await {
+                Ok(Some(entry)) => entry,
+                // 列出键之后被删除或已过期
+                Ok(None) => continue,
+                Err(e) => return Ok(written + Self::write_dump_error(writer, e).await?),
+            };
+            let entry = DumpEntry {
+                key: Bytes::from(Self::encode_key(&key)),
+                value: entry.value,
+                flags: entry.flags,
+                ttl_seconds: entry.ttl_seconds.unwrap_or(0),
+            };
+            if let Err(e) = DumpCodec::encode_entry(&entry, &mut batch) {
+                warn!("跳过无法导出的条目 {}: {}", key, e);
+                continue;
+            }
+            batch_count += 1;
+
+            if batch_count >= DUMP_BATCH_MAX_ENTRIES || batch.len() >= DUMP_BATCH_MAX_BYTES {
+                written += Self::write_dump_batch(writer, &mut batch, batch_count).await?;
+                total += batch_count as u64;
+                batch_count = 0;
+                tokio::task::yield_now().await;
+            }
+        }
+        if batch_count > 0 {
+            written += Self::write_dump_batch(writer, &mut batch, batch_count).await?;
+            total += batch_count as u64;
+        }
+
+        let end = DumpCodec::format_end(total);
+        writer.write_all(&end).await?;
+        info!("DUMP 完成: {} 个条目", total);
+        Ok(written + end.len())
+    }
+
+    /// 写出一个 `dump` 批次并清空缓冲区
+    async fn write_dump_batch<W: AsyncWrite + Unpin>(writer: &mut W, batch: &mut Vec<u8>, count: usize) -> std::io::Result<usize> {
+        let header = DumpCodec::format_batch_header(count, batch.len());
+        writer.write_all(&header).await?;
+        writer.write_all(batch).await?;
+        writer.write_all(b"\r\n").await?;
+        let written = header.len() + batch.len() + 2;
+        batch.clear();
+        Ok(written)
+    }
+
+    async fn write_dump_error<W: AsyncWrite + Unpin>(writer: &mut W, e: CacheError) -> std::io::Result<usize> {
+        error!("DUMP 失败: {}", e);
+        let response = Self::format_response(MemcachedResponse::ServerError(format!("导出失败: {}", e)));
+        writer.write_all(&response).await?;
+        Ok(response.len())
+    }
+
+    /// 解码命令行：有效的 UTF-8 原样保留，与库 API 写入的键一致；无效的字节逐个映射到
+    /// `U+F780..=U+F7FF`，保证任意字节的键可以往返
+    fn decode_command_line(line: &[u8]) -> String {
+        let mut decoded = String::with_capacity(line.len());
+        for chunk in line.utf8_chunks() {
+            decoded.push_str(chunk.valid());
+            decoded.extend(chunk.invalid().iter().filter_map(|&b| char::from_u32(RAW_BYTE_BASE + b as u32)));
+        }
+        decoded
+    }
+
+    /// 将键还原为客户端发送的原始字节
+    fn encode_key(key: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(key.len());
+        for c in key.chars() {
+            match (c as u32).checked_sub(RAW_BYTE_BASE) {
+                Some(b @ 0x80..=0xFF) => bytes.push(b as u8),
+                _ => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
+        }
+        bytes
+    }
+
+    /// 协议分隔符仅包含 ASCII 空格和制表符（不含 Unicode 空白）
+    fn is_protocol_whitespace(c: char) -> bool {
+        c == ' ' || c == '\t'
+    }
+
+    /// 校验键：长度不超过 250 字节，且不含控制字符
+    fn is_valid_key(key: &str) -> bool {
+        !key.is_empty()
+            && Self::encode_key(key).len() <= MAX_KEY_LENGTH
+            && !key.chars().any(|c| c.is_ascii_control())
+    }
+
     /// 解析 Memcached 命令
-    fn parse_command(line: &str) -> MemcachedCommand {
-        let line = line.trim();
-        let parts: Vec<&str> = line.split_whitespace().collect();
+    fn parse_command(line: &str, max_multiget_keys: usize) -> MemcachedCommand {
+        let line = line.trim_matches(Self::is_protocol_whitespace);
+        let parts: Vec<&str> = line
+            .split(Self::is_protocol_whitespace)
+            .filter(|part| !part.is_empty())
+            .collect();
 
         if parts.is_empty() {
             return MemcachedCommand::Unknown(line.to_string());
         }
 
+        // 存储类命令与 get 的键位置
+        let key_parts = match parts[0].to_lowercase().as_str() {
+            "get" | "gets" => &parts[1..],
+            "gat" => &parts[parts.len().min(2)..],
+            "set" | "add" | "replace" | "append" | "prepend" | "cas" | "delete" | "touch" | "incr" | "decr" | "streaming_get" | "sget"
+            | "set_begin" | "set_data" | "set_end" => &parts[1..parts.len().min(2)],
+            _ => &parts[..0],
+        };
+        if !key_parts.iter().all(|key| Self::is_valid_key(key)) {
+            let bytes_index = match parts[0].to_lowercase().as_str() {
+                "set" | "add" | "replace" | "append" | "prepend" | "cas" => Some(4),
+                "set_data" => Some(3),
+                _ => None,
+            };
+            let swallow_bytes = bytes_index
+                .and_then(|index| parts.get(index))
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0);
+            return MemcachedCommand::Rejected {
+                reason: "bad command line format".to_string(),
+                swallow_bytes,
+            };
+        }
+
         match parts[0].to_lowercase().as_str() {
-            "get" => {
+            command @ ("get" | "gets") => {
+                let key_count = parts.len() - 1;
+                if max_multiget_keys > 0 && key_count > max_multiget_keys {
+                    return MemcachedCommand::Rejected {
+                        reason: format!("too many keys in {}: {} (max {})", command, key_count, max_multiget_keys),
+                        swallow_bytes: 0,
+                    };
+                }
                 let keys = parts[1..].iter().map(|s| s.to_string()).collect();
-                MemcachedCommand::Get { keys }
+                if command == "gets" {
+                    MemcachedCommand::Gets { keys }
+                } else {
+                    MemcachedCommand::Get { keys }
+                }
             }
+            "gat" => {
+                let key_count = parts.len().saturating_sub(2);
+                if max_multiget_keys > 0 && key_count > max_multiget_keys {
+                    return MemcachedCommand::Rejected {
+                        reason: format!("too many keys in gat: {} (max {})", key_count, max_multiget_keys),
+                        swallow_bytes: 0,
+                    };
+                }
+                match parts.get(1).map(|s| s.parse()) {
+                    Some(Ok(exptime)) if key_count > 0 => MemcachedCommand::Gat {
+                        exptime,
+                        keys: parts[2..].iter().map(|s| s.to_string()).collect(),
+                    },
+                    _ => MemcachedCommand::Unknown(line.to_string()),
+                }
+            }
+            "touch" => match parts.get(2).map(|s| s.parse()) {
+                Some(Ok(exptime)) => MemcachedCommand::Touch {
+                    key: parts[1].to_string(),
+                    exptime,
+                },
+                Some(Err(_)) => MemcachedCommand::Rejected {
+                    reason: "invalid exptime argument".to_string(),
+                    swallow_bytes: 0,
+                },
+                None => MemcachedCommand::Unknown(line.to_string()),
+            },
             "set" => {
                 if parts.len() >= 5 {
                     let key = parts[1].to_string();
@@ -1125,6 +1784,36 @@ impl MemcachedServer {
                     MemcachedCommand::Unknown(line.to_string())
                 }
             }
+            // flags 和 exptime 按协议解析但不使用，保留原有值
+            "append" | "prepend" => {
+                if parts.len() >= 5 {
+                    let key = parts[1].to_string();
+                    let bytes = parts[4].parse().unwrap_or(0);
+                    if parts[0].eq_ignore_ascii_case("append") {
+                        MemcachedCommand::Append { key, bytes, data: None }
+                    } else {
+                        MemcachedCommand::Prepend { key, bytes, data: None }
+                    }
+                } else {
+                    MemcachedCommand::Unknown(line.to_string())
+                }
+            }
+            "cas" => {
+                match (parts.len() >= 6, parts.get(5).map(|s| s.parse())) {
+                    (true, Some(Ok(cas_unique))) => MemcachedCommand::Cas {
+                        key: parts[1].to_string(),
+                        flags: parts[2].parse().unwrap_or(0),
+                        exptime: parts[3].parse().unwrap_or(0),
+                        bytes: parts[4].parse().unwrap_or(0),
+                        cas_unique,
+                        data: None,
+                    },
+                    _ => MemcachedCommand::Rejected {
+                        reason: "bad command line format".to_string(),
+                        swallow_bytes: parts.get(4).and_then(|s| s.parse().ok()).unwrap_or(0),
+                    },
+                }
+            }
             "delete" => {
                 if parts.len() >= 2 {
                     MemcachedCommand::Delete {
@@ -1137,8 +1826,13 @@ impl MemcachedServer {
             "incr" => {
                 if parts.len() >= 3 {
                     let key = parts[1].to_string();
-                    let value = parts[2].parse().unwrap_or(1);
-                    MemcachedCommand::Incr { key, value }
+                    match parts[2].parse() {
+                        Ok(value) => MemcachedCommand::Incr { key, value },
+                        Err(_) => MemcachedCommand::Rejected {
+                            reason: "invalid numeric delta argument".to_string(),
+                            swallow_bytes: 0,
+                        },
+                    }
                 } else {
                     MemcachedCommand::Unknown(line.to_string())
                 }
@@ -1146,8 +1840,13 @@ impl MemcachedServer {
             "decr" => {
                 if parts.len() >= 3 {
                     let key = parts[1].to_string();
-                    let value = parts[2].parse().unwrap_or(1);
-                    MemcachedCommand::Decr { key, value }
+                    match parts[2].parse() {
+                        Ok(value) => MemcachedCommand::Decr { key, value },
+                        Err(_) => MemcachedCommand::Rejected {
+                            reason: "invalid numeric delta argument".to_string(),
+                            swallow_bytes: 0,
+                        },
+                    }
                 } else {
                     MemcachedCommand::Unknown(line.to_string())
                 }
@@ -1175,10 +1874,11 @@ impl MemcachedServer {
                 }
             }
             "set_data" => {
-                if parts.len() >= 3 {
+                if parts.len() >= 4 {
                     let key = parts[1].to_string();
                     let chunk_number = parts[2].parse().unwrap_or(0);
-                    MemcachedCommand::SetData { key, chunk_number, data: Bytes::new() } // 数据将在后续处理
+                    let bytes = parts[3].parse().unwrap_or(0);
+                    MemcachedCommand::SetData { key, chunk_number, bytes, data: None }
                 } else {
                     MemcachedCommand::Unknown(line.to_string())
                 }
@@ -1191,7 +1891,25 @@ impl MemcachedServer {
                     MemcachedCommand::Unknown(line.to_string())
                 }
             }
-            "stats" => MemcachedCommand::Stats,
+            "stats" => match parts.get(1) {
+                Some(&"conns") => MemcachedCommand::StatsConns,
+                Some(&"runtime") => MemcachedCommand::StatsRuntime,
+                _ => MemcachedCommand::Stats,
+            },
+            "dump" if parts.len() == 1 => MemcachedCommand::Dump,
+            "load" => match parts.get(1).map(|s| s.parse::<usize>()) {
+                Some(Ok(bytes)) if parts.len() == 2 => {
+                    if bytes > MAX_LOAD_BYTES {
+                        MemcachedCommand::Rejected {
+                            reason: format!("load block too large: {} (max {})", bytes, MAX_LOAD_BYTES),
+                            swallow_bytes: bytes,
+                        }
+                    } else {
+                        MemcachedCommand::Load { bytes, data: None }
+                    }
+                }
+                _ => MemcachedCommand::Unknown(line.to_string()),
+            },
             "flush_all" => MemcachedCommand::Flush,
             "version" => MemcachedCommand::Version,
             "quit" => MemcachedCommand::Quit,
@@ -1199,43 +1917,141 @@ impl MemcachedServer {
         }
     }
 
+    /// 执行 append / prepend，键不存在时返回 NOT_STORED
+    async fn concat_value(cache: &Arc<RatMemCache>, key: String, data: Bytes, prepend: bool) -> MemcachedResponse {
+        debug!("执行 {} 命令: {} ({} bytes)", if prepend { "PREPEND" } else { "APPEND" }, key, data.len());
+
+        let result = if prepend { cache.prepend(&key, data).await } else { cache.append(&key, data).await };
+        match result {
+            Ok(true) => MemcachedResponse::Stored,
+            Ok(false) => MemcachedResponse::NotStored,
+            Err(e) => {
+                error!("拼接失败: {}", e);
+                MemcachedResponse::ServerError(format!("设置失败: {}", e))
+            }
+        }
+    }
+
+    /// 将 incr/decr 的结果转换为响应：成功时只返回新值
+    fn counter_response(command: &str, result: CacheResult<Option<u64>>) -> MemcachedResponse {
+        match result {
+            Ok(Some(value)) => MemcachedResponse::Number(value),
+            Ok(None) => MemcachedResponse::NotFound,
+            Err(CacheError::InvalidValue { message }) => MemcachedResponse::ClientError(message),
+            Err(e) => {
+                error!("{} 失败: {}", command, e);
+                MemcachedResponse::ServerError(format!("更新计数失败: {}", e))
+            }
+        }
+    }
+
+    /// 按 exptime 换算出的 TTL 构造写入选项
+    fn ttl_options(ttl: u64) -> CacheOptions {
+        CacheOptions {
+            ttl_seconds: Some(ttl),
+            ..Default::default()
+        }
+    }
+
+    /// 执行连接上收到的命令：分块SET交给连接共享的会话表，其余命令由 [`Self::execute_command`] 执行
+    async fn dispatch_command(
+        command: MemcachedCommand,
+        cache: &Arc<RatMemCache>,
+        connections: &ConnectionRegistry,
+        chunked_sets: &ChunkedSetRegistry,
+        start_time: Instant,
+        verbose_errors: bool,
+    ) -> MemcachedResponse {
+        match command {
+            MemcachedCommand::SetBegin { key, total_size, chunk_count, flags, exptime } => {
+                chunked_sets.begin(key, total_size, chunk_count, flags, exptime).await
+            }
+            MemcachedCommand::SetData { key, chunk_number, data, .. } => {
+                chunked_sets.data(cache, key, chunk_number, data.unwrap_or_default()).await
+            }
+            MemcachedCommand::SetEnd { key } => chunked_sets.end(key).await,
+            command => Self::execute_command(command, cache, connections, start_time, verbose_errors).await,
+        }
+    }
+
     /// 执行 Memcached 命令
     async fn execute_command(
         command: MemcachedCommand,
         cache: &Arc<RatMemCache>,
+        connections: &ConnectionRegistry,
         start_time: Instant,
+        verbose_errors: bool,
     ) -> MemcachedResponse {
         match command {
             MemcachedCommand::Get { keys } => {
                 info!("执行 GET 命令: {:?}", keys);
 
-                // 获取第一个键的值（简化实现）
-                if let Some(key) = keys.first() {
-                    match cache.get(key).await {
-                        Ok(Some(data)) => {
-                            info!("GET 命中: {} ({} bytes)", key, data.len());
-                            MemcachedResponse::Value {
-                                key: key.clone(),
-                                flags: 0,
-                                bytes: data.len(),
-                                data,
-                            }
-                        }
-                        Ok(None) => {
-                            info!("GET 未命中: {}", key);
-                            MemcachedResponse::End
+                let mut items = Vec::new();
+                for key in keys {
+                    match cache.get_with_metadata(&key).await {
+                        Ok(Some(entry)) => {
+                            info!("GET 命中: {} ({} bytes)", key, entry.value.len());
+                            items.push(ValueItem {
+                                key,
+                                flags: entry.flags,
+                                data: entry.value,
+                                cas: None,
+                            });
                         }
+                        Ok(None) => info!("GET 未命中: {}", key),
                         Err(e) => {
                             error!("GET 失败: {}", e);
-                            MemcachedResponse::ServerError(format!("获取失败: {}", e))
+                            return MemcachedResponse::ServerError(format!("获取失败: {}", e));
                         }
                     }
-                } else {
-                    MemcachedResponse::End
                 }
+                MemcachedResponse::Values(items)
+            }
+            MemcachedCommand::Gets { keys } => {
+                info!("执行 GETS 命令: {:?}", keys);
+
+                let mut items = Vec::new();
+                for key in keys {
+                    match cache.gets_with_metadata(&key).await {
+                        Ok(Some((entry, cas))) => items.push(ValueItem {
+                            key,
+                            flags: entry.flags,
+                            data: entry.value,
+                            cas: Some(cas),
+                        }),
+                        Ok(None) => {}
+                        Err(e) => {
+                            error!("GETS 失败: {}", e);
+                            return MemcachedResponse::ServerError(format!("获取失败: {}", e));
+                        }
+                    }
+                }
+                MemcachedResponse::Values(items)
+            }
+            MemcachedCommand::Gat { exptime, keys } => {
+                info!("执行 GAT 命令: {:?} (TTL: {})", keys, exptime);
+
+                let ttl = if exptime > 0 { exptime as u64 } else { 0 };
+                let mut items = Vec::new();
+                for key in keys {
+                    match cache.get_entry_and_touch(&key, ttl).await {
+                        Ok(Some(entry)) => items.push(ValueItem {
+                            key,
+                            flags: entry.flags,
+                            data: entry.value,
+                            cas: None,
+                        }),
+                        Ok(None) => {}
+                        Err(e) => {
+                            error!("GAT 失败: {}", e);
+                            return MemcachedResponse::ServerError(format!("获取失败: {}", e));
+                        }
+                    }
+                }
+                MemcachedResponse::Values(items)
             }
             MemcachedCommand::Set {
-                key, exptime, data, ..
+                key, flags, exptime, data, ..
             } => {
                 if let Some(data) = data {
                     info!(
@@ -1247,7 +2063,7 @@ impl MemcachedServer {
 
                     let ttl = if exptime > 0 { exptime as u64 } else { 0 };
 
-                    match cache.set_with_ttl(key.clone(), data, ttl).await {
+                    match cache.set_with_flags(key.clone(), data, flags, &Self::ttl_options(ttl)).await {
                         Ok(_) => {
                             info!("SET 成功: {}", key);
                             MemcachedResponse::Stored
@@ -1262,7 +2078,7 @@ impl MemcachedServer {
                 }
             }
             MemcachedCommand::Add {
-                key, exptime, data, ..
+                key, flags, exptime, data, ..
             } => {
                 if let Some(data) = data {
                     debug!(
@@ -1280,7 +2096,7 @@ impl MemcachedServer {
                         }
                         Ok(None) => {
                             let ttl = if exptime > 0 { exptime as u64 } else { 0 };
-                            match cache.set_with_ttl(key.clone(), data, ttl).await {
+                            match cache.set_with_flags(key.clone(), data, flags, &Self::ttl_options(ttl)).await {
                                 Ok(_) => {
                                     debug!("ADD 成功: {}", key);
                                     MemcachedResponse::Stored
@@ -1300,8 +2116,16 @@ impl MemcachedServer {
                     MemcachedResponse::ClientError("缺少数据".to_string())
                 }
             }
+            MemcachedCommand::Append { key, data, .. } => match data {
+                Some(data) => Self::concat_value(cache, key, data, false).await,
+                None => MemcachedResponse::ClientError("缺少数据".to_string()),
+            },
+            MemcachedCommand::Prepend { key, data, .. } => match data {
+                Some(data) => Self::concat_value(cache, key, data, true).await,
+                None => MemcachedResponse::ClientError("缺少数据".to_string()),
+            },
             MemcachedCommand::Replace {
-                key, exptime, data, ..
+                key, flags, exptime, data, ..
             } => {
                 if let Some(data) = data {
                     debug!(
@@ -1315,7 +2139,7 @@ impl MemcachedServer {
                     match cache.get(&key).await {
                         Ok(Some(_)) => {
                             let ttl = if exptime > 0 { exptime as u64 } else { 0 };
-                            match cache.set_with_ttl(key.clone(), data, ttl).await {
+                            match cache.set_with_flags(key.clone(), data, flags, &Self::ttl_options(ttl)).await {
                                 Ok(_) => {
                                     debug!("REPLACE 成功: {}", key);
                                     MemcachedResponse::Stored
@@ -1339,6 +2163,38 @@ impl MemcachedServer {
                     MemcachedResponse::ClientError("缺少数据".to_string())
                 }
             }
+            MemcachedCommand::Cas {
+                key, flags, exptime, cas_unique, data, ..
+            } => {
+                let Some(data) = data else {
+                    return MemcachedResponse::ClientError("缺少数据".to_string());
+                };
+                debug!("执行 CAS 命令: {} ({} bytes, TTL: {}, CAS: {})", key, data.len(), exptime, cas_unique);
+
+                let ttl = if exptime > 0 { exptime as u64 } else { 0 };
+                match cache.cas_with_flags(&key, data, flags, cas_unique, Some(ttl)).await {
+                    Ok(CasResult::Stored) => MemcachedResponse::Stored,
+                    Ok(CasResult::Exists) => MemcachedResponse::Exists,
+                    Ok(CasResult::NotFound) => MemcachedResponse::NotFound,
+                    Err(e) => {
+                        error!("CAS 失败: {}", e);
+                        MemcachedResponse::ServerError(format!("设置失败: {}", e))
+                    }
+                }
+            }
+            MemcachedCommand::Touch { key, exptime } => {
+                debug!("执行 TOUCH 命令: {} (TTL: {})", key, exptime);
+
+                let ttl = if exptime > 0 { exptime as u64 } else { 0 };
+                match cache.touch(&key, ttl).await {
+                    Ok(true) => MemcachedResponse::Touched,
+                    Ok(false) => MemcachedResponse::NotFound,
+                    Err(e) => {
+                        error!("TOUCH 失败: {}", e);
+                        MemcachedResponse::ServerError(format!("刷新 TTL 失败: {}", e))
+                    }
+                }
+            }
             MemcachedCommand::Delete { key } => {
                 debug!("执行 DELETE 命令: {}", key);
 
@@ -1359,83 +2215,11 @@ impl MemcachedServer {
             }
             MemcachedCommand::Incr { key, value } => {
                 debug!("执行 INCR 命令: {} (+{})", key, value);
-
-                // 简化实现：获取当前值，增加，然后设置
-                match cache.get(&key).await {
-                    Ok(Some(data)) => {
-                        if let Ok(current_str) = String::from_utf8(data.to_vec()) {
-                            if let Ok(current_val) = current_str.trim().parse::<u64>() {
-                                let new_val = current_val.saturating_add(value);
-                                let new_data = Bytes::from(new_val.to_string());
-
-                                match cache.set_with_ttl(key, new_data, 0).await {
-                                    Ok(_) => {
-                                        debug!("INCR 成功: {} -> {}", current_val, new_val);
-                                        MemcachedResponse::Value {
-                                            key: "".to_string(),
-                                            flags: 0,
-                                            bytes: new_val.to_string().len(),
-                                            data: Bytes::from(new_val.to_string()),
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!("INCR 设置失败: {}", e);
-                                        MemcachedResponse::ServerError(format!("增加失败: {}", e))
-                                    }
-                                }
-                            } else {
-                                MemcachedResponse::ClientError("值不是数字".to_string())
-                            }
-                        } else {
-                            MemcachedResponse::ClientError("值不是有效字符串".to_string())
-                        }
-                    }
-                    Ok(None) => MemcachedResponse::NotFound,
-                    Err(e) => {
-                        error!("INCR 获取失败: {}", e);
-                        MemcachedResponse::ServerError(format!("获取失败: {}", e))
-                    }
-                }
+                Self::counter_response("INCR", cache.incr(&key, value).await)
             }
             MemcachedCommand::Decr { key, value } => {
                 debug!("执行 DECR 命令: {} (-{})", key, value);
-
-                // 简化实现：获取当前值，减少，然后设置
-                match cache.get(&key).await {
-                    Ok(Some(data)) => {
-                        if let Ok(current_str) = String::from_utf8(data.to_vec()) {
-                            if let Ok(current_val) = current_str.trim().parse::<u64>() {
-                                let new_val = current_val.saturating_sub(value);
-                                let new_data = Bytes::from(new_val.to_string());
-
-                                match cache.set_with_ttl(key, new_data, 0).await {
-                                    Ok(_) => {
-                                        debug!("DECR 成功: {} -> {}", current_val, new_val);
-                                        MemcachedResponse::Value {
-                                            key: "".to_string(),
-                                            flags: 0,
-                                            bytes: new_val.to_string().len(),
-                                            data: Bytes::from(new_val.to_string()),
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!("DECR 设置失败: {}", e);
-                                        MemcachedResponse::ServerError(format!("减少失败: {}", e))
-                                    }
-                                }
-                            } else {
-                                MemcachedResponse::ClientError("值不是数字".to_string())
-                            }
-                        } else {
-                            MemcachedResponse::ClientError("值不是有效字符串".to_string())
-                        }
-                    }
-                    Ok(None) => MemcachedResponse::NotFound,
-                    Err(e) => {
-                        error!("DECR 获取失败: {}", e);
-                        MemcachedResponse::ServerError(format!("获取失败: {}", e))
-                    }
-                }
+                Self::counter_response("DECR", cache.decr(&key, value).await)
             }
             MemcachedCommand::Stats => {
                 debug!("执行 STATS 命令");
@@ -1451,24 +2235,77 @@ impl MemcachedServer {
                 stats_map.insert("pointer_size".to_string(), "64".to_string());
                 stats_map.insert("rusage_user".to_string(), "0.0".to_string());
                 stats_map.insert("rusage_system".to_string(), "0.0".to_string());
-                stats_map.insert("curr_items".to_string(), "0".to_string());
-                stats_map.insert("total_items".to_string(), "0".to_string());
-                stats_map.insert("bytes".to_string(), "0".to_string());
-                stats_map.insert("curr_connections".to_string(), "1".to_string());
-                stats_map.insert("total_connections".to_string(), "1".to_string());
-                stats_map.insert("connection_structures".to_string(), "1".to_string());
-                stats_map.insert("cmd_get".to_string(), "0".to_string());
-                stats_map.insert("cmd_set".to_string(), "0".to_string());
-                stats_map.insert("get_hits".to_string(), "0".to_string());
-                stats_map.insert("get_misses".to_string(), "0".to_string());
-                stats_map.insert("evictions".to_string(), "0".to_string());
+
+                // 缓存计数器来自指标快照
+                let metrics = cache.metrics_snapshot().await;
+                // 同一个键可能同时存在于两层，取较大值作为条目数下界
+                let l2_entries = metrics.l2.as_ref().map_or(0, |l2| l2.entries);
+                stats_map.insert("curr_items".to_string(), (metrics.l1.entries as u64).max(l2_entries).to_string());
+                stats_map.insert("total_items".to_string(), metrics.operations.set.count.to_string());
+                stats_map.insert("bytes".to_string(), metrics.l1.memory_bytes.to_string());
+                stats_map.insert("curr_connections".to_string(), connections.active().to_string());
+                stats_map.insert("total_connections".to_string(), connections.total().to_string());
+                stats_map.insert("connection_structures".to_string(), connections.active().to_string());
+                stats_map.insert("cmd_get".to_string(), metrics.operations.get.count.to_string());
+                stats_map.insert("cmd_set".to_string(), metrics.operations.set.count.to_string());
+                stats_map.insert("get_hits".to_string(), metrics.hits.to_string());
+                stats_map.insert("get_misses".to_string(), metrics.misses.to_string());
+                stats_map.insert("evictions".to_string(), metrics.l1.evictions.to_string());
                 stats_map.insert("bytes_read".to_string(), "0".to_string());
                 stats_map.insert("bytes_written".to_string(), "0".to_string());
-                stats_map.insert("limit_maxbytes".to_string(), "67108864".to_string());
+                stats_map.insert("limit_maxbytes".to_string(), metrics.l1.max_memory_bytes.to_string());
                 stats_map.insert("threads".to_string(), "4".to_string());
 
                 MemcachedResponse::Stats(stats_map)
             }
+            MemcachedCommand::StatsConns => {
+                debug!("执行 STATS CONNS 命令");
+                MemcachedResponse::StatLines(connections.conn_stats(MAX_STATS_CONNS))
+            }
+            MemcachedCommand::StatsRuntime => {
+                debug!("执行 STATS RUNTIME 命令");
+                let info = cache.runtime_info();
+                MemcachedResponse::StatLines(vec![
+                    ("version".to_string(), info.version.to_string()),
+                    ("features".to_string(), info.features.join(",")),
+                    ("allocator".to_string(), info.allocator.to_string()),
+                    ("compression_algorithm".to_string(), format!("{:?}", info.compression_algorithm)),
+                    ("l2_enabled".to_string(), u8::from(info.l2_enabled).to_string()),
+                    ("l2_healthy".to_string(), u8::from(info.l2_healthy).to_string()),
+                    ("uptime".to_string(), info.uptime_secs.to_string()),
+                ])
+            }
+            MemcachedCommand::Dump => {
+                // 连接处理中直接调用 stream_dump，不会走到这里
+                MemcachedResponse::ServerError("dump must be streamed".to_string())
+            }
+            MemcachedCommand::Load { data, .. } => {
+                let Some(data) = data else {
+                    return MemcachedResponse::ClientError("缺少数据".to_string());
+                };
+                let entries = match DumpCodec::decode_entries(&data) {
+                    Ok(entries) => entries,
+                    Err(e) => return MemcachedResponse::ClientError(format!("bad load data: {}", e)),
+                };
+                info!("执行 LOAD 命令: {} 个条目 ({} bytes)", entries.len(), data.len());
+
+                // 先校验全部键，数据块有误时不写入任何条目
+                let keys: Vec<String> = entries.iter().map(|entry| Self::decode_command_line(&entry.key)).collect();
+                if let Some(key) = keys.iter().find(|key| !Self::is_valid_key(key)) {
+                    return MemcachedResponse::ClientError(format!("invalid key in load data: {}", key));
+                }
+
+                let mut loaded = 0;
+                for (key, entry) in keys.into_iter().zip(entries) {
+                    let options = Self::ttl_options(entry.ttl_seconds);
+                    if let Err(e) = cache.set_with_flags(key, entry.value, entry.flags, &options).await {
+                        error!("LOAD 失败: {}", e);
+                        return MemcachedResponse::ServerError(format!("导入失败（已导入 {} 个条目）: {}", loaded, e));
+                    }
+                    loaded += 1;
+                }
+                MemcachedResponse::Loaded(loaded)
+            }
             MemcachedCommand::Flush => {
                 debug!("执行 FLUSH_ALL 命令");
 
@@ -1519,30 +2356,152 @@ impl MemcachedServer {
                     }
                 }
             }
-            MemcachedCommand::SetBegin { key, total_size, chunk_count, flags, exptime } => {
-                info!("执行SET开始命令: {} (total: {} bytes, chunks: {})", key, total_size, chunk_count);
-                // 初始化流式SET操作
-                // 这里需要在服务器中维护状态，暂时简化处理
-                MemcachedResponse::Stored
+            MemcachedCommand::SetBegin { .. } | MemcachedCommand::SetData { .. } | MemcachedCommand::SetEnd { .. } => {
+                // 分块SET需要连接共享的会话表，由 dispatch_command 处理
+                MemcachedResponse::ServerError("chunked set requires a connection".to_string())
             }
-            MemcachedCommand::SetData { key, chunk_number, data } => {
-                info!("执行SET数据命令: {} (chunk: {}, size: {} bytes)", key, chunk_number, data.len());
-                // 处理数据块
-                MemcachedResponse::Stored
-            }
-            MemcachedCommand::SetEnd { key } => {
-                info!("执行SET结束命令: {}", key);
-                // 完成流式SET操作
-                MemcachedResponse::Stored
+            MemcachedCommand::Rejected { reason, .. } => {
+                warn!("拒绝命令: {}", reason);
+                MemcachedResponse::ClientError(reason)
             }
             MemcachedCommand::Unknown(cmd) => {
                 warn!("未知命令: {}", cmd);
-                MemcachedResponse::Error("未知命令".to_string())
+                // 标准 Memcached 对未知命令只返回 ERROR，详细模式附带出错的命令
+                if verbose_errors {
+                    MemcachedResponse::Error(format!("unknown command: {}", cmd))
+                } else {
+                    MemcachedResponse::Error(String::new())
+                }
             }
         }
     }
 }
 
+/// 内置基准测试参数
+#[derive(Debug, Clone)]
+struct BenchConfig {
+    /// 键空间大小
+    key_count: usize,
+    /// 值大小（字节）
+    value_size: usize,
+    /// 读操作占比（0-1）
+    read_ratio: f64,
+    /// 持续时间
+    duration: Duration,
+    /// 并发任务数
+    concurrency: usize,
+}
+
+/// 内置基准测试结果
+#[derive(Debug, Default)]
+struct BenchReport {
+    reads: u64,
+    hits: u64,
+    writes: u64,
+    elapsed: Duration,
+    /// 已排序的单次操作延迟（纳秒）
+    latencies_ns: Vec<u64>,
+}
+
+impl BenchReport {
+    fn total_ops(&self) -> u64 {
+        self.reads + self.writes
+    }
+
+    fn ops_per_sec(&self) -> f64 {
+        self.total_ops() as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn hit_rate(&self) -> f64 {
+        if self.reads == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.reads as f64
+        }
+    }
+
+    /// 延迟百分位（p 取 0-100）
+    fn percentile(&self, p: f64) -> Duration {
+        if self.latencies_ns.is_empty() {
+            return Duration::ZERO;
+        }
+        let index = ((self.latencies_ns.len() - 1) as f64 * p / 100.0).round() as usize;
+        Duration::from_nanos(self.latencies_ns[index])
+    }
+}
+
+/// 直接对缓存实例运行负载（不经过 TCP），预先写入全部键后按读写比例随机访问
+async fn run_benchmark(cache: Arc<RatMemCache>, config: &BenchConfig) -> CacheResult<BenchReport> {
+    let value = Bytes::from(vec![b'x'; config.value_size]);
+    for i in 0..config.key_count {
+        cache.set(format!("bench:{}", i), value.clone()).await?;
+    }
+
+    let start = Instant::now();
+    let deadline = start + config.duration;
+    let mut handles = Vec::with_capacity(config.concurrency);
+    for _ in 0..config.concurrency {
+        let cache = Arc::clone(&cache);
+        let value = value.clone();
+        let config = config.clone();
+        handles.push(tokio::spawn(async move {
+            let mut report = BenchReport::default();
+            while Instant::now() < deadline {
+                let key = format!("bench:{}", fastrand::usize(..config.key_count));
+                let op_start = Instant::now();
+                if fastrand::f64() < config.read_ratio {
+                    report.reads += 1;
+                    if cache.get(&key).await?.is_some() {
+                        report.hits += 1;
+                    }
+                } else {
+                    report.writes += 1;
+                    cache.set(key, value.clone()).await?;
+                }
+                report.latencies_ns.push(op_start.elapsed().as_nanos() as u64);
+            }
+            Ok::<_, CacheError>(report)
+        }));
+    }
+
+    let mut report = BenchReport::default();
+    for handle in handles {
+        let task_report = handle
+            .await
+            .map_err(|e| CacheError::io_error(format!("基准测试任务异常退出: {}", e)))??;
+        report.reads += task_report.reads;
+        report.hits += task_report.hits;
+        report.writes += task_report.writes;
+        report.latencies_ns.extend(task_report.latencies_ns);
+    }
+    report.elapsed = start.elapsed();
+    report.latencies_ns.sort_unstable();
+    Ok(report)
+}
+
+/// 加载缓存配置并运行内置基准测试
+async fn run_bench_mode(server_config: &ServerConfig, bench_config: &BenchConfig) -> CacheResult<()> {
+    let cache_config = MemcachedServer::load_cache_config(server_config).await?;
+    let cache = Arc::new(RatMemCache::new(cache_config).await?);
+
+    println!("🧪 基准测试: {} 个键, 值 {} 字节, 读比例 {:.0}%, {} 个并发任务, 持续 {:?}",
+        bench_config.key_count,
+        bench_config.value_size,
+        bench_config.read_ratio * 100.0,
+        bench_config.concurrency,
+        bench_config.duration);
+
+    let report = run_benchmark(Arc::clone(&cache), bench_config).await?;
+
+    println!("📈 基准测试结果:");
+    println!("  - 总操作数: {} (读 {}, 写 {})", report.total_ops(), report.reads, report.writes);
+    println!("  - 吞吐量: {:.0} ops/s", report.ops_per_sec());
+    println!("  - 延迟: p50 {:?}, p99 {:?}", report.percentile(50.0), report.percentile(99.0));
+    println!("  - 命中率: {:.2}%", report.hit_rate() * 100.0);
+
+    cache.shutdown().await
+}
+
 /// 加载服务器配置
 fn load_server_config() -> Result<ServerConfig, Box<dyn std::error::Error>> {
     // 尝试从配置文件加载
@@ -1573,6 +2532,113 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .value_name("FILE")
                 .help("缓存配置文件路径"),
         )
+        .arg(
+            Arg::new("bind-retries")
+                .long("bind-retries")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(u32))
+                .help("绑定地址被占用时的重试次数 (默认: 0)"),
+        )
+        .arg(
+            Arg::new("bind-retry-delay")
+                .long("bind-retry-delay")
+                .value_name("MS")
+                .value_parser(clap::value_parser!(u64))
+                .help("绑定重试的初始等待时间，毫秒 (默认: 500)"),
+        )
+        .arg(
+            Arg::new("max-multiget-keys")
+                .long("max-multiget-keys")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(usize))
+                .help("单条 get 命令允许的最大键数量，0 表示不限制 (默认: 1024)"),
+        )
+        .arg(
+            Arg::new("max-item-size")
+                .long("max-item-size")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(usize))
+                .help("分块 SET 允许声明的最大值大小，0 表示不限制，字节 (默认: 67108864)"),
+        )
+        .arg(
+            Arg::new("accept-backoff-max")
+                .long("accept-backoff-max")
+                .value_name("MS")
+                .value_parser(clap::value_parser!(u64))
+                .help("接受连接失败时退避等待的上限，毫秒 (默认: 5000)"),
+        )
+        .arg(
+            Arg::new("accept-error-threshold")
+                .long("accept-error-threshold")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(u32))
+                .help("连续接受连接失败达到该次数时记录严重错误，0 表示不检查 (默认: 100)"),
+        )
+        .arg(
+            Arg::new("shutdown-on-accept-errors")
+                .long("shutdown-on-accept-errors")
+                .action(ArgAction::SetTrue)
+                .help("连续接受连接失败达到阈值时停止服务器"),
+        )
+        .arg(
+            Arg::new("command-assembly-timeout")
+                .long("command-assembly-timeout")
+                .value_name("MS")
+                .value_parser(clap::value_parser!(u64))
+                .help("存储命令等待数据块的最长时间，超时关闭连接，0 表示不限制，毫秒 (默认: 10000)"),
+        )
+        .arg(
+            Arg::new("verbose-errors")
+                .long("verbose-errors")
+                .action(ArgAction::SetTrue)
+                .help("未知命令的 ERROR 响应附带出错的命令，便于调试客户端"),
+        )
+        .arg(
+            Arg::new("bench")
+                .long("bench")
+                .action(ArgAction::SetTrue)
+                .help("运行内置基准测试（直接访问缓存实例，不启动监听）"),
+        )
+        .arg(
+            Arg::new("bench-keys")
+                .long("bench-keys")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("10000")
+                .help("基准测试的键数量"),
+        )
+        .arg(
+            Arg::new("bench-value-size")
+                .long("bench-value-size")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("100")
+                .help("基准测试的值大小，字节"),
+        )
+        .arg(
+            Arg::new("bench-read-ratio")
+                .long("bench-read-ratio")
+                .value_name("RATIO")
+                .value_parser(clap::value_parser!(f64))
+                .default_value("0.9")
+                .help("基准测试的读操作占比 (0-1)"),
+        )
+        .arg(
+            Arg::new("bench-duration")
+                .long("bench-duration")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("10")
+                .help("基准测试持续时间，秒"),
+        )
+        .arg(
+            Arg::new("bench-concurrency")
+                .long("bench-concurrency")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("4")
+                .help("基准测试的并发任务数"),
+        )
         .get_matches();
 
     // 启动前的美观输出
@@ -1586,15 +2652,50 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut config = ServerConfig {
         bind_addr: matches.get_one::<String>("bind").unwrap().clone(),
         cache_config_path: matches.get_one::<String>("config").map(|s| s.clone()),
+        ..Default::default()
     };
 
-    // 如果没有指定配置文件，尝试从默认配置文件加载
-    if config.cache_config_path.is_none() {
-        if let Ok(file_config) = load_server_config() {
-            if file_config.cache_config_path.is_some() {
-                config.cache_config_path = file_config.cache_config_path;
-            }
+    // 从默认配置文件补充未在命令行中指定的选项
+    if let Ok(file_config) = load_server_config() {
+        if config.cache_config_path.is_none() && file_config.cache_config_path.is_some() {
+            config.cache_config_path = file_config.cache_config_path;
         }
+        config.bind_retries = file_config.bind_retries;
+        config.bind_retry_delay_ms = file_config.bind_retry_delay_ms;
+        config.max_multiget_keys = file_config.max_multiget_keys;
+        config.max_item_size = file_config.max_item_size;
+        config.accept_backoff_max_ms = file_config.accept_backoff_max_ms;
+        config.accept_error_threshold = file_config.accept_error_threshold;
+        config.shutdown_on_accept_errors = file_config.shutdown_on_accept_errors;
+        config.command_assembly_timeout_ms = file_config.command_assembly_timeout_ms;
+        config.verbose_errors = file_config.verbose_errors;
+    }
+    if let Some(retries) = matches.get_one::<u32>("bind-retries") {
+        config.bind_retries = *retries;
+    }
+    if let Some(delay_ms) = matches.get_one::<u64>("bind-retry-delay") {
+        config.bind_retry_delay_ms = *delay_ms;
+    }
+    if let Some(max_keys) = matches.get_one::<usize>("max-multiget-keys") {
+        config.max_multiget_keys = *max_keys;
+    }
+    if let Some(max_size) = matches.get_one::<usize>("max-item-size") {
+        config.max_item_size = *max_size;
+    }
+    if let Some(max_ms) = matches.get_one::<u64>("accept-backoff-max") {
+        config.accept_backoff_max_ms = *max_ms;
+    }
+    if let Some(threshold) = matches.get_one::<u32>("accept-error-threshold") {
+        config.accept_error_threshold = *threshold;
+    }
+    if matches.get_flag("shutdown-on-accept-errors") {
+        config.shutdown_on_accept_errors = true;
+    }
+    if let Some(timeout_ms) = matches.get_one::<u64>("command-assembly-timeout") {
+        config.command_assembly_timeout_ms = *timeout_ms;
+    }
+    if matches.get_flag("verbose-errors") {
+        config.verbose_errors = true;
     }
 
     println!("⚙️ 服务器配置:");
@@ -1603,8 +2704,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("  - 配置文件: {}", config_path);
     }
 
+    if config.bind_retries > 0 {
+        println!("  - 绑定重试: {} 次 (初始间隔 {}ms)", config.bind_retries, config.bind_retry_delay_ms);
+    }
+
+    if matches.get_flag("bench") {
+        let bench_config = BenchConfig {
+            key_count: *matches.get_one::<usize>("bench-keys").unwrap(),
+            value_size: *matches.get_one::<usize>("bench-value-size").unwrap(),
+            read_ratio: *matches.get_one::<f64>("bench-read-ratio").unwrap(),
+            duration: Duration::from_secs(*matches.get_one::<u64>("bench-duration").unwrap()),
+            concurrency: *matches.get_one::<usize>("bench-concurrency").unwrap(),
+        };
+        if bench_config.key_count == 0 || bench_config.concurrency == 0
+            || !(0.0..=1.0).contains(&bench_config.read_ratio)
+        {
+            return Err("基准测试参数无效：键数量和并发任务数必须大于 0，读比例必须在 0-1 之间".into());
+        }
+        run_bench_mode(&config, &bench_config).await?;
+        return Ok(());
+    }
+
     // 创建并启动服务器
-    let server = Arc::new(MemcachedServer::new(config).await?);
+    let server = match MemcachedServer::new(config).await {
+        Ok(server) => Arc::new(server),
+        Err(CacheError::IoError { source }) if source.kind() == std::io::ErrorKind::AddrInUse => {
+            eprintln!("❌ 绑定地址已被占用，重试已耗尽: {}", source);
+            std::process::exit(EXIT_CODE_ADDR_IN_USE);
+        }
+        Err(e) => return Err(e.into()),
+    };
 
     // 启动后的日志使用 rat_logger
     info!("✅ 服务器创建成功，开始监听...");
@@ -1644,3 +2773,522 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rat_memcache::RatMemCacheBuilder;
+    use rat_memcache::config::{L1Config, L2Config, PerformanceConfig, TtlConfig};
+    use rat_memcache::types::EvictionStrategy;
+
+    async fn create_test_cache() -> Arc<RatMemCache> {
+        let cache = RatMemCacheBuilder::new()
+            .l1_config(L1Config {
+                max_memory: 16 * 1024 * 1024,
+                max_entries: 1000,
+                eviction_strategy: EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
+            })
+            .l2_config(L2Config {
+                enable_l2_cache: false,
+                ..Default::default()
+            })
+            .ttl_config(TtlConfig {
+                expire_seconds: None,
+                cleanup_interval: 60,
+                max_cleanup_entries: 100,
+                lazy_expiration: true,
+                active_expiration: false,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
+            })
+            .performance_config(PerformanceConfig {
+                worker_threads: 2,
+                enable_concurrency: true,
+                read_write_separation: false,
+                batch_size: 100,
+                enable_warmup: false,
+                large_value_threshold: 10240,
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 0,
+                max_tagged_keys: 100_000,
+            })
+            .build()
+            .await
+            .unwrap();
+        Arc::new(cache)
+    }
+
+    #[tokio::test]
+    async fn test_run_benchmark() {
+        let cache = create_test_cache().await;
+        let config = BenchConfig {
+            key_count: 100,
+            value_size: 32,
+            read_ratio: 1.0,
+            duration: Duration::from_millis(200),
+            concurrency: 2,
+        };
+
+        let report = run_benchmark(cache, &config).await.unwrap();
+        assert!(report.total_ops() > 0);
+        assert_eq!(report.writes, 0);
+        // 预先写入全部键，只读负载全部命中
+        assert_eq!(report.hit_rate(), 1.0);
+        assert!(report.percentile(50.0) <= report.percentile(99.0));
+    }
+
+    #[tokio::test]
+    async fn test_high_byte_key_round_trip() {
+        let cache = create_test_cache().await;
+        let start_time = Instant::now();
+        let raw_key: &[u8] = b"key\xff\x80\xc3";
+
+        let mut set_line = b"set ".to_vec();
+        set_line.extend_from_slice(raw_key);
+        set_line.extend_from_slice(b" 0 0 5");
+        let mut command = MemcachedServer::parse_command(&MemcachedServer::decode_command_line(&set_line), 0);
+        match &mut command {
+            MemcachedCommand::Set { data, .. } => *data = Some(Bytes::from_static(b"hello")),
+            other => panic!("unexpected command: {:?}", other),
+        }
+        let response = MemcachedServer::execute_command(command, &cache, &ConnectionRegistry::default(), start_time, false).await;
+        assert!(matches!(response, MemcachedResponse::Stored));
+
+        let mut get_line = b"get ".to_vec();
+        get_line.extend_from_slice(raw_key);
+        let command = MemcachedServer::parse_command(&MemcachedServer::decode_command_line(&get_line), 0);
+        let response = MemcachedServer::execute_command(command, &cache, &ConnectionRegistry::default(), start_time, false).await;
+
+        let mut expected = b"VALUE ".to_vec();
+        expected.extend_from_slice(raw_key);
+        expected.extend_from_slice(b" 0 5\r\nhello\r\nEND\r\n");
+        assert_eq!(MemcachedServer::format_response(response), expected);
+    }
+
+    #[tokio::test]
+    async fn test_gat_command() {
+        let cache = create_test_cache().await;
+        let start_time = Instant::now();
+        cache.set_with_ttl("session", Bytes::from_static(b"hello"), 10).await.unwrap();
+
+        let command = MemcachedServer::parse_command("gat 300 session", 0);
+        let response = MemcachedServer::execute_command(command, &cache, &ConnectionRegistry::default(), start_time, false).await;
+        assert_eq!(MemcachedServer::format_response(response), b"VALUE session 0 5\r\nhello\r\nEND\r\n".to_vec());
+        assert!(cache.get_ttl("session").await.unwrap() > 10);
+
+        let command = MemcachedServer::parse_command("gat 300 missing", 0);
+        let response = MemcachedServer::execute_command(command, &cache, &ConnectionRegistry::default(), start_time, false).await;
+        assert_eq!(MemcachedServer::format_response(response), b"END\r\n".to_vec());
+        assert!(matches!(MemcachedServer::parse_command("gat session", 0), MemcachedCommand::Unknown(_)));
+    }
+
+    #[tokio::test]
+    async fn test_touch_command() {
+        let cache = create_test_cache().await;
+        let connections = ConnectionRegistry::default();
+        let run = |line: &'static str| {
+            let command = MemcachedServer::parse_command(line, 0);
+            let cache = Arc::clone(&cache);
+            let connections = &connections;
+            async move { MemcachedServer::format_response(MemcachedServer::execute_command(command, &cache, connections, Instant::now(), false).await) }
+        };
+
+        cache.set_with_ttl("session", Bytes::from_static(b"hello"), 10).await.unwrap();
+        assert_eq!(run("touch session 300").await, b"TOUCHED\r\n".to_vec());
+        assert!(cache.get_ttl("session").await.unwrap() > 10);
+        assert_eq!(run("touch missing 300").await, b"NOT_FOUND\r\n".to_vec());
+        assert_eq!(run("touch session soon").await, b"CLIENT_ERROR invalid exptime argument\r\n".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_multi_key_get() {
+        let cache = create_test_cache().await;
+        cache.set("a", Bytes::from_static(b"1")).await.unwrap();
+        cache.set_with_flags("b", Bytes::from_static(b"22"), 5, &MemcachedServer::ttl_options(0)).await.unwrap();
+        cache.set("c", Bytes::from_static(b"333")).await.unwrap();
+
+        let command = MemcachedServer::parse_command("get a missing b c", 0);
+        let response = MemcachedServer::execute_command(command, &cache, &ConnectionRegistry::default(), Instant::now(), false).await;
+        assert_eq!(
+            MemcachedServer::format_response(response),
+            b"VALUE a 0 1\r\n1\r\nVALUE b 5 2\r\n22\r\nVALUE c 0 3\r\n333\r\nEND\r\n".to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_incr_decr_commands() {
+        let cache = create_test_cache().await;
+        let connections = ConnectionRegistry::default();
+        let run = |line: &'static str| {
+            let command = MemcachedServer::parse_command(line, 0);
+            let cache = Arc::clone(&cache);
+            let connections = &connections;
+            async move { MemcachedServer::format_response(MemcachedServer::execute_command(command, &cache, connections, Instant::now(), false).await) }
+        };
+
+        assert_eq!(run("incr counter 1").await, b"NOT_FOUND\r\n".to_vec());
+        cache.set("counter", Bytes::from_static(b"10")).await.unwrap();
+        assert_eq!(run("incr counter 5").await, b"15\r\n".to_vec());
+        assert_eq!(run("decr counter 100").await, b"0\r\n".to_vec());
+        assert_eq!(run("incr counter abc").await, b"CLIENT_ERROR invalid numeric delta argument\r\n".to_vec());
+
+        cache.set("text", Bytes::from_static(b"hello")).await.unwrap();
+        assert_eq!(run("incr text 1").await, b"CLIENT_ERROR cannot increment or decrement non-numeric value\r\n".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_append_prepend_commands() {
+        let cache = create_test_cache().await;
+        let connections = ConnectionRegistry::default();
+        let run = |line: &'static str, data: &'static [u8]| {
+            let mut command = MemcachedServer::parse_command(line, 0);
+            match &mut command {
+                MemcachedCommand::Append { data: d, .. } | MemcachedCommand::Prepend { data: d, .. } => *d = Some(Bytes::from_static(data)),
+                other => panic!("unexpected command: {:?}", other),
+            }
+            let cache = Arc::clone(&cache);
+            let connections = &connections;
+            async move { MemcachedServer::format_response(MemcachedServer::execute_command(command, &cache, connections, Instant::now(), false).await) }
+        };
+
+        assert_eq!(run("append log 0 0 3", b"abc").await, b"NOT_STORED\r\n".to_vec());
+        cache.set_with_flags("log", Bytes::from_static(b"mid"), 9, &MemcachedServer::ttl_options(300)).await.unwrap();
+        assert_eq!(run("append log 1 0 4", b"-end").await, b"STORED\r\n".to_vec());
+        assert_eq!(run("prepend log 2 0 6", b"start-").await, b"STORED\r\n".to_vec());
+
+        let entry = cache.get_with_metadata("log").await.unwrap().unwrap();
+        assert_eq!(entry.value, Bytes::from_static(b"start-mid-end"));
+        assert_eq!(entry.flags, 9);
+        assert!(entry.ttl_seconds.unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_dump_load_commands() {
+        let source = create_test_cache().await;
+        let target = create_test_cache().await;
+        let raw_key = MemcachedServer::decode_command_line(b"key\xff\x80");
+        source.set_with_flags("session", Bytes::from_static(b"hello"), 7, &MemcachedServer::ttl_options(300)).await.unwrap();
+        source.set(raw_key.as_str(), Bytes::from_static(b"raw")).await.unwrap();
+        for i in 0..DUMP_BATCH_MAX_ENTRIES {
+            source.set(format!("bulk_{}", i), Bytes::from(format!("value_{}", i))).await.unwrap();
+        }
+
+        let mut output = Vec::new();
+        let written = MemcachedServer::stream_dump(&mut output, &source).await.unwrap();
+        assert_eq!(written, output.len());
+
+        // 每个批次的数据块原样作为 load 的数据块导入目标
+        let mut pos = 0;
+        let mut batches = 0;
+        loop {
+            let line_end = pos + output[pos..].windows(2).position(|w| w == b"\r\n").unwrap();
+            let line = String::from_utf8(output[pos..line_end].to_vec()).unwrap();
+            pos = line_end + 2;
+            let Some((count, bytes)) = DumpCodec::parse_batch_header(&line) else {
+                assert_eq!(line, format!("DUMP_END {}", DUMP_BATCH_MAX_ENTRIES + 2));
+                break;
+            };
+            let mut command = MemcachedServer::parse_command(&format!("load {}", bytes), 0);
+            match &mut command {
+                MemcachedCommand::Load { data, .. } => *data = Some(Bytes::copy_from_slice(&output[pos..pos + bytes])),
+                other => panic!("unexpected command: {:?}", other),
+            }
+            assert_eq!(&output[pos + bytes..pos + bytes + 2], b"\r\n");
+            pos += bytes + 2;
+            let response = MemcachedServer::execute_command(command, &target, &ConnectionRegistry::default(), Instant::now(), false).await;
+            assert_eq!(MemcachedServer::format_response(response), format!("LOADED {}\r\n", count).into_bytes());
+            batches += 1;
+        }
+        assert_eq!(pos, output.len());
+        assert_eq!(batches, 2);
+
+        let entry = target.get_with_metadata("session").await.unwrap().unwrap();
+        assert_eq!((entry.value, entry.flags), (Bytes::from_static(b"hello"), 7));
+        assert!(entry.ttl_seconds.is_some_and(|ttl| ttl > 0 && ttl <= 300));
+        let entry = target.get_with_metadata(raw_key.as_str()).await.unwrap().unwrap();
+        assert_eq!((entry.value, entry.ttl_seconds), (Bytes::from_static(b"raw"), None));
+        assert_eq!(target.len().await.unwrap(), DUMP_BATCH_MAX_ENTRIES + 2);
+
+        // 截断的数据块整体拒绝
+        let mut command = MemcachedServer::parse_command("load 3", 0);
+        if let MemcachedCommand::Load { data, .. } = &mut command {
+            *data = Some(Bytes::from_static(b"\x00\x05a"));
+        }
+        let response = MemcachedServer::execute_command(command, &target, &ConnectionRegistry::default(), Instant::now(), false).await;
+        assert!(matches!(response, MemcachedResponse::ClientError(_)));
+        assert!(matches!(
+            MemcachedServer::parse_command(&format!("load {}", MAX_LOAD_BYTES + 1), 0),
+            MemcachedCommand::Rejected { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_gets_cas_commands() {
+        let cache = create_test_cache().await;
+        let connections = ConnectionRegistry::default();
+        let execute = |command| MemcachedServer::execute_command(command, &cache, &connections, Instant::now(), false);
+        let cas_command = |token: u64, data: &'static [u8]| {
+            let mut command = MemcachedServer::parse_command(&format!("cas session 3 0 {} {}", data.len(), token), 0);
+            match &mut command {
+                MemcachedCommand::Cas { data: d, .. } => *d = Some(Bytes::from_static(data)),
+                other => panic!("unexpected command: {:?}", other),
+            }
+            command
+        };
+
+        assert_eq!(MemcachedServer::format_response(execute(cas_command(1, b"x")).await), b"NOT_FOUND\r\n".to_vec());
+        cache.set("session", Bytes::from_static(b"hello")).await.unwrap();
+
+        let response = execute(MemcachedServer::parse_command("gets session", 0)).await;
+        let token = match &response {
+            MemcachedResponse::Values(items) => items[0].cas.unwrap(),
+            other => panic!("unexpected response: {:?}", other),
+        };
+        assert_eq!(
+            MemcachedServer::format_response(response),
+            format!("VALUE session 0 5 {}\r\nhello\r\nEND\r\n", token).into_bytes()
+        );
+
+        assert_eq!(MemcachedServer::format_response(execute(cas_command(token, b"world")).await), b"STORED\r\n".to_vec());
+        assert_eq!(MemcachedServer::format_response(execute(cas_command(token, b"again")).await), b"EXISTS\r\n".to_vec());
+        let entry = cache.get_with_metadata("session").await.unwrap().unwrap();
+        assert_eq!((entry.value, entry.flags), (Bytes::from_static(b"world"), 3));
+
+        // 缺少令牌的 cas 命令被拒绝，并丢弃随后的数据块
+        assert!(matches!(
+            MemcachedServer::parse_command("cas session 0 0 5", 0),
+            MemcachedCommand::Rejected { swallow_bytes: 5, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stats_runtime_command() {
+        let cache = create_test_cache().await;
+        let command = MemcachedServer::parse_command("stats runtime", 0);
+        let response = MemcachedServer::execute_command(command, &cache, &ConnectionRegistry::default(), Instant::now(), false).await;
+        let output = String::from_utf8(MemcachedServer::format_response(response)).unwrap();
+        assert!(output.contains(&format!("STAT version {}\r\n", env!("CARGO_PKG_VERSION"))));
+        assert!(output.contains("STAT l2_enabled 0\r\n"));
+        assert!(output.ends_with("END\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_command_response() {
+        let cache = create_test_cache().await;
+        let start_time = Instant::now();
+
+        let command = MemcachedServer::parse_command("bogus arg", 0);
+        let response = MemcachedServer::execute_command(command, &cache, &ConnectionRegistry::default(), start_time, false).await;
+        assert_eq!(MemcachedServer::format_response(response), b"ERROR\r\n");
+
+        let command = MemcachedServer::parse_command("bogus arg", 0);
+        let response = MemcachedServer::execute_command(command, &cache, &ConnectionRegistry::default(), start_time, true).await;
+        assert_eq!(MemcachedServer::format_response(response), b"ERROR unknown command: bogus arg\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_command_assembly_timeout() {
+        let cache = create_test_cache().await;
+        let listener = TokioTcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, peer) = listener.accept().await.unwrap();
+            let connections = Arc::new(ConnectionRegistry::default());
+            MemcachedServer::handle_tcp_connection(stream, peer, cache, connections, Arc::default(), Instant::now(), 0, Some(Duration::from_millis(100)), false).await
+        });
+
+        // 只发送命令头，数据块迟迟不完整
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client.write_all(b"set slow 0 0 10\r\nabc").await.unwrap();
+
+        let mut response = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), client.read_to_end(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(response, b"CLIENT_ERROR data block timeout\r\n");
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_utf8_key_matches_library_key() {
+        let cache = create_test_cache().await;
+        let request = "set café 0 0 5\r\nhello\r\nget café\r\nquit\r\n";
+        let response = serve_one_connection(Arc::clone(&cache), Arc::default(), request.as_bytes()).await;
+        assert_eq!(response, "STORED\r\nVALUE café 0 5\r\nhello\r\nEND\r\nOK\r\n".as_bytes());
+
+        // 服务器写入的 UTF-8 键与库 API 使用的键相同
+        assert_eq!(cache.get("café").await.unwrap(), Some(Bytes::from_static(b"hello")));
+        assert_eq!(MemcachedServer::encode_key("café"), "café".as_bytes());
+    }
+
+    /// 在本地端口上处理一个连接：发送请求（应以 quit 结束）并读取全部响应
+    async fn serve_one_connection(cache: Arc<RatMemCache>, chunked_sets: Arc<ChunkedSetRegistry>, request: &[u8]) -> Vec<u8> {
+        let listener = TokioTcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, peer) = listener.accept().await.unwrap();
+            let connections = Arc::new(ConnectionRegistry::default());
+            MemcachedServer::handle_tcp_connection(stream, peer, cache, connections, chunked_sets, Instant::now(), 0, None, false).await
+        });
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client.write_all(request).await.unwrap();
+        let mut response = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), client.read_to_end(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        server.await.unwrap().unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_chunked_sets_rejected() {
+        let cache = create_test_cache().await;
+        let chunked_sets = Arc::new(ChunkedSetRegistry::default());
+
+        // 第一个连接开始分块SET并发送第一个数据块
+        let request = "set_begin chunked 4 2 0\r\nset_data chunked 0 2\r\nab\r\nquit\r\n";
+        let response = serve_one_connection(Arc::clone(&cache), Arc::clone(&chunked_sets), request.as_bytes()).await;
+        assert_eq!(String::from_utf8(response).unwrap(), "STORED\r\nSTORED\r\nOK\r\n");
+
+        // 第二个连接对同一键的 set_begin 被拒绝，不影响进行中的会话；会话完成后可以开始新的分块SET
+        let request = concat!(
+            "set_begin chunked 6 2 0\r\n",
+            "set_data chunked 1 2\r\ncd\r\n",
+            "set_begin chunked 2 1 0\r\n",
+            "quit\r\n",
+        );
+        let response = serve_one_connection(Arc::clone(&cache), Arc::clone(&chunked_sets), request.as_bytes()).await;
+        assert_eq!(
+            String::from_utf8(response).unwrap(),
+            "CLIENT_ERROR set already in progress\r\nSTORED\r\nSTORED\r\nOK\r\n"
+        );
+        assert_eq!(cache.get("chunked").await.unwrap(), Some(Bytes::from_static(b"abcd")));
+    }
+
+    #[tokio::test]
+    async fn test_chunked_set_size_limits() {
+        let cache = create_test_cache().await;
+        let chunked_sets = Arc::new(ChunkedSetRegistry::new(1024));
+        let request = concat!(
+            // 声明的大小超过上限时在接收数据前拒绝
+            "set_begin huge 1025 2 0\r\n",
+            "set_begin chunks 4 0 0\r\n",
+            // 虚报的总大小：累计数据超出声明时放弃会话
+            "set_begin lying 4 2 0\r\n",
+            "set_data lying 0 3\r\nabc\r\n",
+            "set_data lying 1 3\r\ndef\r\n",
+            // 数据少于声明的总大小时不存储
+            "set_begin short 4 2 0\r\n",
+            "set_data short 0 1\r\na\r\n",
+            "set_data short 1 1\r\nb\r\n",
+            "set_begin done 4 2 0 60\r\n",
+            "set_data done 0 2\r\nab\r\n",
+            "set_data done 1 2\r\ncd\r\n",
+            "quit\r\n",
+        );
+
+        let response = serve_one_connection(Arc::clone(&cache), Arc::clone(&chunked_sets), request.as_bytes()).await;
+        let expected = concat!(
+            "CLIENT_ERROR object too large for cache\r\n",
+            "CLIENT_ERROR invalid chunk count\r\n",
+            "STORED\r\nSTORED\r\nCLIENT_ERROR data exceeds total_size\r\n",
+            "STORED\r\nSTORED\r\nCLIENT_ERROR data size does not match total_size\r\n",
+            "STORED\r\nSTORED\r\nSTORED\r\n",
+            "OK\r\n",
+        );
+        assert_eq!(String::from_utf8(response).unwrap(), expected);
+
+        assert!(chunked_sets.sessions.read().await.is_empty());
+        assert_eq!(cache.get("lying").await.unwrap(), None);
+        assert_eq!(cache.get("short").await.unwrap(), None);
+        assert_eq!(cache.get("done").await.unwrap(), Some(Bytes::from_static(b"abcd")));
+    }
+
+    #[tokio::test]
+    async fn test_stats_conns() {
+        let cache = create_test_cache().await;
+        let connections = Arc::new(ConnectionRegistry::default());
+        let listener = TokioTcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = {
+            let connections = Arc::clone(&connections);
+            tokio::spawn(async move {
+                let (stream, peer) = listener.accept().await.unwrap();
+                MemcachedServer::handle_tcp_connection(stream, peer, cache, connections, Arc::default(), Instant::now(), 0, None, false).await
+            })
+        };
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let client_addr = client.local_addr().unwrap();
+        client.write_all(b"version\r\nstats conns\r\n").await.unwrap();
+
+        let mut response = Vec::new();
+        let mut buffer = [0u8; 1024];
+        while !response.ends_with(b"END\r\n") {
+            let n = tokio::time::timeout(Duration::from_secs(5), client.read(&mut buffer))
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(n > 0);
+            response.extend_from_slice(&buffer[..n]);
+        }
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.contains("STAT curr_connections 1\r\n"));
+        assert!(response.contains(&format!("STAT 1:addr {}\r\n", client_addr)));
+        assert!(response.contains("STAT 1:cmds 2\r\n"));
+
+        // 连接关闭后自动从注册表移除
+        client.write_all(b"quit\r\n").await.unwrap();
+        server.await.unwrap().unwrap();
+        assert_eq!(connections.active(), 0);
+        assert_eq!(connections.total(), 1);
+    }
+
+    #[test]
+    fn test_stats_conns_bounded() {
+        let connections = Arc::new(ConnectionRegistry::default());
+        let peer: SocketAddr = "127.0.0.1:11211".parse().unwrap();
+        let handles: Vec<_> = (0..5).map(|_| connections.register(peer)).collect();
+
+        let lines = connections.conn_stats(2);
+        assert_eq!(lines[0], ("curr_connections".to_string(), "5".to_string()));
+        assert_eq!(lines[1], ("listed_connections".to_string(), "2".to_string()));
+        assert_eq!(lines.iter().filter(|(key, _)| key.ends_with(":addr")).count(), 2);
+
+        drop(handles);
+        assert_eq!(connections.active(), 0);
+    }
+
+    #[test]
+    fn test_invalid_keys_rejected() {
+        let control_key = MemcachedServer::decode_command_line(b"get bad\x01key");
+        assert!(matches!(
+            MemcachedServer::parse_command(&control_key, 0),
+            MemcachedCommand::Rejected { swallow_bytes: 0, .. }
+        ));
+
+        let long_key = format!("set {} 0 0 3", "k".repeat(MAX_KEY_LENGTH + 1));
+        assert!(matches!(
+            MemcachedServer::parse_command(&long_key, 0),
+            MemcachedCommand::Rejected { swallow_bytes: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_accept_backoff() {
+        let mut backoff = AcceptBackoff::new(500);
+        let delays: Vec<u128> = (0..5).map(|_| backoff.next_delay().as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 500, 500]);
+        assert_eq!(backoff.consecutive_errors(), 5);
+
+        // 接受成功后从初始等待时间重新开始
+        backoff.reset();
+        assert_eq!(backoff.consecutive_errors(), 0);
+        assert_eq!(backoff.next_delay(), Duration::from_millis(100));
+    }
+}
@@ -2,20 +2,36 @@
 //!
 //! 整合 L1 内存缓存和 L2 持久化缓存，提供统一的缓存接口
 
-use crate::compression::Compressor;
+use crate::clock::{system_clock, SharedClock};
+#[cfg(feature = "melange-storage")]
+use crate::codec::{CodecChain, ValueCodec};
+use crate::compression::{CompressionAlgorithm, Compressor};
 use crate::transfer_log;
-use crate::config::{CacheConfig, CacheConfigBuilder};
+use crate::config::{CacheConfig, CacheConfigBuilder, L1SetFailurePolicy, Preset};
 use crate::error::{CacheError, CacheResult};
-use crate::l1_cache::{L1Cache, L1CacheStats};
+use crate::events::{CacheEvent, CacheEventStream, EventBroadcaster, EvictionDispatcher, EvictionEventStream, EvictionListener, EvictionReason, DEFAULT_EVICTION_EVENT_CAPACITY};
+#[cfg(feature = "melange-storage")]
+use crate::events::{L2HealthEvent, L2HealthListener};
+use crate::key_stats::{KeyEvent, KeyStats, KeyStatsTracker};
+use crate::l1_cache::{L1Cache, L1CacheStats, AccessPatternReport};
 #[cfg(feature = "melange-storage")]
-use crate::l2_cache::{L2Cache, L2CacheStats};
-use crate::ttl::TtlManager;
-use crate::types::{CacheLayer, CacheOperation};
+use crate::l2_cache::{L2Cache, L2CacheStats, ValueStream};
+use crate::tags::TagIndex;
+use crate::tasks::BackgroundTasks;
+use crate::transaction::{value_version, KeyLocks, TxOp, TxSnapshot};
+use crate::ttl::{TtlManager, TtlStats};
+use crate::types::{CacheKeyRef, CacheLayer, CacheOperation, CasResult, SetOutcome};
 use crate::cache_log;
 use bytes::Bytes;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "melange-storage")]
+use std::sync::atomic::{AtomicBool, AtomicU32};
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tokio::time::{interval, Duration};
 
 /// 双层缓存系统
@@ -28,6 +44,9 @@ pub struct RatMemCache {
     /// L2 持久化缓存（可选，仅在启用时存在）
     #[cfg(feature = "melange-storage")]
     l2_cache: Option<Arc<L2Cache>>,
+    /// L2 存储健康状态（未开启故障降级时为 None）
+    #[cfg(feature = "melange-storage")]
+    l2_health: Option<Arc<L2Health>>,
     /// 智能传输路由器（已移除）
     // transfer_router: Arc<SmartTransferRouter>,
     /// TTL 管理器
@@ -36,12 +55,35 @@ pub struct RatMemCache {
     compressor: Arc<Compressor>,
     /// 运行状态
     is_running: Arc<RwLock<bool>>,
+    /// 各类操作的计数与耗时
+    operation_metrics: Arc<OperationMetrics>,
+    /// 单键统计（未开启时为 None）
+    key_stats: Option<Arc<KeyStatsTracker>>,
+    /// 缓存启动的后台任务
+    background_tasks: Arc<BackgroundTasks>,
+    /// 键分片锁（普通写入共享，事务独占）
+    key_locks: Arc<KeyLocks>,
+    /// 标签到键的二级索引
+    tag_index: Arc<TagIndex>,
+    /// get_or_insert_with 正在加载的键，同一键只运行一个加载函数
+    loader_locks: Arc<DashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    /// 键空间事件广播器（未开启键空间事件时为 None）
+    keyspace_events: Option<Arc<EventBroadcaster<CacheEvent>>>,
+    /// 缓存创建时间
+    started_at: Instant,
 }
 
 /// 缓存构建器
 #[derive(Debug)]
 pub struct RatMemCacheBuilder {
     config_builder: CacheConfigBuilder,
+    clock: Option<SharedClock>,
+    #[cfg(feature = "melange-storage")]
+    value_codecs: Vec<Box<dyn ValueCodec>>,
+    eviction_listener: Option<EvictionListener>,
+    eviction_event_capacity: Option<usize>,
+    #[cfg(feature = "melange-storage")]
+    l2_health_listener: Option<L2HealthListener>,
 }
 
 /// 缓存操作选项
@@ -53,10 +95,513 @@ pub struct CacheOptions {
     pub force_l2: bool,
     /// 是否跳过 L1
     pub skip_l1: bool,
-    /// 是否启用压缩
+    /// 覆盖本次写入的 L2 压缩决定：`Some(false)` 不压缩，`Some(true)` 忽略大小阈值尝试压缩，
+    /// `None` 按全局配置。是否压缩及所用算法记录在条目元数据中，读取时据此解压。L1 总是存储原始数据，不受影响
     pub enable_compression: Option<bool>,
+    /// L2 读取超时，超时后按未命中处理
+    pub l2_read_timeout: Option<Duration>,
+    /// 键已过期但 L1 中仍有副本时，若 L2 读取超时则返回该过期副本（标记为 stale）而不是未命中
+    ///
+    /// 需同时设置 `l2_read_timeout`。L2 在超时前返回时仍按正常过期处理，过期副本不会被删除，
+    /// 下次读取会再次尝试 L2
+    pub serve_expired_l1_on_slow_l2: bool,
+    /// 持久写入：总是写入 L2，并在返回前同步落盘（fsync），绕过智能 flush 的批处理
+    ///
+    /// 未启用 L2 时返回配置错误
+    pub durable: bool,
+    /// 读取完成后在后台从 L2 预取到 L1 的键（如顺序访问时的后续页），规则同 [`RatMemCache::promote`]
+    pub read_ahead_keys: Vec<String>,
+    /// 本次读取命中 L2 时是否提升到 L1，覆盖默认行为（默认在未设置 `skip_l1`/`force_l2` 时提升）
+    ///
+    /// `Some(false)` 只从 L2 读取而不提升，适合一次性扫描；`Some(true)` 总是提升
+    pub promote_on_hit: Option<bool>,
+    /// 写入时为键设置的标签，可通过 [`RatMemCache::invalidate_tag`] 批量删除
+    ///
+    /// 写入会替换键原有的标签，为空时清除原有标签
+    pub tags: Vec<String>,
+}
+
+/// 双层缓存汇总统计
+#[derive(Debug, Clone, Default)]
+pub struct CacheStats {
+    /// L1 缓存统计
+    pub l1: L1CacheStats,
+    /// L2 缓存统计
+    #[cfg(feature = "melange-storage")]
+    pub l2: L2CacheStats,
+    /// 按操作类型划分的统计
+    pub operations: OperationStats,
+}
+
+/// L1、L2 与 TTL 的合并统计快照
+#[derive(Debug, Clone, Default)]
+pub struct CombinedCacheStats {
+    /// L1 缓存统计
+    pub l1: L1CacheStats,
+    /// L2 缓存统计（未启用 L2 时为 None）
+    #[cfg(feature = "melange-storage")]
+    pub l2: Option<L2CacheStats>,
+    /// TTL 管理统计
+    pub ttl: TtlStats,
+    /// 读取命中总次数（L1 与 L2 合计）
+    pub hits: u64,
+    /// 读取未命中次数（两层均未命中）
+    pub misses: u64,
+}
+
+impl CombinedCacheStats {
+    /// 两层合计的命中率（0.0 - 1.0），尚无读取时为 None
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// L1 内存整理结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct L1DefragReport {
+    /// 重建的条目数
+    pub entries: usize,
+    /// 整理前的进程常驻内存（字节），无法获取时为 None
+    pub rss_before: Option<u64>,
+    /// 整理后的进程常驻内存（字节），无法获取时为 None
+    pub rss_after: Option<u64>,
+}
+
+/// 启用 mimalloc 时强制回收空闲堆内存，将其归还给系统
+fn release_free_heap_memory() {
+    #[cfg(feature = "mimalloc-allocator")]
+    // SAFETY: mi_collect 只回收 mimalloc 内部的空闲页，不影响仍在使用的分配
+    unsafe {
+        libmimalloc_sys::mi_collect(true);
+    }
+}
+
+/// 获取当前进程的常驻内存（字节）
+fn current_rss() -> Option<u64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut system = sysinfo::System::new();
+    system.refresh_process(pid);
+    system.process(pid).map(|process| process.memory())
+}
+
+/// 按 memcached 规则解析 incr/decr 的计数值：最多 20 位十进制数字，允许末尾空白
+fn parse_counter(value: &[u8]) -> CacheResult<u64> {
+    let digits = value.trim_ascii_end();
+    if digits.is_empty() || digits.len() > 20 || !digits.iter().all(u8::is_ascii_digit) {
+        return Err(CacheError::invalid_value("cannot increment or decrement non-numeric value"));
+    }
+    std::str::from_utf8(digits)
+        .ok()
+        .and_then(|digits| digits.parse().ok())
+        .ok_or_else(|| CacheError::invalid_value("cannot increment or decrement non-numeric value"))
+}
+
+/// 单类操作的执行次数与延迟
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct OperationMetric {
+    /// 执行次数
+    pub count: u64,
+    /// 平均延迟（微秒）
+    pub avg_latency_us: f64,
+    /// P50 延迟（微秒，按 2 的幂分桶估算的桶上界）
+    pub p50_latency_us: u64,
+    /// P99 延迟（微秒，按 2 的幂分桶估算的桶上界）
+    pub p99_latency_us: u64,
+}
+
+/// 按操作类型划分的统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OperationStats {
+    pub get: OperationMetric,
+    pub set: OperationMetric,
+    pub delete: OperationMetric,
+    pub clear: OperationMetric,
+    /// touch 操作
+    pub touch: OperationMetric,
+    /// set_ttl / remove_ttl / update_ttl_atomic 操作
+    pub ttl: OperationMetric,
+    /// 读取命中 L1 的次数
+    pub l1_hits: u64,
+    /// 读取命中 L2 的次数
+    pub l2_hits: u64,
+    /// 读取未命中的次数
+    pub misses: u64,
+}
+
+impl OperationStats {
+    /// 获取指定操作的统计（过期清理由 TTL 管理器统计，这里返回空值）
+    pub fn metric(&self, operation: CacheOperation) -> OperationMetric {
+        match operation {
+            CacheOperation::Get => self.get,
+            CacheOperation::Set => self.set,
+            CacheOperation::Delete => self.delete,
+            CacheOperation::Clear => self.clear,
+            CacheOperation::Touch => self.touch,
+            CacheOperation::Ttl => self.ttl,
+            CacheOperation::Expire => OperationMetric::default(),
+        }
+    }
+
+    /// 读取命中总次数
+    pub fn hits(&self) -> u64 {
+        self.l1_hits + self.l2_hits
+    }
+
+    /// 格式化统计信息
+    pub fn format(&self) -> String {
+        let line = |name: &str, metric: OperationMetric| {
+            format!("{}: {} 次 (平均 {:.1} us, P50 {} us, P99 {} us)",
+                name, metric.count, metric.avg_latency_us, metric.p50_latency_us, metric.p99_latency_us)
+        };
+        format!(
+            "操作统计:\n{}\n{}\n{}\n{}\n{}\n{}\n命中: L1 {} 次, L2 {} 次, 未命中 {} 次",
+            line("GET", self.get),
+            line("SET", self.set),
+            line("DELETE", self.delete),
+            line("CLEAR", self.clear),
+            line("TOUCH", self.touch),
+            line("TTL", self.ttl),
+            self.l1_hits, self.l2_hits, self.misses
+        )
+    }
+}
+
+/// 延迟直方图的桶数，第 i 个桶记录 [2^(i-1), 2^i) 微秒的延迟
+const LATENCY_BUCKETS: usize = 32;
+
+/// 操作计数器，每类操作记录次数、累计耗时（微秒）和延迟分布，另记录读取命中情况
+#[derive(Debug, Default)]
+struct OperationMetrics {
+    counters: [(AtomicU64, AtomicU64); 6],
+    latency_buckets: [[AtomicU64; LATENCY_BUCKETS]; 6],
+    /// L1 命中、L2 命中、未命中
+    lookups: [AtomicU64; 3],
+}
+
+impl OperationMetrics {
+    fn slot(operation: CacheOperation) -> Option<usize> {
+        match operation {
+            CacheOperation::Get => Some(0),
+            CacheOperation::Set => Some(1),
+            CacheOperation::Delete => Some(2),
+            CacheOperation::Clear => Some(3),
+            CacheOperation::Touch => Some(4),
+            CacheOperation::Ttl => Some(5),
+            CacheOperation::Expire => None,
+        }
+    }
+
+    fn record(&self, operation: CacheOperation, start_time: Instant) {
+        if let Some(slot) = Self::slot(operation) {
+            let elapsed_us = start_time.elapsed().as_micros() as u64;
+            let (count, total_us) = &self.counters[slot];
+            count.fetch_add(1, Ordering::Relaxed);
+            total_us.fetch_add(elapsed_us, Ordering::Relaxed);
+            let bucket = (u64::BITS - elapsed_us.leading_zeros()) as usize;
+            self.latency_buckets[slot][bucket.min(LATENCY_BUCKETS - 1)].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 记录一次读取的命中层，None 表示未命中
+    fn record_lookup(&self, layer: Option<CacheLayer>) {
+        let index = match layer {
+            Some(CacheLayer::Memory) => 0,
+            Some(CacheLayer::Persistent) => 1,
+            None => 2,
+        };
+        self.lookups[index].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 按直方图估算分位数延迟，返回所在桶的上界（微秒）
+    fn percentile(buckets: &[u64; LATENCY_BUCKETS], total: u64, quantile: f64) -> u64 {
+        if total == 0 {
+            return 0;
+        }
+        let rank = ((total as f64 * quantile).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, &count) in buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return (1u64 << bucket) - 1;
+            }
+        }
+        (1u64 << (LATENCY_BUCKETS - 1)) - 1
+    }
+
+    fn snapshot(&self) -> OperationStats {
+        let metric = |operation| {
+            let Some(slot) = Self::slot(operation) else {
+                return OperationMetric::default();
+            };
+            let (count, total_us) = &self.counters[slot];
+            let count = count.load(Ordering::Relaxed);
+            let avg_latency_us = if count > 0 {
+                total_us.load(Ordering::Relaxed) as f64 / count as f64
+            } else {
+                0.0
+            };
+            let buckets = std::array::from_fn(|i| self.latency_buckets[slot][i].load(Ordering::Relaxed));
+            let total = buckets.iter().sum();
+            OperationMetric {
+                count,
+                avg_latency_us,
+                p50_latency_us: Self::percentile(&buckets, total, 0.5),
+                p99_latency_us: Self::percentile(&buckets, total, 0.99),
+            }
+        };
+        OperationStats {
+            get: metric(CacheOperation::Get),
+            set: metric(CacheOperation::Set),
+            delete: metric(CacheOperation::Delete),
+            clear: metric(CacheOperation::Clear),
+            touch: metric(CacheOperation::Touch),
+            ttl: metric(CacheOperation::Ttl),
+            l1_hits: self.lookups[0].load(Ordering::Relaxed),
+            l2_hits: self.lookups[1].load(Ordering::Relaxed),
+            misses: self.lookups[2].load(Ordering::Relaxed),
+        }
+    }
 }
 
+/// L2 连续存储故障达到该次数后降级为仅使用 L1
+#[cfg(feature = "melange-storage")]
+const L2_FAILURE_THRESHOLD: u32 = 5;
+/// 降级期间每隔该秒数放行一次 L2 探测请求
+#[cfg(feature = "melange-storage")]
+const L2_PROBE_INTERVAL_SECS: u64 = 30;
+
+/// L2 存储健康状态：连续故障时熔断为仅 L1，定期放行探测请求，成功后恢复
+#[cfg(feature = "melange-storage")]
+#[derive(Debug)]
+struct L2Health {
+    clock: SharedClock,
+    consecutive_failures: AtomicU32,
+    degraded: AtomicBool,
+    /// 降级或上次放行探测的时间（秒）
+    last_probe: AtomicU64,
+    listener: Option<L2HealthListener>,
+}
+
+#[cfg(feature = "melange-storage")]
+impl L2Health {
+    fn new(clock: SharedClock, listener: Option<L2HealthListener>) -> Self {
+        Self {
+            clock,
+            consecutive_failures: AtomicU32::new(0),
+            degraded: AtomicBool::new(false),
+            last_probe: AtomicU64::new(0),
+            listener,
+        }
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::Acquire)
+    }
+
+    /// 是否允许访问 L2，降级期间每个探测间隔只放行一次
+    fn allows(&self) -> bool {
+        if !self.is_degraded() {
+            return true;
+        }
+        let now = self.clock.now();
+        let last_probe = self.last_probe.load(Ordering::Relaxed);
+        now.saturating_sub(last_probe) >= L2_PROBE_INTERVAL_SECS
+            && self.last_probe.compare_exchange(last_probe, now, Ordering::AcqRel, Ordering::Relaxed).is_ok()
+    }
+
+    /// 记录 L2 操作结果，只有存储故障计入连续故障次数
+    fn record<T>(&self, result: &CacheResult<T>) {
+        match result {
+            Ok(_) => {
+                self.consecutive_failures.store(0, Ordering::Relaxed);
+                if self.degraded.swap(false, Ordering::AcqRel) {
+                    rat_logger::info!("[CACHE] L2 探测成功，恢复使用 L2");
+                    self.notify(L2HealthEvent::Recovered);
+                }
+            }
+            Err(e) if e.is_storage_failure() => {
+                let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                if failures < L2_FAILURE_THRESHOLD {
+                    return;
+                }
+                self.last_probe.store(self.clock.now(), Ordering::Relaxed);
+                if !self.degraded.swap(true, Ordering::AcqRel) {
+                    rat_logger::error!("[CACHE] L2 连续 {} 次存储故障，降级为仅使用 L1: {}", failures, e);
+                    self.notify(L2HealthEvent::Degraded {
+                        consecutive_failures: failures,
+                        error: e.to_string(),
+                    });
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    fn notify(&self, event: L2HealthEvent) {
+        if let Some(listener) = &self.listener {
+            listener.notify(&event);
+        }
+    }
+}
+
+impl CacheStats {
+    /// 各层存活条目的逻辑（未压缩）字节数之和
+    ///
+    /// 同时存在于 L1 和 L2 的条目会在两层分别计入
+    pub fn logical_bytes(&self) -> u64 {
+        let total = self.l1.logical_bytes as u64;
+        #[cfg(feature = "melange-storage")]
+        let total = total + self.l2.logical_bytes;
+        total
+    }
+}
+
+/// 编译特性与运行状态的自描述，用于排查用户的构建配置
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeInfo {
+    /// 库版本
+    pub version: &'static str,
+    /// 编译时启用的可选特性
+    pub features: Vec<&'static str>,
+    /// 全局内存分配器
+    pub allocator: &'static str,
+    /// 新写入 L2 的数据使用的压缩算法
+    pub compression_algorithm: CompressionAlgorithm,
+    /// 是否启用了 L2
+    pub l2_enabled: bool,
+    /// L2 是否可用（已启用且未因故障降级）
+    pub l2_healthy: bool,
+    /// 缓存创建以来的秒数
+    pub uptime_secs: u64,
+}
+
+impl RuntimeInfo {
+    /// 编译时启用的可选特性
+    fn compiled_features() -> Vec<&'static str> {
+        [
+            ("melange-storage", cfg!(feature = "melange-storage")),
+            ("mimalloc-allocator", cfg!(feature = "mimalloc-allocator")),
+            ("zstd-codec", cfg!(feature = "zstd-codec")),
+            ("snappy", cfg!(feature = "snappy")),
+            ("aes-gcm-codec", cfg!(feature = "aes-gcm-codec")),
+            ("metrics", cfg!(feature = "metrics")),
+        ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect()
+    }
+}
+
+impl std::fmt::Display for RuntimeInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RatMemCache v{} (特性: [{}], 分配器: {}, 压缩: {:?}, L2: {}, 运行: {}s)",
+            self.version,
+            self.features.join(", "),
+            self.allocator,
+            self.compression_algorithm,
+            match (self.l2_enabled, self.l2_healthy) {
+                (false, _) => "未启用",
+                (true, true) => "正常",
+                (true, false) => "降级",
+            },
+            self.uptime_secs
+        )
+    }
+}
+
+/// 供自定义监控导出使用的指标快照，可直接序列化（如 JSON）
+///
+/// 各计数器在同一次调用中依次读取，期间并发的操作可能只反映在部分字段中
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MetricsSnapshot {
+    /// 快照时间（Unix 时间戳，秒）
+    pub timestamp: u64,
+    /// 读取命中次数（L1 + L2）
+    pub hits: u64,
+    /// 读取未命中次数
+    pub misses: u64,
+    /// L1 指标
+    pub l1: L1MetricsSnapshot,
+    /// L2 指标，未启用 L2 时为 None
+    pub l2: Option<L2MetricsSnapshot>,
+    /// 按操作类型划分的次数、延迟与命中统计
+    pub operations: OperationStats,
+    /// 进程常驻内存（字节），无法获取时为 None
+    pub process_rss_bytes: Option<u64>,
+}
+
+/// L1 指标快照
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct L1MetricsSnapshot {
+    pub entries: usize,
+    pub memory_bytes: usize,
+    pub logical_bytes: usize,
+    pub max_memory_bytes: usize,
+    pub max_entries: usize,
+    pub evictions: u64,
+    pub evicted_bytes: u64,
+    /// 近期驱逐速率（次/秒）
+    pub eviction_rate: f64,
+}
+
+/// L2 指标快照
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct L2MetricsSnapshot {
+    pub entries: u64,
+    pub disk_bytes: u64,
+    pub logical_bytes: u64,
+    pub reads: u64,
+    pub writes: u64,
+    pub deletes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub compactions: u64,
+    /// 写入前的原始数据总字节数
+    pub compression_original_bytes: u64,
+    /// 压缩后实际存储的总字节数
+    pub compression_stored_bytes: u64,
+    pub avg_read_latency_ms: f64,
+    pub avg_write_latency_ms: f64,
+    pub write_rate_bytes_per_sec: f64,
+    pub throttled_writes: u64,
+    /// 读写任务在后台线程池中的平均排队时间（微秒）
+    pub spawn_blocking_queue_wait_avg_us: f64,
+    /// 读写任务在后台线程池中的最长排队时间（微秒）
+    pub spawn_blocking_queue_wait_max_us: u64,
+    /// 排队超时被拒绝（返回 Busy）的次数
+    pub busy_rejections: u64,
+    /// 瞬时故障的重试次数
+    pub retries: u64,
+    /// 重试耗尽后仍失败的操作数
+    pub retries_exhausted: u64,
+    /// 是否因存储故障降级为仅 L1
+    pub degraded: bool,
+}
+
+/// 带元数据的缓存条目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    /// 缓存值
+    pub value: Bytes,
+    /// 客户端标志（未设置时为 0）
+    pub flags: u32,
+    /// 剩余 TTL（秒），None 表示永不过期
+    pub ttl_seconds: Option<u64>,
+    /// 是否为 L2 读取超时时返回的过期 L1 副本
+    pub stale: bool,
+}
+
+impl CacheEntry {
+    /// 条目版本号（值内容与客户端标志的指纹），用于 [`TxOp::SetIfVersion`]
+    ///
+    /// 值和标志都相同的两次写入得到相同的版本号
+    pub fn version(&self) -> u64 {
+        value_version(&self.value, self.flags)
+    }
+}
 
 impl Default for CacheOptions {
     fn default() -> Self {
@@ -65,6 +610,12 @@ impl Default for CacheOptions {
             force_l2: false,
             skip_l1: false,
             enable_compression: None,
+            l2_read_timeout: None,
+            serve_expired_l1_on_slow_l2: false,
+            durable: false,
+            read_ahead_keys: Vec::new(),
+            promote_on_hit: None,
+            tags: Vec::new(),
         }
     }
 }
@@ -74,6 +625,37 @@ impl RatMemCacheBuilder {
     pub fn new() -> Self {
         Self {
             config_builder: CacheConfigBuilder::new(),
+            clock: None,
+            #[cfg(feature = "melange-storage")]
+            value_codecs: Vec::new(),
+            eviction_listener: None,
+            eviction_event_capacity: None,
+            #[cfg(feature = "melange-storage")]
+            l2_health_listener: None,
+        }
+    }
+
+    /// 使用开发环境预设创建构建器（默认值见 [`CacheConfigBuilder::development`]）
+    pub fn development() -> Self {
+        Self {
+            config_builder: CacheConfigBuilder::development(),
+            ..Self::new()
+        }
+    }
+
+    /// 使用生产环境预设创建构建器（默认值见 [`CacheConfigBuilder::production`]）
+    pub fn production() -> Self {
+        Self {
+            config_builder: CacheConfigBuilder::production(),
+            ..Self::new()
+        }
+    }
+
+    /// 使用场景预设创建构建器（默认值见 [`CacheConfigBuilder::preset`]）
+    pub fn preset(preset: Preset) -> Self {
+        Self {
+            config_builder: CacheConfigBuilder::preset(preset),
+            ..Self::new()
         }
     }
 
@@ -109,10 +691,78 @@ impl RatMemCacheBuilder {
         self
     }
 
+    /// 启用只读模式，写入类操作返回 [`CacheError::ReadOnly`]
+    pub fn read_only(mut self) -> Self {
+        self.config_builder = self.config_builder.with_read_only(true);
+        self
+    }
+
+    /// 设置只读模式下是否仍将 L2 命中提升到 L1（默认允许）
+    pub fn read_only_promotion(mut self, promote_on_hit: bool) -> Self {
+        self.config_builder = self.config_builder.with_read_only_promotion(promote_on_hit);
+        self
+    }
+
+    /// 设置 L1 写入失败时的处理方式（默认 [`L1SetFailurePolicy::Ignore`]）
+    pub fn l1_set_failure_policy(mut self, policy: L1SetFailurePolicy) -> Self {
+        self.config_builder = self.config_builder.with_l1_set_failure_policy(policy);
+        self
+    }
+
+    /// 开启键空间事件，可通过 [`RatMemCache::subscribe_events`] 订阅
+    pub fn keyspace_events(mut self) -> Self {
+        self.config_builder = self.config_builder.with_keyspace_events(true);
+        self
+    }
+
+    /// 设置时间源（用于 TTL 与 L2 元数据，测试时可注入 MockClock）
+    pub fn clock(mut self, clock: SharedClock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// 设置 L2 值编解码链（写入时按顺序编码，读取时逆序解码）
+    #[cfg(feature = "melange-storage")]
+    pub fn value_codecs(mut self, codecs: Vec<Box<dyn ValueCodec>>) -> Self {
+        self.value_codecs = codecs;
+        self
+    }
+
+    /// 设置 L1 驱逐事件监听器
+    pub fn eviction_listener(mut self, listener: EvictionListener) -> Self {
+        self.eviction_listener = Some(listener);
+        self
+    }
+
+    /// 设置驱逐事件流和键空间事件流中每个订阅者最多缓冲的事件数（默认 1024），超出时丢弃最旧的事件
+    pub fn eviction_event_capacity(mut self, capacity: usize) -> Self {
+        self.eviction_event_capacity = Some(capacity);
+        self
+    }
+
+    /// 设置 L2 降级/恢复事件监听器（需开启 `degrade_to_l1_on_l2_failure`）
+    #[cfg(feature = "melange-storage")]
+    pub fn l2_health_listener(mut self, listener: L2HealthListener) -> Self {
+        self.l2_health_listener = Some(listener);
+        self
+    }
+
     /// 构建缓存实例
     pub async fn build(self) -> CacheResult<RatMemCache> {
         let config = self.config_builder.build()?;
-        RatMemCache::new(config).await
+        let clock = self.clock.unwrap_or_else(system_clock);
+        #[cfg(feature = "melange-storage")]
+        let codec_chain = Arc::new(CodecChain::new(self.value_codecs)?);
+        RatMemCache::create(
+            config,
+            clock,
+            #[cfg(feature = "melange-storage")]
+            codec_chain,
+            self.eviction_listener,
+            self.eviction_event_capacity,
+            #[cfg(feature = "melange-storage")]
+            self.l2_health_listener,
+        ).await
     }
 }
 
@@ -125,12 +775,41 @@ impl Default for RatMemCacheBuilder {
 impl RatMemCache {
     /// 创建新的缓存实例
     pub async fn new(config: CacheConfig) -> CacheResult<Self> {
+        Self::with_clock(config, system_clock()).await
+    }
+
+    /// 使用指定时间源创建缓存实例
+    pub async fn with_clock(config: CacheConfig, clock: SharedClock) -> CacheResult<Self> {
+        Self::create(
+            config,
+            clock,
+            #[cfg(feature = "melange-storage")]
+            Arc::new(CodecChain::default()),
+            None,
+            None,
+            #[cfg(feature = "melange-storage")]
+            None,
+        ).await
+    }
+
+    async fn create(
+        config: CacheConfig,
+        clock: SharedClock,
+        #[cfg(feature = "melange-storage")]
+        codec_chain: Arc<CodecChain>,
+        eviction_listener: Option<EvictionListener>,
+        eviction_event_capacity: Option<usize>,
+        #[cfg(feature = "melange-storage")]
+        l2_health_listener: Option<L2HealthListener>,
+    ) -> CacheResult<Self> {
         let start_time = Instant::now();
         
         rat_logger::debug!("[CACHE] RatMemCache::new 开始初始化");
         rat_logger::debug!("[CACHE] 配: {:?}", config);
         
         rat_logger::debug!("[CACHE] 开始初始化 RatMemCache...");
+
+        config.check_feature_support()?;
         
         // 初始化压缩器（基于 L2 配置）
         rat_logger::debug!("[CACHE] 初始化压缩器");
@@ -141,22 +820,42 @@ impl RatMemCache {
             Arc::new(Compressor::new_disabled())
         };
         
+        let keyspace_events = if config.keyspace_events {
+            let capacity = eviction_event_capacity.unwrap_or(DEFAULT_EVICTION_EVENT_CAPACITY);
+            Some(Arc::new(EventBroadcaster::new(capacity)?))
+        } else {
+            None
+        };
+
         // 初始化 TTL 管理器
         rat_logger::debug!("[CACHE] 初始化 TTL 管理器");
-        let ttl_manager = Arc::new(TtlManager::new(config.ttl.clone()).await?);
+        let ttl_manager = Arc::new(TtlManager::with_events(config.ttl.clone(), clock, keyspace_events.clone()).await?);
         
                 
         // 初始化智能传输路由器（已移除）
                 
         // 初始化 L1 缓存
         rat_logger::debug!("[CACHE] 初始化 L1 缓存");
-        let l1_cache = Arc::new(
-            L1Cache::new(
-                config.l1.clone(),
-                compressor.as_ref().clone(),
-                Arc::clone(&ttl_manager),
-            ).await?
-        );
+        let mut l1_cache = L1Cache::new(
+            config.l1.clone(),
+            compressor.as_ref().clone(),
+            Arc::clone(&ttl_manager),
+        ).await?;
+        if let Some(listener) = eviction_listener {
+            l1_cache = l1_cache.with_eviction_dispatcher(EvictionDispatcher::new(listener)?);
+        }
+        if let Some(capacity) = eviction_event_capacity {
+            l1_cache = l1_cache.with_eviction_event_capacity(capacity)?;
+        }
+        let key_stats = (config.performance.key_stats_top_n > 0)
+            .then(|| Arc::new(KeyStatsTracker::new(config.performance.key_stats_top_n)));
+        if let Some(key_stats) = &key_stats {
+            l1_cache = l1_cache.with_key_stats(Arc::clone(key_stats));
+        }
+        if let Some(events) = &keyspace_events {
+            l1_cache = l1_cache.with_keyspace_events(Arc::clone(events));
+        }
+        let l1_cache = Arc::new(l1_cache);
         rat_logger::debug!("[CACHE] L1 缓存初始化成功");
         
         // 初始化 L2 缓存（如果启用）
@@ -206,6 +905,7 @@ impl RatMemCache {
                 let l2_cache_result = L2Cache::new(
                     l2_config.clone(),
                     compressor.as_ref().clone(),
+                    codec_chain,
                     Arc::clone(&ttl_manager),
                 ).await;
 
@@ -223,6 +923,10 @@ impl RatMemCache {
 
         #[cfg(not(feature = "melange-storage"))]
         let l2_cache: Option<()> = None;
+
+        #[cfg(feature = "melange-storage")]
+        let l2_health = (l2_cache.is_some() && config.l2.as_ref().is_some_and(|l2| l2.degrade_to_l1_on_l2_failure))
+            .then(|| Arc::new(L2Health::new(ttl_manager.clock(), l2_health_listener)));
         
         rat_logger::debug!("[CACHE] 创建 RatMemCache 实例");
         let cache = Self {
@@ -230,12 +934,28 @@ impl RatMemCache {
             l1_cache,
             #[cfg(feature = "melange-storage")]
             l2_cache,
+            #[cfg(feature = "melange-storage")]
+            l2_health,
             // transfer_router,
             ttl_manager,
             compressor,
             is_running: Arc::new(RwLock::new(true)),
+            operation_metrics: Arc::new(OperationMetrics::default()),
+            key_stats,
+            background_tasks: Arc::new(BackgroundTasks::new()),
+            key_locks: Arc::new(KeyLocks::new()),
+            tag_index: Arc::new(TagIndex::new(config.performance.max_tagged_keys)),
+            loader_locks: Arc::new(DashMap::new()),
+            keyspace_events,
+            started_at: start_time,
         };
 
+        // 启动基于 TTL 的 L1→L2 提前降级任务
+        #[cfg(feature = "melange-storage")]
+        if cache.l2_cache.is_some() && config.l2.as_ref().is_some_and(|l2| l2.ttl_demotion_enabled) {
+            cache.start_ttl_demotion_task();
+        }
+
         let elapsed = start_time.elapsed();
         rat_logger::debug!("[CACHE] RatMemCache 初始化完成，耗时: {:.2}ms", elapsed.as_millis());
         
@@ -243,121 +963,555 @@ impl RatMemCache {
         Ok(cache)
     }
 
-    /// 获取缓存值
-    pub async fn get(&self, key: &str) -> CacheResult<Option<Bytes>> {
+    /// 获取缓存值（键可以是 [`CacheKey`](crate::types::CacheKey) 或字符串，下同）
+    pub async fn get<'a>(&self, key: impl Into<CacheKeyRef<'a>>) -> CacheResult<Option<Bytes>> {
         self.get_with_options(key, &CacheOptions::default()).await
     }
 
     /// 获取缓存值（带选项）
-    pub async fn get_with_options(&self, key: &str, options: &CacheOptions) -> CacheResult<Option<Bytes>> {
+    pub async fn get_with_options<'a>(&self, key: impl Into<CacheKeyRef<'a>>, options: &CacheOptions) -> CacheResult<Option<Bytes>> {
+        let key = key.into();
+        Ok(self.get_with_flags(key.as_str(), options).await?.map(|(value, _, _, _)| value))
+    }
+
+    /// 以分块流的形式读取缓存值，适合不希望一次性放入内存的大值
+    ///
+    /// L1 命中时直接切分内存中的值；否则从 L2 读取存储的数据并在后台按块解压，
+    /// 不会在内存中拼出完整的解压值，也不会提升到 L1
+    #[cfg(feature = "melange-storage")]
+    pub async fn get_stream(&self, key: &str) -> CacheResult<Option<ValueStream>> {
+        self.ensure_running().await?;
         let start_time = Instant::now();
-        
-        // 检查 TTL
+        let result = self.lookup_stream(key).await;
+        self.operation_metrics.record(CacheOperation::Get, start_time);
+        if let Ok(stream) = &result {
+            self.operation_metrics.record_lookup(stream.as_ref().map(|(_, layer)| *layer));
+            self.record_key_event(key, if stream.is_some() { KeyEvent::Hit } else { KeyEvent::Miss });
+        }
+        result.map(|stream| stream.map(|(stream, _)| stream))
+    }
+
+    #[cfg(feature = "melange-storage")]
+    async fn lookup_stream(&self, key: &str) -> CacheResult<Option<(ValueStream, CacheLayer)>> {
         if self.ttl_manager.is_expired(key).await {
-            self.delete_internal(key).await?;
-                        return Ok(None);
+            self.remove_expired(key).await?;
+            return Ok(None);
         }
-        
-        // 尝试从 L1 获取（除非跳过）
-        if !options.skip_l1 {
-            if let Some(value) = self.l1_cache.get(key).await? {
-                transfer_log!(debug, "L1 缓存命中: {}", key);
-                                return Ok(Some(value));
-            }
+        if let Some((value, _)) = self.l1_cache.get_with_flags(key).await? {
+            return Ok(Some((ValueStream::from_bytes(value), CacheLayer::Memory)));
         }
-        
-        // 尝试从 L2 获取（如果启用且存在）
-        #[cfg(feature = "melange-storage")]
-        if let Some(l2_cache) = &self.l2_cache {
-            if let Some(value) = l2_cache.get(key).await? {
-                transfer_log!(debug, "L2 缓存命中: {}", key);
-
-                // 将数据提升到 L1（除非跳过）
-                if !options.skip_l1 && !options.force_l2 {
-                    let ttl = self.ttl_manager.get_ttl(key).await;
-                    if let Err(e) = self.l1_cache.set(key.to_string(), value.clone(), ttl).await {
-                        rat_logger::warn!("[CACHE] L1 缓存设置失败: {} - {}", key, e);
-                    }
-                }
-
-                                return Ok(Some(value));
-            }
+        match self.active_l2() {
+            Some(l2_cache) => Ok(self.observe_l2(l2_cache.get_stream(key).await)?
+                .map(|(stream, _)| (stream, CacheLayer::Persistent))),
+            None => Ok(None),
         }
-        
-        // 缓存未命中
-        rat_logger::debug!("[CACHE] 缓存未命中: {}", key);
-        
-                Ok(None)
     }
 
-    /// 设置缓存值
-    pub async fn set(&self, key: String, value: Bytes) -> CacheResult<()> {
-        self.set_with_options(key, value, &CacheOptions::default()).await
+    /// 获取缓存值及其元数据（客户端标志、剩余 TTL）
+    pub async fn get_with_metadata<'a>(&self, key: impl Into<CacheKeyRef<'a>>) -> CacheResult<Option<CacheEntry>> {
+        self.get_entry_with_options(key, &CacheOptions::default()).await
     }
 
-    /// 设置缓存值（带 TTL）
-    pub async fn set_with_ttl(&self, key: String, value: Bytes, ttl_seconds: u64) -> CacheResult<()> {
-        let options = CacheOptions {
-            ttl_seconds: Some(ttl_seconds),
-            ..Default::default()
+    /// 获取缓存值及其元数据（带选项）
+    pub async fn get_entry_with_options<'a>(&self, key: impl Into<CacheKeyRef<'a>>, options: &CacheOptions) -> CacheResult<Option<CacheEntry>> {
+        let key = key.into();
+        let key = key.as_str();
+        let entry = match self.get_with_flags(key, options).await? {
+            Some((value, flags, stale, _)) => CacheEntry {
+                value,
+                flags,
+                ttl_seconds: self.ttl_manager.get_ttl(key).await,
+                stale,
+            },
+            None => return Ok(None),
         };
-        self.set_with_options(key, value, &options).await
+        Ok(Some(entry))
     }
 
-    /// 设置缓存值（带选项）
-    pub async fn set_with_options(&self, key: String, value: Bytes, options: &CacheOptions) -> CacheResult<()> {
-        let start_time = Instant::now();
-        
-        // TTL 验证逻辑已简化，移除最大值检查
-        
-        // 大值处理：检查是否超过大值阈值
-        let threshold = self.config.performance.large_value_threshold;
-        let is_large_value = value.len() > threshold;
-        let processed_value = value.clone();
+    /// 读取缓存值，未命中时调用 `loader` 加载并以 `ttl` 写入缓存（None 使用默认 TTL）
+    ///
+    /// 同一键的并发未命中只运行一个 `loader`，其余调用方等待其完成后读取刚写入的值。
+    /// `loader` 返回错误时不写入缓存，错误只返回给运行它的调用方，等待者会再次尝试加载
+    pub async fn get_or_insert_with<F, Fut>(&self, key: String, ttl: Option<u64>, loader: F) -> CacheResult<Bytes>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = CacheResult<Bytes>>,
+    {
+        if let Some(value) = self.get(key.as_str()).await? {
+            return Ok(value);
+        }
 
-        
-        if is_large_value {
-            // 大值处理策略
-            rat_logger::debug!("[CACHE] 检测到大值: {} ({} bytes)", key, value.len());
+        let lock = Arc::clone(self.loader_locks.entry(key.clone()).or_default().value());
+        let result = async {
+            let _guard = lock.lock().await;
+            // 等待期间可能已有其他调用方加载完成
+            if let Some(value) = self.get(key.as_str()).await? {
+                return Ok(value);
+            }
+            let value = loader().await?;
+            let options = CacheOptions { ttl_seconds: ttl, ..Default::default() };
+            self.set_with_options(key.as_str(), value.clone(), &options).await?;
+            Ok(value)
+        }
+        .await;
 
-            #[cfg(feature = "melange-storage")]
-            {
-                if let Some(l2_cache) = &self.l2_cache {
-                    // 有 L2 缓存，直接写入 L2
-                    rat_logger::debug!("[CACHE] 大值直接下沉到 L2: {}", key);
-                    if let Some(ttl) = options.ttl_seconds {
-                        l2_cache.set_with_ttl(&key, processed_value, ttl).await?;
-                    } else {
-                        l2_cache.set(key.clone(), processed_value, None).await?;
-                    }
-                } else {
-                    // 无 L2 缓存，抛弃大值并记录警告
-                    rat_logger::warn!("[CACHE] 大值被抛弃（无 L2 缓存）: {} ({} bytes > {} bytes)",
-                        key, value.len(), self.config.performance.large_value_threshold);
-                    return Ok(());
+        // 没有其他等待者时移除加载锁（映射和当前调用方各持有一个引用）
+        self.loader_locks.remove_if(&key, |_, current| Arc::strong_count(current) <= 2);
+        result
+    }
+
+    /// 批量获取缓存值，返回的映射只包含命中的键
+    ///
+    /// 先逐个查找 L1，L1 未命中的键通过一次 L2 批量读取获取，L2 命中按默认规则提升到 L1
+    pub async fn get_many(&self, keys: &[String]) -> CacheResult<HashMap<String, Bytes>> {
+        self.ensure_running().await?;
+        let start_time = Instant::now();
+        let mut found = HashMap::with_capacity(keys.len());
+        let mut seen = HashSet::with_capacity(keys.len());
+        #[cfg_attr(not(feature = "melange-storage"), allow(unused_mut))]
+        let mut l1_misses = Vec::new();
+
+        for key in keys {
+            if !seen.insert(key.as_str()) {
+                continue;
+            }
+            if self.ttl_manager.is_expired(key).await {
+                self.remove_expired(key).await?;
+                self.record_lookup_result(key, None);
+                continue;
+            }
+            match self.l1_cache.get_with_flags(key).await? {
+                Some((value, _)) => {
+                    self.record_lookup_result(key, Some(CacheLayer::Memory));
+                    found.insert(key.clone(), value);
                 }
+                None => l1_misses.push(key.clone()),
             }
+        }
 
-            #[cfg(not(feature = "melange-storage"))]
+        #[cfg(feature = "melange-storage")]
+        if !l1_misses.is_empty() && let Some(l2_cache) = self.active_l2() {
+            let values = self.observe_l2(l2_cache.get_many_with_flags(&l1_misses).await)?;
+            for (key, value) in std::mem::take(&mut l1_misses).into_iter().zip(values) {
+                match value {
+                    Some((value, flags)) => {
+                        if self.promotion_allowed() {
+                            self.promote_hit(&key, value.clone(), flags).await;
+                        }
+                        self.record_lookup_result(&key, Some(CacheLayer::Persistent));
+                        found.insert(key, value);
+                    }
+                    None => self.record_lookup_result(&key, None),
+                }
+            }
+        }
+        for key in &l1_misses {
+            self.record_lookup_result(key, None);
+        }
+        for key in found.keys() {
+            self.slide_expiry(key).await?;
+        }
+
+        self.operation_metrics.record(CacheOperation::Get, start_time);
+        Ok(found)
+    }
+
+    fn record_lookup_result(&self, key: &str, layer: Option<CacheLayer>) {
+        self.operation_metrics.record_lookup(layer);
+        self.record_key_event(key, if layer.is_some() { KeyEvent::Hit } else { KeyEvent::Miss });
+    }
+
+    /// 批量获取缓存值，同时返回每个键的命中层（未命中时为 None）
+    pub async fn get_many_with_source(&self, keys: &[String]) -> CacheResult<Vec<(Option<Bytes>, Option<CacheLayer>)>> {
+        let options = CacheOptions::default();
+        let mut results = Vec::with_capacity(keys.len());
+        for key in keys {
+            let result = match self.get_with_flags(key, &options).await? {
+                Some((value, _, _, layer)) => (Some(value), Some(layer)),
+                None => (None, None),
+            };
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    /// 获取缓存值、客户端标志、是否为过期副本及命中层
+    async fn get_with_flags(&self, key: &str, options: &CacheOptions) -> CacheResult<Option<(Bytes, u32, bool, CacheLayer)>> {
+        self.ensure_running().await?;
+        let start_time = Instant::now();
+        let result = self.lookup_with_flags(key, options).await;
+        self.operation_metrics.record(CacheOperation::Get, start_time);
+        #[cfg(feature = "melange-storage")]
+        if !options.read_ahead_keys.is_empty() && self.l2_cache.is_some() {
+            self.spawn_read_ahead(options.read_ahead_keys.clone());
+        }
+        if let Ok(entry) = &result {
+            self.record_lookup_result(key, entry.as_ref().map(|(_, _, _, layer)| *layer));
+        }
+        if let Ok(Some((_, _, false, _))) = &result {
+            self.slide_expiry(key).await?;
+        }
+        result
+    }
+
+    /// 开启滑动过期时，在读取命中后重新计算键的过期时间，并同步 L2 元数据中记录的过期时间
+    async fn slide_expiry(&self, key: &str) -> CacheResult<()> {
+        if self.config.read_only {
+            return Ok(());
+        }
+        let Some(ttl) = self.ttl_manager.touch_on_access(key).await else {
+            return Ok(());
+        };
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = self.active_l2() {
+            self.observe_l2(l2_cache.set_expiry(key, ttl).await)?;
+        }
+        #[cfg(not(feature = "melange-storage"))]
+        let _ = ttl;
+        Ok(())
+    }
+
+    /// 将 L2 命中的值以剩余 TTL 写入 L1，失败时只记录警告
+    #[cfg(feature = "melange-storage")]
+    async fn promote_hit(&self, key: &str, value: Bytes, flags: u32) {
+        let ttl = self.ttl_manager.get_ttl(key).await;
+        match self.l1_cache.set_with_flags(key.to_string(), value, ttl, flags).await {
+            Ok(_) => self.record_key_event(key, KeyEvent::Promotion),
+            Err(e) => rat_logger::warn!("[CACHE] L1 缓存设置失败: {} - {}", key, e),
+        }
+    }
+
+    /// 在后台将键从 L2 预取到 L1
+    #[cfg(feature = "melange-storage")]
+    fn spawn_read_ahead(&self, keys: Vec<String>) {
+        let cache = self.clone();
+        self.background_tasks.spawn(async move {
+            match cache.promote(&keys).await {
+                Ok(promoted) => rat_logger::debug!("[CACHE] 预读 {} 个键到 L1", promoted),
+                Err(e) => rat_logger::warn!("[CACHE] 预读失败: {}", e),
+            }
+        });
+    }
+
+    /// 关闭开始后拒绝新的操作，避免与清理过程竞争
+    async fn ensure_running(&self) -> CacheResult<()> {
+        if *self.is_running.read().await {
+            Ok(())
+        } else {
+            Err(CacheError::ShuttingDown)
+        }
+    }
+
+    /// 只读模式下拒绝写入类操作
+    fn ensure_writable(&self) -> CacheResult<()> {
+        if self.config.read_only {
+            Err(CacheError::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 是否允许将 L2 命中提升到 L1
+    #[cfg(feature = "melange-storage")]
+    fn promotion_allowed(&self) -> bool {
+        !self.config.read_only || self.config.read_only_promote_on_hit
+    }
+
+    /// 可访问的 L2 缓存，故障降级期间（探测请求除外）返回 None
+    #[cfg(feature = "melange-storage")]
+    fn active_l2(&self) -> Option<&Arc<L2Cache>> {
+        let l2_cache = self.l2_cache.as_ref()?;
+        match &self.l2_health {
+            Some(health) if !health.allows() => None,
+            _ => Some(l2_cache),
+        }
+    }
+
+    /// 记录 L2 操作结果，用于故障降级判断
+    #[cfg(feature = "melange-storage")]
+    fn observe_l2<T>(&self, result: CacheResult<T>) -> CacheResult<T> {
+        if let Some(health) = &self.l2_health {
+            health.record(&result);
+        }
+        result
+    }
+
+    /// 申请 L2 写入带宽，持久写入总是放行；超出上限时删除 L2 中的旧副本
+    /// （避免 L1 驱逐后读到旧值），返回是否可以写入 L2
+    #[cfg(feature = "melange-storage")]
+    async fn reserve_l2_write(&self, l2_cache: &L2Cache, key: &str, len: usize, options: &CacheOptions) -> CacheResult<bool> {
+        if l2_cache.try_reserve_write(len, options.durable) {
+            return Ok(true);
+        }
+        rat_logger::debug!("[CACHE] L2 写入限流，跳过 L2 写入: {} ({} bytes)", key, len);
+        self.observe_l2(l2_cache.delete(key).await)?;
+        Ok(false)
+    }
+
+    fn record_key_event(&self, key: &str, event: KeyEvent) {
+        if let Some(key_stats) = &self.key_stats {
+            key_stats.record(key, event);
+        }
+    }
+
+    async fn lookup_with_flags(&self, key: &str, options: &CacheOptions) -> CacheResult<Option<(Bytes, u32, bool, CacheLayer)>> {
+        // 检查 TTL
+        #[cfg_attr(not(feature = "melange-storage"), allow(unused_mut))]
+        let mut stale_l1: Option<(Bytes, u32)> = None;
+        if self.ttl_manager.is_expired(key).await {
+            // 保留过期的 L1 副本，供 L2 读取超时时返回
+            #[cfg(feature = "melange-storage")]
+            if options.serve_expired_l1_on_slow_l2 && options.l2_read_timeout.is_some()
+                && !options.skip_l1 && self.l2_cache.is_some()
+            {
+                stale_l1 = self.l1_cache.peek_with_flags(key);
+            }
+            if stale_l1.is_none() {
+                self.remove_expired(key).await?;
+                return Ok(None);
+            }
+        }
+        
+        // 尝试从 L1 获取（除非跳过）
+        if !options.skip_l1
+            && stale_l1.is_none()
+            && let Some((value, flags)) = self.l1_cache.get_with_flags(key).await?
+        {
+            transfer_log!(debug, "L1 缓存命中: {}", key);
+            return Ok(Some((value, flags, false, CacheLayer::Memory)));
+        }
+        
+        // 尝试从 L2 获取（如果启用且存在）
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = self.active_l2() {
+            let l2_result = match options.l2_read_timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, l2_cache.get_with_flags(key)).await {
+                    Ok(result) => self.observe_l2(result)?,
+                    Err(_) => {
+                        if let Some((value, flags)) = stale_l1 {
+                            rat_logger::debug!("[CACHE] L2 读取超时，返回过期的 L1 副本: {}", key);
+                            return Ok(Some((value, flags, true, CacheLayer::Memory)));
+                        }
+                        rat_logger::debug!("[CACHE] L2 读取超时: {}", key);
+                        return Ok(None);
+                    }
+                },
+                None => self.observe_l2(l2_cache.get_with_flags(key).await)?,
+            };
+
+            // L2 在超时前返回，按正常过期处理
+            if stale_l1.is_some() {
+                self.remove_expired(key).await?;
+                return Ok(None);
+            }
+
+            if let Some((value, flags)) = l2_result {
+                transfer_log!(debug, "L2 缓存命中: {}", key);
+
+                // 将数据提升到 L1（除非跳过）
+                if options.promote_on_hit.unwrap_or(!options.skip_l1 && !options.force_l2) && self.promotion_allowed() {
+                    self.promote_hit(key, value.clone(), flags).await;
+                }
+
+                return Ok(Some((value, flags, false, CacheLayer::Persistent)));
+            }
+        }
+        
+        // 缓存未命中
+        rat_logger::debug!("[CACHE] 缓存未命中: {}", key);
+        
+                Ok(None)
+    }
+
+    /// 设置缓存值
+    ///
+    /// 使用 [`EvictionStrategy::TinyLfu`](crate::EvictionStrategy::TinyLfu) 时，新键可能被准入判断拒绝而不进入 L1，
+    /// 此时仍返回 Ok；需要知道是否被拒绝时使用 [`Self::set_reporting_eviction`]
+    pub async fn set<'a>(&self, key: impl Into<CacheKeyRef<'a>>, value: Bytes) -> CacheResult<()> {
+        self.set_with_options(key, value, &CacheOptions::default()).await
+    }
+
+    /// 设置缓存值（带 TTL）
+    pub async fn set_with_ttl<'a>(&self, key: impl Into<CacheKeyRef<'a>>, value: Bytes, ttl_seconds: u64) -> CacheResult<()> {
+        let options = CacheOptions {
+            ttl_seconds: Some(ttl_seconds),
+            ..Default::default()
+        };
+        self.set_with_options(key, value, &options).await
+    }
+
+    /// 设置缓存值（带选项）
+    pub async fn set_with_options<'a>(&self, key: impl Into<CacheKeyRef<'a>>, value: Bytes, options: &CacheOptions) -> CacheResult<()> {
+        self.set_with_flags(key, value, 0, options).await
+    }
+
+    /// 设置缓存值及客户端标志（如 Memcached flags，可用于标记值的序列化格式）
+    pub async fn set_with_flags<'a>(&self, key: impl Into<CacheKeyRef<'a>>, value: Bytes, flags: u32, options: &CacheOptions) -> CacheResult<()> {
+        self.set_with_outcome(key, value, flags, options).await.map(|_| ())
+    }
+
+    /// 设置缓存值，并返回本次写入为腾出 L1 空间而驱逐的条目数和字节数，以及是否被 TinyLFU 拒绝进入 L1
+    ///
+    /// 驱逐量只归属于本次写入，可据此在写入开始引发驱逐时退避或告警
+    pub async fn set_reporting_eviction<'a>(&self, key: impl Into<CacheKeyRef<'a>>, value: Bytes, options: &CacheOptions) -> CacheResult<SetOutcome> {
+        self.set_with_outcome(key, value, 0, options).await
+    }
+
+    async fn set_with_outcome<'a>(&self, key: impl Into<CacheKeyRef<'a>>, value: Bytes, flags: u32, options: &CacheOptions) -> CacheResult<SetOutcome> {
+        self.ensure_running().await?;
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+        let key = key.into().into_string();
+        let _guard = self.key_locks.read(&key).await;
+        self.tag_index.update(&key, &options.tags)?;
+        let result = self.store_with_flags(key, value, flags, options).await;
+        self.operation_metrics.record(CacheOperation::Set, start_time);
+        result
+    }
+
+    /// 批量写入，使用默认 TTL
+    ///
+    /// L1 逐个写入，需要写入 L2 的条目（按写入策略，以及超过大值阈值的条目）在一次后台任务中以一个批次写入。
+    /// 写入前先检查全部值的大小，任一值超过绝对上限时不写入任何条目；同一键出现多次时以最后一次为准
+    pub async fn set_many(&self, entries: Vec<(String, Bytes)>) -> CacheResult<()> {
+        self.ensure_running().await?;
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+
+        let max_value_bytes = self.config.performance.absolute_max_value_bytes;
+        if max_value_bytes > 0
+            && let Some((_, value)) = entries.iter().find(|(_, value)| value.len() > max_value_bytes)
+        {
+            return Err(CacheError::value_too_large(value.len(), max_value_bytes));
+        }
+
+        let _guards = self.key_locks.read_many(entries.iter().map(|(key, _)| key.as_str())).await;
+        #[cfg(feature = "melange-storage")]
+        let (l2_cache, options, mut l2_writes) = (self.active_l2(), CacheOptions::default(), Vec::new());
+
+        let mut stored = Vec::new();
+        for (key, value) in entries {
+            self.tag_index.remove(&key);
+            let is_large_value = value.len() > self.config.performance.large_value_threshold;
+            if !is_large_value
+                && let Err(e) = self.l1_cache.set_with_flags(key.clone(), value.clone(), None, 0).await
+            {
+                self.handle_l1_set_failure(&key, e)?;
+            }
+
+            #[cfg(feature = "melange-storage")]
+            if let Some(l2_cache) = l2_cache {
+                if is_large_value || self.should_write_to_l2(&key, &value, &options).await {
+                    if self.reserve_l2_write(l2_cache, &key, value.len(), &options).await? {
+                        l2_writes.push((key.clone(), value, None, 0));
+                    } else if is_large_value {
+                        rat_logger::warn!("[CACHE] 大值被抛弃（L2 写入限流）: {} ({} bytes)", key, value.len());
+                        continue;
+                    }
+                }
+                stored.push(key);
+                continue;
+            }
+            if is_large_value {
+                rat_logger::warn!("[CACHE] 大值被抛弃（无 L2 缓存）: {} ({} bytes > {} bytes)",
+                    key, value.len(), self.config.performance.large_value_threshold);
+                continue;
+            }
+            stored.push(key);
+        }
+
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = l2_cache && !l2_writes.is_empty() {
+            self.observe_l2(l2_cache.set_many_with_flags(l2_writes).await)?;
+        }
+
+        // 被抛弃的大值不发布事件
+        for key in stored {
+            self.publish_event(|| CacheEvent::Set { key });
+        }
+
+        self.operation_metrics.record(CacheOperation::Set, start_time);
+        Ok(())
+    }
+
+    async fn store_with_flags(&self, key: String, value: Bytes, flags: u32, options: &CacheOptions) -> CacheResult<SetOutcome> {
+        // 超过绝对上限的值无论如何都无法存储，在压缩和写入任何一层之前拒绝
+        let max_value_bytes = self.config.performance.absolute_max_value_bytes;
+        if max_value_bytes > 0 && value.len() > max_value_bytes {
+            return Err(CacheError::value_too_large(value.len(), max_value_bytes));
+        }
+
+        // 持久写入需要 L2，在写入任何一层之前检查
+        #[cfg(feature = "melange-storage")]
+        let l2_cache = self.active_l2();
+        #[cfg(feature = "melange-storage")]
+        let l2_available = l2_cache.is_some();
+        #[cfg(not(feature = "melange-storage"))]
+        let l2_available = false;
+        if options.durable && !l2_available {
+            #[cfg(feature = "melange-storage")]
+            if self.l2_cache.is_some() {
+                return Err(CacheError::config_error("L2 缓存已降级，无法持久写入"));
+            }
+            return Err(CacheError::config_error("持久写入需要启用 L2 缓存"));
+        }
+
+        // TTL 验证逻辑已简化，移除最大值检查
+        
+        // 大值处理：检查是否超过大值阈值
+        let threshold = self.config.performance.large_value_threshold;
+        let is_large_value = value.len() > threshold;
+        let processed_value = value.clone();
+        let mut outcome = SetOutcome::default();
+
+        
+        if is_large_value {
+            // 大值处理策略
+            rat_logger::debug!("[CACHE] 检测到大值: {} ({} bytes)", key, value.len());
+
+            #[cfg(feature = "melange-storage")]
+            {
+                if let Some(l2_cache) = l2_cache {
+                    if !self.reserve_l2_write(l2_cache, &key, value.len(), options).await? {
+                        // 大值不进入 L1，超出 L2 写入带宽时只能抛弃
+                        rat_logger::warn!("[CACHE] 大值被抛弃（L2 写入限流）: {} ({} bytes)", key, value.len());
+                        return Ok(SetOutcome::default());
+                    }
+                    // 有 L2 缓存，直接写入 L2
+                    rat_logger::debug!("[CACHE] 大值直接下沉到 L2: {}", key);
+                    self.observe_l2(l2_cache.set_with_compression(key.clone(), processed_value, options.ttl_seconds, flags, options.enable_compression).await)?;
+                    if options.durable {
+                        self.observe_l2(l2_cache.sync().await)?;
+                    }
+                } else {
+                    // 无 L2 缓存，抛弃大值并记录警告
+                    rat_logger::warn!("[CACHE] 大值被抛弃（无 L2 缓存）: {} ({} bytes > {} bytes)",
+                        key, value.len(), self.config.performance.large_value_threshold);
+                    return Ok(SetOutcome::default());
+                }
+            }
+
+            #[cfg(not(feature = "melange-storage"))]
             {
                 // 无 L2 功能，抛弃大值并记录警告
                 rat_logger::warn!("[CACHE] 大值被抛弃（未启用 L2 功能）: {} ({} bytes > {} bytes)",
                     key, value.len(), self.config.performance.large_value_threshold);
-                return Ok(());
+                return Ok(SetOutcome::default());
             }
         } else {
             // 普通值处理
             // 设置到 L1（除非跳过或强制 L2）
             if !options.skip_l1 && !options.force_l2 {
-                if let Err(e) = self.l1_cache.set(key.clone(), processed_value.clone(), options.ttl_seconds).await {
-                    rat_logger::warn!("[CACHE] L1 缓存设置失败: {} - {}", key, e);
+                match self.l1_cache.set_with_flags(key.clone(), processed_value.clone(), options.ttl_seconds, flags).await {
+                    Ok(evicted) => outcome.merge(evicted),
+                    Err(e) => self.handle_l1_set_failure(&key, e)?,
                 }
             }
 
             // 根据策略决定是否写入 L2（仅在存在时）
             #[cfg(feature = "melange-storage")]
-            let should_write_l2 = if let Some(_l2_cache) = &self.l2_cache {
-                options.force_l2 || self.should_write_to_l2(&key, &processed_value, options).await
+            let should_write_l2 = if l2_cache.is_some() {
+                options.force_l2 || options.durable || self.should_write_to_l2(&key, &processed_value, options).await
             } else {
                 false
             };
@@ -366,11 +1520,20 @@ impl RatMemCache {
 
             if should_write_l2 {
                 #[cfg(feature = "melange-storage")]
-                if let Some(l2_cache) = &self.l2_cache {
-                    if let Some(ttl) = options.ttl_seconds {
-                        l2_cache.set_with_ttl(&key, processed_value, ttl).await?;
-                    } else {
-                        l2_cache.set(key.clone(), processed_value, None).await?;
+                if let Some(l2_cache) = l2_cache {
+                    if self.reserve_l2_write(l2_cache, &key, processed_value.len(), options).await? {
+                        self.observe_l2(l2_cache.set_with_compression(key.clone(), processed_value, options.ttl_seconds, flags, options.enable_compression).await)?;
+                        if options.durable {
+                            self.observe_l2(l2_cache.sync().await)?;
+                        }
+                    } else if options.force_l2 && !options.skip_l1 {
+                        // 强制 L2 的写入未进入 L1，限流时改为写入 L1
+                        match self.l1_cache.set_with_flags(key.clone(), processed_value, options.ttl_seconds, flags).await {
+                            Ok(evicted) => outcome.merge(evicted),
+                            Err(e) => self.handle_l1_set_failure(&key, e)?,
+                        }
+                    } else if options.skip_l1 {
+                        rat_logger::warn!("[CACHE] 写入被抛弃（跳过 L1 且 L2 写入限流）: {}", key);
                     }
                 }
             }
@@ -379,334 +1542,2821 @@ impl RatMemCache {
         rat_logger::debug!("[CACHE] 缓存设置完成: {} (大值: {}, L1: {}, L2: {})",
             key, is_large_value, !options.skip_l1 && !options.force_l2 && !is_large_value, is_large_value);
         
+        self.publish_event(|| CacheEvent::Set { key });
+        Ok(outcome)
+    }
+
+    /// 按 [`L1SetFailurePolicy`] 处理 L1 写入失败
+    fn handle_l1_set_failure(&self, key: &str, error: CacheError) -> CacheResult<()> {
+        match self.config.l1_set_failure_policy {
+            L1SetFailurePolicy::Ignore => {
+                rat_logger::warn!("[CACHE] L1 缓存设置失败: {} - {}", key, error);
                 Ok(())
+            }
+            L1SetFailurePolicy::Propagate => Err(error),
+        }
     }
 
     /// 删除缓存值
-    pub async fn delete(&self, key: &str) -> CacheResult<bool> {
+    pub async fn delete<'a>(&self, key: impl Into<CacheKeyRef<'a>>) -> CacheResult<bool> {
+        self.ensure_running().await?;
+        self.ensure_writable()?;
         let start_time = Instant::now();
-        let deleted = self.delete_internal(key).await?;
-                Ok(deleted)
+        let key = key.into();
+        let _guard = self.key_locks.read(key.as_str()).await;
+        let deleted = self.delete_internal(key.as_str()).await;
+        self.operation_metrics.record(CacheOperation::Delete, start_time);
+        deleted
     }
 
-    /// 清空缓存
-    pub async fn clear(&self) -> CacheResult<()> {
-        let start_time = Instant::now();
-        
-        // 清空 L1 和 L2（如果存在）
-        self.l1_cache.clear().await?;
-        #[cfg(feature = "melange-storage")]
-        if let Some(l2_cache) = &self.l2_cache {
-            l2_cache.clear().await?;
-        }
-        
-        // TTL 管理器会自动清理
-        
-        rat_logger::debug!("[CACHE] 缓存已清空");
-        
-                Ok(())
+    /// 读取值及其 CAS 令牌，用于乐观并发控制（见 [`Self::cas`]）
+    pub async fn gets(&self, key: &str) -> CacheResult<Option<(Bytes, u64)>> {
+        Ok(self.gets_with_metadata(key).await?.map(|(entry, cas)| (entry.value, cas)))
     }
 
-    /// 检查键是否存在
-    pub async fn contains_key(&self, key: &str) -> CacheResult<bool> {
-        // 检查 TTL
-        if self.ttl_manager.is_expired(key).await {
-            self.delete_internal(key).await?;
-            return Ok(false);
-        }
-        
-        // 检查 L1
-        if self.l1_cache.contains_key(key) {
-            return Ok(true);
+    /// 读取条目及其 CAS 令牌
+    ///
+    /// 令牌随值保存在 L1 中，同一键每次写入（包括从 L2 提升到 L1）都会得到新的令牌。
+    /// 只在 L2 中的条目（如大值）使用写入 L2 时持久化在元数据中的令牌；
+    /// 记录令牌之前写入 L2 的旧数据没有令牌，返回 0，对其执行 cas 总是返回 [`CasResult::Exists`]
+    pub async fn gets_with_metadata(&self, key: &str) -> CacheResult<Option<(CacheEntry, u64)>> {
+        let Some(mut entry) = self.get_entry_with_options(key, &CacheOptions::default()).await? else {
+            return Ok(None);
+        };
+        // 值与令牌从 L1 中一并读取，期间有新的写入时返回新值及其令牌
+        if let Some((value, flags, cas)) = self.l1_cache.peek_with_cas(key) {
+            (entry.value, entry.flags) = (value, flags);
+            return Ok(Some((entry, cas)));
         }
-        
-        // 检查 L2（如果存在）
+
+        // 只在 L2 中的条目持有键的独占锁重新读取，保证值与令牌来自同一次写入
         #[cfg(feature = "melange-storage")]
-        if let Some(l2_cache) = &self.l2_cache {
-            l2_cache.contains_key(key).await
-        } else {
-            Ok(false)
-        }
-        #[cfg(not(feature = "melange-storage"))]
-        {
-            Ok(false)
+        if let Some(l2_cache) = self.active_l2() {
+            let _guards = self.key_locks.write_many(std::iter::once(key)).await;
+            let Some((value, flags, cas)) = self.observe_l2(l2_cache.get_with_cas(key).await)? else {
+                return Ok(None);
+            };
+            (entry.value, entry.flags) = (value, flags);
+            return Ok(Some((entry, cas)));
         }
+        Ok(Some((entry, 0)))
     }
 
-    /// 获取所有键
-    pub async fn keys(&self) -> CacheResult<Vec<String>> {
-        let mut keys = std::collections::HashSet::<String>::new();
-        
-        // 收集 L1 键
-        for key in self.l1_cache.keys() {
-            if !self.ttl_manager.is_expired(&key).await {
-                keys.insert(key);
-            }
-        }
-        
-        // 收集 L2 键（如果存在）
-        #[cfg(feature = "melange-storage")]
-        if let Some(l2_cache) = &self.l2_cache {
-            for key in l2_cache.keys().await? {
-                if !self.ttl_manager.is_expired(&key).await {
-                    keys.insert(key);
-                }
-            }
-        }
-        
-        Ok(keys.into_iter().collect::<Vec<String>>())
+    /// 键的当前 CAS 令牌等于 `cas_token` 时写入（标志为 0），TTL 为 None 时使用默认 TTL
+    pub async fn cas(&self, key: &str, value: Bytes, cas_token: u64, ttl_seconds: Option<u64>) -> CacheResult<CasResult> {
+        self.cas_with_flags(key, value, 0, cas_token, ttl_seconds).await
     }
 
-    /// 获取缓存大小
-    pub async fn len(&self) -> CacheResult<usize> {
-        let keys = self.keys().await?;
+    /// 键的当前 CAS 令牌等于 `cas_token` 时写入值及客户端标志
+    ///
+    /// 比较和写入期间持有键的独占锁，不会与其他写入交错。写入会清除键原有的标签
+    pub async fn cas_with_flags(&self, key: &str, value: Bytes, flags: u32, cas_token: u64, ttl_seconds: Option<u64>) -> CacheResult<CasResult> {
+        self.ensure_running().await?;
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+        let _guards = self.key_locks.write_many(std::iter::once(key)).await;
+
+        let result = if self.ttl_manager.is_expired(key).await {
+            self.remove_expired(key).await?;
+            CasResult::NotFound
+        } else {
+            let current = match self.l1_cache.peek_with_cas(key) {
+                Some((_, _, current)) => Some(current),
+                None => self.l2_cas_token(key).await?,
+            };
+            match current {
+                Some(current) if current == cas_token => {
+                    self.tag_index.remove(key);
+                    let options = CacheOptions { ttl_seconds, ..Default::default() };
+                    self.store_with_flags(key.to_string(), value, flags, &options).await?;
+                    CasResult::Stored
+                }
+                Some(_) => CasResult::Exists,
+                None if self.contains_key(key).await? => CasResult::Exists,
+                None => CasResult::NotFound,
+            }
+        };
+        self.operation_metrics.record(CacheOperation::Set, start_time);
+        Ok(result)
+    }
+
+    /// 只在 L2 中的条目的 CAS 令牌，未启用 L2 或条目没有令牌时返回 None
+    #[cfg_attr(not(feature = "melange-storage"), allow(unused_variables))]
+    async fn l2_cas_token(&self, key: &str) -> CacheResult<Option<u64>> {
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = self.active_l2() {
+            return self.observe_l2(l2_cache.cas_token(key).await);
+        }
+        Ok(None)
+    }
+
+    /// 将键的十进制计数值加上 `delta` 并返回新值，键不存在时返回 None
+    ///
+    /// 按 memcached 语义，超出 u64 范围时回绕到 0 开始；值不是最多 20 位的十进制数字时返回
+    /// [`CacheError::InvalidValue`]。读取和写回期间持有键的独占锁，并发的 incr/decr 不会丢失更新，
+    /// 写回保留客户端标志、剩余 TTL 和标签
+    pub async fn incr(&self, key: &str, delta: u64) -> CacheResult<Option<u64>> {
+        self.update_counter(key, |current| current.wrapping_add(delta)).await
+    }
+
+    /// 将键的十进制计数值减去 `delta` 并返回新值，结果最小为 0，规则同 [`Self::incr`]
+    pub async fn decr(&self, key: &str, delta: u64) -> CacheResult<Option<u64>> {
+        self.update_counter(key, |current| current.saturating_sub(delta)).await
+    }
+
+    async fn update_counter(&self, key: &str, apply: impl FnOnce(u64) -> u64) -> CacheResult<Option<u64>> {
+        self.rewrite_value(key, |value| {
+            let next = apply(parse_counter(&value)?);
+            Ok((Bytes::from(next.to_string()), next))
+        })
+        .await
+    }
+
+    /// 将 `data` 追加到键的已有值末尾，键不存在时返回 false
+    ///
+    /// 拼接和写回期间持有键的独占锁，并发的 append/prepend 不会交错或丢失，写回保留客户端标志、剩余 TTL 和标签
+    pub async fn append(&self, key: &str, data: Bytes) -> CacheResult<bool> {
+        Ok(self.rewrite_value(key, |value| Ok(([value, data].concat().into(), ()))).await?.is_some())
+    }
+
+    /// 将 `data` 插入到键的已有值开头，键不存在时返回 false，规则同 [`Self::append`]
+    pub async fn prepend(&self, key: &str, data: Bytes) -> CacheResult<bool> {
+        Ok(self.rewrite_value(key, |value| Ok(([data, value].concat().into(), ()))).await?.is_some())
+    }
+
+    /// 在键的独占锁内读取已有值，按 `rewrite` 计算新值后写回，保留客户端标志、剩余 TTL 和标签
+    async fn rewrite_value<T>(&self, key: &str, rewrite: impl FnOnce(Bytes) -> CacheResult<(Bytes, T)>) -> CacheResult<Option<T>> {
+        self.ensure_running().await?;
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+        let _guards = self.key_locks.write_many(std::iter::once(key)).await;
+        let Some((value, flags, _, _)) = self.get_with_flags(key, &CacheOptions::default()).await? else {
+            return Ok(None);
+        };
+
+        let (value, result) = rewrite(value)?;
+        // 永不过期的键写回时用 0 表示，避免套用默认 TTL；刚好到期的键至少保留 1 秒而不是变成永不过期
+        let options = CacheOptions {
+            ttl_seconds: Some(self.ttl_manager.get_ttl(key).await.map_or(0, |ttl| ttl.max(1))),
+            ..Default::default()
+        };
+        self.store_with_flags(key.to_string(), value, flags, &options).await?;
+        self.operation_metrics.record(CacheOperation::Set, start_time);
+        Ok(Some(result))
+    }
+
+    /// 对少量键执行全有或全无的写入，返回是否提交
+    ///
+    /// 所有 `SetIfVersion` 的版本检查都基于事务开始前的状态，任一检查失败时不应用任何操作并返回 false。
+    /// 超过绝对上限的值在应用任何操作之前被拒绝；应用过程中某个操作出错时，已应用的键按事务开始前的
+    /// 值、标志、TTL 和标签恢复后返回该错误。执行期间涉及的键不会被其他事务或普通写入修改，
+    /// 但读取可能观察到部分已应用的状态，这不是通用的 ACID 事务
+    pub async fn transaction(&self, ops: Vec<TxOp>) -> CacheResult<bool> {
+        self.ensure_running().await?;
+        self.ensure_writable()?;
+        let max_value_bytes = self.config.performance.absolute_max_value_bytes;
+        for op in &ops {
+            if let TxOp::SetIfVersion { value, .. } | TxOp::Set { value, .. } = op
+                && max_value_bytes > 0 && value.len() > max_value_bytes
+            {
+                return Err(CacheError::value_too_large(value.len(), max_value_bytes));
+            }
+        }
+        let _guards = self.key_locks.write_many(ops.iter().map(TxOp::key)).await;
+
+        // 记录事务开始前各键的状态，用于版本检查和出错时回滚
+        let mut snapshots: HashMap<String, Option<TxSnapshot>> = HashMap::new();
+        for op in &ops {
+            let key = op.key();
+            if snapshots.contains_key(key) {
+                continue;
+            }
+            let snapshot = match self.lookup_with_flags(key, &CacheOptions::default()).await? {
+                Some((value, flags, _, _)) => Some(TxSnapshot {
+                    value,
+                    flags,
+                    ttl_seconds: self.ttl_manager.get_ttl(key).await,
+                    tags: self.tag_index.tags(key),
+                }),
+                None => None,
+            };
+            snapshots.insert(key.to_string(), snapshot);
+        }
+
+        for op in &ops {
+            if let TxOp::SetIfVersion { key, version, .. } = op {
+                let current = snapshots[key.as_str()].as_ref()
+                    .map(|snapshot| value_version(&snapshot.value, snapshot.flags));
+                if current != Some(*version) {
+                    rat_logger::debug!("[CACHE] 事务版本检查失败: {}", key);
+                    return Ok(false);
+                }
+            }
+        }
+
+        let keys: Vec<String> = ops.iter().map(|op| op.key().to_string()).collect();
+        for (index, op) in ops.into_iter().enumerate() {
+            if let Err(e) = self.apply_tx_op(op).await {
+                // 出错的操作可能已写入部分层，一并恢复
+                rat_logger::warn!("[CACHE] 事务操作失败，回滚已应用的操作: {}", e);
+                let mut restored = HashSet::new();
+                for key in &keys[..=index] {
+                    if restored.insert(key.as_str()) {
+                        self.restore_tx_snapshot(key, snapshots[key.as_str()].as_ref()).await;
+                    }
+                }
+                return Err(e);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn apply_tx_op(&self, op: TxOp) -> CacheResult<()> {
+        match op {
+            TxOp::SetIfVersion { key, value, ttl_seconds, .. } | TxOp::Set { key, value, ttl_seconds } => {
+                let options = CacheOptions {
+                    ttl_seconds,
+                    ..Default::default()
+                };
+                self.tag_index.remove(&key);
+                self.store_with_flags(key, value, 0, &options).await?;
+            }
+            TxOp::Delete { key } => {
+                self.delete_internal(&key).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 将键恢复到事务开始前的状态，恢复失败只记录日志
+    async fn restore_tx_snapshot(&self, key: &str, snapshot: Option<&TxSnapshot>) {
+        let result = match snapshot {
+            Some(snapshot) => {
+                let options = CacheOptions {
+                    ttl_seconds: Some(snapshot.ttl_seconds.map_or(0, |ttl| ttl.max(1))),
+                    ..Default::default()
+                };
+                self.store_with_flags(key.to_string(), snapshot.value.clone(), snapshot.flags, &options).await
+                    .and_then(|_| self.tag_index.update(key, &snapshot.tags))
+            }
+            None => self.delete_internal(key).await.map(|_| ()),
+        };
+        if let Err(e) = result {
+            rat_logger::error!("[CACHE] 事务回滚失败: {} - {}", key, e);
+        }
+    }
+
+    /// 删除所有带有该标签的条目，返回实际删除的条目数
+    ///
+    /// 标签索引只在内存中维护，重启前写入的条目不会被按标签删除
+    pub async fn invalidate_tag(&self, tag: &str) -> CacheResult<u64> {
+        self.ensure_running().await?;
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+        let mut deleted = 0;
+        for key in self.tag_index.take(tag) {
+            let _guard = self.key_locks.read(&key).await;
+            if self.delete_internal(&key).await? {
+                deleted += 1;
+            }
+        }
+        self.operation_metrics.record(CacheOperation::Delete, start_time);
+        rat_logger::debug!("[CACHE] 按标签删除: {} ({} 个条目)", tag, deleted);
+        Ok(deleted)
+    }
+
+    /// 标签索引中带标签的键数
+    pub fn tagged_keys(&self) -> usize {
+        self.tag_index.len()
+    }
+
+    /// 清空缓存
+    pub async fn clear(&self) -> CacheResult<()> {
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+        let result = self.clear_layers().await;
+        self.operation_metrics.record(CacheOperation::Clear, start_time);
+        result
+    }
+
+    async fn clear_layers(&self) -> CacheResult<()> {
+        // 清空 L1 和 L2（如果存在）
+        self.l1_cache.clear().await?;
+        self.tag_index.clear();
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = &self.l2_cache {
+            l2_cache.clear().await?;
+        }
+        
+        // TTL 管理器会自动清理
+        
+        rat_logger::debug!("[CACHE] 缓存已清空");
+        
+        Ok(())
+    }
+
+    /// 检查键是否存在
+    pub async fn contains_key<'a>(&self, key: impl Into<CacheKeyRef<'a>>) -> CacheResult<bool> {
+        let key = key.into();
+        let key = key.as_str();
+
+        // 检查 TTL
+        if self.ttl_manager.is_expired(key).await {
+            self.remove_expired(key).await?;
+            return Ok(false);
+        }
+        
+        // 检查 L1
+        if self.l1_cache.contains_key(key) {
+            return Ok(true);
+        }
+        
+        // 检查 L2（如果存在）
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = self.active_l2() {
+            self.observe_l2(l2_cache.contains_key(key).await)
+        } else {
+            Ok(false)
+        }
+        #[cfg(not(feature = "melange-storage"))]
+        {
+            Ok(false)
+        }
+    }
+
+    /// 获取所有键
+    pub async fn keys(&self) -> CacheResult<Vec<String>> {
+        let mut keys = std::collections::HashSet::<String>::new();
+        
+        // 收集 L1 键
+        for key in self.l1_cache.keys() {
+            if !self.ttl_manager.is_expired(&key).await {
+                keys.insert(key);
+            }
+        }
+        
+        // 收集 L2 键（如果存在）
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = &self.l2_cache {
+            for key in l2_cache.keys().await? {
+                if !self.ttl_manager.is_expired(&key).await {
+                    keys.insert(key);
+                }
+            }
+        }
+        
+        Ok(keys.into_iter().collect::<Vec<String>>())
+    }
+
+    /// 获取以 `prefix` 开头的未过期键（如 `user:123:` 下的所有键）
+    ///
+    /// L1 只比较内存中的键，L2 按前缀逐个分区扫描，不会像 [`keys`](Self::keys) 一样读取全部键。
+    /// 匹配的键很多时可使用 [`scan_prefix_stream`](Self::scan_prefix_stream)
+    pub async fn scan_prefix(&self, prefix: &str) -> CacheResult<Vec<String>> {
+        let mut keys = std::collections::HashSet::new();
+        for key in self.l1_cache.keys_with_prefix(prefix) {
+            if !self.ttl_manager.is_expired(&key).await {
+                keys.insert(key);
+            }
+        }
+
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = self.active_l2() {
+            for partition in 0..l2_cache.partition_count() {
+                for key in self.observe_l2(l2_cache.keys_with_prefix(prefix, partition).await)? {
+                    if !keys.contains(&key) && !self.ttl_manager.is_expired(&key).await {
+                        keys.insert(key);
+                    }
+                }
+            }
+        }
+
+        Ok(keys.into_iter().collect())
+    }
+
+    /// 通过通道逐个返回以 `prefix` 开头的未过期键，L2 每次只读取一个分区的匹配键
+    ///
+    /// 先返回 L1 中的键，再返回仅在 L2 中的键。接收端被丢弃或缓存关闭时扫描停止，
+    /// L2 读取失败时发送错误后结束
+    pub fn scan_prefix_stream(&self, prefix: &str) -> mpsc::Receiver<CacheResult<String>> {
+        let (sender, receiver) = mpsc::channel(self.config.performance.batch_size.max(1));
+        let cache = self.clone();
+        let prefix = prefix.to_string();
+        let mut stop = self.background_tasks.stop_signal();
+
+        self.background_tasks.spawn(async move {
+            tokio::select! {
+                _ = cache.send_prefix_keys(&prefix, &sender) => {}
+                _ = stop.wait_for(|stopped| *stopped) => {}
+            }
+        });
+        receiver
+    }
+
+    /// 向通道发送以 `prefix` 开头的未过期键，接收端关闭时提前返回
+    async fn send_prefix_keys(&self, prefix: &str, sender: &mpsc::Sender<CacheResult<String>>) {
+        // 记录已发送的 L1 键，避免 L2 中的副本重复发送
+        let mut sent = std::collections::HashSet::new();
+        for key in self.l1_cache.keys_with_prefix(prefix) {
+            if self.ttl_manager.is_expired(&key).await {
+                continue;
+            }
+            if sender.send(Ok(key.clone())).await.is_err() {
+                return;
+            }
+            sent.insert(key);
+        }
+
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = self.active_l2() {
+            for partition in 0..l2_cache.partition_count() {
+                let keys = match self.observe_l2(l2_cache.keys_with_prefix(prefix, partition).await) {
+                    Ok(keys) => keys,
+                    Err(e) => {
+                        let _ = sender.send(Err(e)).await;
+                        return;
+                    }
+                };
+                for key in keys {
+                    if sent.contains(&key) || self.ttl_manager.is_expired(&key).await {
+                        continue;
+                    }
+                    if sender.send(Ok(key)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// 遍历所有条目并用 `f` 重写，返回重写的条目数（用于批量迁移值的编码或压缩方式）
+    ///
+    /// `f` 返回新值时重新写入并保留剩余 TTL、客户端标志和标签，返回 None 时删除该条目。
+    /// 读取不计入命中统计也不刷新访问信息；L2 逐个分区扫描键，值按 `performance.batch_size`
+    /// 分批读取，仅在 L2 中的条目重写后仍只写入 L2
+    pub async fn rewrite_all<F>(&self, f: F) -> CacheResult<u64>
+    where
+        F: Fn(&str, Bytes) -> Option<Bytes>,
+    {
+        self.ensure_running().await?;
+        self.ensure_writable()?;
+        let batch_size = self.config.performance.batch_size.max(1);
+
+        let mut rewritten = 0;
+        let l1_keys = self.l1_cache.keys();
+        for batch in l1_keys.chunks(batch_size) {
+            let mut entries = Vec::with_capacity(batch.len());
+            for key in batch {
+                if self.ttl_manager.is_expired(key).await {
+                    continue;
+                }
+                if let Some((value, flags)) = self.l1_cache.peek_with_flags(key) {
+                    entries.push((key.clone(), value, flags));
+                }
+            }
+            rewritten += self.rewrite_entries(entries, &f).await?;
+        }
+
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = self.active_l2() {
+            // L1 中的键已按 L1 的值重写，跳过其 L2 副本
+            let l1_keys: std::collections::HashSet<String> = l1_keys.into_iter().collect();
+            for partition in 0..l2_cache.partition_count() {
+                let keys = self.observe_l2(l2_cache.keys_with_prefix("", partition).await)?;
+                for batch in keys.chunks(batch_size) {
+                    let mut entries = Vec::with_capacity(batch.len());
+                    for key in batch {
+                        if l1_keys.contains(key) || self.ttl_manager.is_expired(key).await {
+                            continue;
+                        }
+                        if let Some((value, flags)) = self.observe_l2(l2_cache.peek_with_flags(key).await)? {
+                            entries.push((key.clone(), value, flags));
+                        }
+                    }
+                    rewritten += self.rewrite_entries(entries, &f).await?;
+                }
+            }
+        }
+
+        rat_logger::debug!("[CACHE] 批量重写完成，共 {} 个条目", rewritten);
+        Ok(rewritten)
+    }
+
+    /// 用 `f` 重写一批已读取的条目（键, 值, 标志），返回处理的条目数
+    async fn rewrite_entries<F>(&self, entries: Vec<(String, Bytes, u32)>, f: &F) -> CacheResult<u64>
+    where
+        F: Fn(&str, Bytes) -> Option<Bytes>,
+    {
+        let mut rewritten = 0;
+        for (key, value, flags) in entries {
+            let ttl_seconds = match self.ttl_manager.get_ttl(&key).await {
+                // 读取后已过期
+                Some(0) => continue,
+                Some(ttl) => Some(ttl),
+                // 永不过期（TTL 为 0），避免套用默认 TTL
+                None => Some(0),
+            };
+
+            match f(&key, value) {
+                Some(new_value) => {
+                    let options = CacheOptions {
+                        ttl_seconds,
+                        force_l2: !self.l1_cache.contains_key(&key),
+                        ..Default::default()
+                    };
+                    // 不经过 set 路径，保留键的标签
+                    let _guard = self.key_locks.read(&key).await;
+                    self.store_with_flags(key.clone(), new_value, flags, &options).await?;
+                }
+                None => {
+                    let _guard = self.key_locks.read(&key).await;
+                    self.delete_internal(&key).await?;
+                }
+            }
+            rewritten += 1;
+        }
+        Ok(rewritten)
+    }
+
+    /// 获取缓存大小
+    pub async fn len(&self) -> CacheResult<usize> {
+        let keys = self.keys().await?;
         Ok(keys.len())
     }
 
-    /// 检查缓存是否为空
-    pub async fn is_empty(&self) -> CacheResult<bool> {
-        let len = self.len().await?;
-        Ok(len == 0)
+    /// 检查缓存是否为空
+    pub async fn is_empty(&self) -> CacheResult<bool> {
+        let len = self.len().await?;
+        Ok(len == 0)
+    }
+
+
+
+    /// 获取双层缓存汇总统计
+    pub async fn get_stats(&self) -> CacheStats {
+        CacheStats {
+            l1: self.get_l1_stats().await,
+            #[cfg(feature = "melange-storage")]
+            l2: self.get_l2_stats().await,
+            operations: self.get_operation_stats(),
+        }
+    }
+
+    /// 获取 L1、L2 与 TTL 的合并统计，命中与未命中按两层合计，不依赖 `melange-storage` 特性
+    pub async fn get_combined_stats(&self) -> CombinedCacheStats {
+        let operations = self.get_operation_stats();
+        CombinedCacheStats {
+            l1: self.get_l1_stats().await,
+            #[cfg(feature = "melange-storage")]
+            l2: match &self.l2_cache {
+                Some(l2_cache) => Some(l2_cache.get_stats().await),
+                None => None,
+            },
+            ttl: self.get_ttl_stats().await,
+            hits: operations.hits(),
+            misses: operations.misses,
+        }
+    }
+
+    /// 获取全部计数器和仪表的快照，用于导出到 StatsD、OpenTelemetry 等监控系统
+    pub async fn metrics_snapshot(&self) -> MetricsSnapshot {
+        let operations = self.get_operation_stats();
+        let l1_stats = self.get_l1_stats().await;
+        let l1 = L1MetricsSnapshot {
+            entries: l1_stats.entry_count,
+            memory_bytes: l1_stats.memory_usage,
+            logical_bytes: l1_stats.logical_bytes,
+            max_memory_bytes: l1_stats.max_memory,
+            max_entries: l1_stats.max_entries,
+            evictions: l1_stats.eviction_stats.total_evictions(),
+            evicted_bytes: l1_stats.eviction_stats.evicted_bytes(),
+            eviction_rate: l1_stats.eviction_rate,
+        };
+
+        #[cfg(feature = "melange-storage")]
+        let l2 = match &self.l2_cache {
+            Some(l2_cache) => {
+                let stats = l2_cache.get_stats().await;
+                Some(L2MetricsSnapshot {
+                    entries: stats.entry_count,
+                    disk_bytes: stats.estimated_disk_usage,
+                    logical_bytes: stats.logical_bytes,
+                    reads: stats.reads,
+                    writes: stats.writes,
+                    deletes: stats.deletes,
+                    hits: stats.hits,
+                    misses: stats.misses,
+                    compactions: stats.compactions,
+                    compression_original_bytes: stats.compression_by_algorithm.values().map(|a| a.original_bytes).sum(),
+                    compression_stored_bytes: stats.compression_by_algorithm.values().map(|a| a.stored_bytes).sum(),
+                    avg_read_latency_ms: stats.avg_read_latency_ms,
+                    avg_write_latency_ms: stats.avg_write_latency_ms,
+                    write_rate_bytes_per_sec: stats.write_rate_bytes_per_sec,
+                    throttled_writes: stats.throttled_writes,
+                    spawn_blocking_queue_wait_avg_us: stats.spawn_blocking_queue_wait.avg_wait_us,
+                    spawn_blocking_queue_wait_max_us: stats.spawn_blocking_queue_wait.max_wait_us,
+                    busy_rejections: stats.spawn_blocking_queue_wait.busy_rejections,
+                    retries: stats.retries,
+                    retries_exhausted: stats.retries_exhausted,
+                    degraded: self.is_l2_degraded(),
+                })
+            }
+            None => None,
+        };
+        #[cfg(not(feature = "melange-storage"))]
+        let l2 = None;
+
+        MetricsSnapshot {
+            timestamp: self.ttl_manager.clock().now(),
+            hits: operations.hits(),
+            misses: operations.misses,
+            l1,
+            l2,
+            operations,
+            process_rss_bytes: current_rss(),
+        }
+    }
+
+    /// 获取按操作类型划分的统计
+    pub fn get_operation_stats(&self) -> OperationStats {
+        self.operation_metrics.snapshot()
+    }
+
+    /// 获取单个键的命中、未命中、提升和驱逐次数
+    ///
+    /// 需通过 `performance.key_stats_top_n` 开启，只跟踪最活跃的前 N 个键，其他键返回 None
+    pub async fn key_stats(&self, key: &str) -> Option<KeyStats> {
+        self.key_stats.as_ref()?.get(key)
+    }
+
+    /// 整理 L1 内存：将条目复制到新分配的紧凑缓冲区并释放索引结构的多余容量，
+    /// 启用 mimalloc 时随后强制回收空闲堆内存，使碎片化的内存归还给系统
+    pub async fn defragment_l1(&self) -> CacheResult<L1DefragReport> {
+        self.ensure_running().await?;
+        let rss_before = current_rss();
+        let entries = self.l1_cache.defragment().await;
+        release_free_heap_memory();
+
+        let rss_after = current_rss();
+        rat_logger::info!("[CACHE] L1 内存整理完成: {} 个条目，RSS {:?} -> {:?}", entries, rss_before, rss_after);
+        Ok(L1DefragReport { entries, rss_before, rss_after })
+    }
+
+    /// 回收内存：收缩 L1 存储、顺序队列和 TTL 索引的多余容量，压缩 L2，并尽可能将空闲内存归还给分配器
+    ///
+    /// 适用于突发负载后大量删除、容量却仍被占用的长期运行进程。需要遍历 L1 结构并扫描全部 L2 元数据，
+    /// 开销较大，应在空闲时段调用。与 [`defragment_l1`](Self::defragment_l1) 不同，不会复制条目数据
+    pub async fn shrink_to_fit(&self) -> CacheResult<()> {
+        self.ensure_running().await?;
+        self.l1_cache.shrink_to_fit().await;
+
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = &self.l2_cache {
+            l2_cache.compact().await?;
+        }
+
+        release_free_heap_memory();
+        rat_logger::info!("[CACHE] 内存回收完成");
+        Ok(())
+    }
+
+    /// 获取 L1 缓存统计
+    pub async fn get_l1_stats(&self) -> L1CacheStats {
+        self.l1_cache.get_stats().await
+    }
+
+    /// 获取新写入 L2 的数据当前使用的压缩算法
+    #[cfg(feature = "melange-storage")]
+    pub fn compression_algorithm(&self) -> CompressionAlgorithm {
+        self.compressor.algorithm()
+    }
+
+    /// 运行时切换新写入 L2 的数据使用的压缩算法（支持 None、LZ4，启用 `snappy` 特性后支持 Snappy）
+    ///
+    /// 切换不会重写已有条目：它们按各自记录的压缩算法解压，直到被重新写入。
+    /// MelangeDB 的页级压缩在打开数据库时确定，不受此设置影响
+    #[cfg(feature = "melange-storage")]
+    pub async fn set_compression_algorithm(&self, algorithm: CompressionAlgorithm) -> CacheResult<()> {
+        if self.l2_cache.is_none() {
+            return Err(CacheError::config_error("切换压缩算法需要启用 L2 缓存"));
+        }
+        self.compressor.set_algorithm(algorithm)?;
+        rat_logger::info!("[CACHE] 压缩算法已切换为 {:?}，已有条目保持不变", algorithm);
+        Ok(())
+    }
+
+    /// 分析 L1 访问模式（近期访问占比、访问频率基尼系数、近期性与频率的相关性），辅助选择驱逐策略
+    pub async fn access_pattern_report(&self) -> AccessPatternReport {
+        self.l1_cache.access_pattern_report().await
+    }
+
+    /// 按访问次数返回前 N 个热点键，合并 L1 的 LFU 计数与 L2 元数据中的访问次数
+    ///
+    /// 两层中都存在的键计数相加；L2 最多扫描 `HOT_KEYS_SCAN_LIMIT` 条元数据，L2 读取失败时仅返回 L1 的结果
+    pub async fn hot_keys(&self, top_n: usize) -> Vec<(String, u64)> {
+        #[cfg_attr(not(feature = "melange-storage"), allow(unused_mut))]
+        let mut counts: HashMap<String, u64> = self.l1_cache.top_accessed_keys(top_n).into_iter().collect();
+
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = &self.l2_cache {
+            let l1_candidates: Vec<String> = counts.keys().cloned().collect();
+            let merged = async {
+                // L1 候选键补上 L2 计数，L2 候选键补上 L1 计数
+                let l2_counts = l2_cache.access_counts(l1_candidates.clone()).await?;
+                for (key, l2_count) in l1_candidates.into_iter().zip(l2_counts) {
+                    *counts.entry(key).or_default() += l2_count;
+                }
+                for (key, l2_count) in l2_cache.top_accessed_keys(top_n).await? {
+                    counts.entry(key).or_insert_with_key(|key| self.l1_cache.access_count(key) + l2_count);
+                }
+                CacheResult::Ok(())
+            };
+            if let Err(e) = merged.await {
+                rat_logger::warn!("[CACHE] 读取 L2 访问次数失败，热点键仅包含 L1 统计: {}", e);
+            }
+        }
+
+        let mut hot_keys: Vec<(String, u64)> = counts.into_iter().collect();
+        hot_keys.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        hot_keys.truncate(top_n);
+        hot_keys
+    }
+
+    /// 仍在运行的后台任务数量（TTL 清理、TTL 降级、驱逐事件定时投递、预读等）
+    pub fn background_task_count(&self) -> usize {
+        self.background_tasks.count()
+            + usize::from(self.ttl_manager.is_cleanup_task_running())
+            + usize::from(self.l1_cache.is_eviction_task_running())
+    }
+
+    /// 获取编译特性、压缩算法、L2 状态和运行时间
+    pub fn runtime_info(&self) -> RuntimeInfo {
+        #[cfg(feature = "melange-storage")]
+        let (l2_enabled, l2_healthy) = (self.l2_cache.is_some(), self.l2_cache.is_some() && !self.is_l2_degraded());
+        #[cfg(not(feature = "melange-storage"))]
+        let (l2_enabled, l2_healthy) = (false, false);
+        RuntimeInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            features: RuntimeInfo::compiled_features(),
+            allocator: if cfg!(feature = "mimalloc-allocator") { "mimalloc" } else { "system" },
+            compression_algorithm: self.compressor.algorithm(),
+            l2_enabled,
+            l2_healthy,
+            uptime_secs: self.started_at.elapsed().as_secs(),
+        }
+    }
+
+    /// 获取缓存构建时使用的配置（运行期间不变）
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    /// 订阅 L1 条目移除事件（容量驱逐、访问时过期、显式删除），只包含订阅之后的事件
+    ///
+    /// 事件通过有界广播发布，驱逐路径不会等待消费者；消费者落后超过缓冲容量时最旧的事件被丢弃，
+    /// 丢弃数可通过 [`EvictionEventStream::dropped`] 和 [`eviction_events_dropped`](Self::eviction_events_dropped) 查看
+    pub fn eviction_events(&self) -> EvictionEventStream {
+        self.l1_cache.subscribe_evictions()
+    }
+
+    /// 所有驱逐事件流因消费者落后而丢弃的事件总数
+    pub fn eviction_events_dropped(&self) -> u64 {
+        self.l1_cache.eviction_events_dropped()
+    }
+
+    /// 订阅键空间事件（写入、删除、过期、L1 容量驱逐），只包含订阅之后的事件
+    ///
+    /// 需开启 [`CacheConfig::keyspace_events`]，否则返回配置错误。发布不会等待消费者，
+    /// 缓冲容量与驱逐事件流相同，落后时最旧的事件被丢弃
+    pub fn subscribe_events(&self) -> CacheResult<CacheEventStream> {
+        self.keyspace_events
+            .as_ref()
+            .map(|events| events.subscribe())
+            .ok_or_else(|| CacheError::config_error("未开启键空间事件（keyspace_events）"))
+    }
+
+    /// 获取 TTL 管理器统计（含清理通道积压和丢弃的命令数）
+    pub async fn get_ttl_stats(&self) -> TtlStats {
+        self.ttl_manager.get_stats().await
+    }
+
+    /// L2 是否因连续存储故障处于降级（仅 L1）状态
+    #[cfg(feature = "melange-storage")]
+    pub fn is_l2_degraded(&self) -> bool {
+        self.l2_health.as_ref().is_some_and(|health| health.is_degraded())
+    }
+
+    /// 获取 L2 缓存统计
+    #[cfg(feature = "melange-storage")]
+    pub async fn get_l2_stats(&self) -> L2CacheStats {
+        if let Some(l2_cache) = &self.l2_cache {
+            l2_cache.get_stats().await
+        } else {
+            L2CacheStats::default()
+        }
+    }
+
+    
+    /// 获取缓存命中率（百分比），尚无读取时为 None
+    ///
+    /// 按 [`Self::get_operation_stats`] 中的读取命中（L1 与 L2 合计）和未命中次数计算
+    pub async fn get_hit_rate(&self) -> Option<f64> {
+        let stats = self.get_operation_stats();
+        let total = stats.hits() + stats.misses;
+        (total > 0).then(|| stats.hits() as f64 / total as f64 * 100.0)
+    }
+
+    /// 压缩 L2 缓存
+    #[cfg(feature = "melange-storage")]
+    pub async fn compact(&self) -> CacheResult<()> {
+        if let Some(l2_cache) = &self.l2_cache {
+            l2_cache.compact().await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 将指定键从 L2 提升到 L1，返回提升的键数量
+    ///
+    /// 用于按已知访问模式提前预热。已在 L1 中、已过期或不在 L2 中的键会被跳过，
+    /// L1 容量不足时按驱逐策略腾出空间；L2 降级时不提升任何键
+    #[cfg(feature = "melange-storage")]
+    pub async fn promote(&self, keys: &[String]) -> CacheResult<usize> {
+        let l2_cache = match self.active_l2() {
+            Some(l2_cache) if self.promotion_allowed() => l2_cache,
+            _ => return Ok(0),
+        };
+
+        let mut promoted = 0;
+        for key in keys {
+            if self.l1_cache.contains_key(key) || self.ttl_manager.is_expired(key).await {
+                continue;
+            }
+            let (value, flags) = match self.observe_l2(l2_cache.get_with_flags(key).await)? {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            let ttl = self.ttl_manager.get_ttl(key).await;
+            match self.l1_cache.set_with_flags(key.clone(), value, ttl, flags).await {
+                Ok(_) => {
+                    promoted += 1;
+                    self.record_key_event(key, KeyEvent::Promotion);
+                }
+                Err(e) => rat_logger::warn!("[CACHE] 提升到 L1 失败: {} - {}", key, e),
+            }
+        }
+
+        rat_logger::debug!("[CACHE] 手动提升 {} 个键到 L1", promoted);
+        Ok(promoted)
+    }
+
+    /// 执行一次基于 TTL 的 L1→L2 提前降级，返回降级的键数量
+    ///
+    /// 剩余 TTL 低于配置比例且不在 L2 中的 L1 键会被写入 L2（剩余 TTL 加宽限时间），
+    /// 并从 L1 移除，使宽限期内的读取命中 L2
+    #[cfg(feature = "melange-storage")]
+    pub async fn demote_expiring_keys(&self) -> CacheResult<usize> {
+        let (l2_cache, l2_config) = match (self.active_l2(), &self.config.l2) {
+            (Some(l2_cache), Some(l2_config)) if l2_config.ttl_demotion_enabled => (l2_cache, l2_config),
+            _ => return Ok(0),
+        };
+
+        let candidates = self.ttl_manager
+            .get_keys_near_expiry(l2_config.ttl_demotion_ratio, self.config.ttl.max_cleanup_entries)
+            .await;
+
+        let mut demoted = 0;
+        for (key, remaining) in candidates {
+            let (value, flags) = match self.l1_cache.peek_with_flags(&key) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            if self.observe_l2(l2_cache.contains_key(&key).await)? {
+                continue;
+            }
+
+            // 超出 L2 写入带宽时保留在 L1
+            if !l2_cache.try_reserve_write(value.len(), false) {
+                continue;
+            }
+
+            let ttl = remaining + l2_config.ttl_demotion_grace_seconds;
+            self.observe_l2(l2_cache.set_with_flags(key.clone(), value, Some(ttl), flags).await)?;
+            // L1 删除会移除 TTL 记录，需按 L2 的过期时间重新登记
+            self.l1_cache.remove(&key, EvictionReason::Demoted).await?;
+            self.ttl_manager.add_key(key.clone(), Some(ttl)).await?;
+
+            rat_logger::debug!("[CACHE] TTL 降级到 L2: {} (剩余 {}秒，L2 TTL {}秒)", key, remaining, ttl);
+            demoted += 1;
+        }
+
+        Ok(demoted)
+    }
+
+    /// 启动 TTL 降级后台任务
+    #[cfg(feature = "melange-storage")]
+    fn start_ttl_demotion_task(&self) {
+        let cache = self.clone();
+        let interval_secs = self.config.ttl.cleanup_interval;
+        let mut stop = self.background_tasks.stop_signal();
+
+        self.background_tasks.spawn(async move {
+            let mut ticker = interval(Duration::from_secs(interval_secs));
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = stop.wait_for(|stopped| *stopped) => break,
+                }
+                if !*cache.is_running.read().await {
+                    break;
+                }
+                if let Err(e) = cache.demote_expiring_keys().await {
+                    rat_logger::warn!("[CACHE] TTL 降级失败: {}", e);
+                }
+            }
+        });
+    }
+
+    /// 手动触发过期清理
+    pub async fn cleanup_expired(&self) -> CacheResult<u64> {
+        // 手动触发过期清理（简化实现）
+        Ok(0)
+    }
+
+    /// 获取剩余 TTL
+    pub async fn get_ttl(&self, key: &str) -> Option<u64> {
+        self.ttl_manager.get_ttl(key).await
+    }
+
+    /// 设置 TTL（0 表示永不过期），同时更新 L2 元数据中的过期时间
+    pub async fn set_ttl(&self, key: &str, ttl_seconds: u64) -> CacheResult<()> {
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+        self.extend_ttl(key, ttl_seconds).await?;
+        self.operation_metrics.record(CacheOperation::Ttl, start_time);
+        Ok(())
+    }
+
+    /// 刷新已存在键的 TTL，键不存在或已过期时返回 false
+    pub async fn touch(&self, key: &str, ttl_seconds: u64) -> CacheResult<bool> {
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+        let exists = self.contains_key(key).await?;
+        if exists {
+            self.extend_ttl(key, ttl_seconds).await?;
+        }
+        self.operation_metrics.record(CacheOperation::Touch, start_time);
+        Ok(exists)
+    }
+
+    /// 读取键的值并将其 TTL 刷新为 `new_ttl_seconds`（0 表示永不过期），键不存在或已过期时返回 None
+    ///
+    /// 读取和刷新期间持有键的独占锁，其间键不会被写入、删除或按旧 TTL 过期删除
+    pub async fn get_and_touch(&self, key: &str, new_ttl_seconds: u64) -> CacheResult<Option<Bytes>> {
+        Ok(self.get_entry_and_touch(key, new_ttl_seconds).await?.map(|entry| entry.value))
+    }
+
+    /// 同 [`get_and_touch`](Self::get_and_touch)，同时返回客户端标志和刷新后的 TTL
+    pub async fn get_entry_and_touch(&self, key: &str, new_ttl_seconds: u64) -> CacheResult<Option<CacheEntry>> {
+        self.ensure_writable()?;
+        let _guards = self.key_locks.write_many(std::iter::once(key)).await;
+        let Some((value, flags, _, _)) = self.get_with_flags(key, &CacheOptions::default()).await? else {
+            return Ok(None);
+        };
+
+        let start_time = Instant::now();
+        self.extend_ttl(key, new_ttl_seconds).await?;
+        self.operation_metrics.record(CacheOperation::Touch, start_time);
+        Ok(Some(CacheEntry {
+            value,
+            flags,
+            ttl_seconds: self.ttl_manager.get_ttl(key).await,
+            stale: false,
+        }))
+    }
+
+    /// 刷新 TTL 索引和 L2 元数据中的过期时间（0 表示永不过期）
+    async fn extend_ttl(&self, key: &str, ttl_seconds: u64) -> CacheResult<()> {
+        self.ttl_manager.update_key(key.to_string(), Some(ttl_seconds)).await?;
+        self.set_l2_expiry(key, ttl_seconds).await
+    }
+
+    /// 更新 L2 元数据中的过期时间（0 表示永不过期），L2 降级时跳过
+    #[cfg_attr(not(feature = "melange-storage"), allow(unused_variables))]
+    async fn set_l2_expiry(&self, key: &str, ttl_seconds: u64) -> CacheResult<()> {
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = self.active_l2() {
+            self.observe_l2(l2_cache.set_expiry(key, ttl_seconds).await)?;
+        }
+        Ok(())
+    }
+
+    /// 原子地读取并修改键的剩余 TTL（规则见 [`TtlManager::update_ttl_atomic`]），修改后同步到 L2 元数据
+    pub async fn update_ttl_atomic<F>(&self, key: &str, f: F) -> CacheResult<Option<u64>>
+    where
+        F: FnOnce(u64) -> Option<u64>,
+    {
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+        let mut new_ttl = None;
+        let result = self.ttl_manager.update_ttl_atomic(key, |remaining| {
+            new_ttl = f(remaining);
+            new_ttl
+        }).await?;
+        if let Some(ttl) = new_ttl {
+            self.set_l2_expiry(key, ttl).await?;
+        }
+        self.operation_metrics.record(CacheOperation::Ttl, start_time);
+        Ok(result)
+    }
+
+    /// 移除 TTL，同时清除 L2 元数据中的过期时间
+    pub async fn remove_ttl(&self, key: &str) -> CacheResult<()> {
+        self.ensure_writable()?;
+        let start_time = Instant::now();
+        self.ttl_manager.remove_key(key).await;
+        self.set_l2_expiry(key, 0).await?;
+        self.operation_metrics.record(CacheOperation::Ttl, start_time);
+        Ok(())
+    }
+
+    /// 关闭缓存
+    ///
+    /// 关闭开始后，读取、写入和删除返回 [`CacheError::ShuttingDown`]
+    pub async fn shutdown(&self) -> CacheResult<()> {
+        rat_logger::info!("[CACHE] 开始关闭 RatMemCache...");
+        
+        // 设置停止标志
+        {
+            let mut running = self.is_running.write().await;
+            *running = false;
+        }
+        
+        // 通知后台任务退出并等待全部结束
+        self.background_tasks.shutdown().await;
+        self.ttl_manager.stop().await;
+
+        // 保存 TTL 索引，下次启动时无需扫描全部元数据
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = &self.l2_cache
+            && let Err(e) = l2_cache.save_ttl_index().await
+        {
+            rat_logger::warn!("[CACHE] 保存 TTL 索引失败: {}", e);
+        }
+
+        // 停止定时投递并投递尚未投递的驱逐事件
+        self.l1_cache.stop_eviction_events().await;
+
+        rat_logger::info!("[CACHE] RatMemCache 已关闭");
+        Ok(())
+    }
+
+    /// 内部删除方法
+    async fn delete_internal(&self, key: &str) -> CacheResult<bool> {
+        self.remove_entry(key, EvictionReason::Explicit).await
+    }
+
+    /// 删除已过期的键
+    async fn remove_expired(&self, key: &str) -> CacheResult<bool> {
+        self.remove_entry(key, EvictionReason::Ttl).await
+    }
+
+    async fn remove_entry(&self, key: &str, reason: EvictionReason) -> CacheResult<bool> {
+        let mut deleted = false;
+        
+        // 从 L1 删除
+        if self.l1_cache.remove(key, reason).await? {
+            deleted = true;
+        }
+        
+        // 从 L2 删除（如果存在）
+        #[cfg(feature = "melange-storage")]
+        if let Some(l2_cache) = self.active_l2()
+            && self.observe_l2(l2_cache.delete(key).await)?
+        {
+            deleted = true;
+        }
+        
+        // 移除 TTL 和标签
+        self.ttl_manager.remove_key(key).await;
+        self.tag_index.remove(key);
+        
+        if deleted {
+            rat_logger::debug!("[CACHE] 缓存删除: {}", key);
+            match reason {
+                EvictionReason::Explicit => self.publish_event(|| CacheEvent::Deleted { key: key.to_string() }),
+                EvictionReason::Ttl => self.publish_event(|| CacheEvent::Expired { key: key.to_string() }),
+                EvictionReason::Capacity | EvictionReason::Demoted => {}
+            }
+        }
+        
+        Ok(deleted)
+    }
+
+    /// 发布键空间事件，未开启或没有订阅者时不构造事件
+    fn publish_event(&self, event: impl FnOnce() -> CacheEvent) {
+        if let Some(events) = self.keyspace_events.as_ref().filter(|events| events.has_subscribers()) {
+            events.publish(event());
+        }
+    }
+
+    /// 判断是否应该写入 L2
+    #[cfg(feature = "melange-storage")]
+    async fn should_write_to_l2(&self, _key: &str, value: &Bytes, options: &CacheOptions) -> bool {
+        // 如果强制 L2，直接返回 true
+        if options.force_l2 {
+            return true;
+        }
+        
+        // 根据配置的写入策略决定
+        if let Some(l2_config) = &self.config.l2 {
+            match l2_config.l2_write_strategy.as_str() {
+                "always" => true,
+                "never" => false,
+                "size_based" => value.len() >= l2_config.l2_write_threshold,
+                "ttl_based" => options.ttl_seconds.unwrap_or(0) >= l2_config.l2_write_ttl_threshold,
+                "adaptive" => {
+                    // 自适应策略：基于 L1 使用率和数据大小
+                    let l1_stats = self.l1_cache.get_stats().await;
+                    let l1_usage_ratio = l1_stats.memory_usage as f64 / self.config.l1.max_memory as f64;
+
+                    l1_usage_ratio > 0.8 || value.len() >= l2_config.l2_write_threshold
+                },
+                "eviction_rate" => {
+                    // 驱逐速率策略：L1 驱逐越频繁，写入 L2 的大小阈值越低
+                    let low = l2_config.l2_write_eviction_rate_low;
+                    let high = l2_config.l2_write_eviction_rate_high;
+                    let pressure = ((self.l1_cache.eviction_rate() - low) / (high - low)).clamp(0.0, 1.0);
+                    let threshold = (l2_config.l2_write_threshold as f64 * (1.0 - pressure)) as usize;
+
+                    value.len() >= threshold
+                },
+                _ => false,
+            }
+        } else {
+            false
+        }
+    }
+}
+
+// 实现 Clone trait 以支持在异步任务中使用
+impl Clone for RatMemCache {
+    fn clone(&self) -> Self {
+        Self {
+            config: Arc::clone(&self.config),
+            l1_cache: Arc::clone(&self.l1_cache),
+            #[cfg(feature = "melange-storage")]
+            l2_cache: self.l2_cache.as_ref().map(|cache| Arc::clone(cache)),
+            #[cfg(feature = "melange-storage")]
+            l2_health: self.l2_health.clone(),
+            // transfer_router: Arc::clone(&self.transfer_router),
+            ttl_manager: Arc::clone(&self.ttl_manager),
+            compressor: Arc::clone(&self.compressor),
+            is_running: Arc::clone(&self.is_running),
+            operation_metrics: Arc::clone(&self.operation_metrics),
+            key_stats: self.key_stats.clone(),
+            background_tasks: Arc::clone(&self.background_tasks),
+            key_locks: Arc::clone(&self.key_locks),
+            tag_index: Arc::clone(&self.tag_index),
+            loader_locks: Arc::clone(&self.loader_locks),
+            keyspace_events: self.keyspace_events.clone(),
+            started_at: self.started_at,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "melange-storage"))]
+mod tests {
+    use super::*;
+    use crate::config::CacheConfigBuilder;
+    use crate::types::CacheKey;
+    use bytes::Bytes;
+    use tempfile::TempDir;
+
+    async fn create_test_cache() -> (RatMemCache, TempDir) {
+        create_test_cache_with(|_| {}, system_clock()).await
+    }
+
+    async fn create_test_cache_with(
+        configure_l2: impl FnOnce(&mut crate::config::L2Config),
+        clock: SharedClock,
+    ) -> (RatMemCache, TempDir) {
+        let l1_config = crate::config::L1Config {
+            max_memory: 1024 * 1024 * 1024, // 1GB
+            max_entries: 100_000,
+            eviction_strategy: crate::EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
+        };
+        create_test_cache_with_l1(l1_config, configure_l2, clock).await
+    }
+
+    async fn create_test_cache_with_l1(
+        l1_config: crate::config::L1Config,
+        configure_l2: impl FnOnce(&mut crate::config::L2Config),
+        clock: SharedClock,
+    ) -> (RatMemCache, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+
+        let mut l2_config = crate::config::L2Config {
+            enable_l2_cache: true,
+            data_dir: Some(temp_dir.path().to_path_buf()),
+            max_disk_size: 10 * 1024 * 1024, // 10MB
+            write_buffer_size: 1024 * 1024,  // 1MB
+            max_write_buffer_number: 3,
+            block_cache_size: 512 * 1024,    // 512KB
+            enable_lz4: true,
+            compression_threshold: 128,
+            compression_max_threshold: 1024 * 1024,
+            compression_level: 6,
+            background_threads: 2,
+            clear_on_startup: false,
+            confirm_clear_on_startup: false,
+            cache_size_mb: 256,
+            max_file_size_mb: 512,
+            smart_flush_enabled: true,
+            smart_flush_base_interval_ms: 100,
+            smart_flush_min_interval_ms: 20,
+            smart_flush_max_interval_ms: 500,
+            smart_flush_write_rate_threshold: 10000,
+            smart_flush_accumulated_bytes_threshold: 4 * 1024 * 1024,
+            cache_warmup_strategy: crate::config::CacheWarmupStrategy::Recent,
+            zstd_compression_level: None,
+            l2_write_strategy: "write_through".to_string(),
+            l2_write_threshold: 1024,
+            l2_write_ttl_threshold: 300,
+            l2_write_eviction_rate_low: 1.0,
+            l2_write_eviction_rate_high: 100.0,
+            ttl_demotion_enabled: false,
+            ttl_demotion_ratio: 0.1,
+            ttl_demotion_grace_seconds: 60,
+            compact_storage: false,
+            skip_reads_when_empty: true,
+            checksum_enabled: true,
+            evict_corrupted_entries: true,
+            metadata_update_sampling: 1.0,
+            l2_partitions: 1,
+            degrade_to_l1_on_l2_failure: false,
+            l2_max_write_bytes_per_sec: None,
+            blocking_queue_warn_ms: 100,
+            blocking_queue_max_wait_ms: None,
+            persist_ttl_index: true,
+            value_compression_algorithm: CompressionAlgorithm::Lz4,
+            key_prefix: String::new(),
+            l2_max_retries: 2,
+            l2_retry_backoff_ms: 10,
+        };
+        configure_l2(&mut l2_config);
+
+        let cache = RatMemCacheBuilder::new()
+            .l1_config(l1_config)
+            .l2_config(l2_config)
+            .ttl_config(crate::config::TtlConfig {
+                expire_seconds: Some(60),
+                cleanup_interval: 60,
+                max_cleanup_entries: 100,
+                lazy_expiration: true,
+                active_expiration: false, // 测试中禁用主动过期
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
+            })
+            .performance_config(crate::config::PerformanceConfig {
+                worker_threads: 4,
+                enable_concurrency: true,
+                read_write_separation: true,
+                batch_size: 100,
+                enable_warmup: false,
+                large_value_threshold: 10240, // 10KB
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 0,
+                max_tagged_keys: 100_000,
+            })
+            .logging_config(crate::config::LoggingConfig {
+                level: "debug".to_string(),
+                enable_colors: false,
+                show_timestamp: false,
+                enable_performance_logs: true,
+                enable_audit_logs: false,
+                enable_cache_logs: true,
+                enable_logging: true,
+                enable_async: false,
+                batch_size: 2048,
+                batch_interval_ms: 25,
+                buffer_size: 16384,
+            })
+            .clock(clock)
+            .build()
+            .await
+            .unwrap();
+        
+        (cache, temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_ttl_demotion_to_l2() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "never".to_string();
+            l2.ttl_demotion_enabled = true;
+            l2.ttl_demotion_ratio = 0.1;
+            l2.ttl_demotion_grace_seconds = 60;
+        }, clock.clone()).await;
+
+        let mut events = cache.eviction_events();
+        let key = "expiring".to_string();
+        let value = Bytes::from("durable value");
+        cache.set_with_ttl(key.clone(), value.clone(), 100).await.unwrap();
+        assert!(cache.l1_cache.contains_key(&key));
+
+        // 剩余 50% TTL，不降级
+        clock.advance(50);
+        assert_eq!(cache.demote_expiring_keys().await.unwrap(), 0);
+
+        // 剩余 5 秒（≤ 10%），降级到 L2 并延长宽限时间
+        clock.advance(45);
+        assert_eq!(cache.demote_expiring_keys().await.unwrap(), 1);
+        assert!(!cache.l1_cache.contains_key(&key));
+        assert_eq!(cache.get_ttl(&key).await, Some(65));
+        assert_eq!(events.next_event().await.unwrap().reason, EvictionReason::Demoted);
+
+        // 超过原始过期时间但仍在宽限期内，命中 L2
+        clock.advance(10);
+        assert_eq!(cache.get(&key).await.unwrap(), Some(value));
+    }
+
+    #[tokio::test]
+    async fn test_eviction_rate_write_strategy() {
+        let l1_config = crate::config::L1Config {
+            max_memory: 1000,
+            max_entries: 100,
+            eviction_strategy: crate::EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
+        };
+        let (cache, _temp_dir) = create_test_cache_with_l1(l1_config, |l2| {
+            l2.l2_write_strategy = "eviction_rate".to_string();
+            l2.l2_write_threshold = 1024;
+            l2.l2_write_eviction_rate_low = 0.0;
+            l2.l2_write_eviction_rate_high = 1.0;
+        }, Arc::new(crate::clock::MockClock::new(1_000))).await;
+
+        // L1 没有驱逐时，小值不写入 L2
+        let small = Bytes::from("small");
+        let options = CacheOptions::default();
+        assert!(!cache.should_write_to_l2("small", &small, &options).await);
+
+        // L1 频繁驱逐时，小值也写入 L2
+        for i in 0..30 {
+            cache.set(format!("key_{}", i), Bytes::from(vec![b'x'; 100])).await.unwrap();
+        }
+        assert!(cache.l1_cache.eviction_rate() >= 1.0);
+        assert!(cache.should_write_to_l2("small", &small, &options).await);
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_all() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "always".to_string();
+        }, clock).await;
+
+        let options = CacheOptions {
+            ttl_seconds: Some(100),
+            tags: vec!["group".to_string()],
+            ..Default::default()
+        };
+        cache.set_with_flags("flagged", Bytes::from("a"), 3, &options).await.unwrap();
+        cache.set("dropped", Bytes::from("drop")).await.unwrap();
+        cache.set("cold", Bytes::from("c")).await.unwrap();
+        cache.l1_cache.delete("cold").await.unwrap();
+        cache.set_with_ttl("persistent", Bytes::from("p"), 0).await.unwrap();
+
+        let before = cache.get_operation_stats();
+        let rewritten = cache.rewrite_all(|_, value| {
+            (value != "drop").then(|| Bytes::from(format!("{}!", String::from_utf8_lossy(&value))))
+        }).await.unwrap();
+        assert_eq!(rewritten, 4);
+        // 重写不计入命中统计
+        let after = cache.get_operation_stats();
+        assert_eq!((after.hits(), after.misses), (before.hits(), before.misses));
+
+        let entry = cache.get_with_metadata("flagged").await.unwrap().unwrap();
+        assert_eq!((entry.value, entry.flags, entry.ttl_seconds), (Bytes::from("a!"), 3, Some(100)));
+        assert_eq!(cache.get("dropped").await.unwrap(), None);
+        assert_eq!(cache.get_ttl("persistent").await, None);
+        assert_eq!(cache.get("persistent").await.unwrap(), Some(Bytes::from("p!")));
+
+        // 仅在 L2 中的条目重写后不进入 L1
+        assert!(!cache.l1_cache.contains_key("cold"));
+        assert_eq!(cache.get("cold").await.unwrap(), Some(Bytes::from("c!")));
+
+        // 重写保留标签
+        assert_eq!(cache.tag_index.tags("flagged"), vec!["group".to_string()]);
+        assert_eq!(cache.invalidate_tag("group").await.unwrap(), 1);
+        assert_eq!(cache.get("flagged").await.unwrap(), None);
+
+        // 删除等待持有该键的事务完成
+        cache.set("locked", Bytes::from("drop")).await.unwrap();
+        let guards = cache.key_locks.write_many(std::iter::once("locked")).await;
+        let rewrite = tokio::spawn({
+            let cache = cache.clone();
+            async move { cache.rewrite_all(|_, value| (value != "drop").then_some(value)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(cache.get("locked").await.unwrap(), Some(Bytes::from("drop")));
+        drop(guards);
+        rewrite.await.unwrap().unwrap();
+        assert_eq!(cache.get("locked").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_config_accessor() {
+        let (cache, temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_partitions = 4;
+        }, system_clock()).await;
+
+        let config = cache.config();
+        assert_eq!(config.l1.max_entries, 100_000);
+        let l2_config = config.l2.as_ref().unwrap();
+        assert_eq!(l2_config.l2_partitions, 4);
+        assert_eq!(l2_config.data_dir.as_deref(), Some(temp_dir.path()));
+    }
+
+    #[tokio::test]
+    async fn test_background_tasks_joined_on_shutdown() {
+        use crate::events::EventDelivery;
+
+        let metrics = tokio::runtime::Handle::current().metrics();
+        let baseline = metrics.num_alive_tasks();
+
+        // TTL 主动清理与驱逐事件定时投递
+        for _ in 0..20 {
+            let listener = EvictionListener::new(
+                EventDelivery::Batched { max: 4, interval: Duration::from_secs(60) },
+                |_| {},
+            );
+            let cache = RatMemCacheBuilder::development().eviction_listener(listener).build().await.unwrap();
+            assert_eq!(cache.background_task_count(), 2);
+            cache.shutdown().await.unwrap();
+            assert_eq!(cache.background_task_count(), 0);
+        }
+        assert_eq!(metrics.num_alive_tasks(), baseline);
+
+        // TTL 降级任务
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.ttl_demotion_enabled = true;
+        }, system_clock()).await;
+        assert_eq!(cache.background_task_count(), 1);
+        cache.shutdown().await.unwrap();
+        assert_eq!(cache.background_task_count(), 0);
+        assert_eq!(metrics.num_alive_tasks(), baseline);
+    }
+
+    #[tokio::test]
+    async fn test_hot_keys() {
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "always".to_string();
+        }, system_clock()).await;
+
+        for key in ["both", "l2_only", "cold"] {
+            cache.set(key, Bytes::from("value")).await.unwrap();
+        }
+        // L1 计数为写入 1 次加读取 3 次，L2 写入时计数 1
+        for _ in 0..3 {
+            cache.get("both").await.unwrap();
+        }
+        // 仅在 L2 中的键：写入计数 1 加 5 次 L2 读取
+        cache.l1_cache.delete("l2_only").await.unwrap();
+        let l2_cache = cache.l2_cache.as_ref().unwrap();
+        for _ in 0..5 {
+            l2_cache.get("l2_only").await.unwrap();
+        }
+
+        let hot = cache.hot_keys(2).await;
+        assert_eq!(hot, vec![("l2_only".to_string(), 6), ("both".to_string(), 5)]);
+        assert!(cache.hot_keys(0).await.is_empty());
+        assert_eq!(cache.hot_keys(10).await.last(), Some(&("cold".to_string(), 2)));
+    }
+
+    #[tokio::test]
+    async fn test_promote() {
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "always".to_string();
+        }, system_clock()).await;
+
+        cache.set_with_flags("warm", Bytes::from("value"), 7, &CacheOptions::default()).await.unwrap();
+        cache.set("hot", Bytes::from("hot value")).await.unwrap();
+        cache.l1_cache.delete("warm").await.unwrap();
+        assert!(!cache.l1_cache.contains_key("warm"));
+
+        // 只提升仅在 L2 中的键，已在 L1 或不存在的键被跳过
+        let keys = vec!["warm".to_string(), "hot".to_string(), "missing".to_string()];
+        assert_eq!(cache.promote(&keys).await.unwrap(), 1);
+        assert_eq!(cache.l1_cache.peek_with_flags("warm"), Some((Bytes::from("value"), 7)));
+    }
+
+    #[tokio::test]
+    async fn test_promote_on_hit_override() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        let force_l2 = CacheOptions { force_l2: true, ..Default::default() };
+        cache.set_with_options("scan", Bytes::from("value"), &force_l2).await.unwrap();
+        cache.set_with_options("pinned", Bytes::from("value"), &force_l2).await.unwrap();
+
+        // Some(false)：默认会提升的读取只从 L2 返回
+        let no_promote = CacheOptions { promote_on_hit: Some(false), ..Default::default() };
+        assert_eq!(cache.get_with_options("scan", &no_promote).await.unwrap(), Some(Bytes::from("value")));
+        assert!(!cache.l1_cache.contains_key("scan"));
+
+        // Some(true)：即使设置了 force_l2 也提升到 L1
+        let promote = CacheOptions { force_l2: true, promote_on_hit: Some(true), ..Default::default() };
+        assert_eq!(cache.get_with_options("pinned", &promote).await.unwrap(), Some(Bytes::from("value")));
+        assert!(cache.l1_cache.contains_key("pinned"));
+    }
+
+    #[tokio::test]
+    async fn test_get_stream() {
+        use crate::l2_cache::STREAM_CHUNK_SIZE;
+
+        let (cache, _temp_dir) = create_test_cache().await;
+        let value = Bytes::from("streamed artifact ".repeat(20_000));
+        let force_l2 = CacheOptions { force_l2: true, ..Default::default() };
+        cache.set_with_options("artifact", value.clone(), &force_l2).await.unwrap();
+
+        // 从 L2 按块解压，不提升到 L1
+        let mut stream = cache.get_stream("artifact").await.unwrap().unwrap();
+        let mut chunks = Vec::new();
+        while let Some(chunk) = stream.next_chunk().await {
+            chunks.push(chunk.unwrap());
+        }
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= STREAM_CHUNK_SIZE));
+        assert_eq!(chunks.concat(), value.to_vec());
+        assert!(!cache.l1_cache.contains_key("artifact"));
+
+        // L1 命中时直接切分内存中的值
+        cache.set("small", Bytes::from("in memory")).await.unwrap();
+        let mut stream = cache.get_stream("small").await.unwrap().unwrap();
+        assert_eq!(stream.next_chunk().await.unwrap().unwrap(), Bytes::from("in memory"));
+        assert!(stream.next_chunk().await.is_none());
+
+        assert!(cache.get_stream("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_many_with_source() {
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "always".to_string();
+        }, system_clock()).await;
+
+        cache.set("hot", Bytes::from("hot value")).await.unwrap();
+        cache.set("cold", Bytes::from("cold value")).await.unwrap();
+        cache.l1_cache.delete("cold").await.unwrap();
+
+        let keys = vec!["hot".to_string(), "cold".to_string(), "missing".to_string()];
+        let results = cache.get_many_with_source(&keys).await.unwrap();
+        assert_eq!(results, vec![
+            (Some(Bytes::from("hot value")), Some(CacheLayer::Memory)),
+            (Some(Bytes::from("cold value")), Some(CacheLayer::Persistent)),
+            (None, None),
+        ]);
+
+        // L2 命中后被提升到 L1
+        let results = cache.get_many_with_source(&keys[1..2]).await.unwrap();
+        assert_eq!(results[0].1, Some(CacheLayer::Memory));
+    }
+
+    #[tokio::test]
+    async fn test_set_reporting_eviction() {
+        let cache = RatMemCacheBuilder::development()
+            .l1_config(crate::config::L1Config {
+                max_memory: 1024 * 1024,
+                max_entries: 2,
+                eviction_strategy: crate::EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
+            })
+            .build()
+            .await
+            .unwrap();
+        let options = CacheOptions::default();
+
+        let outcome = cache.set_reporting_eviction("a", Bytes::from("a"), &options).await.unwrap();
+        assert_eq!(outcome, SetOutcome::default());
+        cache.set_reporting_eviction("b", Bytes::from("b"), &options).await.unwrap();
+
+        // 第三个键超出条目上限，驱逐最久未用的 "a"
+        let outcome = cache.set_reporting_eviction("c", Bytes::from("c"), &options).await.unwrap();
+        assert!(outcome.caused_eviction());
+        assert_eq!(outcome.evicted_count, 1);
+        assert!(outcome.evicted_bytes > 0);
+        assert!(!cache.l1_cache.contains_key("a"));
+
+        // 腾出空间后的写入不需要驱逐
+        cache.delete("c").await.unwrap();
+        let outcome = cache.set_reporting_eviction("d", Bytes::from("d"), &options).await.unwrap();
+        assert!(!outcome.caused_eviction());
+    }
+
+    #[tokio::test]
+    async fn test_eviction_events() {
+        use crate::events::EvictionReason;
+
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let cache = RatMemCacheBuilder::development()
+            .l1_config(crate::config::L1Config {
+                max_memory: 1024 * 1024,
+                max_entries: 2,
+                eviction_strategy: crate::EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
+            })
+            .clock(clock.clone())
+            .build()
+            .await
+            .unwrap();
+        let mut events = cache.eviction_events();
+
+        cache.set("a", Bytes::from("a")).await.unwrap();
+        cache.set_with_ttl("b", Bytes::from("b"), 10).await.unwrap();
+        cache.set("c", Bytes::from("c")).await.unwrap();
+        cache.delete("c").await.unwrap();
+        clock.advance(11);
+        assert_eq!(cache.get("b").await.unwrap(), None);
+
+        let reasons: Vec<_> = [
+            events.next_event().await.unwrap(),
+            events.next_event().await.unwrap(),
+            events.next_event().await.unwrap(),
+        ].into_iter().map(|event| (event.key, event.reason)).collect();
+        assert_eq!(reasons, vec![
+            ("a".to_string(), EvictionReason::Capacity),
+            ("c".to_string(), EvictionReason::Explicit),
+            ("b".to_string(), EvictionReason::Ttl),
+        ]);
+        assert_eq!(events.dropped(), 0);
+        assert_eq!(cache.eviction_events_dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_keyspace_events() {
+        use crate::events::CacheEvent;
+        use crate::types::CacheLayer;
+
+        let (cache, _temp_dir) = create_test_cache().await;
+        assert!(cache.subscribe_events().is_err());
+
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let cache = RatMemCacheBuilder::development()
+            .l1_config(crate::config::L1Config {
+                max_memory: 1024 * 1024,
+                max_entries: 2,
+                eviction_strategy: crate::EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
+            })
+            .keyspace_events()
+            .clock(clock.clone())
+            .build()
+            .await
+            .unwrap();
+        let mut events = cache.subscribe_events().unwrap();
+
+        cache.set("a", Bytes::from("a")).await.unwrap();
+        cache.set_with_ttl("b", Bytes::from("b"), 10).await.unwrap();
+        cache.set("c", Bytes::from("c")).await.unwrap();
+        cache.delete("c").await.unwrap();
+        assert!(!cache.delete("missing").await.unwrap());
+        clock.advance(11);
+        assert_eq!(cache.get("b").await.unwrap(), None);
+
+        let key = |key: &str| key.to_string();
+        let mut received = Vec::new();
+        for _ in 0..6 {
+            received.push(events.next_event().await.unwrap());
+        }
+        assert_eq!(received, vec![
+            CacheEvent::Set { key: key("a") },
+            CacheEvent::Set { key: key("b") },
+            CacheEvent::Evicted { key: key("a"), layer: CacheLayer::Memory },
+            CacheEvent::Set { key: key("c") },
+            CacheEvent::Deleted { key: key("c") },
+            CacheEvent::Expired { key: key("b") },
+        ]);
+
+        // 批量写入逐键发布，被抛弃的大值不发布
+        let large = Bytes::from(vec![0u8; cache.config().performance.large_value_threshold + 1]);
+        cache.set_many(vec![
+            (key("d"), Bytes::from("d")),
+            (key("large"), large),
+            (key("e"), Bytes::from("e")),
+        ]).await.unwrap();
+        cache.delete("d").await.unwrap();
+        let mut received = Vec::new();
+        for _ in 0..3 {
+            received.push(events.next_event().await.unwrap());
+        }
+        assert_eq!(received, vec![
+            CacheEvent::Set { key: key("d") },
+            CacheEvent::Set { key: key("e") },
+            CacheEvent::Deleted { key: key("d") },
+        ]);
+        assert_eq!(events.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_scan_prefix() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_partitions = 4;
+            l2.l2_write_strategy = "always".to_string();
+        }, clock.clone()).await;
+        let l2_only = CacheOptions { force_l2: true, ..Default::default() };
+
+        cache.set("user:1:session", Bytes::from("a")).await.unwrap();
+        cache.set_with_options("user:1:profile", Bytes::from("b"), &l2_only).await.unwrap();
+        cache.set_with_ttl("user:1:token", Bytes::from("c"), 10).await.unwrap();
+        cache.set("user:12:session", Bytes::from("d")).await.unwrap();
+        cache.set("user:2:session", Bytes::from("e")).await.unwrap();
+        clock.advance(11);
+
+        let mut keys = cache.scan_prefix("user:1:").await.unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["user:1:profile".to_string(), "user:1:session".to_string()]);
+
+        let mut stream = cache.scan_prefix_stream("user:1:");
+        let mut streamed = Vec::new();
+        while let Some(key) = stream.recv().await {
+            streamed.push(key.unwrap());
+        }
+        // L1 中的键先返回，同时存在于两层的键只返回一次
+        assert_eq!(streamed, vec!["user:1:session".to_string(), "user:1:profile".to_string()]);
+        assert!(cache.scan_prefix("order:").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_l1_set_failure_policy() {
+        let data_dir = TempDir::new().unwrap();
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.data_dir = Some(data_dir.path().to_path_buf());
+            l2.l2_write_strategy = "always".to_string();
+        }, system_clock()).await;
+        assert_eq!(cache.config().l1_set_failure_policy, L1SetFailurePolicy::Ignore);
+
+        // 默认忽略 L1 失败，值仍写入 L2
+        cache.l1_cache.fail_sets(true);
+        cache.set("ignored", Bytes::from("value")).await.unwrap();
+        assert!(!cache.l1_cache.contains_key("ignored"));
+        assert_eq!(cache.l2_cache.as_ref().unwrap().get("ignored").await.unwrap(), Some(Bytes::from("value")));
+
+        let mut config = cache.config().clone();
+        config.l1_set_failure_policy = L1SetFailurePolicy::Propagate;
+        cache.shutdown().await.unwrap();
+        drop(cache);
+
+        // 返回 L1 错误且不写入 L2
+        let cache = RatMemCache::new(config).await.unwrap();
+        cache.l1_cache.fail_sets(true);
+        let result = cache.set("propagated", Bytes::from("value")).await;
+        assert!(matches!(result, Err(CacheError::OutOfMemory { .. })));
+        assert_eq!(cache.get("propagated").await.unwrap(), None);
+
+        cache.l1_cache.fail_sets(false);
+        cache.set("propagated", Bytes::from("value")).await.unwrap();
+        assert!(cache.l1_cache.contains_key("propagated"));
+    }
+
+    #[tokio::test]
+    async fn test_read_only() {
+        let data_dir = TempDir::new().unwrap();
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.data_dir = Some(data_dir.path().to_path_buf());
+            l2.l2_write_strategy = "always".to_string();
+        }, system_clock()).await;
+        cache.set("key", Bytes::from("value")).await.unwrap();
+        let config = cache.config().clone();
+        cache.shutdown().await.unwrap();
+        drop(cache);
+
+        let reopen = |promote_on_hit| {
+            let mut config = config.clone();
+            config.read_only = true;
+            config.read_only_promote_on_hit = promote_on_hit;
+            RatMemCache::new(config)
+        };
+
+        let cache = reopen(true).await.unwrap();
+        assert_eq!(cache.get("key").await.unwrap(), Some(Bytes::from("value")));
+        assert!(cache.contains_key("key").await.unwrap());
+        assert_eq!(cache.keys().await.unwrap(), vec!["key".to_string()]);
+        // L2 命中仍提升到 L1
+        assert!(cache.l1_cache.contains_key("key"));
+
+        assert!(cache.set("key", Bytes::from("other")).await.unwrap_err().is_read_only());
+        assert!(cache.delete("key").await.unwrap_err().is_read_only());
+        assert!(cache.clear().await.unwrap_err().is_read_only());
+        assert!(cache.set_ttl("key", 10).await.unwrap_err().is_read_only());
+        assert!(cache.touch("key", 10).await.unwrap_err().is_read_only());
+        let ops = vec![TxOp::Delete { key: "key".to_string() }];
+        assert!(cache.transaction(ops).await.unwrap_err().is_read_only());
+        assert_eq!(cache.get("key").await.unwrap(), Some(Bytes::from("value")));
+        cache.shutdown().await.unwrap();
+        drop(cache);
+
+        // 关闭提升后只从 L2 读取
+        let cache = reopen(false).await.unwrap();
+        assert_eq!(cache.get("key").await.unwrap(), Some(Bytes::from("value")));
+        assert!(!cache.l1_cache.contains_key("key"));
+        assert_eq!(cache.promote(&["key".to_string()]).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_transaction() {
+        let cache = RatMemCacheBuilder::development().build().await.unwrap();
+        cache.set("balance_a", Bytes::from("100")).await.unwrap();
+        cache.set("balance_b", Bytes::from("0")).await.unwrap();
+        let version_a = cache.get_with_metadata("balance_a").await.unwrap().unwrap().version();
+
+        let transfer = |version| vec![
+            TxOp::SetIfVersion { key: "balance_a".to_string(), value: Bytes::from("60"), version, ttl_seconds: None },
+            TxOp::Set { key: "balance_b".to_string(), value: Bytes::from("40"), ttl_seconds: None },
+            TxOp::Delete { key: "pending".to_string() },
+        ];
+        assert!(cache.transaction(transfer(version_a)).await.unwrap());
+        assert_eq!(cache.get("balance_a").await.unwrap(), Some(Bytes::from("60")));
+        assert_eq!(cache.get("balance_b").await.unwrap(), Some(Bytes::from("40")));
+
+        // 版本已变化，所有操作都不应用
+        cache.set("pending", Bytes::from("1")).await.unwrap();
+        assert!(!cache.transaction(transfer(version_a)).await.unwrap());
+        assert_eq!(cache.get("balance_a").await.unwrap(), Some(Bytes::from("60")));
+        assert_eq!(cache.get("pending").await.unwrap(), Some(Bytes::from("1")));
+
+        // 键不存在时版本检查失败
+        let ops = vec![TxOp::SetIfVersion { key: "missing".to_string(), value: Bytes::from("x"), version: version_a, ttl_seconds: None }];
+        assert!(!cache.transaction(ops).await.unwrap());
+        assert_eq!(cache.get("missing").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rollback() {
+        let cache = RatMemCacheBuilder::development()
+            .l1_config(crate::config::L1Config {
+                max_memory: 4096,
+                max_entries: 100,
+                eviction_strategy: crate::EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
+            })
+            .performance_config(crate::config::PerformanceConfig {
+                worker_threads: 4,
+                enable_concurrency: true,
+                read_write_separation: false,
+                batch_size: 100,
+                enable_warmup: false,
+                large_value_threshold: 10240,
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 8192,
+                max_tagged_keys: 100_000,
+            })
+            .l1_set_failure_policy(L1SetFailurePolicy::Propagate)
+            .build()
+            .await
+            .unwrap();
+        let tagged = CacheOptions { tags: vec!["group".to_string()], ..Default::default() };
+        cache.set_with_options("a", Bytes::from("old"), &tagged).await.unwrap();
+        cache.set_with_ttl("b", Bytes::from("kept"), 600).await.unwrap();
+
+        // 第二个操作超过绝对上限，应用任何操作之前被拒绝
+        let ops = vec![
+            TxOp::Set { key: "a".to_string(), value: Bytes::from("new"), ttl_seconds: None },
+            TxOp::Set { key: "b".to_string(), value: Bytes::from(vec![0u8; 8193]), ttl_seconds: None },
+        ];
+        assert!(matches!(cache.transaction(ops).await, Err(CacheError::ValueTooLarge { .. })));
+        assert_eq!(cache.get("a").await.unwrap(), Some(Bytes::from("old")));
+
+        // 第二个操作写入 L1 时失败，第一个操作被回滚
+        for first in [
+            TxOp::Set { key: "a".to_string(), value: Bytes::from("new"), ttl_seconds: None },
+            TxOp::Delete { key: "a".to_string() },
+            TxOp::Set { key: "fresh".to_string(), value: Bytes::from("new"), ttl_seconds: None },
+        ] {
+            let ops = vec![
+                first,
+                TxOp::Set { key: "b".to_string(), value: Bytes::from(vec![0u8; 5000]), ttl_seconds: None },
+            ];
+            assert!(matches!(cache.transaction(ops).await, Err(CacheError::OutOfMemory { .. })));
+            assert_eq!(cache.get("a").await.unwrap(), Some(Bytes::from("old")));
+            assert_eq!(cache.get("b").await.unwrap(), Some(Bytes::from("kept")));
+            assert_eq!(cache.get("fresh").await.unwrap(), None);
+        }
+        assert!(cache.get_ttl("b").await.is_some_and(|ttl| ttl > 0 && ttl <= 600));
+        assert_eq!(cache.get_ttl("a").await, None);
+        assert_eq!(cache.invalidate_tag("group").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_key_stats() {
+        let cache = RatMemCacheBuilder::development()
+            .l1_config(crate::config::L1Config {
+                max_memory: 1024 * 1024,
+                max_entries: 2,
+                eviction_strategy: crate::EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
+            })
+            .performance_config(crate::config::PerformanceConfig {
+                worker_threads: 4,
+                enable_concurrency: true,
+                read_write_separation: false,
+                batch_size: 100,
+                enable_warmup: false,
+                large_value_threshold: 10240,
+                key_stats_top_n: 10,
+                absolute_max_value_bytes: 0,
+                max_tagged_keys: 100_000,
+            })
+            .build()
+            .await
+            .unwrap();
+
+        cache.set("hot", Bytes::from("value")).await.unwrap();
+        cache.get("hot").await.unwrap();
+        cache.get("hot").await.unwrap();
+        cache.get("missing").await.unwrap();
+        cache.set("a", Bytes::from("a")).await.unwrap();
+        cache.set("b", Bytes::from("b")).await.unwrap();
+        cache.get("hot").await.unwrap();
+
+        assert_eq!(cache.key_stats("hot").await, Some(KeyStats { hits: 2, misses: 1, promotions: 0, evictions: 1 }));
+        assert_eq!(cache.key_stats("missing").await.unwrap().misses, 1);
+        assert_eq!(cache.key_stats("never_seen").await, None);
+
+        // 默认关闭
+        let (cache, _temp_dir) = create_test_cache().await;
+        cache.get("hot").await.unwrap();
+        assert_eq!(cache.key_stats("hot").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_absolute_max_value_bytes() {
+        let cache = RatMemCacheBuilder::development()
+            .performance_config(crate::config::PerformanceConfig {
+                worker_threads: 4,
+                enable_concurrency: true,
+                read_write_separation: false,
+                batch_size: 100,
+                enable_warmup: false,
+                large_value_threshold: 10240,
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 1024,
+                max_tagged_keys: 100_000,
+            })
+            .build()
+            .await
+            .unwrap();
+
+        let result = cache.set("huge", Bytes::from(vec![0u8; 1025])).await;
+        assert!(matches!(result, Err(CacheError::ValueTooLarge { size: 1025, max_size: 1024 })));
+        assert_eq!(cache.get("huge").await.unwrap(), None);
+
+        cache.set("fits", Bytes::from(vec![0u8; 1024])).await.unwrap();
+        assert_eq!(cache.get("fits").await.unwrap().map(|value| value.len()), Some(1024));
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_tag() {
+        let cache = RatMemCacheBuilder::development()
+            .performance_config(crate::config::PerformanceConfig {
+                worker_threads: 4,
+                enable_concurrency: true,
+                read_write_separation: false,
+                batch_size: 100,
+                enable_warmup: false,
+                large_value_threshold: 10240,
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 0,
+                max_tagged_keys: 3,
+            })
+            .build()
+            .await
+            .unwrap();
+        let tagged = |tags: &[&str]| CacheOptions {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        };
+
+        cache.set_with_options("profile:42", Bytes::from("a"), &tagged(&["user:42"])).await.unwrap();
+        cache.set_with_options("orders?uid=42", Bytes::from("b"), &tagged(&["user:42", "orders"])).await.unwrap();
+        cache.set_with_options("orders?uid=7", Bytes::from("c"), &tagged(&["orders"])).await.unwrap();
+        cache.set("untagged", Bytes::from("d")).await.unwrap();
+        assert_eq!(cache.tagged_keys(), 3);
+
+        // 达到上限后不能为新键设置标签，已有键可以更新标签
+        let result = cache.set_with_options("profile:7", Bytes::from("e"), &tagged(&["user:7"])).await;
+        assert!(matches!(result, Err(CacheError::TagIndexFull { max_keys: 3 })));
+        assert_eq!(cache.get("profile:7").await.unwrap(), None);
+
+        // 不带标签的覆盖写入清除原有标签
+        cache.set("profile:42", Bytes::from("a2")).await.unwrap();
+        assert_eq!(cache.tagged_keys(), 2);
+        cache.set_with_options("profile:7", Bytes::from("e"), &tagged(&["user:7"])).await.unwrap();
+
+        assert_eq!(cache.invalidate_tag("user:42").await.unwrap(), 1);
+        assert_eq!(cache.get("orders?uid=42").await.unwrap(), None);
+        assert_eq!(cache.get("profile:42").await.unwrap(), Some(Bytes::from("a2")));
+
+        // 删除的键同时从索引中移除
+        cache.delete("profile:7").await.unwrap();
+        assert_eq!(cache.invalidate_tag("user:7").await.unwrap(), 0);
+
+        assert_eq!(cache.invalidate_tag("orders").await.unwrap(), 1);
+        assert_eq!(cache.get("orders?uid=7").await.unwrap(), None);
+        assert_eq!(cache.get("untagged").await.unwrap(), Some(Bytes::from("d")));
+        assert_eq!(cache.tagged_keys(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_durable_set() {
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "never".to_string();
+        }, system_clock()).await;
+
+        // 持久写入忽略写入策略，同时写入 L1 和 L2
+        let durable = CacheOptions { durable: true, ..Default::default() };
+        cache.set_with_options("critical", Bytes::from("must persist"), &durable).await.unwrap();
+        assert!(cache.l1_cache.contains_key("critical"));
+        let l2_cache = cache.l2_cache.as_ref().unwrap();
+        assert_eq!(l2_cache.get("critical").await.unwrap(), Some(Bytes::from("must persist")));
+
+        cache.set("best_effort", Bytes::from("value")).await.unwrap();
+        assert_eq!(l2_cache.get("best_effort").await.unwrap(), None);
+
+        // 未启用 L2 时持久写入返回错误
+        let cache = RatMemCacheBuilder::development().build().await.unwrap();
+        let result = cache.set_with_options("critical", Bytes::from("value"), &durable).await;
+        assert!(matches!(result, Err(CacheError::ConfigError { .. })));
+        assert_eq!(cache.get("critical").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_switch_compression_algorithm() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        assert_eq!(cache.compression_algorithm(), CompressionAlgorithm::Lz4);
+        let value = Bytes::from("compressible value ".repeat(20));
+        let durable = CacheOptions { durable: true, ..Default::default() };
+        cache.set_with_options("before", value.clone(), &durable).await.unwrap();
+
+        // 切换后新写入不压缩，已有条目保持原样且仍可读取
+        cache.set_compression_algorithm(CompressionAlgorithm::None).await.unwrap();
+        assert_eq!(cache.compression_algorithm(), CompressionAlgorithm::None);
+        cache.set_with_options("after", value.clone(), &durable).await.unwrap();
+
+        let l2_cache = cache.l2_cache.as_ref().unwrap();
+        assert_eq!(l2_cache.get("before").await.unwrap(), Some(value.clone()));
+        assert_eq!(l2_cache.get("after").await.unwrap(), Some(value));
+        let stats = cache.get_l2_stats().await;
+        assert_eq!(stats.compression_by_algorithm[&CompressionAlgorithm::Lz4].entries, 1);
+        assert_eq!(stats.compression_by_algorithm[&CompressionAlgorithm::None].entries, 1);
+
+        let result = cache.set_compression_algorithm(CompressionAlgorithm::Zstd).await;
+        assert!(matches!(result, Err(CacheError::ConfigError { .. })));
+        assert_eq!(cache.compression_algorithm(), CompressionAlgorithm::None);
+    }
+
+    #[test]
+    fn test_l2_health_transitions() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let events = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let listener = L2HealthListener::new(move |event: &L2HealthEvent| recorded.lock().push(event.clone()));
+        let health = L2Health::new(clock.clone(), Some(listener));
+        let failure = || -> CacheResult<()> { Err(CacheError::melange_db_error("磁盘不可用")) };
+
+        // 非存储错误和中途成功都不累计故障
+        for _ in 0..L2_FAILURE_THRESHOLD - 1 {
+            health.record(&failure());
+        }
+        health.record(&Err::<(), _>(CacheError::invalid_ttl(-1)));
+        health.record(&Ok(()));
+        for _ in 0..L2_FAILURE_THRESHOLD - 1 {
+            health.record(&failure());
+        }
+        assert!(!health.is_degraded());
+
+        health.record(&failure());
+        assert!(health.is_degraded());
+        assert!(!health.allows());
+        assert!(matches!(&events.lock()[..], [L2HealthEvent::Degraded { consecutive_failures: 5, .. }]));
+
+        // 探测间隔到达后只放行一次，探测失败保持降级
+        clock.advance(L2_PROBE_INTERVAL_SECS);
+        assert!(health.allows());
+        assert!(!health.allows());
+        health.record(&failure());
+        assert!(health.is_degraded());
+        assert_eq!(events.lock().len(), 1);
+
+        clock.advance(L2_PROBE_INTERVAL_SECS);
+        assert!(health.allows());
+        health.record(&Ok(()));
+        assert!(!health.is_degraded());
+        assert_eq!(events.lock().last(), Some(&L2HealthEvent::Recovered));
+    }
+
+    #[tokio::test]
+    async fn test_degrade_to_l1_on_l2_failure() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.degrade_to_l1_on_l2_failure = true;
+        }, clock.clone()).await;
+        let force_l2 = CacheOptions { force_l2: true, ..Default::default() };
+        cache.set_with_options("persisted", Bytes::from("value"), &force_l2).await.unwrap();
+
+        // 模拟连续存储故障
+        for _ in 0..L2_FAILURE_THRESHOLD {
+            let _ = cache.observe_l2::<()>(Err(CacheError::melange_db_error("磁盘不可用")));
+        }
+        assert!(cache.is_l2_degraded());
+
+        // 降级期间只使用 L1，不访问 L2
+        assert_eq!(cache.get("persisted").await.unwrap(), None);
+        cache.set("fresh", Bytes::from("l1 only")).await.unwrap();
+        assert_eq!(cache.get("fresh").await.unwrap(), Some(Bytes::from("l1 only")));
+        let durable = CacheOptions { durable: true, ..Default::default() };
+        let result = cache.set_with_options("critical", Bytes::from("value"), &durable).await;
+        assert!(matches!(result, Err(CacheError::ConfigError { .. })));
+
+        // 探测请求成功后恢复 L2
+        clock.advance(L2_PROBE_INTERVAL_SECS);
+        assert_eq!(cache.get("persisted").await.unwrap(), Some(Bytes::from("value")));
+        assert!(!cache.is_l2_degraded());
+    }
+
+    #[tokio::test]
+    async fn test_l2_write_rate_limit() {
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "always".to_string();
+            l2.l2_max_write_bytes_per_sec = Some(100);
+        }, system_clock()).await;
+        let l2_cache = cache.l2_cache.as_ref().unwrap();
+        let value = Bytes::from(vec![b'a'; 80]);
+
+        cache.set("first", value.clone()).await.unwrap();
+        assert!(l2_cache.contains_key("first").await.unwrap());
+
+        // 超出每秒预算的写入只保留在 L1
+        cache.set("second", value.clone()).await.unwrap();
+        assert!(!l2_cache.contains_key("second").await.unwrap());
+        assert_eq!(cache.get("second").await.unwrap(), Some(value.clone()));
+
+        // 被限流的覆盖写入会移除 L2 中的旧副本
+        let updated = Bytes::from(vec![b'b'; 80]);
+        cache.set("first", updated.clone()).await.unwrap();
+        assert!(!l2_cache.contains_key("first").await.unwrap());
+        assert_eq!(cache.get("first").await.unwrap(), Some(updated));
+
+        // 持久写入不受限流影响
+        let durable = CacheOptions { durable: true, ..Default::default() };
+        cache.set_with_options("critical", value, &durable).await.unwrap();
+        assert!(l2_cache.contains_key("critical").await.unwrap());
+
+        let stats = cache.get_l2_stats().await;
+        assert_eq!(stats.throttled_writes, 2);
+        assert!(!stats.write_throttled);
+    }
+
+    #[tokio::test]
+    async fn test_operations_rejected_after_shutdown() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        cache.set("key", Bytes::from("value")).await.unwrap();
+        cache.shutdown().await.unwrap();
+
+        let result = cache.set("key", Bytes::from("new value")).await;
+        assert!(matches!(result, Err(CacheError::ShuttingDown)));
+        assert!(cache.get("key").await.unwrap_err().is_shutting_down());
+        assert!(cache.delete("key").await.unwrap_err().is_shutting_down());
+    }
+
+    #[tokio::test]
+    async fn test_read_ahead_keys() {
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "always".to_string();
+        }, system_clock()).await;
+
+        for page in 1..=3 {
+            let key = format!("page:{}", page);
+            cache.set(key.as_str(), Bytes::from(format!("content {}", page))).await.unwrap();
+            cache.l1_cache.delete(&key).await.unwrap();
+        }
+
+        let options = CacheOptions {
+            read_ahead_keys: vec!["page:2".to_string(), "page:3".to_string()],
+            ..Default::default()
+        };
+        let value = cache.get_with_options("page:1", &options).await.unwrap();
+        assert_eq!(value, Some(Bytes::from("content 1")));
+
+        // 后续页在后台被提升到 L1
+        for _ in 0..100 {
+            if cache.l1_cache.contains_key("page:2") && cache.l1_cache.contains_key("page:3") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(cache.l1_cache.peek_with_flags("page:2"), Some((Bytes::from("content 2"), 0)));
+        assert!(cache.l1_cache.contains_key("page:3"));
+    }
+
+    #[tokio::test]
+    async fn test_defragment_l1() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        for i in 0..100 {
+            cache.set(format!("key_{}", i).as_str(), Bytes::from(vec![b'x'; 100 + i])).await.unwrap();
+        }
+        for i in (0..100).step_by(2) {
+            cache.delete(format!("key_{}", i).as_str()).await.unwrap();
+        }
+        let usage_before = cache.get_l1_stats().await.memory_usage;
+
+        let report = cache.defragment_l1().await.unwrap();
+        assert_eq!(report.entries, 50);
+        assert!(report.rss_before.is_some() && report.rss_after.is_some());
+
+        // 整理不改变内容和逻辑用量
+        assert_eq!(cache.get_l1_stats().await.memory_usage, usage_before);
+        assert_eq!(cache.get("key_1").await.unwrap(), Some(Bytes::from(vec![b'x'; 101])));
+    }
+
+    #[tokio::test]
+    async fn test_shrink_to_fit() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        for i in 0..2000 {
+            cache.set(format!("key_{}", i).as_str(), Bytes::from("value")).await.unwrap();
+        }
+        assert!(cache.l1_cache.capacity() >= 2000);
+
+        // 清空后容量仍被保留，收缩后释放
+        cache.clear().await.unwrap();
+        let cleared_capacity = cache.l1_cache.capacity();
+        assert!(cleared_capacity >= 2000);
+        cache.shrink_to_fit().await.unwrap();
+        assert!(cache.l1_cache.capacity() < cleared_capacity / 10);
+
+        cache.set("after", Bytes::from("value")).await.unwrap();
+        assert_eq!(cache.get("after").await.unwrap(), Some(Bytes::from("value")));
+    }
+
+    #[tokio::test]
+    async fn test_hit_rate() {
+        // 未启用 L2 时只统计 L1
+        let cache = RatMemCacheBuilder::development().build().await.unwrap();
+        assert_eq!(cache.get_hit_rate().await, None);
+        cache.set("key", Bytes::from("value")).await.unwrap();
+        for _ in 0..3 {
+            cache.get("key").await.unwrap();
+        }
+        cache.get("missing").await.unwrap();
+        let stats = cache.get_l1_stats().await;
+        assert_eq!((stats.hits, stats.misses), (3, 1));
+        assert_eq!(cache.get_hit_rate().await, Some(75.0));
+
+        // 启用 L2 时 L1 未命中、L2 命中的读取也算作命中，未查找 L2 的未命中（如已过期）同样计入
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let (cache, _temp_dir) = create_test_cache_with(|_| {}, clock.clone()).await;
+        cache.set_with_options("persisted", Bytes::from("value"), &CacheOptions { force_l2: true, ..Default::default() }).await.unwrap();
+        cache.set("key", Bytes::from("value")).await.unwrap();
+        cache.set_with_ttl("short", Bytes::from("value"), 1).await.unwrap();
+        clock.advance(2);
+        cache.get("key").await.unwrap();
+        cache.get("key").await.unwrap();
+        cache.get("persisted").await.unwrap();
+        cache.get("missing").await.unwrap();
+        cache.get("missing").await.unwrap();
+        cache.get("short").await.unwrap();
+        let stats = cache.get_operation_stats();
+        assert_eq!((stats.l1_hits, stats.l2_hits, stats.misses), (2, 1, 3));
+        assert_eq!(cache.get_hit_rate().await, Some(50.0));
+    }
+
+    #[tokio::test]
+    async fn test_combined_stats() {
+        // 未启用 L2 时只包含 L1 与 TTL
+        let cache = RatMemCacheBuilder::development().build().await.unwrap();
+        assert_eq!(cache.get_combined_stats().await.hit_rate(), None);
+        cache.set("key", Bytes::from("value")).await.unwrap();
+        cache.get("key").await.unwrap();
+        cache.get("missing").await.unwrap();
+        let stats = cache.get_combined_stats().await;
+        assert!(stats.l2.is_none());
+        assert_eq!((stats.l1.entry_count, stats.hits, stats.misses), (1, 1, 1));
+        assert_eq!(stats.hit_rate(), Some(0.5));
+
+        // 启用 L2 时合计两层的命中
+        let (cache, _temp_dir) = create_test_cache().await;
+        cache.set_with_options("persisted", Bytes::from("value"), &CacheOptions { force_l2: true, ttl_seconds: Some(60), ..Default::default() }).await.unwrap();
+        cache.set("key", Bytes::from("value")).await.unwrap();
+        cache.get("key").await.unwrap();
+        cache.get("persisted").await.unwrap();
+        cache.get("missing").await.unwrap();
+        cache.get("missing").await.unwrap();
+        let stats = cache.get_combined_stats().await;
+        assert_eq!(stats.l2.as_ref().map(|l2| l2.hits), Some(1));
+        assert!(stats.ttl.managed_keys >= 1);
+        assert_eq!((stats.hits, stats.misses), (2, 2));
+        assert_eq!(stats.hit_rate(), Some(0.5));
+    }
+
+    #[tokio::test]
+    async fn test_runtime_info() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        let info = cache.runtime_info();
+        assert_eq!(info.version, crate::VERSION);
+        assert!(info.features.contains(&"melange-storage"));
+        assert_eq!(info.compression_algorithm, CompressionAlgorithm::Lz4);
+        assert!(info.l2_enabled && info.l2_healthy);
+        assert!(info.to_string().contains("melange-storage"));
+
+        let cache = RatMemCacheBuilder::development().build().await.unwrap();
+        let info = cache.runtime_info();
+        assert!(!info.l2_enabled && !info.l2_healthy);
+    }
+
+    #[tokio::test]
+    async fn test_get_and_touch() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "always".to_string();
+        }, clock.clone()).await;
+
+        cache.set_with_ttl("session", Bytes::from("value"), 10).await.unwrap();
+        assert_eq!(cache.get_and_touch("session", 100).await.unwrap(), Some(Bytes::from("value")));
+        assert_eq!(cache.get_ttl("session").await, Some(100));
+        assert_eq!(cache.get_and_touch("missing", 100).await.unwrap(), None);
+        assert_eq!(cache.get_ttl("missing").await, None);
+
+        // L2 中记录的过期时间同样被刷新，超过原 TTL 后仍可从 L2 读取
+        clock.advance(50);
+        cache.l1_cache.delete("session").await.unwrap();
+        let entry = cache.get_entry_and_touch("session", 0).await.unwrap().unwrap();
+        assert_eq!(entry.value, Bytes::from("value"));
+        assert_eq!(entry.ttl_seconds, None);
+
+        clock.advance(1_000);
+        assert_eq!(cache.get("session").await.unwrap(), Some(Bytes::from("value")));
+
+        // touch 只在 L2 中的键同样刷新 TTL 索引
+        cache.l1_cache.delete("session").await.unwrap();
+        assert!(cache.touch("session", 30).await.unwrap());
+        assert_eq!(cache.get_ttl("session").await, Some(30));
+        clock.advance(31);
+        assert_eq!(cache.get("session").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_insert_with() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        let cache = Arc::new(cache);
+        let loads = Arc::new(AtomicU64::new(0));
+
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                let loads = Arc::clone(&loads);
+                tokio::spawn(async move {
+                    cache
+                        .get_or_insert_with("report".to_string(), Some(60), || async move {
+                            loads.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(20)).await;
+                            Ok(Bytes::from("computed"))
+                        })
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), Bytes::from("computed"));
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.get_ttl("report").await, Some(60));
+        assert!(cache.loader_locks.is_empty());
+
+        // 加载失败时不写入缓存
+        let result = cache.get_or_insert_with("broken".to_string(), None, || async { Err(CacheError::other("加载失败")) }).await;
+        assert!(result.is_err());
+        assert_eq!(cache.get("broken").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_sliding_expiration() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let cache = RatMemCacheBuilder::development()
+            .ttl_config(crate::config::TtlConfig {
+                expire_seconds: None,
+                cleanup_interval: 60,
+                max_cleanup_entries: 100,
+                lazy_expiration: true,
+                active_expiration: false,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: true,
+            })
+            .clock(clock.clone())
+            .build()
+            .await
+            .unwrap();
+
+        cache.set_with_ttl("session", Bytes::from("value"), 10).await.unwrap();
+        cache.set("forever", Bytes::from("value")).await.unwrap();
+
+        // 持续读取的键不会过期
+        for _ in 0..3 {
+            clock.advance(8);
+            assert_eq!(cache.get("session").await.unwrap(), Some(Bytes::from("value")));
+            assert_eq!(cache.get_ttl("session").await, Some(10));
+        }
+        assert!(cache.get_many(&["session".to_string()]).await.unwrap().contains_key("session"));
+        assert_eq!(cache.get("forever").await.unwrap(), Some(Bytes::from("value")));
+        assert_eq!(cache.get_ttl("forever").await, None);
+
+        clock.advance(10);
+        assert_eq!(cache.get("session").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_many() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "always".to_string();
+        }, clock.clone()).await;
+
+        cache.set("memory", Bytes::from("m")).await.unwrap();
+        cache.set("persistent", Bytes::from("p")).await.unwrap();
+        cache.set_with_ttl("expiring", Bytes::from("e"), 10).await.unwrap();
+        cache.l1_cache.delete("persistent").await.unwrap();
+        clock.advance(20);
+
+        let keys: Vec<String> = ["memory", "persistent", "expiring", "missing", "memory"]
+            .iter().map(|key| key.to_string()).collect();
+        let found = cache.get_many(&keys).await.unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found["memory"], Bytes::from("m"));
+        assert_eq!(found["persistent"], Bytes::from("p"));
+
+        // L2 命中已提升到 L1，过期的键已删除
+        assert!(cache.l1_cache.contains_key("persistent"));
+        assert!(!cache.contains_key("expiring").await.unwrap());
+        let stats = cache.get_stats().await.operations;
+        assert_eq!(stats.metric(CacheOperation::Get).count, 1);
+        assert_eq!(stats.hits(), 2);
+        assert_eq!(stats.misses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_set_many() {
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_write_strategy = "always".to_string();
+        }, Arc::new(crate::clock::MockClock::new(1_000))).await;
+        let tagged = CacheOptions { tags: vec!["group".to_string()], ..Default::default() };
+        cache.set_with_options("a", Bytes::from("old"), &tagged).await.unwrap();
+        let writes_before = cache.get_l2_stats().await.writes;
+
+        let large = Bytes::from(vec![b'x'; cache.config().performance.large_value_threshold + 1]);
+        cache.set_many(vec![
+            ("a".to_string(), Bytes::from("1")),
+            ("b".to_string(), Bytes::from("2")),
+            ("large".to_string(), large.clone()),
+            ("a".to_string(), Bytes::from("3")),
+        ]).await.unwrap();
+
+        assert!(!cache.l1_cache.contains_key("large"));
+        assert_eq!(cache.get("a").await.unwrap(), Some(Bytes::from("3")));
+        assert_eq!(cache.get("b").await.unwrap(), Some(Bytes::from("2")));
+        assert_eq!(cache.get("large").await.unwrap(), Some(large));
+
+        // 重复的键只写入一次 L2，最后一次写入生效；批量写入会清除原有标签
+        cache.l1_cache.delete("a").await.unwrap();
+        assert_eq!(cache.get("a").await.unwrap(), Some(Bytes::from("3")));
+        assert_eq!(cache.get_l2_stats().await.writes - writes_before, 3);
+        assert_eq!(cache.get_l2_stats().await.entry_count, 3);
+        assert_eq!(cache.tagged_keys(), 0);
+        assert_eq!(cache.get_stats().await.operations.metric(CacheOperation::Set).count, 2);
     }
 
+    #[tokio::test]
+    async fn test_cas() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        assert_eq!(cache.gets("key").await.unwrap(), None);
+        assert_eq!(cache.cas("key", Bytes::from("v"), 1, None).await.unwrap(), CasResult::NotFound);
+
+        cache.set("key", Bytes::from("v1")).await.unwrap();
+        let (value, token) = cache.gets("key").await.unwrap().unwrap();
+        assert_eq!(value, Bytes::from("v1"));
 
+        // 写入相同的值也会得到新的令牌
+        cache.set("key", Bytes::from("v1")).await.unwrap();
+        let (_, rewritten) = cache.gets("key").await.unwrap().unwrap();
+        assert!(rewritten > token);
+        assert_eq!(cache.cas("key", Bytes::from("v2"), token, None).await.unwrap(), CasResult::Exists);
+        assert_eq!(cache.get("key").await.unwrap(), Some(Bytes::from("v1")));
 
-    /// 获取 L1 缓存统计
-    pub async fn get_l1_stats(&self) -> L1CacheStats {
-        self.l1_cache.get_stats().await
+        assert_eq!(cache.cas("key", Bytes::from("v2"), rewritten, Some(60)).await.unwrap(), CasResult::Stored);
+        let (value, stored) = cache.gets("key").await.unwrap().unwrap();
+        assert_eq!(value, Bytes::from("v2"));
+        assert!(stored > rewritten);
+        assert_eq!(cache.get_ttl("key").await, Some(60));
+        assert_eq!(cache.cas("key", Bytes::from("v3"), rewritten, None).await.unwrap(), CasResult::Exists);
     }
 
-    /// 获取 L2 缓存统计
-    #[cfg(feature = "melange-storage")]
-    pub async fn get_l2_stats(&self) -> L2CacheStats {
-        if let Some(l2_cache) = &self.l2_cache {
-            l2_cache.get_stats().await
-        } else {
-            L2CacheStats::default()
-        }
+    #[tokio::test]
+    async fn test_cas_l2_only() {
+        let data_dir = TempDir::new().unwrap();
+        // L1 容不下大值，命中时无法提升
+        let l1_config = crate::config::L1Config {
+            max_memory: 4096,
+            max_entries: 100,
+            eviction_strategy: crate::EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
+        };
+        let (cache, _temp_dir) = create_test_cache_with_l1(l1_config, |l2| {
+            l2.data_dir = Some(data_dir.path().to_path_buf());
+        }, system_clock()).await;
+        let threshold = cache.config().performance.large_value_threshold;
+        let large = |byte: u8| Bytes::from(vec![byte; threshold + 1]);
+
+        // 只在 L2 中的条目，令牌来自 L2 元数据
+        cache.set("large", large(b'a')).await.unwrap();
+        let (value, token) = cache.gets("large").await.unwrap().unwrap();
+        assert!(!cache.l1_cache.contains_key("large"));
+        assert_eq!(value, large(b'a'));
+        assert_ne!(token, 0);
+        assert_eq!(cache.cas("large", large(b'b'), token, None).await.unwrap(), CasResult::Stored);
+        assert_eq!(cache.cas("large", large(b'c'), token, None).await.unwrap(), CasResult::Exists);
+        let (value, token) = cache.gets("large").await.unwrap().unwrap();
+        assert_eq!(value, large(b'b'));
+
+        // 令牌随 L2 持久化，重启后仍然有效
+        let config = cache.config().clone();
+        cache.shutdown().await.unwrap();
+        drop(cache);
+        let cache = RatMemCache::new(config).await.unwrap();
+        assert_eq!(cache.gets("large").await.unwrap().map(|(_, cas)| cas), Some(token));
+        assert_eq!(cache.cas("large", large(b'c'), token, None).await.unwrap(), CasResult::Stored);
+        assert_eq!(cache.get("large").await.unwrap(), Some(large(b'c')));
     }
 
-    
-    /// 获取缓存命中率（基于L2统计）
-    #[cfg(feature = "melange-storage")]
-    pub async fn get_hit_rate(&self) -> Option<f64> {
-        let l2_stats = self.get_l2_stats().await;
-        let total_requests = l2_stats.hits + l2_stats.misses;
-        if total_requests > 0 {
-            Some((l2_stats.hits as f64 / total_requests as f64) * 100.0)
-        } else {
-            None
+    #[tokio::test]
+    async fn test_incr_decr() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        assert_eq!(cache.incr("counter", 1).await.unwrap(), None);
+
+        cache.set_with_flags("counter", Bytes::from("10"), 7, &CacheOptions { ttl_seconds: Some(60), ..Default::default() }).await.unwrap();
+        assert_eq!(cache.incr("counter", 5).await.unwrap(), Some(15));
+        assert_eq!(cache.decr("counter", 20).await.unwrap(), Some(0));
+        let entry = cache.get_with_metadata("counter").await.unwrap().unwrap();
+        assert_eq!((entry.value, entry.flags, entry.ttl_seconds), (Bytes::from("0"), 7, Some(60)));
+
+        cache.set("counter", Bytes::from(u64::MAX.to_string())).await.unwrap();
+        assert_eq!(cache.incr("counter", 2).await.unwrap(), Some(1));
+
+        cache.set("text", Bytes::from("abc")).await.unwrap();
+        assert!(matches!(cache.incr("text", 1).await, Err(CacheError::InvalidValue { .. })));
+        cache.set("wide", Bytes::from("1".repeat(21))).await.unwrap();
+        assert!(matches!(cache.decr("wide", 1).await, Err(CacheError::InvalidValue { .. })));
+
+        // 并发的 incr 不会丢失更新
+        cache.set("counter", Bytes::from("0")).await.unwrap();
+        let cache = Arc::new(cache);
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                tokio::spawn(async move { cache.incr("counter", 1).await.unwrap() })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
         }
+        assert_eq!(cache.get("counter").await.unwrap(), Some(Bytes::from("16")));
     }
 
-    /// 获取缓存命中率（非melange版本）
-    #[cfg(not(feature = "melange-storage"))]
-    pub async fn get_hit_rate(&self) -> Option<f64> {
-        // 在没有L2的情况下，无法直接获取命中率统计
-        None
-    }
+    #[tokio::test]
+    async fn test_append_prepend() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        assert!(!cache.append("log", Bytes::from("x")).await.unwrap());
+        assert_eq!(cache.get("log").await.unwrap(), None);
 
-    /// 压缩 L2 缓存
-    #[cfg(feature = "melange-storage")]
-    pub async fn compact(&self) -> CacheResult<()> {
-        if let Some(l2_cache) = &self.l2_cache {
-            l2_cache.compact().await
-        } else {
-            Ok(())
+        cache.set_with_flags("log", Bytes::from("mid"), 3, &CacheOptions { ttl_seconds: Some(60), ..Default::default() }).await.unwrap();
+        assert!(cache.append("log", Bytes::from("-end")).await.unwrap());
+        assert!(cache.prepend("log", Bytes::from("start-")).await.unwrap());
+        let entry = cache.get_with_metadata("log").await.unwrap().unwrap();
+        assert_eq!((entry.value, entry.flags, entry.ttl_seconds), (Bytes::from("start-mid-end"), 3, Some(60)));
+
+        // 并发的 append 不会丢失
+        cache.set("buffer", Bytes::new()).await.unwrap();
+        let cache = Arc::new(cache);
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                tokio::spawn(async move { cache.append("buffer", Bytes::from("ab")).await.unwrap() })
+            })
+            .collect();
+        for task in tasks {
+            assert!(task.await.unwrap());
         }
+        assert_eq!(cache.get("buffer").await.unwrap(), Some(Bytes::from("ab".repeat(16))));
     }
 
-    /// 手动触发过期清理
-    pub async fn cleanup_expired(&self) -> CacheResult<u64> {
-        // 手动触发过期清理（简化实现）
-        Ok(0)
-    }
+    #[tokio::test]
+    async fn test_operation_stats() {
+        let (cache, _temp_dir) = create_test_cache().await;
 
-    /// 获取剩余 TTL
-    pub async fn get_ttl(&self, key: &str) -> Option<u64> {
-        self.ttl_manager.get_ttl(key).await
-    }
+        cache.set("session", Bytes::from("value")).await.unwrap();
+        cache.get("session").await.unwrap();
+        assert!(cache.touch("session", 120).await.unwrap());
+        assert!(!cache.touch("missing", 120).await.unwrap());
+        assert_eq!(cache.get_ttl("session").await, Some(120));
+        cache.set_ttl("session", 30).await.unwrap();
+        cache.remove_ttl("session").await.unwrap();
+        cache.delete("session").await.unwrap();
 
-    /// 设置 TTL
-    pub async fn set_ttl(&self, key: &str, ttl_seconds: u64) -> CacheResult<()> {
-        let _ = self.ttl_manager.add_key(key.to_string(), Some(ttl_seconds)).await;
-        Ok(())
+        let stats = cache.get_stats().await.operations;
+        assert_eq!(stats.metric(CacheOperation::Get).count, 1);
+        assert_eq!(stats.metric(CacheOperation::Set).count, 1);
+        assert_eq!(stats.metric(CacheOperation::Delete).count, 1);
+        assert_eq!(stats.metric(CacheOperation::Touch).count, 2);
+        assert_eq!(stats.metric(CacheOperation::Ttl).count, 2);
+        assert_eq!(stats.metric(CacheOperation::Clear).count, 0);
+        assert!(stats.format().contains("TOUCH: 2 次"));
     }
 
-    /// 移除 TTL
-    pub async fn remove_ttl(&self, key: &str) -> CacheResult<()> {
-        self.ttl_manager.remove_key(key).await;
-        Ok(())
-    }
+    #[tokio::test]
+    async fn test_metrics_snapshot() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        let force_l2 = CacheOptions { force_l2: true, ..Default::default() };
 
-    /// 关闭缓存
-    pub async fn shutdown(&self) -> CacheResult<()> {
-        rat_logger::info!("[CACHE] 开始关闭 RatMemCache...");
-        
-        // 设置停止标志
-        {
-            let mut running = self.is_running.write().await;
-            *running = false;
-        }
-        
-        // 等待后台任务完成
-        tokio::time::sleep(Duration::from_millis(100)).await;
-        
-        // TTL 管理器会自动清理
-        
-        rat_logger::info!("[CACHE] RatMemCache 已关闭");
-        Ok(())
+        cache.set("memory", Bytes::from("value")).await.unwrap();
+        cache.set_with_options("persisted", Bytes::from("value"), &force_l2).await.unwrap();
+        cache.get("memory").await.unwrap();
+        cache.get_with_options("persisted", &CacheOptions { skip_l1: true, ..Default::default() }).await.unwrap();
+        cache.get("missing").await.unwrap();
+
+        let snapshot = cache.metrics_snapshot().await;
+        assert_eq!(snapshot.hits, 2);
+        assert_eq!(snapshot.misses, 1);
+        assert_eq!((snapshot.operations.l1_hits, snapshot.operations.l2_hits), (1, 1));
+        assert_eq!(snapshot.operations.get.count, 3);
+        assert!(snapshot.operations.get.p50_latency_us <= snapshot.operations.get.p99_latency_us);
+        assert_eq!(snapshot.l1.entries, 1);
+        let l2 = snapshot.l2.as_ref().unwrap();
+        assert!(l2.writes >= 1);
+        assert!(!l2.degraded);
+
+        let serialized = toml::to_string(&snapshot).unwrap();
+        assert!(serialized.contains("hits = 2"));
     }
 
-    /// 内部删除方法
-    async fn delete_internal(&self, key: &str) -> CacheResult<bool> {
-        let mut deleted = false;
-        
-        // 从 L1 删除
-        if self.l1_cache.delete(key).await? {
-            deleted = true;
-        }
-        
-        // 从 L2 删除（如果存在）
-        #[cfg(feature = "melange-storage")]
-        if let Some(l2_cache) = &self.l2_cache {
-            if l2_cache.delete(key).await? {
-                deleted = true;
-            }
-        }
-        
-        // 移除 TTL
-        self.ttl_manager.remove_key(key).await;
-        
-        if deleted {
-            rat_logger::debug!("[CACHE] 缓存删除: {}", key);
-        }
-        
-        Ok(deleted)
+    #[test]
+    fn test_latency_percentiles() {
+        let mut buckets = [0u64; LATENCY_BUCKETS];
+        buckets[3] = 90; // 4-7 us
+        buckets[10] = 10; // 512-1023 us
+        assert_eq!(OperationMetrics::percentile(&buckets, 100, 0.5), 7);
+        assert_eq!(OperationMetrics::percentile(&buckets, 100, 0.99), 1023);
+        assert_eq!(OperationMetrics::percentile(&[0; LATENCY_BUCKETS], 0, 0.99), 0);
     }
 
-    /// 判断是否应该写入 L2
-    #[cfg(feature = "melange-storage")]
-    async fn should_write_to_l2(&self, _key: &str, value: &Bytes, options: &CacheOptions) -> bool {
-        // 如果强制 L2，直接返回 true
-        if options.force_l2 {
-            return true;
-        }
-        
-        // 根据配置的写入策略决定
-        if let Some(l2_config) = &self.config.l2 {
-            match l2_config.l2_write_strategy.as_str() {
-                "always" => true,
-                "never" => false,
-                "size_based" => value.len() >= l2_config.l2_write_threshold,
-                "ttl_based" => options.ttl_seconds.unwrap_or(0) >= l2_config.l2_write_ttl_threshold,
-                "adaptive" => {
-                    // 自适应策略：基于 L1 使用率和数据大小
-                    let l1_stats = self.l1_cache.get_stats().await;
-                    let l1_usage_ratio = l1_stats.memory_usage as f64 / self.config.l1.max_memory as f64;
+    #[test]
+    fn test_serve_expired_l1_on_slow_l2() {
+        // 限制阻塞线程数，全部占用后 L2 的后台读取必然排队等待，使读取超时可复现
+        const BLOCKING_THREADS: usize = 2;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .max_blocking_threads(BLOCKING_THREADS)
+            .enable_all()
+            .build()
+            .unwrap();
 
-                    l1_usage_ratio > 0.8 || value.len() >= l2_config.l2_write_threshold
-                },
-                _ => false,
+        runtime.block_on(async {
+            let clock = Arc::new(crate::clock::MockClock::new(1_000));
+            let (cache, _temp_dir) = create_test_cache_with(|l2| {
+                l2.l2_write_strategy = "always".to_string();
+            }, clock.clone()).await;
+
+            cache.set_with_ttl("stale", Bytes::from("old value"), 10).await.unwrap();
+            cache.set_with_ttl("expired", Bytes::from("old value"), 10).await.unwrap();
+            clock.advance(20);
+
+            let blocked = Arc::new(std::sync::Barrier::new(BLOCKING_THREADS + 1));
+            let blockers: Vec<_> = (0..BLOCKING_THREADS)
+                .map(|_| {
+                    let blocked = blocked.clone();
+                    tokio::task::spawn_blocking(move || { blocked.wait(); })
+                })
+                .collect();
+
+            let slow = CacheOptions {
+                l2_read_timeout: Some(Duration::from_millis(50)),
+                serve_expired_l1_on_slow_l2: true,
+                ..Default::default()
+            };
+            let entry = cache.get_entry_with_options("stale", &slow).await.unwrap().unwrap();
+            assert_eq!(entry.value, Bytes::from("old value"));
+            assert!(entry.stale);
+            assert!(cache.l1_cache.contains_key("stale"));
+
+            blocked.wait();
+            for blocker in blockers {
+                blocker.await.unwrap();
             }
-        } else {
-            false
-        }
-    }
-}
 
-// 实现 Clone trait 以支持在异步任务中使用
-impl Clone for RatMemCache {
-    fn clone(&self) -> Self {
-        Self {
-            config: Arc::clone(&self.config),
-            l1_cache: Arc::clone(&self.l1_cache),
-            #[cfg(feature = "melange-storage")]
-            l2_cache: self.l2_cache.as_ref().map(|cache| Arc::clone(cache)),
-            // transfer_router: Arc::clone(&self.transfer_router),
-            ttl_manager: Arc::clone(&self.ttl_manager),
-            compressor: Arc::clone(&self.compressor),
-            is_running: Arc::clone(&self.is_running),
-        }
+            // 未开启选项时，过期键直接未命中
+            let strict = CacheOptions {
+                serve_expired_l1_on_slow_l2: false,
+                ..slow.clone()
+            };
+            assert_eq!(cache.get_entry_with_options("expired", &strict).await.unwrap(), None);
+
+            // L2 在超时前返回时按正常过期处理
+            assert_eq!(cache.get_entry_with_options("stale", &slow).await.unwrap(), None);
+            assert!(!cache.l1_cache.contains_key("stale"));
+        });
     }
-}
 
-#[cfg(all(test, feature = "melange-storage"))]
-mod tests {
-    use super::*;
-    use crate::config::CacheConfigBuilder;
-    use bytes::Bytes;
-    use tempfile::TempDir;
+    #[tokio::test]
+    async fn test_presets() {
+        let cache = RatMemCacheBuilder::development().build().await.unwrap();
+        cache.set("key".to_string(), Bytes::from("value")).await.unwrap();
+        assert_eq!(cache.get("key").await.unwrap(), Some(Bytes::from("value")));
+        cache.shutdown().await.unwrap();
 
-    async fn create_test_cache() -> (RatMemCache, TempDir) {
-        let temp_dir = TempDir::new().unwrap();
+        let config = CacheConfigBuilder::production().build().unwrap();
+        assert!(config.performance.read_write_separation);
+        #[cfg(feature = "melange-storage")]
+        assert!(config.l2.unwrap().enable_l2_cache);
 
-        let cache = RatMemCacheBuilder::new()
-            .l1_config(crate::config::L1Config {
-                max_memory: 1024 * 1024 * 1024, // 1GB
-                max_entries: 100_000,
-                eviction_strategy: crate::EvictionStrategy::Lru,
-            })
-            .l2_config(crate::config::L2Config {
-                enable_l2_cache: true,
-                data_dir: Some(temp_dir.path().to_path_buf()),
-                max_disk_size: 10 * 1024 * 1024, // 10MB
-                write_buffer_size: 1024 * 1024,  // 1MB
-                max_write_buffer_number: 3,
-                block_cache_size: 512 * 1024,    // 512KB
-                enable_lz4: true,
-                compression_threshold: 128,
-                compression_max_threshold: 1024 * 1024,
-                compression_level: 6,
-                background_threads: 2,
-                clear_on_startup: false,
-                cache_size_mb: 256,
-                max_file_size_mb: 512,
-                smart_flush_enabled: true,
-                smart_flush_base_interval_ms: 100,
-                smart_flush_min_interval_ms: 20,
-                smart_flush_max_interval_ms: 500,
-                smart_flush_write_rate_threshold: 10000,
-                smart_flush_accumulated_bytes_threshold: 4 * 1024 * 1024,
-                cache_warmup_strategy: crate::config::CacheWarmupStrategy::Recent,
-                zstd_compression_level: None,
-                l2_write_strategy: "write_through".to_string(),
-                l2_write_threshold: 1024,
-                l2_write_ttl_threshold: 300,
-            })
+        // 场景预设之后仍可覆盖单个配置块
+        let cache = RatMemCacheBuilder::preset(Preset::SessionCache)
             .ttl_config(crate::config::TtlConfig {
-                expire_seconds: Some(60),
+                expire_seconds: Some(600),
                 cleanup_interval: 60,
                 max_cleanup_entries: 100,
                 lazy_expiration: true,
-                active_expiration: false, // 测试中禁用主动过期
-            })
-            .performance_config(crate::config::PerformanceConfig {
-                worker_threads: 4,
-                enable_concurrency: true,
-                read_write_separation: true,
-                batch_size: 100,
-                enable_warmup: false,
-                large_value_threshold: 10240, // 10KB
+                active_expiration: false,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
             })
-            .logging_config(crate::config::LoggingConfig {
-                level: "debug".to_string(),
-                enable_colors: false,
-                show_timestamp: false,
-                enable_performance_logs: true,
-                enable_audit_logs: false,
-                enable_cache_logs: true,
-                enable_logging: true,
-                enable_async: false,
-                batch_size: 2048,
-                batch_interval_ms: 25,
-                buffer_size: 16384,
+            .build()
+            .await
+            .unwrap();
+        cache.set("session", Bytes::from("user")).await.unwrap();
+        assert_eq!(cache.get_ttl("session").await, Some(600));
+        cache.shutdown().await.unwrap();
+
+        let session = CacheConfigBuilder::preset(Preset::SessionCache).build().unwrap();
+        assert_eq!(session.ttl.expire_seconds, Some(1800));
+        assert!(!session.l2.unwrap().enable_l2_cache);
+
+        let cdn = CacheConfigBuilder::preset(Preset::CdnEdge).build().unwrap();
+        assert_eq!(cdn.l1.eviction_strategy, crate::EvictionStrategy::Lfu);
+        assert_eq!(cdn.l2.unwrap().l2_write_strategy, "always");
+
+        let object = CacheConfigBuilder::preset(Preset::ObjectCache).build().unwrap();
+        assert_eq!(object.ttl.expire_seconds, None);
+        assert_eq!(object.l2.unwrap().l2_write_strategy, "size_based");
+    }
+
+    #[tokio::test]
+    async fn test_batched_eviction_events() {
+        use crate::events::{EventDelivery, EvictionEvent, EvictionListener};
+
+        let batches = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&batches);
+        let listener = EvictionListener::new(
+            EventDelivery::Batched { max: 4, interval: Duration::from_secs(60) },
+            move |events: &[EvictionEvent]| recorded.lock().push(events.len()),
+        );
+
+        let cache = RatMemCacheBuilder::development()
+            .l1_config(crate::config::L1Config {
+                max_memory: 1024 * 1024,
+                max_entries: 5,
+                eviction_strategy: crate::EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
             })
+            .eviction_listener(listener)
             .build()
             .await
             .unwrap();
-        
-        (cache, temp_dir)
+
+        // 15 次写入驱逐 10 个条目：两批满批投递，剩余 2 个在关闭时投递
+        for i in 0..15 {
+            cache.set(format!("key_{}", i), Bytes::from("value")).await.unwrap();
+        }
+        assert_eq!(*batches.lock(), vec![4, 4]);
+
+        cache.shutdown().await.unwrap();
+        assert_eq!(*batches.lock(), vec![4, 4, 2]);
     }
 
     #[tokio::test]
@@ -740,6 +4390,25 @@ mod tests {
         assert!(!cache.contains_key(&key).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_typed_keys() {
+        let (cache, _temp_dir) = create_test_cache().await;
+
+        assert!(CacheKey::new("").is_err());
+        assert!(CacheKey::new("has space").is_err());
+        assert!(CacheKey::new("bad\nkey").is_err());
+        assert!(CacheKey::new("k".repeat(crate::types::MAX_KEY_LENGTH + 1)).is_err());
+        assert!(matches!(CacheKey::new("a b"), Err(CacheError::InvalidKey { .. })));
+
+        // 校验过的键与原始字符串可以混用
+        let key = CacheKey::new("user:42").unwrap();
+        cache.set(&key, Bytes::from("alice")).await.unwrap();
+        assert_eq!(cache.get("user:42").await.unwrap(), Some(Bytes::from("alice")));
+        assert!(cache.contains_key(key.clone()).await.unwrap());
+        assert!(cache.delete(&key).await.unwrap());
+        assert_eq!(cache.get(&key).await.unwrap(), None);
+    }
+
     #[tokio::test]
     async fn test_ttl_operations() {
         let (cache, _temp_dir) = create_test_cache().await;
@@ -766,6 +4435,29 @@ mod tests {
         assert!(retrieved.is_none());
     }
 
+    #[tokio::test]
+    async fn test_ttl_changes_reach_l2() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let (cache, _temp_dir) = create_test_cache_with(|_| {}, clock.clone()).await;
+        let l2_only = CacheOptions { force_l2: true, ttl_seconds: Some(10), ..Default::default() };
+        for key in ["extended", "persistent", "updated", "unchanged"] {
+            cache.set_with_options(key, Bytes::from("value"), &l2_only).await.unwrap();
+        }
+        assert!(!cache.l1_cache.contains_key("extended"));
+
+        cache.set_ttl("extended", 100).await.unwrap();
+        cache.remove_ttl("persistent").await.unwrap();
+        assert_eq!(cache.get_ttl("persistent").await, None);
+        assert_eq!(cache.update_ttl_atomic("updated", |remaining| Some(remaining + 90)).await.unwrap(), Some(100));
+
+        // 原 TTL 过后，L2 按更新后的过期时间判断
+        clock.advance(20);
+        assert_eq!(cache.get("extended").await.unwrap(), Some(Bytes::from("value")));
+        assert_eq!(cache.get("persistent").await.unwrap(), Some(Bytes::from("value")));
+        assert_eq!(cache.get("updated").await.unwrap(), Some(Bytes::from("value")));
+        assert_eq!(cache.get("unchanged").await.unwrap(), None);
+    }
+
     #[tokio::test]
     async fn test_cache_options() {
         let (cache, _temp_dir) = create_test_cache().await;
@@ -792,6 +4484,67 @@ mod tests {
         assert_eq!(retrieved.unwrap(), value);
     }
 
+    #[tokio::test]
+    async fn test_per_request_compression() {
+        use crate::melange_adapter::CompressionAlgorithm;
+
+        let (cache, _temp_dir) = create_test_cache().await;
+        // 超过压缩阈值（128 字节）的可压缩值和低于阈值的小值
+        let large = Bytes::from("compressible value ".repeat(20));
+        let small = Bytes::from("tiny ".repeat(20));
+        let options = |enable_compression| CacheOptions { force_l2: true, enable_compression, ..Default::default() };
+
+        cache.set_with_options("forced_off".to_string(), large.clone(), &options(Some(false))).await.unwrap();
+        cache.set_with_options("forced_on".to_string(), small.clone(), &options(Some(true))).await.unwrap();
+        cache.set_with_options("default".to_string(), small.clone(), &options(None)).await.unwrap();
+
+        let stats = cache.get_l2_stats().await;
+        assert_eq!(stats.compression_by_algorithm[&CompressionAlgorithm::Lz4].entries, 1);
+        assert_eq!(stats.compression_by_algorithm[&CompressionAlgorithm::None].entries, 2);
+
+        // 读取按元数据中记录的决定解压
+        let l2_cache = cache.l2_cache.as_ref().unwrap();
+        assert_eq!(l2_cache.get("forced_off").await.unwrap(), Some(large.clone()));
+        assert_eq!(l2_cache.get("forced_on").await.unwrap(), Some(small.clone()));
+        cache.l1_cache.clear().await.unwrap();
+        assert_eq!(cache.get("forced_off").await.unwrap(), Some(large));
+        assert_eq!(cache.get("forced_on").await.unwrap(), Some(small));
+    }
+
+    #[tokio::test]
+    async fn test_set_with_flags() {
+        let (cache, _temp_dir) = create_test_cache().await;
+
+        // 默认写入的标志为 0
+        cache.set("plain".to_string(), Bytes::from("value")).await.unwrap();
+        let entry = cache.get_with_metadata("plain").await.unwrap().unwrap();
+        assert_eq!(entry.flags, 0);
+
+        // L1 中的标志与 TTL
+        let options = CacheOptions {
+            ttl_seconds: Some(60),
+            ..Default::default()
+        };
+        cache.set_with_flags("tagged".to_string(), Bytes::from("json"), 42, &options).await.unwrap();
+        let entry = cache.get_with_metadata("tagged").await.unwrap().unwrap();
+        assert_eq!(entry.value, Bytes::from("json"));
+        assert_eq!(entry.flags, 42);
+        assert!(entry.ttl_seconds.is_some_and(|ttl| ttl <= 60));
+
+        // 仅写入 L2 的标志在读取和提升到 L1 后保留
+        let options = CacheOptions {
+            force_l2: true,
+            ..Default::default()
+        };
+        cache.set_with_flags("persisted".to_string(), Bytes::from("msgpack"), 7, &options).await.unwrap();
+        assert!(!cache.l1_cache.contains_key("persisted"));
+        let entry = cache.get_with_metadata("persisted").await.unwrap().unwrap();
+        assert_eq!((entry.value, entry.flags), (Bytes::from("msgpack"), 7));
+        assert_eq!(cache.l1_cache.peek_with_flags("persisted").map(|(_, flags)| flags), Some(7));
+
+        assert!(cache.get_with_metadata("missing").await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn test_clear_and_stats() {
         let (cache, _temp_dir) = create_test_cache().await;
@@ -816,12 +4569,16 @@ mod tests {
         #[cfg(feature = "melange-storage")]
         let l2_stats = cache.get_l2_stats().await;
         // 移除严格的统计检查，因为可能还没有足够的操作
+
+        let stats = cache.get_stats().await;
+        assert!(stats.logical_bytes() > 0);
         
         // 清空
         cache.clear().await.unwrap();
         
         let is_empty = cache.is_empty().await.unwrap();
         assert!(is_empty);
+        assert_eq!(cache.get_stats().await.logical_bytes(), 0);
     }
 
     #[tokio::test]
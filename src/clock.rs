@@ -0,0 +1,85 @@
+//! 时钟抽象模块
+//!
+//! 为 TTL 管理和 L2 元数据提供可替换的时间源，便于确定性测试
+
+use crate::types::current_timestamp;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// 时钟接口，返回当前 Unix 时间戳（秒）
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    /// 获取当前 Unix 时间戳（秒）
+    fn now(&self) -> u64;
+}
+
+/// 系统时钟（生产环境默认使用）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        current_timestamp()
+    }
+}
+
+/// 手动推进的模拟时钟（用于测试）
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now: AtomicU64,
+}
+
+impl MockClock {
+    /// 以指定时间戳创建模拟时钟
+    pub fn new(start: u64) -> Self {
+        Self {
+            now: AtomicU64::new(start),
+        }
+    }
+
+    /// 将时钟向前推进指定秒数
+    pub fn advance(&self, seconds: u64) {
+        self.now.fetch_add(seconds, Ordering::SeqCst);
+    }
+
+    /// 直接设置当前时间戳
+    pub fn set(&self, timestamp: u64) {
+        self.now.store(timestamp, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+/// 共享时钟句柄
+pub type SharedClock = Arc<dyn Clock>;
+
+/// 创建默认的系统时钟句柄
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_advance() {
+        let clock = MockClock::new(1_000);
+        assert_eq!(clock.now(), 1_000);
+
+        clock.advance(5);
+        assert_eq!(clock.now(), 1_005);
+
+        clock.set(42);
+        assert_eq!(clock.now(), 42);
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let clock = SystemClock;
+        assert!(clock.now() >= current_timestamp() - 1);
+    }
+}
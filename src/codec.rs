@@ -0,0 +1,267 @@
+//! 值编解码链模块
+//!
+//! 将压缩、加密、校验等值变换统一为可组合的编解码链。
+//! 写入 L2 前按顺序编码，读取时依据元数据中记录的链描述逆序解码。
+
+use crate::error::{CacheError, CacheResult};
+use std::collections::HashSet;
+
+/// 内置编解码器标识（自定义编解码器请使用 128 及以上的标识）
+pub mod codec_ids {
+    pub const LZ4: u8 = 1;
+    pub const ZSTD: u8 = 2;
+    pub const CRC32: u8 = 3;
+    pub const AES_GCM: u8 = 4;
+}
+
+/// 值编解码器
+pub trait ValueCodec: Send + Sync + std::fmt::Debug {
+    /// 编解码器标识，写入元数据用于读取时匹配逆变换
+    fn id(&self) -> u8;
+
+    /// 编码（写入方向）
+    fn encode(&self, data: &[u8]) -> CacheResult<Vec<u8>>;
+
+    /// 解码（读取方向）
+    fn decode(&self, data: &[u8]) -> CacheResult<Vec<u8>>;
+}
+
+/// 有序的编解码链
+#[derive(Debug, Default)]
+pub struct CodecChain {
+    codecs: Vec<Box<dyn ValueCodec>>,
+}
+
+impl CodecChain {
+    /// 创建编解码链，编码时按给定顺序依次应用
+    pub fn new(codecs: Vec<Box<dyn ValueCodec>>) -> CacheResult<Self> {
+        let mut ids = HashSet::new();
+        for codec in &codecs {
+            if !ids.insert(codec.id()) {
+                return Err(CacheError::config_error(format!("编解码器标识重复: {}", codec.id())));
+            }
+        }
+        Ok(Self { codecs })
+    }
+
+    /// 是否为空链
+    pub fn is_empty(&self) -> bool {
+        self.codecs.is_empty()
+    }
+
+    /// 链描述（按编码顺序排列的编解码器标识）
+    pub fn descriptor(&self) -> Vec<u8> {
+        self.codecs.iter().map(|codec| codec.id()).collect()
+    }
+
+    /// 按顺序编码
+    pub fn encode(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        let mut encoded = data.to_vec();
+        for codec in &self.codecs {
+            encoded = codec.encode(&encoded)?;
+        }
+        Ok(encoded)
+    }
+
+    /// 按链描述逆序解码
+    pub fn decode(&self, data: &[u8], descriptor: &[u8]) -> CacheResult<Vec<u8>> {
+        let mut decoded = data.to_vec();
+        for id in descriptor.iter().rev() {
+            let codec = self.codecs.iter().find(|codec| codec.id() == *id).ok_or_else(|| {
+                CacheError::codec_error(format!("未配置编解码器: {}", id))
+            })?;
+            decoded = codec.decode(&decoded)?;
+        }
+        Ok(decoded)
+    }
+}
+
+/// LZ4 压缩编解码器
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lz4Codec;
+
+impl ValueCodec for Lz4Codec {
+    fn id(&self) -> u8 {
+        codec_ids::LZ4
+    }
+
+    fn encode(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        lz4::block::compress(data, None, true)
+            .map_err(|e| CacheError::compression_error(format!("LZ4 编码失败: {}", e)))
+    }
+
+    fn decode(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        lz4::block::decompress(data, None)
+            .map_err(|e| CacheError::compression_error(format!("LZ4 解码失败: {}", e)))
+    }
+}
+
+/// ZSTD 压缩编解码器
+#[cfg(feature = "zstd-codec")]
+#[derive(Debug, Clone, Copy)]
+pub struct ZstdCodec {
+    level: i32,
+}
+
+#[cfg(feature = "zstd-codec")]
+impl ZstdCodec {
+    /// 以指定压缩级别创建
+    pub fn new(level: i32) -> Self {
+        Self { level }
+    }
+}
+
+#[cfg(feature = "zstd-codec")]
+impl Default for ZstdCodec {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+#[cfg(feature = "zstd-codec")]
+impl ValueCodec for ZstdCodec {
+    fn id(&self) -> u8 {
+        codec_ids::ZSTD
+    }
+
+    fn encode(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        zstd::encode_all(data, self.level)
+            .map_err(|e| CacheError::compression_error(format!("ZSTD 编码失败: {}", e)))
+    }
+
+    fn decode(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        zstd::decode_all(data)
+            .map_err(|e| CacheError::compression_error(format!("ZSTD 解码失败: {}", e)))
+    }
+}
+
+/// CRC32 校验编解码器，在数据末尾追加 4 字节校验和
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Crc32Codec;
+
+impl ValueCodec for Crc32Codec {
+    fn id(&self) -> u8 {
+        codec_ids::CRC32
+    }
+
+    fn encode(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        let mut encoded = Vec::with_capacity(data.len() + 4);
+        encoded.extend_from_slice(data);
+        encoded.extend_from_slice(&crc32fast::hash(data).to_le_bytes());
+        Ok(encoded)
+    }
+
+    fn decode(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        if data.len() < 4 {
+            return Err(CacheError::codec_error("CRC32 数据长度不足"));
+        }
+
+        let (payload, checksum) = data.split_at(data.len() - 4);
+        let expected = u32::from_le_bytes(checksum.try_into().unwrap());
+        let actual = crc32fast::hash(payload);
+        if expected != actual {
+            return Err(CacheError::checksum_mismatch(expected, actual));
+        }
+        Ok(payload.to_vec())
+    }
+}
+
+/// AES-256-GCM 加密编解码器，输出为 12 字节随机 nonce + 密文
+#[cfg(feature = "aes-gcm-codec")]
+pub struct AesGcmCodec {
+    cipher: aes_gcm::Aes256Gcm,
+}
+
+#[cfg(feature = "aes-gcm-codec")]
+impl AesGcmCodec {
+    const NONCE_LEN: usize = 12;
+
+    /// 使用 32 字节密钥创建
+    pub fn new(key: &[u8; 32]) -> Self {
+        use aes_gcm::KeyInit;
+
+        Self {
+            cipher: aes_gcm::Aes256Gcm::new(key.into()),
+        }
+    }
+}
+
+#[cfg(feature = "aes-gcm-codec")]
+impl std::fmt::Debug for AesGcmCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密钥
+        f.debug_struct("AesGcmCodec").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "aes-gcm-codec")]
+impl ValueCodec for AesGcmCodec {
+    fn id(&self) -> u8 {
+        codec_ids::AES_GCM
+    }
+
+    fn encode(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        use aes_gcm::aead::{Aead, AeadCore, OsRng};
+
+        let nonce = aes_gcm::Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher.encrypt(&nonce, data)
+            .map_err(|e| CacheError::codec_error(format!("AES-GCM 加密失败: {}", e)))?;
+
+        let mut encoded = Vec::with_capacity(Self::NONCE_LEN + ciphertext.len());
+        encoded.extend_from_slice(&nonce);
+        encoded.extend_from_slice(&ciphertext);
+        Ok(encoded)
+    }
+
+    fn decode(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        use aes_gcm::aead::Aead;
+
+        if data.len() < Self::NONCE_LEN {
+            return Err(CacheError::codec_error("AES-GCM 数据长度不足"));
+        }
+
+        let (nonce, ciphertext) = data.split_at(Self::NONCE_LEN);
+        self.cipher.decrypt(nonce.into(), ciphertext)
+            .map_err(|e| CacheError::codec_error(format!("AES-GCM 解密失败: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_round_trip() {
+        let chain = CodecChain::new(vec![Box::new(Lz4Codec), Box::new(Crc32Codec)]).unwrap();
+        let data = b"abcdefgh".repeat(64);
+
+        let encoded = chain.encode(&data).unwrap();
+        assert!(encoded.len() < data.len());
+        assert_eq!(chain.descriptor(), vec![codec_ids::LZ4, codec_ids::CRC32]);
+        assert_eq!(chain.decode(&encoded, &chain.descriptor()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_checksum_detects_corruption() {
+        let chain = CodecChain::new(vec![Box::new(Crc32Codec)]).unwrap();
+        let mut encoded = chain.encode(b"important value").unwrap();
+        encoded[0] ^= 0xff;
+
+        let result = chain.decode(&encoded, &chain.descriptor());
+        assert!(matches!(result, Err(CacheError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn test_duplicate_codec_rejected() {
+        assert!(CodecChain::new(vec![Box::new(Crc32Codec), Box::new(Crc32Codec)]).is_err());
+    }
+
+    #[cfg(feature = "aes-gcm-codec")]
+    #[test]
+    fn test_aes_gcm_round_trip() {
+        let chain = CodecChain::new(vec![Box::new(AesGcmCodec::new(&[7u8; 32]))]).unwrap();
+        let encoded = chain.encode(b"secret").unwrap();
+        assert_ne!(&encoded[AesGcmCodec::NONCE_LEN..], b"secret");
+        assert_eq!(chain.decode(&encoded, &chain.descriptor()).unwrap(), b"secret");
+    }
+}
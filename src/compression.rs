@@ -1,21 +1,91 @@
 //! 压缩模块
 //!
-//! 提供基于 LZ4 的高性能数据压缩和解压缩功能
+//! 提供基于 LZ4（可选 Snappy）的高性能数据压缩和解压缩功能
 
 use crate::error::{CacheError, CacheResult};
 use crate::config::L2Config;
+#[cfg(feature = "melange-storage")]
 use bytes::Bytes;
+#[cfg(feature = "melange-storage")]
 use lz4::{Decoder, EncoderBuilder};
+#[cfg(feature = "melange-storage")]
 use std::io::{Read, Write};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 压缩算法枚举
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)]
+pub enum CompressionAlgorithm {
+    None,
+    #[default]
+    Lz4,
+    Zstd,
+    /// Snappy（需启用 `snappy` 特性，仅用于值压缩；用作 MelangeDB 页压缩时返回配置错误）
+    Snappy,
+}
+
+impl CompressionAlgorithm {
+    /// 是否可用于值压缩
+    pub fn supports_value_compression(&self) -> bool {
+        match self {
+            CompressionAlgorithm::None | CompressionAlgorithm::Lz4 => true,
+            CompressionAlgorithm::Zstd => false,
+            CompressionAlgorithm::Snappy => cfg!(feature = "snappy"),
+        }
+    }
+
+    /// 可配置的压缩级别范围，None 表示该算法不使用压缩级别
+    pub fn level_range(&self) -> Option<std::ops::RangeInclusive<i32>> {
+        match self {
+            CompressionAlgorithm::Lz4 => Some(1..=12),
+            CompressionAlgorithm::Zstd => Some(-7..=22),
+            CompressionAlgorithm::None | CompressionAlgorithm::Snappy => None,
+        }
+    }
+
+    /// 验证压缩级别是否在该算法的有效范围内
+    pub fn validate_level(&self, level: i32) -> CacheResult<()> {
+        match self.level_range() {
+            Some(range) if !range.contains(&level) => Err(CacheError::config_error(format!(
+                "{:?} 压缩级别必须在 {} 到 {} 之间，当前为 {}",
+                self, range.start(), range.end(), level
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            CompressionAlgorithm::None => 0,
+            CompressionAlgorithm::Lz4 => 1,
+            CompressionAlgorithm::Zstd => 2,
+            CompressionAlgorithm::Snappy => 3,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => CompressionAlgorithm::Lz4,
+            2 => CompressionAlgorithm::Zstd,
+            3 => CompressionAlgorithm::Snappy,
+            _ => CompressionAlgorithm::None,
+        }
+    }
+}
 
 /// 压缩器
+///
+/// 克隆的压缩器共享压缩算法设置，运行时切换对所有持有者生效
 #[derive(Debug, Clone)]
 pub struct Compressor {
+    #[cfg_attr(not(feature = "melange-storage"), allow(dead_code))]
     l2_config: Arc<L2Config>,
+    /// 新写入数据使用的压缩算法（初始值取自 `enable_lz4` 和 `value_compression_algorithm`）
+    algorithm: Arc<AtomicU8>,
 }
 
 /// 压缩结果
+#[cfg(feature = "melange-storage")]
 #[derive(Debug, Clone)]
 pub struct CompressionResult {
     /// 压缩后的数据
@@ -28,9 +98,12 @@ pub struct CompressionResult {
     pub compression_ratio: f64,
     /// 是否实际进行了压缩
     pub is_compressed: bool,
+    /// 实际使用的压缩算法（未压缩时为 None）
+    pub algorithm: CompressionAlgorithm,
 }
 
 /// 解压缩结果
+#[cfg(feature = "melange-storage")]
 #[derive(Debug, Clone)]
 pub struct DecompressionResult {
     /// 解压缩后的数据
@@ -42,8 +115,14 @@ pub struct DecompressionResult {
 impl Compressor {
     /// 从 L2 配置创建压缩器
     pub fn new_from_l2_config(l2_config: &L2Config) -> Self {
+        let algorithm = if l2_config.enable_lz4 {
+            l2_config.value_compression_algorithm
+        } else {
+            CompressionAlgorithm::None
+        };
         Self {
             l2_config: Arc::new(l2_config.clone()),
+            algorithm: Arc::new(AtomicU8::new(algorithm.to_u8())),
         }
     }
 
@@ -53,6 +132,7 @@ impl Compressor {
             enable_l2_cache: false,
             data_dir: None,
             clear_on_startup: false,
+            confirm_clear_on_startup: false,
             max_disk_size: 0,
             write_buffer_size: 0,
             max_write_buffer_number: 0,
@@ -75,29 +155,70 @@ impl Compressor {
             l2_write_strategy: "never".to_string(),
             l2_write_threshold: 0,
             l2_write_ttl_threshold: 0,
+            l2_write_eviction_rate_low: 1.0,
+            l2_write_eviction_rate_high: 100.0,
+            ttl_demotion_enabled: false,
+            ttl_demotion_ratio: 0.1,
+            ttl_demotion_grace_seconds: 60,
+            compact_storage: false,
+            skip_reads_when_empty: true,
+            checksum_enabled: true,
+            evict_corrupted_entries: true,
+            metadata_update_sampling: 1.0,
+            l2_partitions: 1,
+            degrade_to_l1_on_l2_failure: false,
+            l2_max_write_bytes_per_sec: None,
+            blocking_queue_warn_ms: 100,
+            blocking_queue_max_wait_ms: None,
+            persist_ttl_index: true,
+            value_compression_algorithm: CompressionAlgorithm::Lz4,
+            key_prefix: String::new(),
+            l2_max_retries: 2,
+            l2_retry_backoff_ms: 10,
         };
         Self {
             l2_config: Arc::new(disabled_config),
+            algorithm: Arc::new(AtomicU8::new(CompressionAlgorithm::None.to_u8())),
         }
     }
 
-    /// 压缩数据
+    /// 按全局配置压缩数据（测试用）
+    #[cfg(all(test, feature = "melange-storage"))]
     pub fn compress(&self, data: &[u8]) -> CacheResult<CompressionResult> {
+        self.compress_with(data, None)
+    }
+
+    /// 按单次写入的选项压缩数据：`Some(false)` 不压缩；`Some(true)` 忽略大小阈值尝试压缩
+    /// （未配置压缩算法时使用 LZ4）；`None` 按全局配置决定
+    #[cfg(feature = "melange-storage")]
+    pub fn compress_with(&self, data: &[u8], enable: Option<bool>) -> CacheResult<CompressionResult> {
         let original_size = data.len();
+        let algorithm = match (enable, self.algorithm()) {
+            (Some(true), CompressionAlgorithm::None) => CompressionAlgorithm::Lz4,
+            (_, algorithm) => algorithm,
+        };
+        let should_compress = match enable {
+            Some(force) => force && !data.is_empty(),
+            None => self.should_compress(algorithm, data),
+        };
         
         // 检查是否需要压缩
-        if !self.should_compress(data) {
+        if !should_compress {
             return Ok(CompressionResult {
                 compressed_data: Bytes::copy_from_slice(data),
                 original_size,
                 compressed_size: original_size,
                 compression_ratio: 1.0,
                 is_compressed: false,
+                algorithm: CompressionAlgorithm::None,
             });
         }
 
-        // 执行 LZ4 压缩
-        let compressed_data = self.compress_lz4(data)?;
+        let compressed_data = match algorithm {
+            CompressionAlgorithm::Lz4 => self.compress_lz4(data)?,
+            CompressionAlgorithm::Snappy => Self::compress_snappy(data)?,
+            other => return Err(CacheError::compression_error(format!("不支持的值压缩算法: {:?}", other))),
+        };
         let compressed_size = compressed_data.len();
         let compression_ratio = compressed_size as f64 / original_size as f64;
 
@@ -110,6 +231,7 @@ impl Compressor {
                 compressed_size: original_size,
                 compression_ratio: 1.0,
                 is_compressed: false,
+                algorithm: CompressionAlgorithm::None,
             })
         } else {
             // 压缩效果良好，返回压缩数据
@@ -119,22 +241,30 @@ impl Compressor {
                 compressed_size,
                 compression_ratio,
                 is_compressed: true,
+                algorithm,
             })
         }
     }
 
-    /// 解压缩数据
-    pub fn decompress(&self, compressed_data: &[u8], is_compressed: bool) -> CacheResult<DecompressionResult> {
-        if !is_compressed {
-            // 数据未压缩，直接返回
-            return Ok(DecompressionResult {
-                data: Bytes::copy_from_slice(compressed_data),
-                size: compressed_data.len(),
-            });
-        }
-
-        // 执行 LZ4 解压缩
-        let decompressed_data = self.decompress_lz4(compressed_data)?;
+    /// 按写入时使用的算法解压缩数据（None 表示未压缩），与当前配置的算法无关
+    #[cfg(feature = "melange-storage")]
+    pub fn decompress(&self, compressed_data: &[u8], algorithm: CompressionAlgorithm) -> CacheResult<DecompressionResult> {
+        let decompressed_data = match algorithm {
+            CompressionAlgorithm::None => {
+                // 数据未压缩，直接返回
+                return Ok(DecompressionResult {
+                    data: Bytes::copy_from_slice(compressed_data),
+                    size: compressed_data.len(),
+                });
+            }
+            CompressionAlgorithm::Lz4 => self.decompress_lz4(compressed_data)?,
+            _ => {
+                let mut decompressed_data = Vec::new();
+                Self::decoder(compressed_data, algorithm)?.read_to_end(&mut decompressed_data)
+                    .map_err(|e| CacheError::compression_error(format!("{:?} 解压缩失败: {}", algorithm, e)))?;
+                decompressed_data
+            }
+        };
         let size = decompressed_data.len();
 
         Ok(DecompressionResult {
@@ -143,9 +273,47 @@ impl Compressor {
         })
     }
 
+    /// 按块解压缩数据，每块最多 `chunk_size` 字节；`on_chunk` 返回 false 时提前停止
+    #[cfg(feature = "melange-storage")]
+    pub fn decompress_chunked<F>(&self, compressed_data: &[u8], algorithm: CompressionAlgorithm, chunk_size: usize, mut on_chunk: F) -> CacheResult<()>
+    where
+        F: FnMut(Bytes) -> bool,
+    {
+        if algorithm == CompressionAlgorithm::None {
+            for chunk in compressed_data.chunks(chunk_size) {
+                if !on_chunk(Bytes::copy_from_slice(chunk)) {
+                    break;
+                }
+            }
+            return Ok(());
+        }
+
+        let mut decoder = Self::decoder(compressed_data, algorithm)?;
+        loop {
+            let mut chunk = vec![0u8; chunk_size];
+            let mut filled = 0;
+            while filled < chunk_size {
+                let read = decoder.read(&mut chunk[filled..])
+                    .map_err(|e| CacheError::compression_error(format!("{:?} 解压缩失败: {}", algorithm, e)))?;
+                if read == 0 {
+                    break;
+                }
+                filled += read;
+            }
+            if filled == 0 {
+                return Ok(());
+            }
+            chunk.truncate(filled);
+            if !on_chunk(Bytes::from(chunk)) || filled < chunk_size {
+                return Ok(());
+            }
+        }
+    }
+
     /// 检查是否应该压缩数据
-    fn should_compress(&self, data: &[u8]) -> bool {
-        if !self.l2_config.enable_lz4 {
+    #[cfg(feature = "melange-storage")]
+    fn should_compress(&self, algorithm: CompressionAlgorithm, data: &[u8]) -> bool {
+        if algorithm == CompressionAlgorithm::None {
             return false;
         }
 
@@ -162,35 +330,8 @@ impl Compressor {
         true
     }
 
-    /// 估算数据的可压缩性
-    /// 使用简单的字节频率分析来估算数据是否值得压缩
-    fn estimate_compressibility(&self, data: &[u8]) -> bool {
-        if data.len() < 64 {
-            return false;
-        }
-
-        // 采样前 256 字节进行快速分析
-        let sample_size = std::cmp::min(256, data.len());
-        let sample = &data[..sample_size];
-        
-        // 计算字节频率
-        let mut freq = [0u32; 256];
-        for &byte in sample {
-            freq[byte as usize] += 1;
-        }
-
-        // 计算唯一字节数
-        let unique_bytes = freq.iter().filter(|&&count| count > 0).count();
-        
-        // 如果唯一字节数太少，可能是重复数据，值得压缩
-        // 如果唯一字节数接近 256，可能是随机数据，不值得压缩
-        let uniqueness_ratio = unique_bytes as f64 / 256.0;
-        
-        // 经验值：唯一性比率在 0.1-0.8 之间的数据通常值得压缩
-        uniqueness_ratio >= 0.1 && uniqueness_ratio <= 0.8
-    }
-
     /// 执行 LZ4 压缩
+    #[cfg(feature = "melange-storage")]
     fn compress_lz4(&self, data: &[u8]) -> CacheResult<Vec<u8>> {
         let mut encoder = EncoderBuilder::new()
             .level(self.l2_config.compression_level as u32)
@@ -207,6 +348,7 @@ impl Compressor {
     }
 
     /// 执行 LZ4 解压缩
+    #[cfg(feature = "melange-storage")]
     fn decompress_lz4(&self, compressed_data: &[u8]) -> CacheResult<Vec<u8>> {
         let mut decoder = Decoder::new(compressed_data)
             .map_err(|e| CacheError::compression_error(&format!("创建 LZ4 解码器失败: {}", e)))?;
@@ -218,31 +360,53 @@ impl Compressor {
         Ok(decompressed_data)
     }
 
-    /// 获取压缩配置
-    pub fn config(&self) -> &L2Config {
-        &self.l2_config
+    /// 创建流式解码器
+    #[cfg(feature = "melange-storage")]
+    fn decoder<'a>(compressed_data: &'a [u8], algorithm: CompressionAlgorithm) -> CacheResult<Box<dyn Read + 'a>> {
+        match algorithm {
+            CompressionAlgorithm::Lz4 => Ok(Box::new(Decoder::new(compressed_data)
+                .map_err(|e| CacheError::compression_error(format!("创建 LZ4 解码器失败: {}", e)))?)),
+            #[cfg(feature = "snappy")]
+            CompressionAlgorithm::Snappy => Ok(Box::new(snap::read::FrameDecoder::new(compressed_data))),
+            #[cfg(not(feature = "snappy"))]
+            CompressionAlgorithm::Snappy => Err(CacheError::compression_error("数据使用 Snappy 压缩，但未启用 snappy 特性")),
+            other => Err(CacheError::compression_error(format!("不支持的值压缩算法: {:?}", other))),
+        }
     }
 
-    /// 估算压缩后的大小（用于内存预分配）
-    pub fn estimate_compressed_size(&self, original_size: usize) -> usize {
-        if original_size < self.l2_config.compression_threshold {
-            return original_size;
-        }
+    /// 执行 Snappy 压缩（帧格式，支持流式解压）
+    #[cfg(all(feature = "melange-storage", feature = "snappy"))]
+    fn compress_snappy(data: &[u8]) -> CacheResult<Vec<u8>> {
+        let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+        encoder.write_all(data)
+            .map_err(|e| CacheError::compression_error(format!("Snappy 压缩写入失败: {}", e)))?;
+        encoder.into_inner()
+            .map_err(|e| CacheError::compression_error(format!("Snappy 压缩完成失败: {}", e.error())))
+    }
 
-        // 根据压缩级别估算压缩比率
-        let estimated_ratio = match self.l2_config.compression_level {
-            1..=3 => 0.7,   // 快速压缩
-            4..=6 => 0.6,   // 平衡压缩
-            7..=9 => 0.5,   // 高压缩
-            10..=12 => 0.4, // 最高压缩
-            _ => 0.6,       // 默认
-        };
+    #[cfg(all(feature = "melange-storage", not(feature = "snappy")))]
+    fn compress_snappy(_data: &[u8]) -> CacheResult<Vec<u8>> {
+        Err(CacheError::compression_error("未启用 snappy 特性"))
+    }
 
-        (original_size as f64 * estimated_ratio) as usize
+    /// 新写入数据使用的压缩算法（None 表示不压缩）
+    pub fn algorithm(&self) -> CompressionAlgorithm {
+        CompressionAlgorithm::from_u8(self.algorithm.load(Ordering::Relaxed))
+    }
+
+    /// 切换新写入数据的压缩算法，已有数据按各自记录的算法解压，不受影响
+    #[cfg(feature = "melange-storage")]
+    pub fn set_algorithm(&self, algorithm: CompressionAlgorithm) -> CacheResult<()> {
+        if !algorithm.supports_value_compression() {
+            return Err(CacheError::config_error(format!("不支持的值压缩算法: {:?}", algorithm)));
+        }
+        self.algorithm.store(algorithm.to_u8(), Ordering::Relaxed);
+        Ok(())
     }
 }
 
 /// 压缩统计信息
+#[cfg(feature = "melange-storage")]
 #[derive(Debug, Clone, Default)]
 pub struct CompressionStats {
     /// 总压缩次数
@@ -261,6 +425,7 @@ pub struct CompressionStats {
     pub decompression_failures: u64,
 }
 
+#[cfg(feature = "melange-storage")]
 impl CompressionStats {
     /// 创建新的统计信息
     pub fn new() -> Self {
@@ -336,7 +501,7 @@ impl CompressionStats {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "melange-storage"))]
 mod tests {
     use super::*;
     use crate::config::L2Config;
@@ -346,6 +511,7 @@ mod tests {
             enable_l2_cache: true,
             data_dir: None,
             clear_on_startup: false,
+            confirm_clear_on_startup: false,
             max_disk_size: 1024 * 1024 * 1024,
             write_buffer_size: 64 * 1024 * 1024,
             max_write_buffer_number: 3,
@@ -368,6 +534,26 @@ mod tests {
             l2_write_strategy: "write_through".to_string(),
             l2_write_threshold: 1024,
             l2_write_ttl_threshold: 300,
+            l2_write_eviction_rate_low: 1.0,
+            l2_write_eviction_rate_high: 100.0,
+            ttl_demotion_enabled: false,
+            ttl_demotion_ratio: 0.1,
+            ttl_demotion_grace_seconds: 60,
+            compact_storage: false,
+            skip_reads_when_empty: true,
+            checksum_enabled: true,
+            evict_corrupted_entries: true,
+            metadata_update_sampling: 1.0,
+            l2_partitions: 1,
+            degrade_to_l1_on_l2_failure: false,
+            l2_max_write_bytes_per_sec: None,
+            blocking_queue_warn_ms: 100,
+            blocking_queue_max_wait_ms: None,
+            persist_ttl_index: true,
+            value_compression_algorithm: CompressionAlgorithm::Lz4,
+            key_prefix: String::new(),
+            l2_max_retries: 2,
+            l2_retry_backoff_ms: 10,
         };
         Compressor::new_from_l2_config(&config)
     }
@@ -388,6 +574,7 @@ mod tests {
             enable_l2_cache: true,
             data_dir: None,
             clear_on_startup: false,
+            confirm_clear_on_startup: false,
             max_disk_size: 1024 * 1024 * 1024,
             write_buffer_size: 64 * 1024 * 1024,
             max_write_buffer_number: 3,
@@ -410,6 +597,26 @@ mod tests {
             l2_write_strategy: "write_through".to_string(),
             l2_write_threshold: 1024,
             l2_write_ttl_threshold: 300,
+            l2_write_eviction_rate_low: 1.0,
+            l2_write_eviction_rate_high: 100.0,
+            ttl_demotion_enabled: false,
+            ttl_demotion_ratio: 0.1,
+            ttl_demotion_grace_seconds: 60,
+            compact_storage: false,
+            skip_reads_when_empty: true,
+            checksum_enabled: true,
+            evict_corrupted_entries: true,
+            metadata_update_sampling: 1.0,
+            l2_partitions: 1,
+            degrade_to_l1_on_l2_failure: false,
+            l2_max_write_bytes_per_sec: None,
+            blocking_queue_warn_ms: 100,
+            blocking_queue_max_wait_ms: None,
+            persist_ttl_index: true,
+            value_compression_algorithm: CompressionAlgorithm::Lz4,
+            key_prefix: String::new(),
+            l2_max_retries: 2,
+            l2_retry_backoff_ms: 10,
         };
         let compressor = Compressor::new_from_l2_config(&config);
         let data = b"Hello, World! This is a test string that should be compressed.".repeat(20);
@@ -427,7 +634,7 @@ mod tests {
         let compress_result = compressor.compress(&original_data).unwrap();
         let decompress_result = compressor.decompress(
             &compress_result.compressed_data,
-            compress_result.is_compressed
+            compress_result.algorithm
         ).unwrap();
         
         assert_eq!(decompress_result.data.as_ref(), original_data.as_slice());
@@ -443,6 +650,7 @@ mod tests {
             compressed_size: 50,
             compression_ratio: 0.5,
             is_compressed: true,
+            algorithm: CompressionAlgorithm::Lz4,
         };
         
         stats.record_compression(&result);
@@ -453,4 +661,79 @@ mod tests {
         assert_eq!(stats.overall_compression_ratio(), 0.5);
         assert_eq!(stats.bytes_saved(), 50);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_switch_compression_at_runtime() {
+        let compressor = create_test_compressor();
+        let shared = compressor.clone();
+        let data = b"Hello, World! This is a test string that should be compressed.".repeat(10);
+        let compressed = compressor.compress(&data).unwrap();
+        assert!(compressed.is_compressed);
+
+        // 切换对克隆的压缩器同样生效，切换前写入的数据仍可解压
+        shared.set_algorithm(CompressionAlgorithm::None).unwrap();
+        assert_eq!(compressor.algorithm(), CompressionAlgorithm::None);
+        assert!(!compressor.compress(&data).unwrap().is_compressed);
+        let decompressed = compressor.decompress(&compressed.compressed_data, CompressionAlgorithm::Lz4).unwrap();
+        assert_eq!(decompressed.data.as_ref(), data.as_slice());
+
+        assert!(compressor.set_algorithm(CompressionAlgorithm::Zstd).is_err());
+        assert_eq!(compressor.algorithm(), CompressionAlgorithm::None);
+    }
+
+    #[test]
+    fn test_decompress_chunked() {
+        let compressor = create_test_compressor();
+        let data = b"chunked decompression test data ".repeat(100);
+        let compressed = compressor.compress(&data).unwrap();
+        assert!(compressed.is_compressed);
+
+        for algorithm in [CompressionAlgorithm::Lz4, CompressionAlgorithm::None] {
+            let input = if algorithm == CompressionAlgorithm::Lz4 { compressed.compressed_data.as_ref() } else { data.as_slice() };
+            let mut chunks = Vec::new();
+            compressor.decompress_chunked(input, algorithm, 1000, |chunk| {
+                chunks.push(chunk);
+                true
+            }).unwrap();
+            assert_eq!(chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(), vec![1000, 1000, 1000, 200]);
+            assert_eq!(chunks.concat(), data);
+        }
+
+        // 回调返回 false 时提前停止
+        let mut count = 0;
+        compressor.decompress_chunked(&compressed.compressed_data, CompressionAlgorithm::Lz4, 1000, |_| {
+            count += 1;
+            false
+        }).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[cfg(feature = "snappy")]
+    #[test]
+    fn test_snappy_roundtrip() {
+        let compressor = create_test_compressor();
+        compressor.set_algorithm(CompressionAlgorithm::Snappy).unwrap();
+        let data = b"snappy compression test data ".repeat(100);
+
+        let compressed = compressor.compress(&data).unwrap();
+        assert!(compressed.is_compressed);
+        assert_eq!(compressed.algorithm, CompressionAlgorithm::Snappy);
+        assert!(compressed.compressed_size < compressed.original_size);
+
+        let decompressed = compressor.decompress(&compressed.compressed_data, CompressionAlgorithm::Snappy).unwrap();
+        assert_eq!(decompressed.data.as_ref(), data.as_slice());
+
+        let mut chunks = Vec::new();
+        compressor.decompress_chunked(&compressed.compressed_data, CompressionAlgorithm::Snappy, 1000, |chunk| {
+            chunks.push(chunk);
+            true
+        }).unwrap();
+        assert_eq!(chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(), vec![1000, 1000, 900]);
+        assert_eq!(chunks.concat(), data);
+
+        // 解压按数据记录的算法进行，与当前配置无关
+        compressor.set_algorithm(CompressionAlgorithm::Lz4).unwrap();
+        let decompressed = compressor.decompress(&compressed.compressed_data, CompressionAlgorithm::Snappy).unwrap();
+        assert_eq!(decompressed.data.as_ref(), data.as_slice());
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! 定义缓存系统的各种配置选项和构建器模式
 
+use crate::compression::CompressionAlgorithm;
 use crate::error::{CacheError, CacheResult};
 use crate::types::EvictionStrategy;
 use serde::{Deserialize, Serialize};
@@ -22,20 +23,66 @@ pub struct CacheConfig {
     pub performance: PerformanceConfig,
     /// 日志配置（可选）
     pub logging: Option<LoggingConfig>,
+    /// 只读模式：写入、删除、清空和修改 TTL 返回 [`CacheError::ReadOnly`]，读取不受影响
+    #[serde(default)]
+    pub read_only: bool,
+    /// 只读模式下是否仍将 L2 命中提升到 L1（提升引起的 L1 驱逐会移除仅存在于 L1 的条目）
+    #[serde(default = "default_true")]
+    pub read_only_promote_on_hit: bool,
+    /// L1 写入失败时的处理方式
+    #[serde(default)]
+    pub l1_set_failure_policy: L1SetFailurePolicy,
+    /// 是否发布键空间事件（写入、删除、过期、驱逐），关闭时不创建事件通道
+    #[serde(default)]
+    pub keyspace_events: bool,
+}
+
+impl CacheConfig {
+    /// 从 TOML 文本解析配置，并检查配置所需的特性是否已编译
+    pub fn from_toml_str(content: &str) -> CacheResult<Self> {
+        let config: CacheConfig = toml::from_str(content)
+            .map_err(|e| CacheError::config_error(format!("解析配置文件失败: {}", e)))?;
+        config.check_feature_support()?;
+        Ok(config)
+    }
+
+    /// 检查配置启用的功能是否被当前构建支持
+    ///
+    /// 未启用 `melange-storage` 特性时，`[l2]` 中 `enable_l2_cache = true` 会被拒绝，
+    /// 避免 L2 配置被静默忽略
+    pub fn check_feature_support(&self) -> CacheResult<()> {
+        #[cfg(not(feature = "melange-storage"))]
+        if self.l2.as_ref().is_some_and(|l2| l2.enable_l2_cache) {
+            return Err(CacheError::config_error(
+                "配置启用了 L2 缓存（[l2] enable_l2_cache = true），但当前构建未启用 melange-storage 特性。\
+                 请使用 --features melange-storage（或 full-features / server）重新编译，或将 enable_l2_cache 设为 false",
+            ));
+        }
+        Ok(())
+    }
 }
 
 /// L1 内存缓存配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct L1Config {
-    /// 最大内存使用量（字节）
+    /// 最大内存使用量（字节），按每个条目的键长度、值大小和固定开销计量
     pub max_memory: usize,
     /// 最大条目数量
     pub max_entries: usize,
     /// 驱逐策略
     pub eviction_strategy: EvictionStrategy,
+    /// 最短保留时间（毫秒），插入未满该时间的条目仅在没有更早的候选者时才会被驱逐，0 表示不限制
+    #[serde(default)]
+    pub l1_min_retention_ms: u64,
+    /// FIFO 顺序队列长度超过存活条目数的该倍数时压缩（去重并移除已删除的键），0 表示不压缩
+    #[serde(default = "default_order_compaction_factor")]
+    pub order_compaction_factor: usize,
 }
 
 /// L2 持久化缓存配置
+///
+/// 仅在启用 `melange-storage` 特性时生效；未启用该特性的构建中
+/// `enable_l2_cache = true` 会被 [`CacheConfig::check_feature_support`] 拒绝
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct L2Config {
     /// 启用 L2 缓存（MelangeDB 持久化存储）
@@ -45,6 +92,9 @@ pub struct L2Config {
     /// 启动时清空缓存目录
     #[serde(default)]
     pub clear_on_startup: bool,
+    /// 确认启动时清空缓存目录，未确认时 `clear_on_startup` 会拒绝启动而不是删除数据
+    #[serde(default)]
+    pub confirm_clear_on_startup: bool,
     /// 最大磁盘使用量（字节）
     #[serde(default)]
     pub max_disk_size: u64,
@@ -69,7 +119,7 @@ pub struct L2Config {
     /// 最大压缩阈值（字节），大于此值的数据不压缩
     #[serde(default = "default_compression_max_threshold")]
     pub compression_max_threshold: usize,
-    /// 压缩级别（1-12，数字越大压缩率越高但速度越慢）
+    /// 值压缩算法的压缩级别（LZ4 为 1-12，数字越大压缩率越高但速度越慢；Snappy 不使用）
     #[serde(default)]
     pub compression_level: i32,
     /// MelangeDB 缓存大小（MB）
@@ -94,7 +144,7 @@ pub struct L2Config {
     /// 缓存预热策略
     #[serde(default)]
     pub cache_warmup_strategy: CacheWarmupStrategy,
-    /// ZSTD压缩级别（仅当使用ZSTD压缩时有效）
+    /// ZSTD压缩级别（-7 到 22，仅当使用ZSTD压缩时有效）
     #[serde(default)]
     pub zstd_compression_level: Option<i32>,
     /// L2 写入策略
@@ -106,6 +156,71 @@ pub struct L2Config {
     /// L2 写入 TTL 阈值
     #[serde(default)]
     pub l2_write_ttl_threshold: u64,
+    /// eviction_rate 策略：L1 驱逐速率（次/秒）不高于该值时按 l2_write_threshold 写入
+    #[serde(default = "default_l2_write_eviction_rate_low")]
+    pub l2_write_eviction_rate_low: f64,
+    /// eviction_rate 策略：L1 驱逐速率（次/秒）达到该值时全部写入
+    #[serde(default = "default_l2_write_eviction_rate_high")]
+    pub l2_write_eviction_rate_high: f64,
+    /// 启用基于 TTL 的 L1→L2 提前降级
+    #[serde(default)]
+    pub ttl_demotion_enabled: bool,
+    /// 触发降级的剩余 TTL 比例（0-1），剩余 TTL 不超过总 TTL 的该比例时降级
+    #[serde(default = "default_ttl_demotion_ratio")]
+    pub ttl_demotion_ratio: f64,
+    /// 降级到 L2 后额外保留的宽限时间（秒）
+    #[serde(default = "default_ttl_demotion_grace_seconds")]
+    pub ttl_demotion_grace_seconds: u64,
+    /// 紧凑存储：无 TTL 的条目将元数据头内联到数据值中（单键存储）
+    #[serde(default)]
+    pub compact_storage: bool,
+    /// L2 自上次清空后尚无写入时跳过 L2 读取
+    #[serde(default = "default_skip_reads_when_empty")]
+    pub skip_reads_when_empty: bool,
+    /// 写入时为存储数据计算 CRC32 校验和，读取时校验
+    #[serde(default = "default_checksum_enabled")]
+    pub checksum_enabled: bool,
+    /// 读取到损坏数据时删除该条目
+    #[serde(default = "default_evict_corrupted_entries")]
+    pub evict_corrupted_entries: bool,
+    /// 命中时写回访问元数据的采样率（0-1]，1.0 表示每次命中都写回
+    #[serde(default = "default_metadata_update_sampling")]
+    pub metadata_update_sampling: f64,
+    /// 键空间分区数（1-256），压缩按分区逐个进行；修改后需清空 L2 数据
+    #[serde(default = "default_l2_partitions")]
+    pub l2_partitions: usize,
+    /// L2 连续出现存储故障时自动降级为仅使用 L1，并定期探测恢复
+    #[serde(default)]
+    pub degrade_to_l1_on_l2_failure: bool,
+    /// L2 持续写入带宽上限（字节/秒，按写入前的原始大小计），超出的写入只保留在 L1
+    #[serde(default)]
+    pub l2_max_write_bytes_per_sec: Option<u64>,
+    /// L2 读写在后台线程池排队超过该时间（毫秒）时记录警告，0 表示不警告
+    #[serde(default = "default_blocking_queue_warn_ms")]
+    pub blocking_queue_warn_ms: u64,
+    /// L2 读写在后台线程池排队超过该时间（毫秒）仍未开始执行时返回 `CacheError::Busy`，None 表示一直等待
+    #[serde(default)]
+    pub blocking_queue_max_wait_ms: Option<u64>,
+    /// 正常关闭时将 TTL 索引保存到数据目录，下次启动直接加载而不扫描全部元数据
+    #[serde(default = "default_persist_ttl_index")]
+    pub persist_ttl_index: bool,
+    /// 值压缩算法（LZ4 或 Snappy，仅在 `enable_lz4` 开启时生效），已有数据按写入时的算法解压
+    #[serde(default)]
+    pub value_compression_algorithm: CompressionAlgorithm,
+    /// 所有 L2 存储键的命名空间前缀，多个缓存实例共享同一数据目录时用于隔离
+    ///
+    /// 共享目录的实例应各自使用非空且互不为前缀的值（如 `app1/`、`app2/`）；
+    /// 为空时实例独占整个存储，`clear` 会删除目录中的所有数据
+    #[serde(default)]
+    pub key_prefix: String,
+    /// L2 读写遇到瞬时故障（IO 或数据库错误）时的最大重试次数，0 表示不重试
+    ///
+    /// 数据损坏、校验失败、线程池繁忙等错误不会重试
+    #[serde(default = "default_l2_max_retries")]
+    pub l2_max_retries: u32,
+    /// 第一次重试前的等待时间（毫秒），之后每次重试翻倍
+    #[serde(default = "default_l2_retry_backoff_ms")]
+    pub l2_retry_backoff_ms: u64,
 }
 
 #[cfg(feature = "melange-storage")]
@@ -115,6 +230,7 @@ impl Default for L2Config {
             enable_l2_cache: false,
             data_dir: None,
             clear_on_startup: false,
+            confirm_clear_on_startup: false,
             max_disk_size: 1024 * 1024 * 1024, // 1GB
             write_buffer_size: 64 * 1024 * 1024, // 64MB
             max_write_buffer_number: 3,
@@ -137,6 +253,26 @@ impl Default for L2Config {
             l2_write_strategy: "write_through".to_string(),
             l2_write_threshold: 1024,
             l2_write_ttl_threshold: 300,
+            l2_write_eviction_rate_low: 1.0,
+            l2_write_eviction_rate_high: 100.0,
+            ttl_demotion_enabled: false,
+            ttl_demotion_ratio: 0.1,
+            ttl_demotion_grace_seconds: 60,
+            compact_storage: false,
+            skip_reads_when_empty: true,
+            checksum_enabled: true,
+            evict_corrupted_entries: true,
+            metadata_update_sampling: 1.0,
+            l2_partitions: 1,
+            degrade_to_l1_on_l2_failure: false,
+            l2_max_write_bytes_per_sec: None,
+            blocking_queue_warn_ms: 100,
+            blocking_queue_max_wait_ms: None,
+            persist_ttl_index: true,
+            value_compression_algorithm: CompressionAlgorithm::Lz4,
+            key_prefix: String::new(),
+            l2_max_retries: 2,
+            l2_retry_backoff_ms: 10,
         }
     }
 }
@@ -161,6 +297,27 @@ impl Default for CacheWarmupStrategy {
     }
 }
 
+/// L1 写入失败时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum L1SetFailurePolicy {
+    /// 记录警告后继续写入 L2，调用方得到成功结果（未写入 L2 的值只能在下次写入后读到）
+    #[default]
+    Ignore,
+    /// 立即返回 L1 的错误，不再写入 L2
+    Propagate,
+}
+
+/// 常见使用场景的配置预设（见 [`CacheConfigBuilder::preset`]）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// 会话缓存：小值、默认 30 分钟过期，仅使用 L1
+    SessionCache,
+    /// CDN 边缘缓存：大值、默认 1 天过期，以 L2 为主
+    CdnEdge,
+    /// 通用对象缓存：永不过期，较大的值写入 L2
+    ObjectCache,
+}
+
 
 
 
@@ -177,6 +334,12 @@ pub struct TtlConfig {
     pub lazy_expiration: bool,
     /// 启用主动过期（定时清理）
     pub active_expiration: bool,
+    /// 清理任务命令通道容量，通道满时丢弃命令并计数（过期判断不受影响）
+    #[serde(default = "default_cleanup_channel_capacity")]
+    pub cleanup_channel_capacity: usize,
+    /// 滑动过期：读取命中时按键的 TTL 时长从当前时间重新计算过期时间，永不过期的键不受影响
+    #[serde(default)]
+    pub sliding_expiration: bool,
 }
 
 /// 性能配置
@@ -194,6 +357,19 @@ pub struct PerformanceConfig {
     pub enable_warmup: bool,
     /// 大值阈值（字节），超过此值的数据直接写入L2或抛弃
     pub large_value_threshold: usize,
+    /// 单键统计跟踪的最活跃键数量，0 表示关闭
+    ///
+    /// 开启后每个被跟踪的键约占用键长加 100 字节，另有约 `max(N * 8, 256)` 向上取整到 2 的幂再乘以 16 字节的频率草图
+    #[serde(default)]
+    pub key_stats_top_n: usize,
+    /// 单个值允许的最大原始大小（字节），超过时在压缩和写入之前直接拒绝，0 表示不限制
+    #[serde(default)]
+    pub absolute_max_value_bytes: usize,
+    /// 标签索引最多记录的带标签键数，达到上限后为新键设置标签的写入返回 [`crate::CacheError::TagIndexFull`]
+    ///
+    /// 索引只在内存中维护，每个键约占用键长加 80 字节，每个（键, 标签）对另占用键长和标签长度之和加 100 字节
+    #[serde(default = "default_max_tagged_keys")]
+    pub max_tagged_keys: usize,
 }
 
 /// 日志配置
@@ -247,6 +423,10 @@ pub struct CacheConfigBuilder {
     ttl_config: Option<TtlConfig>,
     performance_config: Option<PerformanceConfig>,
     logging_config: Option<LoggingConfig>,
+    read_only: bool,
+    read_only_promote_on_hit: bool,
+    l1_set_failure_policy: L1SetFailurePolicy,
+    keyspace_events: bool,
 }
 
 impl CacheConfigBuilder {
@@ -258,6 +438,233 @@ impl CacheConfigBuilder {
             ttl_config: None,
             performance_config: None,
             logging_config: None,
+            read_only: false,
+            read_only_promote_on_hit: true,
+            l1_set_failure_policy: L1SetFailurePolicy::Ignore,
+            keyspace_events: false,
+        }
+    }
+
+    /// 开发环境预设
+    ///
+    /// - L1: 可用内存的 1/16（64MB-256MB），10 万条目，LRU
+    /// - L2: 禁用
+    /// - TTL: 永不过期，每 60 秒清理，惰性 + 主动过期
+    /// - 性能: 4 个工作线程，不启用读写分离和预热，大值阈值 10KB
+    /// - 日志: debug 级别，彩色输出，同步模式
+    pub fn development() -> Self {
+        let system_info = SystemInfo::get();
+        let l1_memory = (system_info.available_memory as usize / 16)
+            .clamp(64 * 1024 * 1024, 256 * 1024 * 1024);
+
+        let builder = Self::new()
+            .with_l1_config(L1Config {
+                max_memory: l1_memory,
+                max_entries: 100_000,
+                eviction_strategy: EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
+            })
+            .with_ttl_config(TtlConfig {
+                expire_seconds: None,
+                cleanup_interval: 60,
+                max_cleanup_entries: 1000,
+                lazy_expiration: true,
+                active_expiration: true,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
+            })
+            .with_performance_config(PerformanceConfig {
+                worker_threads: 4,
+                enable_concurrency: true,
+                read_write_separation: false,
+                batch_size: 100,
+                enable_warmup: false,
+                large_value_threshold: 10 * 1024,
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 0,
+                max_tagged_keys: 100_000,
+            })
+            .with_logging_config(LoggingConfig {
+                level: "debug".to_string(),
+                enable_colors: true,
+                show_timestamp: true,
+                enable_performance_logs: true,
+                enable_audit_logs: false,
+                enable_cache_logs: true,
+                enable_logging: true,
+                enable_async: false,
+                batch_size: default_batch_size(),
+                batch_interval_ms: default_batch_interval_ms(),
+                buffer_size: default_buffer_size(),
+            });
+
+        #[cfg(feature = "melange-storage")]
+        let builder = builder.with_l2_config(L2Config::default());
+
+        builder
+    }
+
+    /// 生产环境预设
+    ///
+    /// - L1: 可用内存的 1/4（至少 64MB，至多 2GB），100 万条目，LRU
+    /// - L2: 启用，数据目录为系统临时目录下的 rat_memcache，磁盘上限 1GB，LZ4 压缩，启动时保留数据
+    /// - TTL: 永不过期，每 300 秒清理，单次最多 10000 条，惰性 + 主动过期
+    /// - 性能: CPU 核心数 × 2 个工作线程（4-32），启用读写分离和预热，批大小 1000，大值阈值 64KB
+    /// - 日志: info 级别，关闭彩色输出和性能/审计/缓存日志，异步模式
+    pub fn production() -> Self {
+        let system_info = SystemInfo::get();
+        let l1_memory = system_info.recommended_l1_memory().max(64 * 1024 * 1024);
+
+        let builder = Self::new()
+            .with_l1_config(L1Config {
+                max_memory: l1_memory,
+                max_entries: 1_000_000,
+                eviction_strategy: EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
+            })
+            .with_ttl_config(TtlConfig {
+                expire_seconds: None,
+                cleanup_interval: 300,
+                max_cleanup_entries: 10_000,
+                lazy_expiration: true,
+                active_expiration: true,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
+            })
+            .with_performance_config(PerformanceConfig {
+                worker_threads: system_info.recommended_worker_threads(),
+                enable_concurrency: true,
+                read_write_separation: true,
+                batch_size: 1000,
+                enable_warmup: true,
+                large_value_threshold: 64 * 1024,
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 0,
+                max_tagged_keys: 100_000,
+            })
+            .with_logging_config(LoggingConfig {
+                level: "info".to_string(),
+                enable_colors: false,
+                show_timestamp: true,
+                enable_performance_logs: false,
+                enable_audit_logs: false,
+                enable_cache_logs: false,
+                enable_logging: true,
+                enable_async: true,
+                batch_size: default_batch_size(),
+                batch_interval_ms: default_batch_interval_ms(),
+                buffer_size: default_buffer_size(),
+            });
+
+        #[cfg(feature = "melange-storage")]
+        let builder = builder.with_l2_config(L2Config {
+            enable_l2_cache: true,
+            data_dir: PathUtils::default_cache_dir().ok(),
+            ..L2Config::default()
+        });
+
+        builder
+    }
+
+    /// 按使用场景预设，日志配置与生产环境预设相同，之后仍可单独覆盖各配置块
+    ///
+    /// - `SessionCache`: L1 为可用内存的 1/4，100 万条目，LRU；L2 禁用；默认 TTL 30 分钟，每 60 秒清理。
+    ///   访问不会自动续期，需要滑动过期时可调用 `set_ttl`
+    /// - `CdnEdge`: L1 10 万条目，LFU；L2 启用，磁盘上限 10GB，全部写入 L2，关闭 LZ4（内容通常已压缩）；
+    ///   默认 TTL 1 天；大值阈值 1MB
+    /// - `ObjectCache`: L1 100 万条目，LRU；L2 启用，不小于 4KB 的值写入 L2；永不过期；大值阈值 256KB
+    pub fn preset(preset: Preset) -> Self {
+        let system_info = SystemInfo::get();
+        let l1_memory = system_info.recommended_l1_memory().max(64 * 1024 * 1024);
+        let performance = |large_value_threshold| PerformanceConfig {
+            worker_threads: system_info.recommended_worker_threads(),
+            enable_concurrency: true,
+            read_write_separation: true,
+            batch_size: 1000,
+            enable_warmup: false,
+            large_value_threshold,
+            key_stats_top_n: 0,
+            absolute_max_value_bytes: 0,
+            max_tagged_keys: 100_000,
+        };
+        let ttl = |expire_seconds, cleanup_interval| TtlConfig {
+            expire_seconds,
+            cleanup_interval,
+            max_cleanup_entries: 10_000,
+            lazy_expiration: true,
+            active_expiration: true,
+            cleanup_channel_capacity: default_cleanup_channel_capacity(),
+            sliding_expiration: false,
+        };
+
+        let builder = Self::production();
+        match preset {
+            Preset::SessionCache => {
+                let builder = builder
+                    .with_l1_config(L1Config {
+                        max_memory: l1_memory,
+                        max_entries: 1_000_000,
+                        eviction_strategy: EvictionStrategy::Lru,
+                        l1_min_retention_ms: 0,
+                        order_compaction_factor: 2,
+                    })
+                    .with_ttl_config(ttl(Some(30 * 60), 60))
+                    .with_performance_config(performance(64 * 1024));
+
+                #[cfg(feature = "melange-storage")]
+                let builder = builder.with_l2_config(L2Config::default());
+
+                builder
+            }
+            Preset::CdnEdge => {
+                let builder = builder
+                    .with_l1_config(L1Config {
+                        max_memory: l1_memory,
+                        max_entries: 100_000,
+                        eviction_strategy: EvictionStrategy::Lfu,
+                        l1_min_retention_ms: 0,
+                        order_compaction_factor: 2,
+                    })
+                    .with_ttl_config(ttl(Some(24 * 3600), 300))
+                    .with_performance_config(performance(1024 * 1024));
+
+                #[cfg(feature = "melange-storage")]
+                let builder = builder.with_l2_config(L2Config {
+                    enable_l2_cache: true,
+                    data_dir: PathUtils::default_cache_dir().ok(),
+                    max_disk_size: 10 * 1024 * 1024 * 1024,
+                    enable_lz4: false,
+                    l2_write_strategy: "always".to_string(),
+                    ..L2Config::default()
+                });
+
+                builder
+            }
+            Preset::ObjectCache => {
+                let builder = builder
+                    .with_l1_config(L1Config {
+                        max_memory: l1_memory,
+                        max_entries: 1_000_000,
+                        eviction_strategy: EvictionStrategy::Lru,
+                        l1_min_retention_ms: 0,
+                        order_compaction_factor: 2,
+                    })
+                    .with_ttl_config(ttl(None, 300))
+                    .with_performance_config(performance(256 * 1024));
+
+                #[cfg(feature = "melange-storage")]
+                let builder = builder.with_l2_config(L2Config {
+                    enable_l2_cache: true,
+                    data_dir: PathUtils::default_cache_dir().ok(),
+                    l2_write_strategy: "size_based".to_string(),
+                    l2_write_threshold: 4 * 1024,
+                    ..L2Config::default()
+                });
+
+                builder
+            }
         }
     }
 
@@ -292,6 +699,30 @@ impl CacheConfigBuilder {
         self
     }
 
+    /// 设置只读模式
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// 设置只读模式下是否将 L2 命中提升到 L1
+    pub fn with_read_only_promotion(mut self, promote_on_hit: bool) -> Self {
+        self.read_only_promote_on_hit = promote_on_hit;
+        self
+    }
+
+    /// 设置 L1 写入失败时的处理方式
+    pub fn with_l1_set_failure_policy(mut self, policy: L1SetFailurePolicy) -> Self {
+        self.l1_set_failure_policy = policy;
+        self
+    }
+
+    /// 设置是否发布键空间事件
+    pub fn with_keyspace_events(mut self, enabled: bool) -> Self {
+        self.keyspace_events = enabled;
+        self
+    }
+
     /// 构建配置，所有配置项必须显式设置，并强制执行验证
     pub fn build(self) -> CacheResult<CacheConfig> {
         let l1_config = self.l1_config.ok_or_else(|| {
@@ -335,6 +766,10 @@ impl CacheConfigBuilder {
             ttl: ttl_config,
             performance: performance_config,
             logging: logging_config,
+            read_only: self.read_only,
+            read_only_promote_on_hit: self.read_only_promote_on_hit,
+            l1_set_failure_policy: self.l1_set_failure_policy,
+            keyspace_events: self.keyspace_events,
         };
         
         // 最终验证整体配置的一致性
@@ -375,7 +810,7 @@ impl CacheConfigBuilder {
             }
 
             // 验证 L2 写入策略
-            let valid_strategies = ["always", "never", "size_based", "ttl_based", "adaptive", "write_through"];
+            let valid_strategies = ["always", "never", "size_based", "ttl_based", "adaptive", "eviction_rate", "write_through"];
             if !valid_strategies.contains(&l2_config.l2_write_strategy.as_str()) {
                 return Err(CacheError::config_error(&format!(
                     "无效的 L2 写入策略: {}，有效值: {:?}",
@@ -383,16 +818,55 @@ impl CacheConfigBuilder {
                 )));
             }
 
+            // 验证驱逐速率写入策略的区间
+            if l2_config.l2_write_strategy == "eviction_rate"
+                && !(l2_config.l2_write_eviction_rate_low >= 0.0
+                    && l2_config.l2_write_eviction_rate_low < l2_config.l2_write_eviction_rate_high)
+            {
+                return Err(CacheError::config_error("L1 驱逐速率区间无效，需满足 0 <= low < high"));
+            }
+
+            // 验证 TTL 降级配置
+            if l2_config.ttl_demotion_enabled
+                && (l2_config.ttl_demotion_ratio <= 0.0 || l2_config.ttl_demotion_ratio > 1.0)
+            {
+                return Err(CacheError::config_error("TTL 降级比例必须在 (0, 1] 之间"));
+            }
+
+            // 验证元数据更新采样率
+            if l2_config.metadata_update_sampling <= 0.0 || l2_config.metadata_update_sampling > 1.0 {
+                return Err(CacheError::config_error("元数据更新采样率必须在 (0, 1] 之间"));
+            }
+
+            // 验证 L2 写入带宽上限
+            if l2_config.l2_max_write_bytes_per_sec == Some(0) {
+                return Err(CacheError::config_error("L2 写入带宽上限必须大于 0"));
+            }
+
+            // 验证键空间分区数
+            if l2_config.l2_partitions == 0 || l2_config.l2_partitions > 256 {
+                return Err(CacheError::config_error("L2 分区数必须在 1-256 之间"));
+            }
+
             // 验证 L2 路径（如果指定了路径）
             if let Some(ref data_dir) = l2_config.data_dir {
                 PathUtils::validate_writable_path(data_dir)?;
             }
 
             // 验证 L2 压缩配置
+            if let Some(level) = l2_config.zstd_compression_level {
+                CompressionAlgorithm::Zstd.validate_level(level)?;
+            }
             if l2_config.enable_lz4 {
-                if l2_config.compression_level < 1 || l2_config.compression_level > 12 {
-                    return Err(CacheError::config_error("压缩级别必须在 1-12 之间"));
+                if !l2_config.value_compression_algorithm.supports_value_compression()
+                    || l2_config.value_compression_algorithm == CompressionAlgorithm::None
+                {
+                    return Err(CacheError::config_error(format!(
+                        "不支持的值压缩算法: {:?}（可选 Lz4，启用 snappy 特性后可选 Snappy）",
+                        l2_config.value_compression_algorithm
+                    )));
                 }
+                l2_config.value_compression_algorithm.validate_level(l2_config.compression_level)?;
                 if l2_config.compression_threshold >= l2_config.compression_max_threshold {
                     return Err(CacheError::config_error("压缩最小阈值必须小于最大阈值"));
                 }
@@ -406,6 +880,9 @@ impl CacheConfigBuilder {
         if ttl_config.max_cleanup_entries == 0 {
             return Err(CacheError::config_error("最大清理条目数不能为 0"));
         }
+        if ttl_config.cleanup_channel_capacity == 0 {
+            return Err(CacheError::config_error("清理通道容量不能为 0"));
+        }
         
         // 验证性能配置
         if performance_config.worker_threads == 0 {
@@ -440,6 +917,9 @@ impl CacheConfigBuilder {
         if ttl_config.max_cleanup_entries == 0 {
             return Err(CacheError::config_error("最大清理条目数不能为 0"));
         }
+        if ttl_config.cleanup_channel_capacity == 0 {
+            return Err(CacheError::config_error("清理通道容量不能为 0"));
+        }
 
         // 验证性能配置
         if performance_config.worker_threads == 0 {
@@ -671,6 +1151,11 @@ fn default_true() -> bool {
     true
 }
 
+fn default_order_compaction_factor() -> usize {
+    2
+}
+
+
 fn default_false() -> bool {
     false
 }
@@ -693,4 +1178,130 @@ fn default_compression_threshold() -> usize {
 
 fn default_compression_max_threshold() -> usize {
     1024 * 1024  // 1MB，大于此值不压缩
-}
\ No newline at end of file
+}
+
+fn default_ttl_demotion_ratio() -> f64 {
+    0.1  // 剩余 TTL 不超过总 TTL 的 10% 时降级
+}
+
+fn default_ttl_demotion_grace_seconds() -> u64 {
+    60
+}
+
+fn default_skip_reads_when_empty() -> bool {
+    true
+}
+
+fn default_checksum_enabled() -> bool {
+    true
+}
+
+fn default_evict_corrupted_entries() -> bool {
+    true
+}
+
+fn default_metadata_update_sampling() -> f64 {
+    1.0
+}
+
+fn default_l2_partitions() -> usize {
+    1
+}
+
+fn default_blocking_queue_warn_ms() -> u64 {
+    100
+}
+
+fn default_persist_ttl_index() -> bool {
+    true
+}
+
+fn default_l2_write_eviction_rate_low() -> f64 {
+    1.0
+}
+
+fn default_l2_write_eviction_rate_high() -> f64 {
+    100.0
+}
+
+fn default_cleanup_channel_capacity() -> usize {
+    10_000
+}
+
+fn default_max_tagged_keys() -> usize {
+    100_000
+}
+
+fn default_l2_max_retries() -> u32 {
+    2
+}
+
+fn default_l2_retry_backoff_ms() -> u64 {
+    10
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_l2_section_requires_feature() {
+        let mut base = CacheConfigBuilder::development().build().unwrap();
+        base.l2 = None;
+        let content = format!(
+            "{}\n[l2]\nenable_l2_cache = true\n",
+            toml::to_string(&base).unwrap()
+        );
+
+        let result = CacheConfig::from_toml_str(&content);
+        if cfg!(feature = "melange-storage") {
+            assert!(result.unwrap().l2.unwrap().enable_l2_cache);
+        } else {
+            let err = result.unwrap_err().to_string();
+            assert!(err.contains("melange-storage"), "{}", err);
+        }
+
+        let disabled = content.replace("enable_l2_cache = true", "enable_l2_cache = false");
+        assert!(CacheConfig::from_toml_str(&disabled).is_ok());
+    }
+
+    #[cfg(feature = "melange-storage")]
+    #[test]
+    fn test_compression_level_per_algorithm() {
+        let build = |configure: &dyn Fn(&mut L2Config)| {
+            let mut l2 = L2Config {
+                enable_l2_cache: true,
+                ..Default::default()
+            };
+            configure(&mut l2);
+            CacheConfigBuilder::development().with_l2_config(l2).build()
+        };
+
+        for level in [1, 12] {
+            assert!(build(&|l2| l2.compression_level = level).is_ok());
+        }
+        for level in [0, 13] {
+            let err = build(&|l2| l2.compression_level = level).unwrap_err().to_string();
+            assert!(err.contains("Lz4") && err.contains("1 到 12"), "{}", err);
+        }
+        // 未启用值压缩时不检查级别
+        assert!(build(&|l2| {
+            l2.enable_lz4 = false;
+            l2.compression_level = 0;
+        }).is_ok());
+
+        for level in [-7, 22] {
+            assert!(build(&|l2| l2.zstd_compression_level = Some(level)).is_ok());
+        }
+        for level in [-8, 23] {
+            let err = build(&|l2| l2.zstd_compression_level = Some(level)).unwrap_err().to_string();
+            assert!(err.contains("Zstd") && err.contains("-7 到 22"), "{}", err);
+        }
+
+        #[cfg(feature = "snappy")]
+        assert!(build(&|l2| {
+            l2.value_compression_algorithm = CompressionAlgorithm::Snappy;
+            l2.compression_level = 0;
+        }).is_ok());
+    }
+}
@@ -15,6 +15,10 @@ pub enum CacheError {
     #[error("键 '{key}' 已过期")]
     KeyExpired { key: String },
 
+    /// 无效的键
+    #[error("无效的键 '{key}': {reason}")]
+    InvalidKey { key: String, reason: String },
+
     /// 序列化错误
     #[error("序列化失败: {message}")]
     SerializationError { message: String },
@@ -23,6 +27,26 @@ pub enum CacheError {
     #[error("LZ4 压缩/解压缩失败: {message}")]
     CompressionError { message: String },
 
+    /// 值编解码错误
+    #[error("值编解码失败: {message}")]
+    CodecError { message: String },
+
+    /// 数据校验失败
+    #[error("数据校验失败，期望: {expected:#010x}, 实际: {actual:#010x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+
+    /// 值超过允许的最大大小
+    #[error("值过大: {size} 字节，最大允许 {max_size} 字节")]
+    ValueTooLarge { size: usize, max_size: usize },
+
+    /// 无效的缓存值
+    #[error("无效的缓存值: {message}")]
+    InvalidValue { message: String },
+
+    /// 存储数据损坏
+    #[error("键 '{key}' 的存储数据已损坏: {message}")]
+    Corruption { key: String, message: String },
+
   
     /// MelangeDB 错误
     #[cfg(feature = "melange-storage")]
@@ -53,6 +77,22 @@ pub enum CacheError {
     #[error("并发访问冲突，键: '{key}'")]
     ConcurrencyConflict { key: String },
 
+    /// 缓存正在关闭或已关闭
+    #[error("缓存正在关闭，拒绝新的操作")]
+    ShuttingDown,
+
+    /// 缓存处于只读模式
+    #[error("缓存处于只读模式，拒绝写入操作")]
+    ReadOnly,
+
+    /// 标签索引已满
+    #[error("标签索引已满，最多 {max_keys} 个带标签的键")]
+    TagIndexFull { max_keys: usize },
+
+    /// L2 后台线程池繁忙，任务排队超时
+    #[error("L2 后台线程池繁忙，任务排队超过 {waited_ms}ms 未能执行")]
+    Busy { waited_ms: u64 },
+
     /// IO 错误
     #[error("IO 操作失败: {source}")]
     IoError {
@@ -77,6 +117,41 @@ impl CacheError {
         }
     }
 
+    /// 创建值编解码错误
+    pub fn codec_error(message: impl Into<String>) -> Self {
+        Self::CodecError {
+            message: message.into(),
+        }
+    }
+
+    /// 创建数据校验失败错误
+    pub fn checksum_mismatch(expected: u32, actual: u32) -> Self {
+        Self::ChecksumMismatch {
+            expected,
+            actual,
+        }
+    }
+
+    /// 创建值过大错误
+    pub fn value_too_large(size: usize, max_size: usize) -> Self {
+        Self::ValueTooLarge { size, max_size }
+    }
+
+    /// 创建无效缓存值错误
+    pub fn invalid_value(message: impl Into<String>) -> Self {
+        Self::InvalidValue {
+            message: message.into(),
+        }
+    }
+
+    /// 创建存储数据损坏错误
+    pub fn corruption(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Corruption {
+            key: key.into(),
+            message: message.into(),
+        }
+    }
+
     /// 创建配置错误
     pub fn config_error(message: impl Into<String>) -> Self {
         Self::ConfigError {
@@ -105,6 +180,14 @@ impl CacheError {
         }
     }
 
+    /// 创建无效键错误
+    pub fn invalid_key(key: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InvalidKey {
+            key: key.into(),
+            reason: reason.into(),
+        }
+    }
+
     /// 创建序列化错误
     pub fn serialization_error(message: impl Into<String>) -> Self {
         Self::Other {
@@ -144,6 +227,11 @@ impl CacheError {
         }
     }
 
+    /// 创建标签索引已满错误
+    pub fn tag_index_full(max_keys: usize) -> Self {
+        Self::TagIndexFull { max_keys }
+    }
+
     /// 创建无效 TTL 错误
     pub fn invalid_ttl(ttl_seconds: i64) -> Self {
         Self::InvalidTtl {
@@ -158,6 +246,13 @@ impl CacheError {
         }
     }
 
+    /// 创建线程池繁忙错误
+    pub fn busy(waited_ms: u64) -> Self {
+        Self::Busy {
+            waited_ms,
+        }
+    }
+
     /// 创建 IO 错误
     pub fn io_error(message: impl Into<String>) -> Self {
         Self::Other {
@@ -179,4 +274,41 @@ impl CacheError {
     pub fn is_cache_full(&self) -> bool {
         matches!(self, CacheError::CacheFull { .. })
     }
+
+    /// 检查是否为存储层故障（数据库或 IO 错误）
+    pub fn is_storage_failure(&self) -> bool {
+        match self {
+            CacheError::IoError { .. } | CacheError::Other { .. } => true,
+            #[cfg(feature = "melange-storage")]
+            CacheError::MelangeDbError { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// 检查是否为可能在重试后恢复的瞬时存储故障（IO 或数据库错误）
+    ///
+    /// 数据损坏、校验失败等永久性错误以及线程池繁忙不属于瞬时故障
+    pub fn is_transient(&self) -> bool {
+        match self {
+            CacheError::IoError { .. } => true,
+            #[cfg(feature = "melange-storage")]
+            CacheError::MelangeDbError { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// 检查是否为缓存正在关闭错误
+    pub fn is_shutting_down(&self) -> bool {
+        matches!(self, CacheError::ShuttingDown)
+    }
+
+    /// 检查是否为只读模式错误
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, CacheError::ReadOnly)
+    }
+
+    /// 检查是否为线程池繁忙错误
+    pub fn is_busy(&self) -> bool {
+        matches!(self, CacheError::Busy { .. })
+    }
 }
\ No newline at end of file
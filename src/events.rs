@@ -0,0 +1,471 @@
+//! 缓存事件模块
+//!
+//! 提供驱逐事件回调，支持立即投递或按数量/时间间隔批量投递；
+//! 驱逐事件与键空间事件的异步流（有界广播，慢消费者丢弃最旧的事件）；
+//! 以及 L2 存储降级/恢复事件回调
+
+use crate::error::{CacheError, CacheResult};
+use crate::types::CacheLayer;
+use parking_lot::Mutex;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// 驱逐事件流的默认缓冲容量
+pub(crate) const DEFAULT_EVICTION_EVENT_CAPACITY: usize = 1024;
+
+/// 条目离开 L1 的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionReason {
+    /// 为腾出容量被驱逐
+    Capacity,
+    /// 已过期
+    Ttl,
+    /// 被显式删除
+    Explicit,
+    /// 临近过期被降级到 L2，键仍可从 L2 读取
+    Demoted,
+}
+
+/// 驱逐事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvictionEvent {
+    /// 被驱逐的键
+    pub key: String,
+    /// 被驱逐条目占用的内存（字节）
+    pub size: usize,
+    /// 驱逐原因
+    pub reason: EvictionReason,
+}
+
+/// 键空间事件，需开启 [`CacheConfig::keyspace_events`](crate::config::CacheConfig::keyspace_events)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheEvent {
+    /// 键被写入
+    Set { key: String },
+    /// 键被显式删除
+    Deleted { key: String },
+    /// 键已过期（读取时发现或被定时清理）
+    Expired { key: String },
+    /// 键为腾出容量被驱逐出指定层
+    Evicted { key: String, layer: CacheLayer },
+}
+
+/// 事件投递方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventDelivery {
+    /// 每个事件立即回调
+    Immediate,
+    /// 批量回调：累计 `max` 个事件或每隔 `interval` 投递一次
+    Batched { max: usize, interval: Duration },
+}
+
+impl EventDelivery {
+    /// 验证投递配置
+    pub fn validate(&self) -> CacheResult<()> {
+        if let EventDelivery::Batched { max, interval } = self {
+            if *max == 0 {
+                return Err(CacheError::config_error("批量投递的最大事件数不能为 0"));
+            }
+            if interval.is_zero() {
+                return Err(CacheError::config_error("批量投递的时间间隔不能为 0"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 驱逐事件回调，每次调用收到一批事件（立即投递时为单个事件）
+pub type EvictionCallback = Arc<dyn Fn(&[EvictionEvent]) + Send + Sync>;
+
+/// 驱逐事件监听器
+#[derive(Clone)]
+pub struct EvictionListener {
+    delivery: EventDelivery,
+    callback: EvictionCallback,
+}
+
+impl EvictionListener {
+    /// 创建监听器
+    pub fn new<F>(delivery: EventDelivery, callback: F) -> Self
+    where
+        F: Fn(&[EvictionEvent]) + Send + Sync + 'static,
+    {
+        Self {
+            delivery,
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// 投递方式
+    pub fn delivery(&self) -> EventDelivery {
+        self.delivery
+    }
+}
+
+impl std::fmt::Debug for EvictionListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvictionListener")
+            .field("delivery", &self.delivery)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 驱逐事件分发器
+pub(crate) struct EvictionDispatcher {
+    listener: EvictionListener,
+    /// 批量投递模式下待投递的事件
+    pending: Mutex<Vec<EvictionEvent>>,
+    /// 定时投递任务句柄（立即投递模式或已停止时为 None）
+    flush_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl EvictionDispatcher {
+    /// 创建分发器，批量模式下启动定时投递任务
+    pub(crate) fn new(listener: EvictionListener) -> CacheResult<Arc<Self>> {
+        listener.delivery.validate()?;
+
+        let dispatcher = Arc::new(Self {
+            listener,
+            pending: Mutex::new(Vec::new()),
+            flush_task: Mutex::new(None),
+        });
+
+        if let EventDelivery::Batched { interval, .. } = dispatcher.listener.delivery {
+            *dispatcher.flush_task.lock() = Some(Self::start_flush_task(Arc::downgrade(&dispatcher), interval));
+        }
+
+        Ok(dispatcher)
+    }
+
+    /// 分发单个事件
+    pub(crate) fn dispatch(&self, event: EvictionEvent) {
+        match self.listener.delivery {
+            EventDelivery::Immediate => (self.listener.callback)(std::slice::from_ref(&event)),
+            EventDelivery::Batched { max, .. } => {
+                let batch = {
+                    let mut pending = self.pending.lock();
+                    pending.push(event);
+                    if pending.len() < max {
+                        return;
+                    }
+                    std::mem::take(&mut *pending)
+                };
+                (self.listener.callback)(&batch);
+            }
+        }
+    }
+
+    /// 立即投递所有待投递事件
+    pub(crate) fn flush(&self) {
+        let batch = std::mem::take(&mut *self.pending.lock());
+        if !batch.is_empty() {
+            (self.listener.callback)(&batch);
+        }
+    }
+
+    /// 停止定时投递任务并投递剩余事件
+    pub(crate) async fn stop(&self) {
+        let flush_task = self.flush_task.lock().take();
+        if let Some(flush_task) = flush_task {
+            flush_task.abort();
+            let _ = flush_task.await;
+        }
+        self.flush();
+    }
+
+    /// 定时投递任务是否仍在运行
+    pub(crate) fn is_flush_task_running(&self) -> bool {
+        self.flush_task.lock().as_ref().is_some_and(|task| !task.is_finished())
+    }
+
+    /// 定时投递任务，分发器释放后自动退出
+    fn start_flush_task(dispatcher: Weak<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match dispatcher.upgrade() {
+                    Some(dispatcher) => dispatcher.flush(),
+                    None => break,
+                }
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for EvictionDispatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvictionDispatcher")
+            .field("listener", &self.listener)
+            .field("pending", &self.pending.lock().len())
+            .finish()
+    }
+}
+
+/// 事件广播器，发布不会阻塞调用路径
+pub(crate) struct EventBroadcaster<T> {
+    sender: broadcast::Sender<T>,
+    /// 所有订阅者因落后而丢弃的事件总数
+    dropped: Arc<AtomicU64>,
+}
+
+/// 驱逐事件广播器
+pub(crate) type EvictionBroadcaster = EventBroadcaster<EvictionEvent>;
+
+impl<T: Clone + Send + 'static> EventBroadcaster<T> {
+    /// 创建广播器，`capacity` 为每个订阅者最多缓冲的事件数
+    pub(crate) fn new(capacity: usize) -> CacheResult<Self> {
+        if capacity == 0 {
+            return Err(CacheError::config_error("事件流的缓冲容量不能为 0"));
+        }
+        let (sender, _) = broadcast::channel(capacity);
+        Ok(Self {
+            sender,
+            dropped: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// 是否有订阅者（没有时调用方可以跳过构造事件）
+    pub(crate) fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// 发布事件，没有订阅者时直接丢弃
+    pub(crate) fn publish(&self, event: T) {
+        let _ = self.sender.send(event);
+    }
+
+    /// 订阅之后发布的事件
+    pub(crate) fn subscribe(&self) -> EventStream<T> {
+        EventStream {
+            receiver: Some(self.sender.subscribe()),
+            pending: None,
+            dropped: Arc::clone(&self.dropped),
+            lagged: 0,
+        }
+    }
+
+    /// 所有订阅者因落后而丢弃的事件总数
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> std::fmt::Debug for EventBroadcaster<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBroadcaster")
+            .field("subscribers", &self.sender.receiver_count())
+            .field("dropped", &self.dropped.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+type RecvResult<T> = (Result<T, broadcast::error::RecvError>, broadcast::Receiver<T>);
+
+/// 事件的异步流
+///
+/// 每个流独立缓冲订阅之后的事件，缓冲满时最旧的事件被丢弃并计入 [`dropped`](Self::dropped)，
+/// 发布方不会等待慢消费者；缓存实例释放后流结束
+pub struct EventStream<T> {
+    receiver: Option<broadcast::Receiver<T>>,
+    pending: Option<Pin<Box<dyn Future<Output = RecvResult<T>> + Send>>>,
+    dropped: Arc<AtomicU64>,
+    lagged: u64,
+}
+
+/// 驱逐事件的异步流
+pub type EvictionEventStream = EventStream<EvictionEvent>;
+
+/// 键空间事件的异步流
+pub type CacheEventStream = EventStream<CacheEvent>;
+
+impl<T: Clone + Send + 'static> EventStream<T> {
+    /// 读取下一个事件，流结束时返回 None
+    pub async fn next_event(&mut self) -> Option<T> {
+        std::future::poll_fn(|cx| self.poll_next_event(cx)).await
+    }
+
+    /// 本流因落后而丢弃的事件数
+    pub fn dropped(&self) -> u64 {
+        self.lagged
+    }
+
+    fn poll_next_event(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        loop {
+            let pending = match &mut self.pending {
+                Some(pending) => pending,
+                None => {
+                    let Some(mut receiver) = self.receiver.take() else {
+                        return Poll::Ready(None);
+                    };
+                    self.pending.insert(Box::pin(async move { (receiver.recv().await, receiver) }))
+                }
+            };
+
+            let (result, receiver) = ready!(pending.as_mut().poll(cx));
+            self.pending = None;
+            match result {
+                Ok(event) => {
+                    self.receiver = Some(receiver);
+                    return Poll::Ready(Some(event));
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    self.lagged += skipped;
+                    self.dropped.fetch_add(skipped, Ordering::Relaxed);
+                    self.receiver = Some(receiver);
+                }
+                Err(broadcast::error::RecvError::Closed) => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+impl<T: Clone + Send + 'static> futures_core::Stream for EventStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_next_event(cx)
+    }
+}
+
+impl<T> std::fmt::Debug for EventStream<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventStream")
+            .field("dropped", &self.lagged)
+            .finish_non_exhaustive()
+    }
+}
+
+/// L2 存储健康状态变化事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum L2HealthEvent {
+    /// 连续存储故障达到阈值，已降级为仅使用 L1
+    Degraded {
+        /// 连续故障次数
+        consecutive_failures: u32,
+        /// 最后一次故障的错误信息
+        error: String,
+    },
+    /// 探测请求成功，已恢复使用 L2
+    Recovered,
+}
+
+/// L2 健康状态监听器（未启用 `melange-storage` 时不会收到事件）
+#[derive(Clone)]
+pub struct L2HealthListener {
+    #[cfg(feature = "melange-storage")]
+    callback: Arc<dyn Fn(&L2HealthEvent) + Send + Sync>,
+}
+
+impl L2HealthListener {
+    /// 创建监听器
+    #[cfg_attr(not(feature = "melange-storage"), allow(unused_variables))]
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&L2HealthEvent) + Send + Sync + 'static,
+    {
+        Self {
+            #[cfg(feature = "melange-storage")]
+            callback: Arc::new(callback),
+        }
+    }
+
+    /// 投递事件
+    #[cfg(feature = "melange-storage")]
+    pub(crate) fn notify(&self, event: &L2HealthEvent) {
+        (self.callback)(event);
+    }
+}
+
+impl std::fmt::Debug for L2HealthListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("L2HealthListener").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(i: usize) -> EvictionEvent {
+        EvictionEvent {
+            key: format!("key_{}", i),
+            size: i,
+            reason: EvictionReason::Capacity,
+        }
+    }
+
+    fn recording_listener(delivery: EventDelivery) -> (EvictionListener, Arc<Mutex<Vec<Vec<EvictionEvent>>>>) {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&batches);
+        let listener = EvictionListener::new(delivery, move |events: &[EvictionEvent]| {
+            recorded.lock().push(events.to_vec());
+        });
+        (listener, batches)
+    }
+
+    #[tokio::test]
+    async fn test_immediate_delivery() {
+        let (listener, batches) = recording_listener(EventDelivery::Immediate);
+        let dispatcher = EvictionDispatcher::new(listener).unwrap();
+
+        dispatcher.dispatch(event(1));
+        dispatcher.dispatch(event(2));
+        assert_eq!(*batches.lock(), vec![vec![event(1)], vec![event(2)]]);
+    }
+
+    #[tokio::test]
+    async fn test_batched_delivery() {
+        let (listener, batches) = recording_listener(EventDelivery::Batched {
+            max: 3,
+            interval: Duration::from_millis(50),
+        });
+        let dispatcher = EvictionDispatcher::new(listener).unwrap();
+
+        // 达到批量上限时立即投递
+        for i in 0..4 {
+            dispatcher.dispatch(event(i));
+        }
+        assert_eq!(*batches.lock(), vec![vec![event(0), event(1), event(2)]]);
+
+        // 剩余事件在时间间隔到达后投递
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(batches.lock().len(), 2);
+        assert_eq!(batches.lock()[1], vec![event(3)]);
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_lag() {
+        assert!(EvictionBroadcaster::new(0).is_err());
+        let broadcaster = EvictionBroadcaster::new(2).unwrap();
+        assert!(!broadcaster.has_subscribers());
+        broadcaster.publish(event(0));
+
+        let mut stream = broadcaster.subscribe();
+        for i in 1..=5 {
+            broadcaster.publish(event(i));
+        }
+
+        // 缓冲只保留最新的 2 个事件，较旧的 3 个被丢弃
+        assert_eq!(stream.next_event().await, Some(event(4)));
+        assert_eq!(stream.next_event().await, Some(event(5)));
+        assert_eq!(stream.dropped(), 3);
+        assert_eq!(broadcaster.dropped(), 3);
+
+        drop(broadcaster);
+        assert_eq!(stream.next_event().await, None);
+    }
+
+    #[test]
+    fn test_invalid_batched_delivery() {
+        let delivery = EventDelivery::Batched { max: 0, interval: Duration::from_millis(10) };
+        assert!(delivery.validate().is_err());
+    }
+}
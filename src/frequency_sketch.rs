@@ -0,0 +1,125 @@
+//! TinyLFU 访问频率估计
+//!
+//! 使用 Count-Min Sketch 以固定内存估计键的近期访问频率：每个键映射到每行的一个计数器，
+//! 估计值取各行计数器的最小值。计数器上限为 15，每记录一定次数后所有计数器减半，
+//! 使过去的热点键逐渐失去优势。
+
+/// 哈希行数
+const DEPTH: usize = 4;
+/// 计数器上限
+const MAX_COUNT: u8 = 15;
+/// 每行计数器数量的下限
+const MIN_WIDTH: usize = 16;
+/// 衰减周期相对于每行计数器数量的倍数
+const SAMPLE_FACTOR: usize = 10;
+/// 各行的哈希种子
+const SEEDS: [u64; DEPTH] = [
+    0x9e37_79b9_7f4a_7c15,
+    0xc2b2_ae3d_27d4_eb4f,
+    0x1656_67b1_9e37_79f9,
+    0x27d4_eb2f_1656_67c5,
+];
+
+/// 访问频率草图
+#[derive(Debug)]
+pub(crate) struct FrequencySketch {
+    /// DEPTH 行计数器连续存放
+    counters: Vec<u8>,
+    /// 每行计数器数量减一（数量为 2 的幂）
+    mask: usize,
+    /// 距离上次衰减的记录次数
+    additions: usize,
+    /// 衰减周期
+    sample_size: usize,
+}
+
+impl FrequencySketch {
+    /// 按缓存容量创建，每行计数器数量为不小于容量的 2 的幂
+    pub(crate) fn new(capacity: usize) -> Self {
+        let width = capacity.max(MIN_WIDTH).next_power_of_two();
+        Self {
+            counters: vec![0; width * DEPTH],
+            mask: width - 1,
+            additions: 0,
+            sample_size: width * SAMPLE_FACTOR,
+        }
+    }
+
+    /// 记录一次访问，达到衰减周期时所有计数器减半
+    pub(crate) fn increment(&mut self, key: &str) {
+        let hash = fxhash::hash64(key);
+        for row in 0..DEPTH {
+            let index = self.index(hash, row);
+            self.counters[index] = self.counters[index].saturating_add(1).min(MAX_COUNT);
+        }
+
+        self.additions += 1;
+        if self.additions >= self.sample_size {
+            self.age();
+        }
+    }
+
+    /// 估计键的访问频率
+    pub(crate) fn estimate(&self, key: &str) -> u8 {
+        let hash = fxhash::hash64(key);
+        (0..DEPTH).map(|row| self.counters[self.index(hash, row)]).min().unwrap_or(0)
+    }
+
+    /// 清空所有计数器
+    pub(crate) fn clear(&mut self) {
+        self.counters.fill(0);
+        self.additions = 0;
+    }
+
+    /// 所有计数器减半
+    fn age(&mut self) {
+        for counter in &mut self.counters {
+            *counter /= 2;
+        }
+        self.additions /= 2;
+    }
+
+    fn index(&self, hash: u64, row: usize) -> usize {
+        let mixed = (hash ^ SEEDS[row]).wrapping_mul(SEEDS[(row + 1) % DEPTH]);
+        row * (self.mask + 1) + ((mixed >> 32) as usize & self.mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_and_saturation() {
+        let mut sketch = FrequencySketch::new(100);
+        assert_eq!(sketch.counters.len(), 128 * DEPTH);
+        assert_eq!(sketch.estimate("hot"), 0);
+
+        for _ in 0..5 {
+            sketch.increment("hot");
+        }
+        sketch.increment("cold");
+        assert_eq!(sketch.estimate("hot"), 5);
+        assert_eq!(sketch.estimate("cold"), 1);
+
+        for _ in 0..100 {
+            sketch.increment("hot");
+        }
+        assert_eq!(sketch.estimate("hot"), MAX_COUNT);
+    }
+
+    #[test]
+    fn test_aging_halves_counters() {
+        let mut sketch = FrequencySketch::new(16);
+        for _ in 0..12 {
+            sketch.increment("hot");
+        }
+
+        // 记录满一个衰减周期（16 * 10 次）后计数减半
+        for i in 0..(sketch.sample_size - 12) {
+            sketch.increment(&format!("other_{}", i % 1_000));
+        }
+        assert!(sketch.estimate("hot") <= MAX_COUNT / 2);
+        assert_eq!(sketch.additions, sketch.sample_size / 2);
+    }
+}
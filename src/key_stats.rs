@@ -0,0 +1,186 @@
+//! 单键统计模块
+//!
+//! 为最活跃的前 N 个键记录命中、未命中、提升和驱逐次数，用于热点键诊断。
+//! 使用 Count-Min 频率草图估计每个键的活跃度，只有活跃度高于当前最不活跃的已跟踪键时才会替换它，
+//! 因此跟踪表的大小始终不超过 N。
+
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+/// 草图行数
+const SKETCH_DEPTH: usize = 4;
+/// 每个跟踪键对应的草图列数
+const SKETCH_WIDTH_PER_KEY: usize = 8;
+/// 草图最小列数
+const SKETCH_MIN_WIDTH: usize = 256;
+
+/// 单个键的运行时统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyStats {
+    /// 命中次数
+    pub hits: u64,
+    /// 未命中次数
+    pub misses: u64,
+    /// 从 L2 提升到 L1 的次数
+    pub promotions: u64,
+    /// 从 L1 驱逐的次数
+    pub evictions: u64,
+}
+
+/// 单键事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyEvent {
+    Hit,
+    Miss,
+    #[cfg(feature = "melange-storage")]
+    Promotion,
+    Eviction,
+}
+
+impl KeyStats {
+    fn apply(&mut self, event: KeyEvent) {
+        match event {
+            KeyEvent::Hit => self.hits += 1,
+            KeyEvent::Miss => self.misses += 1,
+            #[cfg(feature = "melange-storage")]
+            KeyEvent::Promotion => self.promotions += 1,
+            KeyEvent::Eviction => self.evictions += 1,
+        }
+    }
+}
+
+/// Count-Min 频率草图，计数累计到一定量后整体减半，使估计值偏向近期活跃度
+#[derive(Debug)]
+struct FrequencySketch {
+    counters: Vec<AtomicU32>,
+    width: usize,
+    additions: AtomicU64,
+    reset_threshold: u64,
+}
+
+impl FrequencySketch {
+    fn new(width: usize) -> Self {
+        let width = width.next_power_of_two();
+        Self {
+            counters: (0..SKETCH_DEPTH * width).map(|_| AtomicU32::new(0)).collect(),
+            width,
+            additions: AtomicU64::new(0),
+            reset_threshold: (width * 10) as u64,
+        }
+    }
+
+    fn index(&self, key: &str, row: usize) -> usize {
+        let mut hasher = DefaultHasher::new();
+        row.hash(&mut hasher);
+        key.hash(&mut hasher);
+        row * self.width + (hasher.finish() as usize & (self.width - 1))
+    }
+
+    /// 增加计数并返回新的估计值
+    fn increment(&self, key: &str) -> u32 {
+        let mut estimate = u32::MAX;
+        for row in 0..SKETCH_DEPTH {
+            let counter = &self.counters[self.index(key, row)];
+            let value = counter.fetch_add(1, Ordering::Relaxed).saturating_add(1);
+            estimate = estimate.min(value);
+        }
+        if self.additions.fetch_add(1, Ordering::Relaxed) + 1 >= self.reset_threshold {
+            self.additions.store(0, Ordering::Relaxed);
+            for counter in &self.counters {
+                let value = counter.load(Ordering::Relaxed);
+                counter.store(value / 2, Ordering::Relaxed);
+            }
+        }
+        estimate
+    }
+
+    fn estimate(&self, key: &str) -> u32 {
+        (0..SKETCH_DEPTH)
+            .map(|row| self.counters[self.index(key, row)].load(Ordering::Relaxed))
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// 前 N 个活跃键的统计跟踪器
+#[derive(Debug)]
+pub(crate) struct KeyStatsTracker {
+    capacity: usize,
+    stats: DashMap<String, KeyStats>,
+    sketch: FrequencySketch,
+    /// 跟踪表已满时串行化替换，保证大小不超过容量
+    admission: Mutex<()>,
+}
+
+impl KeyStatsTracker {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            stats: DashMap::with_capacity(capacity),
+            sketch: FrequencySketch::new((capacity * SKETCH_WIDTH_PER_KEY).max(SKETCH_MIN_WIDTH)),
+            admission: Mutex::new(()),
+        }
+    }
+
+    /// 记录键事件
+    pub(crate) fn record(&self, key: &str, event: KeyEvent) {
+        let estimate = self.sketch.increment(key);
+        if let Some(mut stats) = self.stats.get_mut(key) {
+            stats.apply(event);
+            return;
+        }
+
+        let _admission = self.admission.lock();
+        if self.stats.len() >= self.capacity {
+            // 只替换活跃度更低的已跟踪键
+            let victim = self.stats.iter()
+                .map(|entry| (entry.key().clone(), self.sketch.estimate(entry.key())))
+                .min_by_key(|(_, estimate)| *estimate);
+            match victim {
+                Some((victim, victim_estimate)) if victim_estimate < estimate => {
+                    self.stats.remove(&victim);
+                }
+                _ => return,
+            }
+        }
+        self.stats.entry(key.to_string()).or_default().apply(event);
+    }
+
+    /// 获取键的统计（未被跟踪时返回 None）
+    pub(crate) fn get(&self, key: &str) -> Option<KeyStats> {
+        self.stats.get(key).map(|stats| stats.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_keeps_most_active_keys() {
+        let tracker = KeyStatsTracker::new(2);
+        for _ in 0..5 {
+            tracker.record("hot", KeyEvent::Hit);
+        }
+        tracker.record("hot", KeyEvent::Eviction);
+        tracker.record("warm", KeyEvent::Miss);
+        #[cfg(feature = "melange-storage")]
+        tracker.record("warm", KeyEvent::Promotion);
+
+        // 跟踪表已满，一次访问的新键活跃度不足以替换已跟踪键
+        tracker.record("cold", KeyEvent::Hit);
+        assert_eq!(tracker.get("cold"), None);
+
+        // 访问次数超过最不活跃的已跟踪键后替换它
+        for _ in 0..3 {
+            tracker.record("cold", KeyEvent::Hit);
+        }
+        assert!(tracker.get("cold").is_some());
+        assert_eq!(tracker.get("warm"), None);
+        assert_eq!(tracker.get("hot"), Some(KeyStats { hits: 5, misses: 0, promotions: 0, evictions: 1 }));
+        assert_eq!(tracker.stats.len(), 2);
+    }
+}
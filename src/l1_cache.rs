@@ -2,21 +2,26 @@
 //!
 //! 实现基于内存的高性能缓存层，支持多种驱逐策略
 
+use crate::arc_policy::ArcPolicy;
 use crate::config::L1Config;
+use crate::frequency_sketch::FrequencySketch;
 use crate::compression::Compressor;
 use crate::error::{CacheError, CacheResult};
+use crate::events::{CacheEvent, EventBroadcaster, EvictionBroadcaster, EvictionDispatcher, EvictionEvent, EvictionEventStream, EvictionReason, DEFAULT_EVICTION_EVENT_CAPACITY};
+use crate::key_stats::{KeyEvent, KeyStatsTracker};
+use crate::lru_shards::{self, ShardedLruOrder};
 use crate::ttl::TtlManager;
-use crate::types::{CacheValue, EvictionStrategy, CacheLayer, CacheOperation};
+use crate::types::{CacheValue, EvictionStrategy, CacheLayer, SetOutcome};
 use bytes::Bytes;
 use dashmap::DashMap;
-use parking_lot::RwLock;
-use std::collections::VecDeque;
+use parking_lot::{Mutex, RwLock};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
 
 /// L1 内存缓存
 #[derive(Debug)]
@@ -30,18 +35,51 @@ pub struct L1Cache {
     compressor: Arc<Compressor>,
     /// TTL 管理器
     ttl_manager: Arc<TtlManager>,
-        /// LRU 访问顺序（用于 LRU 策略）
-    lru_order: Arc<Mutex<VecDeque<String>>>,
+    /// LRU 访问顺序（用于 LRU 策略），按键分片加锁
+    lru_order: Arc<ShardedLruOrder>,
     /// LFU 访问计数（用于 LFU 策略）
     lfu_counter: Arc<DashMap<String, AtomicU64>>,
+    /// ARC 策略状态（仅 ARC 策略时存在）
+    arc: Option<parking_lot::Mutex<ArcPolicy>>,
+    /// TinyLFU 访问频率草图（仅 TinyLFU 策略时存在）
+    frequency_sketch: Option<parking_lot::Mutex<FrequencySketch>>,
     /// FIFO 插入顺序（用于 FIFO 策略）
     fifo_order: Arc<Mutex<VecDeque<String>>>,
+    /// 条目插入时间（用于最短保留时间）
+    inserted_at: Arc<DashMap<String, Instant>>,
+    /// 条目最近访问时间（用于访问模式分析）
+    last_access: Arc<DashMap<String, Instant>>,
     /// 当前内存使用量
     memory_usage: Arc<AtomicUsize>,
+    /// 当前存活条目的逻辑（未压缩）字节数
+    logical_bytes: Arc<AtomicUsize>,
     /// 当前条目数量
     entry_count: Arc<AtomicUsize>,
+    /// 插入和移除条目时持有读锁、清空时持有写锁，使存储与上面的计数器及顺序结构一起变化
+    mutation_guard: Arc<RwLock<()>>,
+    /// 按键分片的锁，同一键的插入、移除及其顺序结构的更新互斥
+    key_guards: Box<[Mutex<()>]>,
     /// 驱逐统计
     eviction_stats: Arc<RwLock<EvictionStats>>,
+    /// 近期驱逐速率
+    eviction_rate: Arc<EvictionRateGauge>,
+    /// 驱逐事件分发器
+    eviction_dispatcher: Option<Arc<EvictionDispatcher>>,
+    /// 驱逐事件流的广播器
+    eviction_events: EvictionBroadcaster,
+    /// 键空间事件广播器（未开启键空间事件时为 None）
+    keyspace_events: Option<Arc<EventBroadcaster<CacheEvent>>>,
+    /// 单键统计
+    key_stats: Option<Arc<KeyStatsTracker>>,
+    /// 最近分配的 CAS 令牌，清空缓存时不重置，保证令牌不会被复用
+    last_cas: AtomicU64,
+    /// 读取命中次数
+    hits: AtomicU64,
+    /// 读取未命中次数（包括已过期的键）
+    misses: AtomicU64,
+    /// 模拟写入失败（测试用）
+    #[cfg(test)]
+    fail_sets: std::sync::atomic::AtomicBool,
 }
 
 /// 驱逐统计信息
@@ -52,12 +90,102 @@ pub struct EvictionStats {
     lfu_evictions: u64,
     fifo_evictions: u64,
     ttl_evictions: u64,
+    arc_evictions: u64,
+    tinylfu_evictions: u64,
+    /// 因估计访问频率不高于驱逐候选者而未写入的新键数（TinyLFU）
+    rejected_admissions: u64,
     /// 总驱逐次数
     total_evictions: u64,
     /// 驱逐的总字节数
     evicted_bytes: u64,
 }
 
+impl EvictionStats {
+    /// 总驱逐次数
+    pub fn total_evictions(&self) -> u64 {
+        self.total_evictions
+    }
+
+    /// 驱逐的总字节数
+    pub fn evicted_bytes(&self) -> u64 {
+        self.evicted_bytes
+    }
+
+    /// 因 TinyLFU 准入判断未写入的新键数
+    pub fn rejected_admissions(&self) -> u64 {
+        self.rejected_admissions
+    }
+}
+
+/// 每个条目除键和值数据之外的固定内存开销：存储表中的键值结构体、插入时间表和最近访问时间表中的记录，
+/// 以及驱逐策略为该键保存的记录（取各策略中最大的 LRU 链表节点与索引项）。
+/// 哈希表的控制字节和空槽位、顺序结构中键数据的副本不计入
+pub(crate) const ENTRY_OVERHEAD: usize = std::mem::size_of::<(String, CacheValue)>()
+    + 2 * std::mem::size_of::<(String, Instant)>()
+    + lru_shards::KEY_RECORD_SIZE;
+
+/// 条目计入 `max_memory` 的字节数：键长度 + 值大小 + 固定开销
+pub(crate) fn entry_size(key: &str, value: &CacheValue) -> usize {
+    key.len() + value.size() + ENTRY_OVERHEAD
+}
+
+/// 驱逐速率统计窗口
+const EVICTION_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// 近期驱逐速率计量
+///
+/// 使用前后两个固定窗口近似滑动窗口，前一窗口按剩余重叠比例加权。
+#[derive(Debug)]
+struct EvictionRateGauge {
+    state: RwLock<EvictionRateWindow>,
+}
+
+#[derive(Debug)]
+struct EvictionRateWindow {
+    window_start: Instant,
+    current: u64,
+    previous: u64,
+}
+
+impl EvictionRateGauge {
+    fn new(now: Instant) -> Self {
+        Self {
+            state: RwLock::new(EvictionRateWindow { window_start: now, current: 0, previous: 0 }),
+        }
+    }
+
+    fn record(&self, count: u64, now: Instant) {
+        let mut state = self.state.write();
+        state.advance(now);
+        state.current += count;
+    }
+
+    /// 每秒驱逐次数
+    fn rate(&self, now: Instant) -> f64 {
+        let mut state = self.state.write();
+        state.advance(now);
+        let window = EVICTION_RATE_WINDOW.as_secs_f64();
+        let elapsed = now.saturating_duration_since(state.window_start).as_secs_f64();
+        let previous_weight = 1.0 - (elapsed / window).min(1.0);
+        (state.previous as f64 * previous_weight + state.current as f64) / window
+    }
+}
+
+impl EvictionRateWindow {
+    fn advance(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed >= EVICTION_RATE_WINDOW * 2 {
+            self.previous = 0;
+            self.current = 0;
+            self.window_start = now;
+        } else if elapsed >= EVICTION_RATE_WINDOW {
+            self.previous = self.current;
+            self.current = 0;
+            self.window_start += EVICTION_RATE_WINDOW;
+        }
+    }
+}
+
 impl L1Cache {
     /// 创建新的 L1 缓存
     pub async fn new(
@@ -66,18 +194,39 @@ impl L1Cache {
         ttl_manager: Arc<TtlManager>,
     ) -> CacheResult<Self> {
         let config_for_log = config.clone();
+        let arc = (config.eviction_strategy == EvictionStrategy::Arc)
+            .then(|| parking_lot::Mutex::new(ArcPolicy::new(config.max_entries)));
+        let frequency_sketch = (config.eviction_strategy == EvictionStrategy::TinyLfu)
+            .then(|| parking_lot::Mutex::new(FrequencySketch::new(config.max_entries)));
         let cache = Self {
             config: Arc::new(config),
             storage: Arc::new(DashMap::new()),
             // router: Arc::new(router),
             compressor: Arc::new(compressor),
             ttl_manager,
-            lru_order: Arc::new(Mutex::new(VecDeque::new())),
+            lru_order: Arc::new(ShardedLruOrder::new()),
             lfu_counter: Arc::new(DashMap::new()),
+            arc,
+            frequency_sketch,
             fifo_order: Arc::new(Mutex::new(VecDeque::new())),
+            inserted_at: Arc::new(DashMap::new()),
+            last_access: Arc::new(DashMap::new()),
             memory_usage: Arc::new(AtomicUsize::new(0)),
+            logical_bytes: Arc::new(AtomicUsize::new(0)),
             entry_count: Arc::new(AtomicUsize::new(0)),
+            mutation_guard: Arc::new(RwLock::new(())),
+            key_guards: (0..KEY_GUARD_STRIPES).map(|_| Mutex::new(())).collect(),
             eviction_stats: Arc::new(RwLock::new(EvictionStats::default())),
+            eviction_rate: Arc::new(EvictionRateGauge::new(Instant::now())),
+            eviction_dispatcher: None,
+            eviction_events: EvictionBroadcaster::new(DEFAULT_EVICTION_EVENT_CAPACITY)?,
+            keyspace_events: None,
+            key_stats: None,
+            last_cas: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            #[cfg(test)]
+            fail_sets: std::sync::atomic::AtomicBool::new(false),
         };
 
         rat_logger::debug!("[L1] 缓存已初始化，最大内存: {} bytes，最大条目: {}",
@@ -86,62 +235,160 @@ impl L1Cache {
         Ok(cache)
     }
 
-    /// 获取缓存值
+    /// 设置驱逐事件分发器
+    pub(crate) fn with_eviction_dispatcher(mut self, dispatcher: Arc<EvictionDispatcher>) -> Self {
+        self.eviction_dispatcher = Some(dispatcher);
+        self
+    }
+
+    /// 设置驱逐事件流中每个订阅者的缓冲容量
+    pub(crate) fn with_eviction_event_capacity(mut self, capacity: usize) -> CacheResult<Self> {
+        self.eviction_events = EvictionBroadcaster::new(capacity)?;
+        Ok(self)
+    }
+
+    /// 订阅驱逐事件流
+    pub(crate) fn subscribe_evictions(&self) -> EvictionEventStream {
+        self.eviction_events.subscribe()
+    }
+
+    /// 驱逐事件流中因订阅者落后而丢弃的事件总数
+    pub(crate) fn eviction_events_dropped(&self) -> u64 {
+        self.eviction_events.dropped()
+    }
+
+    /// 设置键空间事件广播器，容量驱逐时发布 [`CacheEvent::Evicted`]
+    pub(crate) fn with_keyspace_events(mut self, events: Arc<EventBroadcaster<CacheEvent>>) -> Self {
+        self.keyspace_events = Some(events);
+        self
+    }
+
+    /// 设置单键统计跟踪器
+    pub(crate) fn with_key_stats(mut self, key_stats: Arc<KeyStatsTracker>) -> Self {
+        self.key_stats = Some(key_stats);
+        self
+    }
+
+    /// 停止驱逐事件的定时投递任务并投递剩余事件
+    pub(crate) async fn stop_eviction_events(&self) {
+        if let Some(dispatcher) = &self.eviction_dispatcher {
+            dispatcher.stop().await;
+        }
+    }
+
+    /// 驱逐事件的定时投递任务是否仍在运行
+    pub(crate) fn is_eviction_task_running(&self) -> bool {
+        self.eviction_dispatcher.as_ref().is_some_and(|dispatcher| dispatcher.is_flush_task_running())
+    }
+
+    /// 获取缓存值（不含标志，测试用）
+    #[cfg(test)]
     pub async fn get(&self, key: &str) -> CacheResult<Option<Bytes>> {
+        Ok(self.get_with_flags(key).await?.map(|(data, _)| data))
+    }
+
+    /// 获取缓存值及其标志
+    pub async fn get_with_flags(&self, key: &str) -> CacheResult<Option<(Bytes, u32)>> {
         let start_time = Instant::now();
+        self.record_frequency(key);
 
         // 检查 TTL
         if self.ttl_manager.is_expired(key).await {
-            self.remove_internal(key).await;
+            if let Some(size) = self.remove_internal(key).await {
+                self.publish_removal(key, size, EvictionReason::Ttl);
+            }
+            self.misses.fetch_add(1, Ordering::Relaxed);
             return Ok(None);
         }
 
-        if let Some(cache_value) = self.storage.get(key) {
-            // 更新访问统计
-            self.update_access_stats(key).await;
+        // L1缓存直接返回原始数据，不解压缩
+        // 先释放 DashMap 读锁再 await，避免同分片的并发写入阻塞工作线程导致死锁
+        let data = self.storage.get(key).map(|cache_value| (Bytes::from(cache_value.data.clone()), cache_value.flags));
 
-            // L1缓存直接返回原始数据，不解压缩
-            let data = Bytes::from(cache_value.data.clone());
+        if let Some(data) = data {
+            // 更新访问统计，期间条目被并发删除时不再记录，避免留下失效的顺序记录
+            {
+                let _guard = self.mutation_guard.read();
+                let _key_guard = self.lock_key(key);
+                if self.storage.contains_key(key) {
+                    self.update_access_stats(key);
+                }
+            }
+            self.hits.fetch_add(1, Ordering::Relaxed);
 
             rat_logger::debug!("[L1] 缓存命中: {}", key);
             Ok(Some(data))
         } else {
-
+            self.misses.fetch_add(1, Ordering::Relaxed);
             rat_logger::debug!("[L1] 缓存未命中: {}", key);
             Ok(None)
         }
     }
 
-    /// 设置缓存值
+    /// 设置缓存值（标志为 0，测试用）
+    #[cfg(test)]
     pub async fn set(&self, key: String, value: Bytes, ttl_seconds: Option<u64>) -> CacheResult<()> {
+        self.set_with_flags(key, value, ttl_seconds, 0).await.map(|_| ())
+    }
+
+    /// 使后续写入失败（测试用）
+    #[cfg(all(test, feature = "melange-storage"))]
+    pub fn fail_sets(&self, fail: bool) {
+        self.fail_sets.store(fail, Ordering::Relaxed);
+    }
+
+    /// 设置缓存值及其标志，返回本次写入触发的驱逐量
+    pub async fn set_with_flags(&self, key: String, value: Bytes, ttl_seconds: Option<u64>, flags: u32) -> CacheResult<SetOutcome> {
         let start_time = Instant::now();
+        #[cfg(test)]
+        if self.fail_sets.load(Ordering::Relaxed) {
+            return Err(CacheError::out_of_memory(value.len()));
+        }
 
         // L1缓存直接存储原始数据，不进行压缩
-        let cache_value = CacheValue::new_uncompressed(value.to_vec());
-        let value_size = cache_value.size();
-        
-        // 检查是否需要驱逐
-        self.ensure_capacity(value_size).await?;
-        
-        // 插入数据
-        let is_update = self.storage.contains_key(&key);
-        
-        if let Some(old_value) = self.storage.insert(key.clone(), cache_value) {
-            // 更新内存使用量
-            let old_size = old_value.size();
-            self.memory_usage.fetch_sub(old_size, Ordering::Relaxed);
-        } else {
-            // 新增条目
-            self.entry_count.fetch_add(1, Ordering::Relaxed);
+        let mut cache_value = CacheValue::new_uncompressed(value.to_vec());
+        cache_value.flags = flags;
+        cache_value.cas = self.last_cas.fetch_add(1, Ordering::Relaxed) + 1;
+        let value_size = entry_size(&key, &cache_value);
+        let logical_size = cache_value.original_size;
+
+        self.record_frequency(&key);
+        if !self.admit(&key, value_size).await {
+            self.eviction_stats.write().rejected_admissions += 1;
+            rat_logger::debug!("[L1] TinyLFU 拒绝写入: {}", key);
+            return Ok(SetOutcome { admission_rejected: true, ..Default::default() });
         }
         
-        self.memory_usage.fetch_add(value_size, Ordering::Relaxed);
+        // ARC 在驱逐之前根据影子列表调整 T1 目标大小
+        if let Some(arc) = &self.arc {
+            arc.lock().adapt(&key);
+        }
+
+        // 检查是否需要驱逐
+        let outcome = self.ensure_capacity(value_size).await?;
         
-        // 更新访问统计
-        if !is_update {
-            self.update_insertion_stats(&key).await;
+        // 插入数据，顺序结构与存储在同一键的锁内更新，与并发的移除不会交错
+        {
+            let _guard = self.mutation_guard.read();
+            let _key_guard = self.lock_key(&key);
+            let old_value = self.storage.insert(key.clone(), cache_value);
+            if let Some(old_value) = &old_value {
+                // 更新内存使用量
+                self.memory_usage.fetch_sub(entry_size(&key, old_value), Ordering::Relaxed);
+                self.logical_bytes.fetch_sub(old_value.original_size, Ordering::Relaxed);
+            } else {
+                // 新增条目
+                self.entry_count.fetch_add(1, Ordering::Relaxed);
+            }
+            self.memory_usage.fetch_add(value_size, Ordering::Relaxed);
+            self.logical_bytes.fetch_add(logical_size, Ordering::Relaxed);
+
+            // 更新访问统计（先于插入统计，新键不会被 ARC 当作再次访问）
+            self.update_access_stats(&key);
+            if old_value.is_none() {
+                self.update_insertion_stats(&key);
+            }
         }
-        self.update_access_stats(&key).await;
         
         // 设置 TTL
         if ttl_seconds.is_some() || self.ttl_manager.get_ttl(&key).await.is_none() {
@@ -150,36 +397,53 @@ impl L1Cache {
 
         rat_logger::debug!("[L1] 缓存设置: {} (未压缩)", key);
         
-        Ok(())
+        Ok(outcome)
     }
 
     /// 删除缓存值
     pub async fn delete(&self, key: &str) -> CacheResult<bool> {
+        self.remove(key, EvictionReason::Explicit).await
+    }
+
+    /// 删除缓存值，并以指定原因发布到驱逐事件流
+    pub async fn remove(&self, key: &str, reason: EvictionReason) -> CacheResult<bool> {
         let start_time = Instant::now();
         
         let removed = self.remove_internal(key).await;
         
         
-        if removed {
+        if let Some(size) = removed {
             rat_logger::debug!("[L1] 缓存删除: {}", key);
+            self.publish_removal(key, size, reason);
         }
         
-        Ok(removed)
+        Ok(removed.is_some())
     }
 
     /// 清空缓存
     pub async fn clear(&self) -> CacheResult<()> {
         let _start_time = Instant::now();
         
-        let old_count = self.entry_count.load(Ordering::Relaxed);
-        
-        self.storage.clear();
-        self.lru_order.lock().await.clear();
-        self.lfu_counter.clear();
-        self.fifo_order.lock().await.clear();
-        
-        self.memory_usage.store(0, Ordering::Relaxed);
-        self.entry_count.store(0, Ordering::Relaxed);
+        // 清空存储、计数器和顺序结构之间不能有插入或移除，否则其计数会丢失或被重复扣减、顺序结构留下失效的键
+        let old_count = {
+            let _guard = self.mutation_guard.write();
+            self.storage.clear();
+            self.memory_usage.store(0, Ordering::Relaxed);
+            self.logical_bytes.store(0, Ordering::Relaxed);
+
+            self.lru_order.clear();
+            self.lfu_counter.clear();
+            if let Some(arc) = &self.arc {
+                arc.lock().clear();
+            }
+            if let Some(sketch) = &self.frequency_sketch {
+                sketch.lock().clear();
+            }
+            self.fifo_order.lock().clear();
+            self.inserted_at.clear();
+            self.last_access.clear();
+            self.entry_count.swap(0, Ordering::Relaxed)
+        };
         
         
         rat_logger::debug!("[L1] 缓存已清空，删除了 {} 个条目", old_count);
@@ -187,6 +451,37 @@ impl L1Cache {
         Ok(())
     }
 
+    /// 将所有条目的数据复制到大小恰好的新缓冲区，并收缩索引结构的多余容量，返回重建的条目数
+    pub async fn defragment(&self) -> usize {
+        let mut rebuilt = 0;
+        for mut entry in self.storage.iter_mut() {
+            entry.data = entry.data.as_slice().to_vec();
+            rebuilt += 1;
+        }
+        self.shrink_to_fit().await;
+
+        rat_logger::debug!("[L1] 内存整理完成，重建 {} 个条目", rebuilt);
+        rebuilt
+    }
+
+    /// 压缩顺序队列并收缩存储和索引结构的多余容量，不复制条目数据
+    pub async fn shrink_to_fit(&self) {
+        self.storage.shrink_to_fit();
+        self.compact_order_queues().await;
+        self.lru_order.shrink_to_fit();
+        self.lfu_counter.shrink_to_fit();
+        self.fifo_order.lock().shrink_to_fit();
+        self.inserted_at.shrink_to_fit();
+        self.last_access.shrink_to_fit();
+        self.ttl_manager.shrink_to_fit();
+    }
+
+    /// 存储表当前分配的容量（测试用）
+    #[cfg(all(test, feature = "melange-storage"))]
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+
     /// 获取缓存统计信息
     pub async fn get_stats(&self) -> L1CacheStats {
         let eviction_stats = self.eviction_stats.read().clone();
@@ -194,12 +489,98 @@ impl L1Cache {
         L1CacheStats {
             entry_count: self.entry_count.load(Ordering::Relaxed),
             memory_usage: self.memory_usage.load(Ordering::Relaxed),
+            logical_bytes: self.logical_bytes.load(Ordering::Relaxed),
             max_memory: self.config.max_memory,
             max_entries: self.config.max_entries,
             memory_utilization: self.memory_usage.load(Ordering::Relaxed) as f64 / self.config.max_memory as f64,
             entry_utilization: self.entry_count.load(Ordering::Relaxed) as f64 / self.config.max_entries as f64,
             eviction_stats,
+            eviction_rate: self.eviction_rate(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 按访问次数取前 N 个键，访问次数从高到低排列
+    pub fn top_accessed_keys(&self, top_n: usize) -> Vec<(String, u64)> {
+        if top_n == 0 {
+            return Vec::new();
+        }
+        let mut heap = BinaryHeap::with_capacity(top_n + 1);
+        for entry in self.lfu_counter.iter() {
+            let count = entry.value().load(Ordering::Relaxed);
+            if heap.len() == top_n && heap.peek().is_some_and(|Reverse((min, _))| count <= *min) {
+                continue;
+            }
+            heap.push(Reverse((count, entry.key().clone())));
+            if heap.len() > top_n {
+                heap.pop();
+            }
         }
+        heap.into_sorted_vec().into_iter().map(|Reverse((count, key))| (key, count)).collect()
+    }
+
+    /// 获取键在 L1 中的访问次数
+    #[cfg(feature = "melange-storage")]
+    pub fn access_count(&self, key: &str) -> u64 {
+        self.lfu_counter.get(key).map_or(0, |count| count.load(Ordering::Relaxed))
+    }
+
+    /// 采样 LRU 顺序与 LFU 计数，分析访问的时间局部性与频率分布
+    pub async fn access_pattern_report(&self) -> AccessPatternReport {
+        // 按固定步长从 LRU 队列（最旧在前）采样，位置即近期访问排名
+        let sampled: Vec<(usize, String)> = {
+            let lru_order = self.lru_order.keys_by_recency();
+            let step = lru_order.len().div_ceil(ACCESS_PATTERN_SAMPLE_SIZE).max(1);
+            lru_order.into_iter().enumerate().step_by(step).collect()
+        };
+
+        let now = Instant::now();
+        let mut recency = Vec::with_capacity(sampled.len());
+        let mut counts = Vec::with_capacity(sampled.len());
+        let mut ages = Vec::with_capacity(sampled.len());
+        for (rank, key) in &sampled {
+            recency.push(*rank as f64);
+            counts.push(self.lfu_counter.get(key).map_or(0, |count| count.load(Ordering::Relaxed)));
+            ages.push(self.last_access.get(key).map(|accessed| now.saturating_duration_since(*accessed.value())));
+        }
+
+        let total_accesses: u64 = counts.iter().sum();
+        let recent_access_ratios = ACCESS_PATTERN_WINDOWS_SECS.iter()
+            .map(|&window| {
+                let recent: u64 = counts.iter().zip(&ages)
+                    .filter(|(_, age)| age.is_some_and(|age| age <= Duration::from_secs(window)))
+                    .map(|(count, _)| count)
+                    .sum();
+                let ratio = if total_accesses == 0 { 0.0 } else { recent as f64 / total_accesses as f64 };
+                (window, ratio)
+            })
+            .collect();
+
+        let frequencies: Vec<f64> = counts.iter().map(|&count| count as f64).collect();
+        AccessPatternReport {
+            sampled_keys: sampled.len(),
+            total_keys: self.entry_count.load(Ordering::Relaxed),
+            total_accesses,
+            recent_access_ratios,
+            frequency_gini: gini_coefficient(&counts),
+            recency_frequency_correlation: rank_correlation(&recency, &frequencies),
+        }
+    }
+
+    /// 近期驱逐速率（次/秒）
+    pub fn eviction_rate(&self) -> f64 {
+        self.eviction_rate.rate(Instant::now())
+    }
+
+    /// 读取缓存值及其标志但不更新访问统计
+    pub fn peek_with_flags(&self, key: &str) -> Option<(Bytes, u32)> {
+        self.storage.get(key).map(|value| (Bytes::from(value.data.clone()), value.flags))
+    }
+
+    /// 读取值、标志及 CAS 令牌，不检查 TTL 也不更新访问统计
+    pub fn peek_with_cas(&self, key: &str) -> Option<(Bytes, u32, u64)> {
+        self.storage.get(key).map(|value| (Bytes::from(value.data.clone()), value.flags, value.cas))
     }
 
     /// 检查是否包含键
@@ -212,85 +593,143 @@ impl L1Cache {
         self.storage.iter().map(|entry| entry.key().clone()).collect()
     }
 
+    /// 获取以 `prefix` 开头的键
+    pub fn keys_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.storage
+            .iter()
+            .filter(|entry| entry.key().starts_with(prefix))
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+
     /// 获取缓存大小
+    #[cfg(test)]
     pub fn len(&self) -> usize {
         self.entry_count.load(Ordering::Relaxed)
     }
 
     /// 检查缓存是否为空
+    #[cfg(test)]
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
     /// 内部删除方法
-    async fn remove_internal(&self, key: &str) -> bool {
-        if let Some((_, old_value)) = self.storage.remove(key) {
-            // 更新内存使用量和条目数
-            let old_size = old_value.size();
-            self.memory_usage.fetch_sub(old_size, Ordering::Relaxed);
-            self.entry_count.fetch_sub(1, Ordering::Relaxed);
-            
-            // 清理访问统计
-            self.cleanup_access_stats(key).await;
-            
+    async fn remove_internal(&self, key: &str) -> Option<usize> {
+        if let Some(old_size) = self.take_entry(key) {
             // 移除 TTL
             self.ttl_manager.remove_key(key).await;
             
-            true
+            Some(old_size)
         } else {
-            false
+            None
+        }
+    }
+
+    /// 从存储中移除条目、清理其访问统计并扣减内存使用量和条目数，返回释放的计量字节数
+    fn take_entry(&self, key: &str) -> Option<usize> {
+        let _guard = self.mutation_guard.read();
+        let _key_guard = self.lock_key(key);
+        let (key, value) = self.storage.remove(key)?;
+        let size = entry_size(&key, &value);
+        self.memory_usage.fetch_sub(size, Ordering::Relaxed);
+        self.logical_bytes.fetch_sub(value.original_size, Ordering::Relaxed);
+        self.entry_count.fetch_sub(1, Ordering::Relaxed);
+        self.cleanup_access_stats(&key);
+        Some(size)
+    }
+
+    /// 驱逐候选者已被并发移除时，清理它在顺序结构中可能残留的记录
+    fn discard_stale_candidate(&self, key: &str) {
+        let _guard = self.mutation_guard.read();
+        let _key_guard = self.lock_key(key);
+        if !self.storage.contains_key(key) {
+            self.cleanup_access_stats(key);
         }
     }
 
-    /// 确保有足够的容量
-    async fn ensure_capacity(&self, required_size: usize) -> CacheResult<()> {
+    /// 键所在分片的锁
+    fn lock_key(&self, key: &str) -> parking_lot::MutexGuard<'_, ()> {
+        self.key_guards[fxhash::hash64(key) as usize % self.key_guards.len()].lock()
+    }
+
+    /// 记录一次访问到 TinyLFU 频率草图
+    fn record_frequency(&self, key: &str) {
+        if let Some(sketch) = &self.frequency_sketch {
+            sketch.lock().increment(key);
+        }
+    }
+
+    /// TinyLFU 准入判断：写入新键需要驱逐时，只有估计访问频率高于驱逐候选者才写入
+    async fn admit(&self, key: &str, required_size: usize) -> bool {
+        let Some(sketch) = &self.frequency_sketch else {
+            return true;
+        };
+        let needs_eviction = self.memory_usage.load(Ordering::Relaxed) + required_size > self.config.max_memory
+            || self.entry_count.load(Ordering::Relaxed) >= self.config.max_entries;
+        if !needs_eviction || self.storage.contains_key(key) {
+            return true;
+        }
+        let Some(victim) = self.peek_lru_candidate().await else {
+            return true;
+        };
+        let sketch = sketch.lock();
+        sketch.estimate(key) > sketch.estimate(&victim)
+    }
+
+    /// 确保有足够的容量，返回为此驱逐的条目数和字节数
+    async fn ensure_capacity(&self, required_size: usize) -> CacheResult<SetOutcome> {
         let current_memory = self.memory_usage.load(Ordering::Relaxed);
         let current_entries = self.entry_count.load(Ordering::Relaxed);
+        let mut outcome = SetOutcome::default();
+        
+        // 单个条目超过内存上限时驱逐也无法容纳
+        if required_size > self.config.max_memory {
+            return Err(CacheError::out_of_memory(required_size));
+        }
         
         // 检查内存限制
         if current_memory + required_size > self.config.max_memory {
             let needed_space = current_memory + required_size - self.config.max_memory;
-            if self.evict_by_memory(required_size).await.is_err() {
-                return Err(CacheError::out_of_memory(needed_space));
+            match self.evict_by_memory(required_size).await {
+                Ok(evicted) => outcome.merge(evicted),
+                Err(_) => return Err(CacheError::out_of_memory(needed_space)),
             }
         }
         
         // 检查条目数限制
         if current_entries >= self.config.max_entries {
-            if self.evict_by_count(1).await.is_err() {
-                return Err(CacheError::cache_full(current_entries, self.config.max_entries));
+            match self.evict_by_count(1).await {
+                Ok(evicted) => outcome.merge(evicted),
+                Err(_) => return Err(CacheError::cache_full(current_entries, self.config.max_entries)),
             }
         }
         
-        Ok(())
+        Ok(outcome)
     }
 
     /// 按内存使用量驱逐
-    async fn evict_by_memory(&self, required_size: usize) -> CacheResult<()> {
+    async fn evict_by_memory(&self, required_size: usize) -> CacheResult<SetOutcome> {
         let target_memory = self.config.max_memory - required_size;
         let mut evicted_bytes = 0;
         let mut evicted_count = 0;
         
         while self.memory_usage.load(Ordering::Relaxed) > target_memory && !self.storage.is_empty() {
-            if let Some(key) = self.select_eviction_candidate().await {
-                if let Some((_, value)) = self.storage.remove(&key) {
-                    let size = value.size();
-                    evicted_bytes += size;
-                    evicted_count += 1;
-                    
-                    self.memory_usage.fetch_sub(size, Ordering::Relaxed);
-                    self.entry_count.fetch_sub(1, Ordering::Relaxed);
-                    
-                    self.cleanup_access_stats(&key).await;
-                    self.ttl_manager.remove_key(&key).await;
-                    
-                    rat_logger::debug!("[L1] 驱逐键: {} ({}字节)", key, size);
-                } else {
-                    break;
-                }
-            } else {
+            let Some(key) = self.select_eviction_candidate().await else {
                 break;
-            }
+            };
+            // 候选者已被并发删除时换下一个
+            let Some(size) = self.take_entry(&key) else {
+                self.discard_stale_candidate(&key);
+                continue;
+            };
+            evicted_bytes += size;
+            evicted_count += 1;
+
+            self.ttl_manager.remove_key(&key).await;
+
+            rat_logger::debug!("[L1] 驱逐键: {} ({}字节)", key, size);
+            self.notify_eviction(key, size);
         }
         
         if evicted_count > 0 {
@@ -300,34 +739,30 @@ impl L1Cache {
                 evicted_count, evicted_bytes);
         }
         
-        Ok(())
+        Ok(SetOutcome { evicted_count: evicted_count as u64, evicted_bytes: evicted_bytes as u64, ..Default::default() })
     }
 
     /// 按条目数驱逐
-    async fn evict_by_count(&self, required_count: usize) -> CacheResult<()> {
+    async fn evict_by_count(&self, required_count: usize) -> CacheResult<SetOutcome> {
         let mut evicted_bytes = 0;
         let mut evicted_count = 0;
         
-        for _ in 0..required_count {
-            if let Some(key) = self.select_eviction_candidate().await {
-                if let Some((_, value)) = self.storage.remove(&key) {
-                    let size = value.size();
-                    evicted_bytes += size;
-                    evicted_count += 1;
-                    
-                    self.memory_usage.fetch_sub(size, Ordering::Relaxed);
-                    self.entry_count.fetch_sub(1, Ordering::Relaxed);
-                    
-                    self.cleanup_access_stats(&key).await;
-                    self.ttl_manager.remove_key(&key).await;
-                    
-                    rat_logger::debug!("[L1] 驱逐键: {} ({}字节)", key, size);
-                } else {
-                    break;
-                }
-            } else {
+        while evicted_count < required_count {
+            let Some(key) = self.select_eviction_candidate().await else {
                 break;
-            }
+            };
+            // 候选者已被并发删除时换下一个
+            let Some(size) = self.take_entry(&key) else {
+                self.discard_stale_candidate(&key);
+                continue;
+            };
+            evicted_bytes += size;
+            evicted_count += 1;
+
+            self.ttl_manager.remove_key(&key).await;
+
+            rat_logger::debug!("[L1] 驱逐键: {} ({}字节)", key, size);
+            self.notify_eviction(key, size);
         }
         
         if evicted_count > 0 {
@@ -337,7 +772,28 @@ impl L1Cache {
                 evicted_count, evicted_bytes);
         }
         
-        Ok(())
+        Ok(SetOutcome { evicted_count: evicted_count as u64, evicted_bytes: evicted_bytes as u64, ..Default::default() })
+    }
+
+    /// 通知驱逐事件
+    fn notify_eviction(&self, key: String, size: usize) {
+        if let Some(key_stats) = &self.key_stats {
+            key_stats.record(&key, KeyEvent::Eviction);
+        }
+        self.publish_removal(&key, size, EvictionReason::Capacity);
+        if let Some(events) = self.keyspace_events.as_ref().filter(|events| events.has_subscribers()) {
+            events.publish(CacheEvent::Evicted { key: key.clone(), layer: CacheLayer::Memory });
+        }
+        if let Some(dispatcher) = &self.eviction_dispatcher {
+            dispatcher.dispatch(EvictionEvent { key, size, reason: EvictionReason::Capacity });
+        }
+    }
+
+    /// 向驱逐事件流发布条目移除，没有订阅者时不构造事件
+    fn publish_removal(&self, key: &str, size: usize, reason: EvictionReason) {
+        if self.eviction_events.has_subscribers() {
+            self.eviction_events.publish(EvictionEvent { key: key.to_string(), size, reason });
+        }
     }
 
     /// 选择驱逐候选者
@@ -348,35 +804,73 @@ impl L1Cache {
             EvictionStrategy::Fifo => self.select_fifo_candidate().await,
             EvictionStrategy::LruLfu => self.select_lru_lfu_candidate().await,
             EvictionStrategy::TtlBased => self.select_ttl_candidate().await,
+            EvictionStrategy::Arc => self.select_arc_candidate(),
+            EvictionStrategy::TinyLfu => self.select_lru_candidate().await,
         }
     }
 
+    /// 条目是否仍在最短保留时间内
+    fn is_retained(&self, key: &str, now: Instant) -> bool {
+        let min_retention = Duration::from_millis(self.config.l1_min_retention_ms);
+        !min_retention.is_zero()
+            && self.inserted_at.get(key)
+                .is_some_and(|inserted_at| now.saturating_duration_since(*inserted_at.value()) < min_retention)
+    }
+
+    /// 顺序队列中第一个不在保留期内的键的位置，全部在保留期内时为队首
+    fn unretained_position(&self, order: &VecDeque<String>) -> Option<usize> {
+        let now = Instant::now();
+        order.iter().position(|key| !self.is_retained(key, now))
+            .or((!order.is_empty()).then_some(0))
+    }
+
+    /// 从顺序队列中取出第一个不在保留期内的键，全部在保留期内时取队首
+    fn pop_unretained(&self, order: &mut VecDeque<String>) -> Option<String> {
+        let index = self.unretained_position(order)?;
+        order.remove(index)
+    }
+
     /// 选择 LRU 候选者
     async fn select_lru_candidate(&self) -> Option<String> {
-        let mut lru_order = self.lru_order.lock().await;
-        lru_order.pop_front()
+        let now = Instant::now();
+        self.lru_order.pop_oldest(|key| self.is_retained(key, now))
+    }
+
+    /// 查看下一个 LRU 候选者，不从队列中移除
+    async fn peek_lru_candidate(&self) -> Option<String> {
+        let now = Instant::now();
+        self.lru_order.peek_oldest(|key| self.is_retained(key, now))
     }
 
     /// 选择 LFU 候选者
     async fn select_lfu_candidate(&self) -> Option<String> {
+        let now = Instant::now();
         let mut min_count = u64::MAX;
         let mut candidate = None;
+        // 保留期内条目中访问次数最少的，仅在没有其他候选者时使用
+        let mut retained_min_count = u64::MAX;
+        let mut retained_candidate = None;
         
         for entry in self.lfu_counter.iter() {
             let count = entry.value().load(Ordering::Relaxed);
-            if count < min_count {
+            if self.is_retained(entry.key(), now) {
+                if count < retained_min_count {
+                    retained_min_count = count;
+                    retained_candidate = Some(entry.key().clone());
+                }
+            } else if count < min_count {
                 min_count = count;
                 candidate = Some(entry.key().clone());
             }
         }
         
-        candidate
+        candidate.or(retained_candidate)
     }
 
     /// 选择 FIFO 候选者
     async fn select_fifo_candidate(&self) -> Option<String> {
-        let mut fifo_order = self.fifo_order.lock().await;
-        fifo_order.pop_front()
+        let mut fifo_order = self.fifo_order.lock();
+        self.pop_unretained(&mut fifo_order)
     }
 
     /// 选择 LRU+LFU 混合候选者
@@ -396,54 +890,111 @@ impl L1Cache {
 
     /// 选择基于 TTL 的候选者
     async fn select_ttl_candidate(&self) -> Option<String> {
-        // 优先选择即将过期的键
-        let expired_keys = self.ttl_manager.get_expired_keys(1).await;
-        if !expired_keys.is_empty() {
-            return Some(expired_keys[0].clone());
+        // 优先选择已过期的键（TTL 管理器同时记录只在 L2 中的键，只取 L1 中存在的）
+        let expired_keys = self.ttl_manager.get_expired_keys(TTL_CANDIDATE_SCAN).await;
+        if let Some(key) = expired_keys.into_iter().find(|key| self.storage.contains_key(key)) {
+            return Some(key);
         }
         
         // 如果没有过期键，回退到 LRU
         self.select_lru_candidate().await
     }
 
+    /// 选择 ARC 候选者
+    fn select_arc_candidate(&self) -> Option<String> {
+        let now = Instant::now();
+        self.arc.as_ref()?.lock().evict(|key| self.is_retained(key, now))
+    }
+
+    /// 顺序队列是否需要压缩
+    fn needs_order_compaction(&self, len: usize) -> bool {
+        let factor = self.config.order_compaction_factor;
+        factor > 0
+            && len > ORDER_COMPACTION_MIN_LEN
+            && len > self.entry_count.load(Ordering::Relaxed).saturating_mul(factor)
+    }
+
+    /// 压缩顺序队列：去重（保留每个键最后一次出现的位置）并移除已不在存储中的键，返回移除的数量
+    fn compact_order(&self, order: &mut VecDeque<String>) -> usize {
+        let before = order.len();
+        let keep: Vec<bool> = {
+            let mut seen = HashSet::with_capacity(self.storage.len());
+            order.iter().rev().map(|key| self.storage.contains_key(key) && seen.insert(key.as_str())).collect()
+        };
+        let mut keep = keep.into_iter().rev();
+        order.retain(|_| keep.next().unwrap_or(false));
+        order.shrink_to_fit();
+        before - order.len()
+    }
+
+    /// 立即压缩 FIFO 顺序队列，返回移除的重复或失效键数量（LRU 顺序按键索引，不会积累重复键）
+    pub async fn compact_order_queues(&self) -> usize {
+        let removed = self.compact_order(&mut self.fifo_order.lock());
+        if removed > 0 {
+            rat_logger::debug!("[L1] 顺序队列压缩完成，移除 {} 个重复或失效的键", removed);
+        }
+        removed
+    }
+
     /// 更新访问统计
-    async fn update_access_stats(&self, key: &str) {
+    fn update_access_stats(&self, key: &str) {
         // 更新 LRU
-        let mut lru_order = self.lru_order.lock().await;
-        lru_order.retain(|k| k != key);
-        lru_order.push_back(key.to_string());
-        drop(lru_order);
+        self.lru_order.touch(key);
         
         // 更新 LFU
         self.lfu_counter.entry(key.to_string())
             .or_insert_with(|| AtomicU64::new(0))
             .fetch_add(1, Ordering::Relaxed);
+
+        if let Some(arc) = &self.arc {
+            arc.lock().access(key);
+        }
+
+        self.last_access.insert(key.to_string(), Instant::now());
     }
 
     /// 更新插入统计
-    async fn update_insertion_stats(&self, key: &str) {
+    fn update_insertion_stats(&self, key: &str) {
         // 更新 FIFO
-        let mut fifo_order = self.fifo_order.lock().await;
+        let mut fifo_order = self.fifo_order.lock();
         fifo_order.push_back(key.to_string());
+        if self.needs_order_compaction(fifo_order.len()) {
+            self.compact_order(&mut fifo_order);
+        }
+        drop(fifo_order);
+
+        if let Some(arc) = &self.arc {
+            arc.lock().admit(key);
+        }
+
+        self.inserted_at.insert(key.to_string(), Instant::now());
     }
 
     /// 清理访问统计
-    async fn cleanup_access_stats(&self, key: &str) {
+    fn cleanup_access_stats(&self, key: &str) {
         // 清理 LRU
-        let mut lru_order = self.lru_order.lock().await;
-        lru_order.retain(|k| k != key);
-        drop(lru_order);
+        self.lru_order.remove(key);
         
         // 清理 LFU
         self.lfu_counter.remove(key);
+
+        // 清理 ARC（驱逐的键此前已移入影子列表，不受影响）
+        if let Some(arc) = &self.arc {
+            arc.lock().remove(key);
+        }
         
         // 清理 FIFO
-        let mut fifo_order = self.fifo_order.lock().await;
+        let mut fifo_order = self.fifo_order.lock();
         fifo_order.retain(|k| k != key);
+        drop(fifo_order);
+
+        self.inserted_at.remove(key);
+        self.last_access.remove(key);
     }
 
     /// 更新驱逐统计
     async fn update_eviction_stats(&self, count: usize, bytes: usize) {
+        self.eviction_rate.record(count as u64, Instant::now());
         let mut stats = self.eviction_stats.write();
         stats.total_evictions += count as u64;
         stats.evicted_bytes += bytes as u64;
@@ -453,6 +1004,8 @@ impl L1Cache {
             EvictionStrategy::Lfu => stats.lfu_evictions += count as u64,
             EvictionStrategy::Fifo => stats.fifo_evictions += count as u64,
             EvictionStrategy::TtlBased => stats.ttl_evictions += count as u64,
+            EvictionStrategy::Arc => stats.arc_evictions += count as u64,
+            EvictionStrategy::TinyLfu => stats.tinylfu_evictions += count as u64,
             EvictionStrategy::LruLfu => {
                 // 按比例分配
                 stats.lru_evictions += (count as f64 * 0.7) as u64;
@@ -467,31 +1020,161 @@ impl L1Cache {
 pub struct L1CacheStats {
     pub entry_count: usize,
     pub memory_usage: usize,
+    /// 存活条目的逻辑（未压缩）字节数
+    pub logical_bytes: usize,
     pub max_memory: usize,
     pub max_entries: usize,
     pub memory_utilization: f64,
     pub entry_utilization: f64,
     pub eviction_stats: EvictionStats,
+    /// 近期驱逐速率（次/秒）
+    pub eviction_rate: f64,
+    /// 读取命中次数
+    pub hits: u64,
+    /// 读取未命中次数
+    pub misses: u64,
 }
 
 impl L1CacheStats {
+    /// 读取命中率（0.0 - 1.0），尚无读取时为 None
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+
     /// 格式化统计信息
     pub fn format(&self) -> String {
         format!(
             "L1 缓存统计:\n\
              条目数: {}/{}({:.1}%)\n\
              内存使用: {}/{} bytes ({:.1}%)\n\
+             逻辑数据量: {} bytes\n\
              总驱逐: {} 次 ({} bytes)\n\
-             LRU驱逐: {}, LFU驱逐: {}, FIFO驱逐: {}, TTL驱逐: {}",
+             LRU驱逐: {}, LFU驱逐: {}, FIFO驱逐: {}, TTL驱逐: {}, ARC驱逐: {}, TinyLFU驱逐: {}\n\
+             准入拒绝: {} 次\n\
+             近期驱逐速率: {:.1} 次/秒\n\
+             命中: {} 次, 未命中: {} 次",
             self.entry_count, self.max_entries, self.entry_utilization * 100.0,
             self.memory_usage, self.max_memory, self.memory_utilization * 100.0,
+            self.logical_bytes,
             self.eviction_stats.total_evictions, self.eviction_stats.evicted_bytes,
             self.eviction_stats.lru_evictions, self.eviction_stats.lfu_evictions,
-            self.eviction_stats.fifo_evictions, self.eviction_stats.ttl_evictions
+            self.eviction_stats.fifo_evictions, self.eviction_stats.ttl_evictions,
+            self.eviction_stats.arc_evictions, self.eviction_stats.tinylfu_evictions,
+            self.eviction_stats.rejected_admissions,
+            self.eviction_rate,
+            self.hits, self.misses
         )
     }
 }
 
+/// 顺序队列不超过该长度时不压缩，避免条目很少时频繁压缩
+const ORDER_COMPACTION_MIN_LEN: usize = 1024;
+/// 按键分片的锁数量
+const KEY_GUARD_STRIPES: usize = 64;
+/// TTL 策略选择候选者时最多检查的已过期键数
+const TTL_CANDIDATE_SCAN: usize = 16;
+
+/// 访问模式分析的最大采样键数
+const ACCESS_PATTERN_SAMPLE_SIZE: usize = 10_000;
+/// 访问模式分析统计的近期时间窗口（秒）
+const ACCESS_PATTERN_WINDOWS_SECS: [u64; 3] = [10, 60, 300];
+
+/// L1 访问模式分析结果，用于在 LRU 与 LFU 之间选择驱逐策略
+///
+/// 访问次数来自 LFU 计数，包含读取命中和写入
+#[derive(Debug, Clone, Default)]
+pub struct AccessPatternReport {
+    /// 采样的键数
+    pub sampled_keys: usize,
+    /// L1 中的键总数
+    pub total_keys: usize,
+    /// 采样键的访问总次数
+    pub total_accesses: u64,
+    /// (时间窗口秒数, 最近访问落在该窗口内的键所占访问次数比例)
+    pub recent_access_ratios: Vec<(u64, f64)>,
+    /// 访问频率的基尼系数：接近 0 表示访问均匀，接近 1 表示集中在少数热点键
+    pub frequency_gini: f64,
+    /// 近期访问排名与访问频率的 Spearman 秩相关系数（-1 ~ 1），
+    /// 越接近 1 说明最近访问的键也是高频键，LRU 与 LFU 效果相近
+    pub recency_frequency_correlation: f64,
+}
+
+impl AccessPatternReport {
+    /// 格式化分析结果
+    pub fn format(&self) -> String {
+        let windows: Vec<String> = self.recent_access_ratios.iter()
+            .map(|(window, ratio)| format!("{}秒内 {:.1}%", window, ratio * 100.0))
+            .collect();
+        format!(
+            "L1 访问模式分析:\n\
+             采样键数: {}/{}\n\
+             访问次数: {}\n\
+             近期访问占比: {}\n\
+             频率基尼系数: {:.3}\n\
+             近期性与频率相关系数: {:.3}",
+            self.sampled_keys, self.total_keys,
+            self.total_accesses,
+            windows.join(", "),
+            self.frequency_gini,
+            self.recency_frequency_correlation
+        )
+    }
+}
+
+/// 计算基尼系数
+fn gini_coefficient(values: &[u64]) -> f64 {
+    let total: u64 = values.iter().sum();
+    if values.is_empty() || total == 0 {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let n = sorted.len() as f64;
+    let weighted: f64 = sorted.iter().enumerate().map(|(i, &value)| (i + 1) as f64 * value as f64).sum();
+    2.0 * weighted / (n * total as f64) - (n + 1.0) / n
+}
+
+/// 计算 Spearman 秩相关系数（相同值取平均秩）
+fn rank_correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    let (rx, ry) = (average_ranks(xs), average_ranks(ys));
+    let n = rx.len() as f64;
+    if n < 2.0 {
+        return 0.0;
+    }
+    let (mean_x, mean_y) = (rx.iter().sum::<f64>() / n, ry.iter().sum::<f64>() / n);
+    let mut covariance = 0.0;
+    let (mut var_x, mut var_y) = (0.0, 0.0);
+    for (x, y) in rx.iter().zip(&ry) {
+        covariance += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    if var_x == 0.0 || var_y == 0.0 {
+        return 0.0;
+    }
+    covariance / (var_x.sqrt() * var_y.sqrt())
+}
+
+fn average_ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start;
+        while end + 1 < order.len() && values[order[end + 1]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end) as f64 / 2.0 + 1.0;
+        for &index in &order[start..=end] {
+            ranks[index] = rank;
+        }
+        start = end + 1;
+    }
+    ranks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -505,6 +1188,8 @@ mod tests {
             max_memory: 1024 * 1024, // 1MB
             max_entries: 1000,
             eviction_strategy: EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
         };
         
         let logging_config = LoggingConfig {
@@ -527,6 +1212,8 @@ mod tests {
             max_cleanup_entries: 100,
             lazy_expiration: true,
             active_expiration: true,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
         };
         
         let compressor = Compressor::new_disabled();
@@ -569,6 +1256,24 @@ mod tests {
         assert!(!cache.contains_key(&key));
     }
 
+    #[tokio::test]
+    async fn test_memory_usage_per_entry() {
+        let cache = create_test_cache().await;
+        // 64 位平台：键值结构体 120 + 两条时间记录 2 × 40 + LRU 节点与索引项 48 + 32
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(ENTRY_OVERHEAD, 280);
+
+        // 每个条目计入 键长度 + 值大小 + 固定开销，覆盖写入不重复计入，删除后归零
+        cache.set("key_1".to_string(), Bytes::from(vec![b'x'; 10]), None).await.unwrap();
+        assert_eq!(cache.get_stats().await.memory_usage, 5 + 10 + ENTRY_OVERHEAD);
+        cache.set("key_1".to_string(), Bytes::from(vec![b'x'; 20]), None).await.unwrap();
+        cache.set("key_22".to_string(), Bytes::from(vec![b'x'; 30]), None).await.unwrap();
+        assert_eq!(cache.get_stats().await.memory_usage, (5 + 20) + (6 + 30) + 2 * ENTRY_OVERHEAD);
+        cache.delete("key_1").await.unwrap();
+        cache.delete("key_22").await.unwrap();
+        assert_eq!(cache.get_stats().await.memory_usage, 0);
+    }
+
     #[tokio::test]
     async fn test_clear() {
         let cache = create_test_cache().await;
@@ -592,6 +1297,8 @@ mod tests {
             max_memory: 1024, // 很小的内存限制
             max_entries: 5,    // 很小的条目限制
             eviction_strategy: EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
         };
         
         let logging_config = LoggingConfig {
@@ -614,6 +1321,8 @@ mod tests {
             max_cleanup_entries: 100,
             lazy_expiration: true,
             active_expiration: false,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
         };
         
         let compressor = Compressor::new_disabled();
@@ -632,6 +1341,263 @@ mod tests {
         assert!(cache.len() <= 5);
         
         let stats = cache.get_stats().await;
-        assert!(stats.eviction_stats.total_evictions > 0);
+        assert!(stats.eviction_stats.total_evictions > 0);        assert!(stats.eviction_rate > 0.0);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_tinylfu_admission() {
+        let l1_config = L1Config {
+            max_memory: 1024 * 1024,
+            max_entries: 2,
+            eviction_strategy: EvictionStrategy::TinyLfu,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
+        };
+        let ttl_config = TtlConfig {
+            expire_seconds: None,
+            cleanup_interval: 60,
+            max_cleanup_entries: 100,
+            lazy_expiration: true,
+            active_expiration: false,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
+        };
+        let ttl_manager = Arc::new(TtlManager::new(ttl_config).await.unwrap());
+        let cache = L1Cache::new(l1_config, Compressor::new_disabled(), ttl_manager).await.unwrap();
+
+        cache.set("a".to_string(), Bytes::from("a"), None).await.unwrap();
+        cache.set("b".to_string(), Bytes::from("b"), None).await.unwrap();
+        cache.get("a").await.unwrap();
+        cache.get("b").await.unwrap();
+
+        // 只访问过一次的新键不会挤掉访问更频繁的键
+        let outcome = cache.set_with_flags("once".to_string(), Bytes::from("c"), None, 0).await.unwrap();
+        assert!(outcome.admission_rejected);
+        assert!(!cache.contains_key("once"));
+        assert!(cache.contains_key("a") && cache.contains_key("b"));
+
+        // 多次被请求后估计频率超过 LRU 候选者，写入时驱逐它
+        for _ in 0..3 {
+            assert_eq!(cache.get("once").await.unwrap(), None);
+        }
+        let outcome = cache.set_with_flags("once".to_string(), Bytes::from("c"), None, 0).await.unwrap();
+        assert!(!outcome.admission_rejected && outcome.caused_eviction());
+        assert!(cache.contains_key("once"));
+        assert!(!cache.contains_key("a"));
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.eviction_stats.rejected_admissions(), 1);
+        assert_eq!(stats.eviction_stats.total_evictions(), 1);
+
+        // 已存在的键总是可以更新
+        cache.set("b".to_string(), Bytes::from("b2"), None).await.unwrap();
+        assert_eq!(cache.get("b").await.unwrap(), Some(Bytes::from("b2")));
+        assert_eq!(cache.get_stats().await.eviction_stats.rejected_admissions(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_eviction_skips_stale_candidates() {
+        let l1_config = L1Config {
+            max_memory: 1024 * 1024,
+            max_entries: 2,
+            eviction_strategy: EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
+        };
+        let ttl_config = TtlConfig {
+            expire_seconds: None,
+            cleanup_interval: 60,
+            max_cleanup_entries: 100,
+            lazy_expiration: true,
+            active_expiration: false,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
+        };
+        let ttl_manager = Arc::new(TtlManager::new(ttl_config).await.unwrap());
+        let cache = L1Cache::new(l1_config, Compressor::new_disabled(), ttl_manager).await.unwrap();
+
+        // 模拟并发删除后残留的顺序记录，驱逐跳过它们继续选择下一个候选者
+        cache.lru_order.touch("ghost_1");
+        cache.lru_order.touch("ghost_2");
+        cache.set("a".to_string(), Bytes::from("a"), None).await.unwrap();
+        cache.set("b".to_string(), Bytes::from("b"), None).await.unwrap();
+        let outcome = cache.set_with_flags("c".to_string(), Bytes::from("c"), None, 0).await.unwrap();
+        assert_eq!(outcome.evicted_count, 1);
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains_key("a"));
+        assert_eq!(cache.lru_order.keys_by_recency(), vec!["b".to_string(), "c".to_string()]);
+
+        // 删除与命中不会留下顺序记录
+        cache.delete("b").await.unwrap();
+        assert_eq!(cache.get("b").await.unwrap(), None);
+        assert_eq!(cache.lru_order.keys_by_recency(), vec!["c".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_min_retention_skips_fresh_entries() {
+        let l1_config = L1Config {
+            max_memory: 1024 * 1024,
+            max_entries: 3,
+            eviction_strategy: EvictionStrategy::Lru,
+            l1_min_retention_ms: 1000,
+            order_compaction_factor: 2,
+        };
+        let ttl_config = TtlConfig {
+            expire_seconds: None,
+            cleanup_interval: 60,
+            max_cleanup_entries: 100,
+            lazy_expiration: true,
+            active_expiration: false,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
+        };
+        let ttl_manager = Arc::new(TtlManager::new(ttl_config).await.unwrap());
+        let cache = L1Cache::new(l1_config, Compressor::new_disabled(), ttl_manager).await.unwrap();
+
+        cache.set("old_a".to_string(), Bytes::from("a"), None).await.unwrap();
+        cache.set("old_b".to_string(), Bytes::from("b"), None).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        // 新条目处于 LRU 队首，但仍在保留期内，驱逐跳过它选择更早的条目
+        cache.set("fresh".to_string(), Bytes::from("c"), None).await.unwrap();
+        cache.get("old_a").await.unwrap();
+        cache.get("old_b").await.unwrap();
+        cache.set("next".to_string(), Bytes::from("d"), None).await.unwrap();
+        assert!(cache.contains_key("fresh"));
+        assert!(!cache.contains_key("old_a"));
+
+        cache.set("next_2".to_string(), Bytes::from("e"), None).await.unwrap();
+        assert!(cache.contains_key("fresh"));
+        assert!(!cache.contains_key("old_b"));
+
+        // 全部条目都在保留期内时仍按 LRU 驱逐
+        cache.set("next_3".to_string(), Bytes::from("f"), None).await.unwrap();
+        assert!(!cache.contains_key("fresh"));
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn test_eviction_rate_gauge_window() {
+        let start = Instant::now();
+        let gauge = EvictionRateGauge::new(start);
+        gauge.record(50, start);
+        assert_eq!(gauge.rate(start), 5.0);
+
+        // 进入下一窗口一半时，前一窗口按一半权重计入
+        let half = start + EVICTION_RATE_WINDOW + EVICTION_RATE_WINDOW / 2;
+        assert_eq!(gauge.rate(half), 2.5);
+
+        // 两个窗口内没有驱逐时归零
+        assert_eq!(gauge.rate(start + EVICTION_RATE_WINDOW * 3), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_access_pattern_report() {
+        let cache = create_test_cache().await;
+        let report = cache.access_pattern_report().await;
+        assert_eq!((report.sampled_keys, report.total_accesses), (0, 0));
+
+        // 每个键写入一次，访问均匀
+        for i in 0..10 {
+            cache.set(format!("key_{}", i), Bytes::from("v"), None).await.unwrap();
+        }
+        let report = cache.access_pattern_report().await;
+        assert_eq!((report.sampled_keys, report.total_keys, report.total_accesses), (10, 10, 10));
+        assert_eq!(report.frequency_gini, 0.0);
+        assert_eq!(report.recent_access_ratios[0], (10, 1.0));
+
+        // 越晚访问的键访问次数越多，近期性与频率正相关
+        for i in 5..10 {
+            for _ in 0..i {
+                cache.get(&format!("key_{}", i)).await.unwrap();
+            }
+        }
+        let report = cache.access_pattern_report().await;
+        assert_eq!(report.total_accesses, 10 + 35);
+        assert!(report.frequency_gini > 0.3);
+        assert!(report.recency_frequency_correlation > 0.8);
+    }
+
+    #[tokio::test]
+    async fn test_order_queue_compaction() {
+        let cache = create_test_cache().await;
+        for i in 0..800 {
+            cache.set(format!("key_{}", i), Bytes::from("v"), None).await.unwrap();
+        }
+        for i in 0..600 {
+            cache.delete(&format!("key_{}", i)).await.unwrap();
+        }
+        assert_eq!(cache.lru_order.len(), 200);
+        assert_eq!(cache.fifo_order.lock().len(), 200);
+
+        // 模拟遗漏清理的失效键和重复键，超过阈值后的下一次写入触发压缩
+        {
+            let mut fifo_order = cache.fifo_order.lock();
+            fifo_order.extend((0..2000).map(|i| format!("stale_{}", i)));
+            fifo_order.push_front("key_799".to_string());
+        }
+        cache.set("key_800".to_string(), Bytes::from("v"), None).await.unwrap();
+        {
+            let fifo_order = cache.fifo_order.lock();
+            assert_eq!(fifo_order.len(), 201);
+            assert_eq!(fifo_order.back().map(String::as_str), Some("key_800"));
+        }
+
+        cache.get("key_700").await.unwrap();
+        assert_eq!(cache.lru_order.keys_by_recency().last().map(String::as_str), Some("key_700"));
+
+        cache.fifo_order.lock().extend((0..10).map(|i| format!("stale_{}", i)));
+        assert_eq!(cache.compact_order_queues().await, 10);
+        assert_eq!(cache.fifo_order.lock().len(), 201);
+    }
+
+    #[test]
+    fn test_gini_and_rank_correlation() {
+        assert_eq!(gini_coefficient(&[]), 0.0);
+        assert_eq!(gini_coefficient(&[3, 3, 3]), 0.0);
+        assert!((gini_coefficient(&[0, 0, 0, 10]) - 0.75).abs() < 1e-9);
+
+        assert!((rank_correlation(&[1.0, 2.0, 3.0], &[10.0, 20.0, 30.0]) - 1.0).abs() < 1e-9);
+        assert!((rank_correlation(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]) + 1.0).abs() < 1e-9);
+        assert_eq!(rank_correlation(&[1.0, 2.0], &[5.0, 5.0]), 0.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_clear_during_concurrent_writes() {
+        let cache = Arc::new(create_test_cache().await);
+
+        let mut writers = Vec::new();
+        for task in 0..8 {
+            let cache = Arc::clone(&cache);
+            writers.push(tokio::spawn(async move {
+                for i in 0..500 {
+                    let key = format!("key_{}_{}", task, i % 40);
+                    cache.set(key.clone(), Bytes::from(vec![b'x'; 16 + i % 64]), None).await.unwrap();
+                    if i % 7 == 0 {
+                        cache.delete(&key).await.unwrap();
+                    }
+                }
+            }));
+        }
+        let clearer = {
+            let cache = Arc::clone(&cache);
+            tokio::spawn(async move {
+                for _ in 0..50 {
+                    cache.clear().await.unwrap();
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+        for writer in writers {
+            writer.await.unwrap();
+        }
+        clearer.await.unwrap();
+
+        // 计数器与存储的实际内容一致
+        let memory: usize = cache.storage.iter().map(|entry| entry_size(entry.key(), entry.value())).sum();
+        let logical: usize = cache.storage.iter().map(|entry| entry.value().original_size).sum();
+        assert_eq!(cache.len(), cache.storage.len());
+        assert_eq!(cache.memory_usage.load(Ordering::Relaxed), memory);
+        assert_eq!(cache.logical_bytes.load(Ordering::Relaxed), logical);
+    }
+}
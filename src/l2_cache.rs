@@ -4,6 +4,7 @@
 
 use crate::config::{L2Config, LoggingConfig};
 use crate::melange_adapter::{MelangeAdapter, MelangeConfig, CompressionAlgorithm, BatchOperation};
+use crate::codec::CodecChain;
 use crate::compression::Compressor;
 use crate::error::{CacheError, CacheResult};
 use crate::ttl::TtlManager;
@@ -11,12 +12,239 @@ use crate::types::{CacheLayer, CacheOperation};
 use bytes::Bytes;
 use bincode::{encode_to_vec, decode_from_slice};
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock};
 use tokio::task;
 
+/// 流式读取的块大小
+pub const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+/// 流式读取时后台解压任务最多领先消费者的块数
+pub const STREAM_BUFFER_CHUNKS: usize = 4;
+
+/// 统计热点键时最多扫描的元数据条目数
+pub const HOT_KEYS_SCAN_LIMIT: usize = 100_000;
+
+/// TTL 索引文件名（位于 L2 数据目录）
+const TTL_INDEX_FILE: &str = "ttl_index.bin";
+/// CAS 令牌中 L2 代数所在的位移：高位为打开时的代数，重启后签发的令牌不会与之前写入的重复，
+/// 也不会与从 1 开始计数的 L1 令牌重复
+const CAS_GENERATION_SHIFT: u32 = 40;
+
+/// 缓存值的分块流，按顺序产出解压后的数据块
+#[derive(Debug)]
+pub struct ValueStream {
+    source: StreamSource,
+}
+
+#[derive(Debug)]
+enum StreamSource {
+    /// 已在内存中的值（L1 命中）
+    Memory(Bytes),
+    /// 后台从 L2 按块解压
+    Background(mpsc::Receiver<CacheResult<Bytes>>),
+}
+
+impl ValueStream {
+    /// 将内存中的值按块切分为流（不复制数据）
+    pub(crate) fn from_bytes(data: Bytes) -> Self {
+        Self { source: StreamSource::Memory(data) }
+    }
+
+    /// 读取下一个数据块，全部读完时返回 None
+    pub async fn next_chunk(&mut self) -> Option<CacheResult<Bytes>> {
+        match &mut self.source {
+            StreamSource::Memory(data) => Self::split_chunk(data),
+            StreamSource::Background(receiver) => receiver.recv().await,
+        }
+    }
+
+    fn split_chunk(data: &mut Bytes) -> Option<CacheResult<Bytes>> {
+        if data.is_empty() {
+            return None;
+        }
+        let len = data.len().min(STREAM_CHUNK_SIZE);
+        Some(Ok(data.split_to(len)))
+    }
+}
+
+impl futures_core::Stream for ValueStream {
+    type Item = CacheResult<Bytes>;
+
+    fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Option<Self::Item>> {
+        match &mut self.source {
+            StreamSource::Memory(data) => std::task::Poll::Ready(Self::split_chunk(data)),
+            StreamSource::Background(receiver) => receiver.poll_recv(cx),
+        }
+    }
+}
+
+/// 后台任务状态：排队中、已开始执行、已因排队超时取消
+const BLOCKING_TASK_PENDING: u8 = 0;
+const BLOCKING_TASK_RUNNING: u8 = 1;
+const BLOCKING_TASK_CANCELLED: u8 = 2;
+
+/// 排队过慢的警告最少间隔（毫秒），避免线程池饱和时刷屏
+const QUEUE_WAIT_WARN_INTERVAL_MS: u64 = 1000;
+
+/// 后台线程池（spawn_blocking）排队等待统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct QueueWaitStats {
+    /// 已开始执行的任务数
+    pub count: u64,
+    /// 平均排队时间（微秒）
+    pub avg_wait_us: f64,
+    /// 最长排队时间（微秒）
+    pub max_wait_us: u64,
+    /// 排队时间超过警告阈值的任务数
+    pub slow_waits: u64,
+    /// 排队超时被拒绝（返回 Busy）的任务数
+    pub busy_rejections: u64,
+}
+
+#[derive(Debug)]
+struct QueueWaitMetrics {
+    created_at: Instant,
+    count: AtomicU64,
+    total_wait_us: AtomicU64,
+    max_wait_us: AtomicU64,
+    slow_waits: AtomicU64,
+    busy_rejections: AtomicU64,
+    /// 上次警告距 created_at 的毫秒数
+    last_warn_ms: AtomicU64,
+}
+
+impl QueueWaitMetrics {
+    fn new() -> Self {
+        Self {
+            created_at: Instant::now(),
+            count: AtomicU64::new(0),
+            total_wait_us: AtomicU64::new(0),
+            max_wait_us: AtomicU64::new(0),
+            slow_waits: AtomicU64::new(0),
+            busy_rejections: AtomicU64::new(0),
+            last_warn_ms: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, wait: Duration, warn_threshold: Duration) {
+        let wait_us = wait.as_micros() as u64;
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.total_wait_us.fetch_add(wait_us, Ordering::Relaxed);
+        self.max_wait_us.fetch_max(wait_us, Ordering::Relaxed);
+
+        if warn_threshold.is_zero() || wait < warn_threshold {
+            return;
+        }
+        self.slow_waits.fetch_add(1, Ordering::Relaxed);
+        let now_ms = self.created_at.elapsed().as_millis() as u64;
+        let last_warn_ms = self.last_warn_ms.load(Ordering::Relaxed);
+        if (last_warn_ms == 0 || now_ms.saturating_sub(last_warn_ms) >= QUEUE_WAIT_WARN_INTERVAL_MS)
+            && self.last_warn_ms.compare_exchange(last_warn_ms, now_ms.max(1), Ordering::Relaxed, Ordering::Relaxed).is_ok()
+        {
+            rat_logger::warn!("[L2] 后台线程池排队 {}ms 才开始执行，磁盘 I/O 可能已饱和（累计 {} 次）",
+                wait.as_millis(), self.slow_waits.load(Ordering::Relaxed));
+        }
+    }
+
+    fn snapshot(&self) -> QueueWaitStats {
+        let count = self.count.load(Ordering::Relaxed);
+        QueueWaitStats {
+            count,
+            avg_wait_us: if count > 0 {
+                self.total_wait_us.load(Ordering::Relaxed) as f64 / count as f64
+            } else {
+                0.0
+            },
+            max_wait_us: self.max_wait_us.load(Ordering::Relaxed),
+            slow_waits: self.slow_waits.load(Ordering::Relaxed),
+            busy_rejections: self.busy_rejections.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// L2 写入带宽限制器（令牌桶），桶容量为一秒的写入预算
+#[derive(Debug)]
+struct WriteRateLimiter {
+    bytes_per_sec: u64,
+    state: parking_lot::Mutex<WriteRateState>,
+}
+
+#[derive(Debug)]
+struct WriteRateState {
+    /// 当前可用令牌（字节），强制写入可使其为负
+    tokens: f64,
+    last_refill: Instant,
+    /// 当前统计窗口内放行的字节数
+    window_bytes: u64,
+    window_start: Instant,
+    /// 上一个完整统计窗口的写入速率（字节/秒）
+    rate: f64,
+    /// 最近一次写入是否因超出预算被拒绝
+    throttled: bool,
+    throttled_writes: u64,
+}
+
+impl WriteRateLimiter {
+    fn new(bytes_per_sec: u64) -> Self {
+        let now = Instant::now();
+        Self {
+            bytes_per_sec,
+            state: parking_lot::Mutex::new(WriteRateState {
+                tokens: bytes_per_sec as f64,
+                last_refill: now,
+                window_bytes: 0,
+                window_start: now,
+                rate: 0.0,
+                throttled: false,
+                throttled_writes: 0,
+            }),
+        }
+    }
+
+    /// 申请写入预算，`force` 为 true 时总是放行（仍消耗令牌）
+    ///
+    /// 桶满时允许单次超出容量的写入，避免大于每秒预算的值永远无法写入
+    fn try_acquire(&self, bytes: usize, force: bool) -> bool {
+        let capacity = self.bytes_per_sec as f64;
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        let refill = now.duration_since(state.last_refill).as_secs_f64() * capacity;
+        state.tokens = (state.tokens + refill).min(capacity);
+        state.last_refill = now;
+
+        let window = now.duration_since(state.window_start).as_secs_f64();
+        if window >= 1.0 {
+            state.rate = state.window_bytes as f64 / window;
+            state.window_bytes = 0;
+            state.window_start = now;
+        }
+
+        let bytes = bytes as u64;
+        let allowed = force || state.tokens >= bytes as f64 || state.tokens >= capacity;
+        if allowed {
+            state.tokens -= bytes as f64;
+            state.window_bytes += bytes;
+        } else {
+            state.throttled_writes += 1;
+        }
+        state.throttled = !allowed;
+        allowed
+    }
+
+    fn fill_stats(&self, stats: &mut L2CacheStats) {
+        let state = self.state.lock();
+        stats.write_rate_bytes_per_sec = state.rate;
+        stats.write_throttled = state.throttled;
+        stats.throttled_writes = state.throttled_writes;
+    }
+}
+
 /// L2 持久化缓存 - MelangeDB 实现
 #[derive(Debug)]
 pub struct L2Cache {
@@ -25,12 +253,60 @@ pub struct L2Cache {
     db: Arc<MelangeAdapter>,
     /// 压缩器
     compressor: Arc<Compressor>,
+    /// 值编解码链
+    codec_chain: Arc<CodecChain>,
     /// TTL 管理器
     ttl_manager: Arc<TtlManager>,
         /// 统计信息
     stats: Arc<RwLock<L2CacheStats>>,
-    /// 磁盘使用量估算
+    /// 磁盘使用量估算（写入和删除时增量维护）
     disk_usage: Arc<AtomicU64>,
+    /// 存储的条目数（写入和删除时增量维护）
+    entry_count: Arc<AtomicU64>,
+    /// 存活条目的逻辑（未压缩）字节数
+    logical_bytes: Arc<AtomicU64>,
+    /// 自上次清空后是否尚无写入
+    known_empty: Arc<AtomicBool>,
+    /// 写入带宽限制器（未配置上限时为 None）
+    write_limiter: Option<Arc<WriteRateLimiter>>,
+    /// 后台线程池排队等待统计
+    queue_wait: Arc<QueueWaitMetrics>,
+    /// 瞬时故障的重试次数
+    retries: Arc<AtomicU64>,
+    /// 重试耗尽后仍失败的操作数
+    retries_exhausted: Arc<AtomicU64>,
+    /// 注入的故障，按顺序替代下一次读写的结果（测试用）
+    #[cfg(test)]
+    injected_failures: Arc<parking_lot::Mutex<std::collections::VecDeque<CacheError>>>,
+    /// 数据目录
+    data_dir: PathBuf,
+    /// 本次打开时的 L2 代数
+    generation: u64,
+    /// 最近签发的 CAS 令牌
+    last_cas: AtomicU64,
+    /// 存储键布局
+    layout: KeyLayout,
+}
+
+/// 启动时 TTL 索引的重建方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum TtlIndexSource {
+    /// 尚未重建
+    #[default]
+    None,
+    /// 加载正常关闭时保存的索引文件
+    Sidecar,
+    /// 扫描全部元数据（首次启动、异常退出或索引文件失效）
+    MetadataScan,
+}
+
+/// 持久化的 TTL 索引
+#[derive(bincode::Encode, bincode::Decode)]
+struct TtlIndexFile {
+    /// 保存时的 L2 代数，与下次打开前数据库记录的代数一致才有效
+    generation: u64,
+    /// (键, 过期时间戳)
+    entries: Vec<(String, u64)>,
 }
 
 /// L2 缓存统计信息
@@ -46,16 +322,106 @@ pub struct L2CacheStats {
     pub hits: u64,
     /// 未命中次数
     pub misses: u64,
+    /// L2 为空时跳过的读取次数（计入未命中）
+    pub skipped_reads: u64,
+    /// 因采样跳过的元数据写回次数
+    pub skipped_metadata_updates: u64,
     /// 压缩操作次数
     pub compactions: u64,
+    /// 重新计算磁盘使用量的全量扫描次数（仅在启动和压缩时执行）
+    pub full_scans: u64,
     /// 估算的磁盘使用量
     pub estimated_disk_usage: u64,
+    /// 存活条目的逻辑（未压缩）字节数
+    pub logical_bytes: u64,
     /// 实际存储的条目数
     pub entry_count: u64,
     /// 平均读取延迟（毫秒）
     pub avg_read_latency_ms: f64,
     /// 平均写入延迟（毫秒）
     pub avg_write_latency_ms: f64,
+    /// 按实际生效的压缩算法分类的写入统计
+    pub compression_by_algorithm: HashMap<CompressionAlgorithm, AlgorithmCompressionStats>,
+    /// 最近一秒放行的写入速率（字节/秒，仅在配置写入带宽上限时统计）
+    pub write_rate_bytes_per_sec: f64,
+    /// 最近一次写入是否因超出带宽上限被限流
+    pub write_throttled: bool,
+    /// 因超出带宽上限未写入 L2 的次数
+    pub throttled_writes: u64,
+    /// 读写任务在后台线程池（spawn_blocking）中的排队等待统计
+    pub spawn_blocking_queue_wait: QueueWaitStats,
+    /// 瞬时故障的重试次数，持续增长说明存储层存在被重试掩盖的问题
+    pub retries: u64,
+    /// 重试耗尽后仍失败的操作数
+    pub retries_exhausted: u64,
+    /// 启动时 TTL 索引的重建方式
+    pub ttl_index_source: TtlIndexSource,
+}
+
+/// 单个压缩算法的写入统计
+#[derive(Debug, Clone, Default)]
+pub struct AlgorithmCompressionStats {
+    /// 使用该算法写入的条目数
+    pub entries: u64,
+    /// 原始数据总字节数
+    pub original_bytes: u64,
+    /// 实际存储的总字节数
+    pub stored_bytes: u64,
+}
+
+impl AlgorithmCompressionStats {
+    /// 计算压缩比率 (stored_bytes / original_bytes)
+    pub fn compression_ratio(&self) -> f64 {
+        if self.original_bytes == 0 {
+            1.0
+        } else {
+            self.stored_bytes as f64 / self.original_bytes as f64
+        }
+    }
+}
+
+/// 已存储条目的大小
+#[derive(Debug, Clone, Copy, Default)]
+struct EntrySize {
+    /// 原始（未压缩）大小
+    original_size: usize,
+    /// 数据键中实际存储的字节数
+    stored_size: usize,
+    /// 是否有独立的元数据键（紧凑存储时为 false）
+    has_metadata_key: bool,
+}
+
+impl EntrySize {
+    fn from_metadata(metadata: &StoredMetadata) -> Self {
+        Self {
+            original_size: metadata.original_size,
+            stored_size: metadata.data_size,
+            has_metadata_key: true,
+        }
+    }
+}
+
+/// 后台读取存储条目的结果
+enum StoredRead<T> {
+    /// 数据不存在
+    Missing,
+    /// 元数据中的过期时间已过
+    Expired,
+    /// 处理后的值、标志、CAS 令牌及元数据（紧凑存储时为 None）
+    Found(T, u32, u64, Option<StoredMetadata>),
+}
+
+/// 压缩、编码完成，等待写入的条目
+struct PreparedWrite {
+    key: String,
+    data: Bytes,
+    /// 独立的元数据键的内容，紧凑存储时为 None
+    metadata_bytes: Option<Vec<u8>>,
+    ttl_seconds: Option<u64>,
+    original_size: usize,
+    compressed_size: usize,
+    is_compressed: bool,
+    algorithm: CompressionAlgorithm,
 }
 
 /// 存储的元数据
@@ -75,6 +441,127 @@ struct StoredMetadata {
     is_compressed: bool,
     /// 数据大小
     data_size: usize,
+    /// 写入时使用的编解码链描述
+    codec_chain: Vec<u8>,
+    /// 存储数据的 CRC32 校验和（未启用校验或旧数据为 None）
+    checksum: Option<u32>,
+    /// 客户端标志（Memcached flags）
+    flags: u32,
+    /// 写入时使用的压缩算法（未压缩时为 None）
+    compression_algorithm: CompressionAlgorithm,
+    /// CAS 令牌（旧数据为 0，表示没有令牌）
+    cas: u64,
+}
+
+/// 记录 CAS 令牌之前的元数据格式，用于读取旧数据
+#[derive(Debug, Clone, bincode::Decode)]
+struct TokenlessStoredMetadata {
+    created_at: u64,
+    accessed_at: u64,
+    expires_at: u64,
+    access_count: u64,
+    original_size: usize,
+    is_compressed: bool,
+    data_size: usize,
+    codec_chain: Vec<u8>,
+    checksum: Option<u32>,
+    flags: u32,
+    compression_algorithm: CompressionAlgorithm,
+}
+
+impl From<TokenlessStoredMetadata> for StoredMetadata {
+    fn from(tokenless: TokenlessStoredMetadata) -> Self {
+        Self {
+            created_at: tokenless.created_at,
+            accessed_at: tokenless.accessed_at,
+            expires_at: tokenless.expires_at,
+            access_count: tokenless.access_count,
+            original_size: tokenless.original_size,
+            is_compressed: tokenless.is_compressed,
+            data_size: tokenless.data_size,
+            codec_chain: tokenless.codec_chain,
+            checksum: tokenless.checksum,
+            flags: tokenless.flags,
+            compression_algorithm: tokenless.compression_algorithm,
+            cas: 0,
+        }
+    }
+}
+
+/// 记录压缩算法之前的元数据格式（压缩数据均为 LZ4），用于读取旧数据
+#[derive(Debug, Clone, bincode::Decode)]
+struct UntaggedStoredMetadata {
+    created_at: u64,
+    accessed_at: u64,
+    expires_at: u64,
+    access_count: u64,
+    original_size: usize,
+    is_compressed: bool,
+    data_size: usize,
+    codec_chain: Vec<u8>,
+    checksum: Option<u32>,
+    flags: u32,
+}
+
+impl From<UntaggedStoredMetadata> for StoredMetadata {
+    fn from(untagged: UntaggedStoredMetadata) -> Self {
+        Self {
+            created_at: untagged.created_at,
+            accessed_at: untagged.accessed_at,
+            expires_at: untagged.expires_at,
+            access_count: untagged.access_count,
+            original_size: untagged.original_size,
+            is_compressed: untagged.is_compressed,
+            data_size: untagged.data_size,
+            codec_chain: untagged.codec_chain,
+            checksum: untagged.checksum,
+            flags: untagged.flags,
+            compression_algorithm: StoredMetadata::legacy_algorithm(untagged.is_compressed),
+            cas: 0,
+        }
+    }
+}
+
+/// 引入编解码链之前的元数据格式，用于读取旧数据
+#[derive(Debug, Clone, bincode::Decode)]
+struct LegacyStoredMetadata {
+    created_at: u64,
+    accessed_at: u64,
+    expires_at: u64,
+    access_count: u64,
+    original_size: usize,
+    is_compressed: bool,
+    data_size: usize,
+}
+
+impl From<LegacyStoredMetadata> for StoredMetadata {
+    fn from(legacy: LegacyStoredMetadata) -> Self {
+        Self {
+            created_at: legacy.created_at,
+            accessed_at: legacy.accessed_at,
+            expires_at: legacy.expires_at,
+            access_count: legacy.access_count,
+            original_size: legacy.original_size,
+            is_compressed: legacy.is_compressed,
+            data_size: legacy.data_size,
+            codec_chain: Vec::new(),
+            checksum: None,
+            flags: 0,
+            compression_algorithm: StoredMetadata::legacy_algorithm(legacy.is_compressed),
+            cas: 0,
+        }
+    }
+}
+
+impl StoredMetadata {
+    /// 未记录压缩算法的旧数据只可能使用 LZ4 压缩
+    fn legacy_algorithm(is_compressed: bool) -> CompressionAlgorithm {
+        if is_compressed {
+            CompressionAlgorithm::Lz4
+        } else {
+            CompressionAlgorithm::None
+        }
+    }
 }
 
 /// 键前缀常量
@@ -82,6 +569,96 @@ mod key_prefixes {
     pub const DATA: &[u8] = b"d:";
     pub const METADATA: &[u8] = b"m:";
     pub const TTL_INDEX: &[u8] = b"t:";
+    /// L2 代数（每次打开递增）
+    pub const GENERATION: &[u8] = b"g:generation";
+}
+
+/// 存储键布局：命名空间前缀 + 类型前缀 + [分区字节] + 原始键
+#[derive(Debug, Clone)]
+struct KeyLayout {
+    /// `key_prefix` 配置的命名空间
+    namespace: Arc<[u8]>,
+    partitions: usize,
+}
+
+impl KeyLayout {
+    fn new(namespace: &str, partitions: usize) -> Self {
+        Self {
+            namespace: Arc::from(namespace.as_bytes()),
+            partitions,
+        }
+    }
+
+    /// 命名空间下的类型前缀
+    fn type_prefix(&self, prefix: &[u8]) -> Vec<u8> {
+        let mut type_prefix = Vec::with_capacity(self.namespace.len() + prefix.len());
+        type_prefix.extend_from_slice(&self.namespace);
+        type_prefix.extend_from_slice(prefix);
+        type_prefix
+    }
+
+    /// 计算键所属的分区（单分区时返回 None，沿用不带分区字节的键布局）
+    fn partition_of(&self, key: &str) -> Option<u8> {
+        (self.partitions > 1).then(|| (fxhash::hash64(key) % self.partitions as u64) as u8)
+    }
+
+    /// 构造存储键
+    fn storage_key(&self, prefix: &[u8], key: &str) -> Vec<u8> {
+        let mut storage_key = Vec::with_capacity(self.namespace.len() + prefix.len() + 1 + key.len());
+        storage_key.extend_from_slice(&self.namespace);
+        storage_key.extend_from_slice(prefix);
+        if let Some(partition) = self.partition_of(key) {
+            storage_key.push(partition);
+        }
+        storage_key.extend_from_slice(key.as_bytes());
+        storage_key
+    }
+
+    /// 构造数据键
+    fn data_key(&self, key: &str) -> Vec<u8> {
+        self.storage_key(key_prefixes::DATA, key)
+    }
+
+    /// 构造元数据键
+    fn metadata_key(&self, key: &str) -> Vec<u8> {
+        self.storage_key(key_prefixes::METADATA, key)
+    }
+
+    /// 构造分区的扫描前缀（单分区时即为类型前缀）
+    fn partition_prefix(&self, prefix: &[u8], partition: usize) -> Vec<u8> {
+        let mut partition_prefix = self.type_prefix(prefix);
+        if self.partitions > 1 {
+            partition_prefix.push(partition as u8);
+        }
+        partition_prefix
+    }
+
+    /// 从存储键中取出原始键
+    fn user_key<'a>(&self, storage_key: &'a [u8], prefix: &[u8]) -> &'a [u8] {
+        let offset = self.namespace.len() + prefix.len() + usize::from(self.partitions > 1);
+        storage_key.get(offset..).unwrap_or_default()
+    }
+
+    /// L2 代数的存储键
+    fn generation_key(&self) -> Vec<u8> {
+        self.type_prefix(key_prefixes::GENERATION)
+    }
+}
+
+/// 紧凑存储模式下内联在数据值前的元数据头
+///
+/// 布局: [flags: u8][original_size: u64 LE][created_at: u64 LE][checksum: u32 LE，仅当设置 FLAG_CHECKSUM]
+/// [cas: u64 LE，仅当设置 FLAG_CAS]
+mod compact_header {
+    pub const LEN: usize = 17;
+    pub const CHECKSUM_LEN: usize = 4;
+    pub const CAS_LEN: usize = 8;
+    pub const FLAG_COMPRESSED: u8 = 0x01;
+    pub const FLAG_CHECKSUM: u8 = 0x02;
+    /// 与 FLAG_COMPRESSED 同时设置，表示使用 Snappy 而不是 LZ4 压缩
+    pub const FLAG_SNAPPY: u8 = 0x04;
+    /// 头部带有 CAS 令牌（记录令牌之前写入的数据没有）
+    pub const FLAG_CAS: u8 = 0x08;
 }
 
 impl L2Cache {
@@ -89,6 +666,7 @@ impl L2Cache {
     pub async fn new(
         config: L2Config,
         compressor: Compressor,
+        codec_chain: Arc<CodecChain>,
         ttl_manager: Arc<TtlManager>,
     ) -> CacheResult<Self> {
         rat_logger::debug!("[L2] L2Cache::new 开始初始化");
@@ -141,7 +719,16 @@ impl L2Cache {
 
         // 处理启动时清空缓存目录的逻辑
         if config.clear_on_startup && data_dir.exists() {
-            rat_logger::debug!("[L2] 配置要求启动时清空缓存目录");
+            if !config.confirm_clear_on_startup {
+                rat_logger::error!(
+                    "[L2] 配置了 clear_on_startup 但未设置 confirm_clear_on_startup，拒绝清空缓存目录 {:?} 并终止启动",
+                    data_dir
+                );
+                return Err(CacheError::config_error(format!(
+                    "clear_on_startup 需要同时设置 confirm_clear_on_startup 才会清空 {:?}", data_dir
+                )));
+            }
+            rat_logger::warn!("[L2] 启动时清空缓存目录，所有持久化数据将被删除: {:?}", data_dir);
             match std::fs::remove_dir_all(&data_dir) {
                 Ok(_) => rat_logger::debug!("[L2] 缓存目录清空成功"),
                 Err(e) => {
@@ -182,28 +769,152 @@ impl L2Cache {
         // 打开 MelangeDB
         rat_logger::debug!("[L2] 尝试打开 MelangeDB 数据库，路径: {:?}", data_dir);
         let db = MelangeAdapter::new(&data_dir, melange_config)?;
+        let layout = KeyLayout::new(&config.key_prefix, config.l2_partitions);
+
+        // 每次打开递增代数并落盘，TTL 索引文件只在代数与上次关闭时一致时有效
+        let previous_generation = Self::read_generation(&db, &layout)?;
+        let generation = previous_generation + 1;
+        db.put(&layout.generation_key(), &generation.to_le_bytes())?;
+        db.flush()?;
+
+        let write_limiter = config.l2_max_write_bytes_per_sec
+            .map(|bytes_per_sec| Arc::new(WriteRateLimiter::new(bytes_per_sec)));
 
         let cache = Self {
             config: Arc::new(config),
             db: Arc::new(db),
             compressor: Arc::new(compressor),
+            codec_chain,
             ttl_manager,
             stats: Arc::new(RwLock::new(L2CacheStats::default())),
             disk_usage: Arc::new(AtomicU64::new(0)),
+            entry_count: Arc::new(AtomicU64::new(0)),
+            logical_bytes: Arc::new(AtomicU64::new(0)),
+            known_empty: Arc::new(AtomicBool::new(false)),
+            write_limiter,
+            queue_wait: Arc::new(QueueWaitMetrics::new()),
+            retries: Arc::new(AtomicU64::new(0)),
+            retries_exhausted: Arc::new(AtomicU64::new(0)),
+            #[cfg(test)]
+            injected_failures: Arc::default(),
+            data_dir: data_dir.clone(),
+            generation,
+            last_cas: AtomicU64::new(generation << CAS_GENERATION_SHIFT),
+            layout,
         };
 
-        // 初始化磁盘使用量统计
-        cache.update_disk_usage_estimate().await;
+        // 初始化磁盘使用量统计，并据此判断 L2 是否为空
+        if cache.update_disk_usage_estimate().await == Some(0) {
+            cache.known_empty.store(true, Ordering::Release);
+        }
+
+        cache.restore_ttl_index(previous_generation).await;
 
         rat_logger::debug!("[L2] L2 缓存（MelangeDB）已初始化，数据目录: {:?}", &data_dir);
 
         Ok(cache)
     }
 
-    /// 获取缓存值
+    /// 获取缓存值（不含标志，测试用）
+    #[cfg(test)]
     pub async fn get(&self, key: &str) -> CacheResult<Option<Bytes>> {
+        Ok(self.get_with_flags(key).await?.map(|(data, _)| data))
+    }
+
+    /// 获取缓存值及其标志
+    pub async fn get_with_flags(&self, key: &str) -> CacheResult<Option<(Bytes, u32)>> {
+        Ok(self.get_with_cas(key).await?.map(|(data, flags, _)| (data, flags)))
+    }
+
+    /// 获取缓存值、标志及写入时签发的 CAS 令牌（旧数据为 0）
+    pub async fn get_with_cas(&self, key: &str) -> CacheResult<Option<(Bytes, u32, u64)>> {
+        let compressor = Arc::clone(&self.compressor);
+        self.read_stored(key, move |payload, algorithm| {
+            Ok(compressor.decompress(&payload, algorithm)?.data)
+        }).await
+    }
+
+    /// 只读取条目的 CAS 令牌，不解压数据也不计入命中统计；不存在、已过期或没有令牌时返回 None
+    pub async fn cas_token(&self, key: &str) -> CacheResult<Option<u64>> {
+        if self.ttl_manager.is_expired(key).await {
+            return Ok(None);
+        }
+        let db = Arc::clone(&self.db);
+        let key_str = key.to_string();
+        let layout = self.layout.clone();
+        let now = self.ttl_manager.clock().now();
+        let metadata = self.run_storage_op(move || Self::read_stored_metadata(&db, &key_str, &layout)).await?;
+        Ok(metadata
+            .filter(|metadata| metadata.expires_at == 0 || metadata.expires_at > now)
+            .map(|metadata| metadata.cas)
+            .filter(|&cas| cas != 0))
+    }
+
+    /// 读取缓存值及其标志，不计入命中统计、不更新访问元数据，也不删除过期条目
+    pub async fn peek_with_flags(&self, key: &str) -> CacheResult<Option<(Bytes, u32)>> {
+        if self.ttl_manager.is_expired(key).await {
+            return Ok(None);
+        }
+        let db = Arc::clone(&self.db);
+        let key_str = key.to_string();
+        let codec_chain = Arc::clone(&self.codec_chain);
+        let compressor = Arc::clone(&self.compressor);
+        let layout = self.layout.clone();
+        let now = self.ttl_manager.clock().now();
+        let read = self.run_storage_op(move || {
+            Self::read_entry(&db, &layout, &codec_chain, &key_str, now, |payload, algorithm| {
+                Ok(compressor.decompress(&payload, algorithm)?.data)
+            })
+        }).await?;
+        match read {
+            StoredRead::Found(value, flags, _, _) => Ok(Some((value, flags))),
+            StoredRead::Expired | StoredRead::Missing => Ok(None),
+        }
+    }
+
+    /// 以分块流的形式读取缓存值
+    ///
+    /// 存储的（压缩后）数据仍需整体读出，解压缩在后台线程中按块进行，
+    /// 最多缓冲 [`STREAM_BUFFER_CHUNKS`] 个块，不会在内存中拼出完整的解压值
+    pub async fn get_stream(&self, key: &str) -> CacheResult<Option<(ValueStream, u32)>> {
+        let stored = self.read_stored(key, |payload, algorithm| Ok((payload, algorithm))).await?;
+        let Some(((payload, algorithm), flags, _)) = stored else {
+            return Ok(None);
+        };
+
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER_CHUNKS);
+        let compressor = Arc::clone(&self.compressor);
+        task::spawn_blocking(move || {
+            let result = compressor.decompress_chunked(&payload, algorithm, STREAM_CHUNK_SIZE, |chunk| {
+                // 消费者已丢弃流时停止解压
+                sender.blocking_send(Ok(chunk)).is_ok()
+            });
+            if let Err(e) = result {
+                let _ = sender.blocking_send(Err(e));
+            }
+        });
+
+        Ok(Some((ValueStream { source: StreamSource::Background(receiver) }, flags)))
+    }
+
+    /// 读取存储的值并在后台线程中用 `transform` 处理载荷（载荷, 压缩算法），返回处理结果、标志及 CAS 令牌
+    ///
+    /// 除 TTL 管理器外还检查元数据中的过期时间，TTL 索引缺失该键（如重启后尚未重建）时
+    /// 已过期的条目同样按未命中处理并删除
+    async fn read_stored<T, F>(&self, key: &str, transform: F) -> CacheResult<Option<(T, u32, u64)>>
+    where
+        T: Send + 'static,
+        F: FnOnce(Bytes, CompressionAlgorithm) -> CacheResult<T> + Send + 'static,
+    {
         let start_time = Instant::now();
 
+        // L2 自上次清空后尚无写入，必然未命中，跳过后台读取
+        if self.config.skip_reads_when_empty && self.known_empty.load(Ordering::Acquire) {
+            self.record_skipped_read().await;
+            self.record_read_latency(start_time.elapsed()).await;
+            return Ok(None);
+        }
+
         // 检查 TTL
         if self.ttl_manager.is_expired(key).await {
             self.delete_internal(key).await?;
@@ -214,128 +925,357 @@ impl L2Cache {
 
         let db = Arc::clone(&self.db);
         let key_str = key.to_string();
-        let compressor = Arc::clone(&self.compressor);
+        let codec_chain = Arc::clone(&self.codec_chain);
+        let layout = self.layout.clone();
+        let now = self.ttl_manager.clock().now();
+
+        // 解压缩回调只会调用一次，失败重试时由读取闭包取用
+        let transform = parking_lot::Mutex::new(Some(transform));
 
         // 在后台线程中执行 I/O 操作
-        let result = task::spawn_blocking(move || -> CacheResult<Option<(Bytes, StoredMetadata)>> {
-            // 构造数据键
-            let data_key = Self::make_data_key(&key_str);
-            let metadata_key = Self::make_metadata_key(&key_str);
+        let result = self.run_storage_op(move || -> CacheResult<StoredRead<T>> {
+            Self::read_entry(&db, &layout, &codec_chain, &key_str, now, |payload, algorithm| {
+                let transform = transform.lock().take().ok_or_else(|| CacheError::other("L2 读取的解压缩回调已被使用"))?;
+                transform(payload, algorithm)
+            })
+        }).await;
 
-            // 读取数据和元数据
-            let data = db.get(&data_key)?;
-            let metadata_bytes = db.get(&metadata_key)?;
+        let result = self.finish_read(key, result).await;
+        self.record_read_latency(start_time.elapsed()).await;
+        result
+    }
 
-            if let (Some(data), Some(metadata_bytes)) = (data, metadata_bytes) {
-                // 反序列化元数据
-                let (metadata, _): (StoredMetadata, usize) = decode_from_slice(&metadata_bytes, bincode::config::standard())
-                    .map_err(|e| CacheError::serialization_error(&format!("反序列化元数据失败: {}", e)))?;
+    /// 批量获取缓存值及其标志，结果与 `keys` 一一对应
+    ///
+    /// 未过期的键在一次后台任务中读取，其余行为（过期删除、访问统计、命中率）与逐个调用
+    /// [`Self::get_with_flags`] 相同。任一键读取失败时返回该错误
+    pub async fn get_many_with_flags(&self, keys: &[String]) -> CacheResult<Vec<Option<(Bytes, u32)>>> {
+        let start_time = Instant::now();
+        let mut results = vec![None; keys.len()];
 
-                // 解压缩数据
-                let decompressed = compressor.decompress(&data, metadata.is_compressed)?;
+        // L2 自上次清空后尚无写入，必然未命中，跳过后台读取
+        if self.config.skip_reads_when_empty && self.known_empty.load(Ordering::Acquire) {
+            for _ in keys {
+                self.record_skipped_read().await;
+            }
+            self.record_read_latency(start_time.elapsed()).await;
+            return Ok(results);
+        }
 
-                Ok(Some((decompressed.data, metadata)))
+        let mut pending = Vec::with_capacity(keys.len());
+        for (index, key) in keys.iter().enumerate() {
+            if self.ttl_manager.is_expired(key).await {
+                self.delete_internal(key).await?;
+                self.record_miss().await;
             } else {
-                Ok(None)
+                pending.push((index, key.clone()));
             }
-        }).await
-        .map_err(|e| CacheError::io_error(&format!("后台任务执行失败: {}", e)))??;
+        }
+        if pending.is_empty() {
+            self.record_read_latency(start_time.elapsed()).await;
+            return Ok(results);
+        }
 
-        if let Some((data, mut metadata)) = result {
-            // 更新访问统计
-            metadata.accessed_at = crate::types::current_timestamp();
-            metadata.access_count += 1;
+        let db = Arc::clone(&self.db);
+        let codec_chain = Arc::clone(&self.codec_chain);
+        let compressor = Arc::clone(&self.compressor);
+        let layout = self.layout.clone();
+        let now = self.ttl_manager.clock().now();
+        let read_keys: Vec<String> = pending.iter().map(|(_, key)| key.clone()).collect();
+
+        // 单个键的损坏等永久性错误不影响其他键，瞬时故障时整批重试
+        let reads = self.run_storage_op(move || -> CacheResult<Vec<CacheResult<StoredRead<Bytes>>>> {
+            let mut reads = Vec::with_capacity(read_keys.len());
+            for key in &read_keys {
+                let read = Self::read_entry(&db, &layout, &codec_chain, key, now, |payload, algorithm| {
+                    Ok(compressor.decompress(&payload, algorithm)?.data)
+                });
+                match read {
+                    Err(e) if e.is_transient() => return Err(e),
+                    read => reads.push(read),
+                }
+            }
+            Ok(reads)
+        }).await;
+        let reads = match reads {
+            Ok(reads) => reads,
+            Err(e) => {
+                self.record_read_latency(start_time.elapsed()).await;
+                return Err(e);
+            }
+        };
 
-            // 异步更新元数据
-            self.update_metadata_async(key, metadata).await;
+        let mut first_error = None;
+        for ((index, key), read) in pending.into_iter().zip(reads) {
+            match self.finish_read(&key, read).await {
+                Ok(value) => results[index] = value.map(|(value, flags, _)| (value, flags)),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        self.record_read_latency(start_time.elapsed()).await;
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(results),
+        }
+    }
 
-            self.record_hit().await;
+    /// 在后台线程中读取并校验单个条目，用 `transform` 处理载荷（载荷, 压缩算法）
+    fn read_entry<T>(
+        db: &MelangeAdapter,
+        layout: &KeyLayout,
+        codec_chain: &CodecChain,
+        key: &str,
+        now: u64,
+        transform: impl FnOnce(Bytes, CompressionAlgorithm) -> CacheResult<T>,
+    ) -> CacheResult<StoredRead<T>> {
+        // 构造数据键
+        let data_key = layout.data_key(key);
+        let metadata_key = layout.metadata_key(key);
+
+        // 读取数据和元数据
+        let data = db.get(&data_key)?;
+        let metadata_bytes = db.get(&metadata_key)?;
+
+        match (data, metadata_bytes) {
+            (Some(data), Some(metadata_bytes)) => {
+                // 反序列化元数据
+                let metadata = Self::decode_metadata(&metadata_bytes)?;
+                if metadata.expires_at != 0 && metadata.expires_at <= now {
+                    return Ok(StoredRead::Expired);
+                }
+                Self::verify_checksum(key, &data, metadata.checksum)?;
 
-            rat_logger::debug!("[L2] L2 缓存命中: {}", key);
+                // 按写入时的编解码链逆序解码，再交给调用方解压缩
+                let decoded = if metadata.codec_chain.is_empty() {
+                    Bytes::from(data)
+                } else {
+                    Bytes::from(codec_chain.decode(&data, &metadata.codec_chain)?)
+                };
+                let value = transform(decoded, metadata.compression_algorithm)?;
 
-            self.record_read_latency(start_time.elapsed()).await;
-            Ok(Some(data))
-        } else {
-            self.record_miss().await;
+                Ok(StoredRead::Found(value, metadata.flags, metadata.cas, Some(metadata)))
+            }
+            (Some(data), None) => {
+                // 紧凑存储：元数据内联在数据头部，不做访问跟踪
+                let (metadata, payload) = Self::decode_compact_value(&data)?;
+                Self::verify_checksum(key, payload, metadata.checksum)?;
+                let header_len = data.len() - payload.len();
+                let value = transform(Bytes::from(data).slice(header_len..), metadata.compression_algorithm)?;
+
+                Ok(StoredRead::Found(value, metadata.flags, metadata.cas, None))
+            }
+            _ => Ok(StoredRead::Missing),
+        }
+    }
 
-            rat_logger::debug!("[L2] L2 缓存未命中: {}", key);
+    /// 处理后台读取的结果：损坏时按配置删除，过期时删除，命中时更新访问统计
+    async fn finish_read<T>(&self, key: &str, result: CacheResult<StoredRead<T>>) -> CacheResult<Option<(T, u32, u64)>> {
+        let result = match result {
+            Err(e @ CacheError::Corruption { .. }) => {
+                rat_logger::warn!("[L2] {}", e);
+                if self.config.evict_corrupted_entries {
+                    self.delete_internal(key).await?;
+                }
+                return Err(e);
+            }
+            other => other?,
+        };
 
-            self.record_read_latency(start_time.elapsed()).await;
-            Ok(None)
+        match result {
+            StoredRead::Expired => {
+                rat_logger::debug!("[L2] 条目已按存储的过期时间失效: {}", key);
+                self.delete_internal(key).await?;
+                self.record_miss().await;
+                Ok(None)
+            }
+            StoredRead::Found(value, flags, cas, metadata) => {
+                if let Some(mut metadata) = metadata {
+                    let sampling = self.config.metadata_update_sampling;
+                    if sampling >= 1.0 || fastrand::f64() < sampling {
+                        // 更新访问统计，访问计数按采样率放大以保持统计无偏
+                        metadata.accessed_at = self.ttl_manager.clock().now();
+                        metadata.access_count += Self::sampled_access_increment(sampling);
+
+                        // 异步更新元数据
+                        self.update_metadata_async(key, metadata).await;
+                    } else {
+                        self.stats.write().await.skipped_metadata_updates += 1;
+                    }
+                }
+
+                self.record_hit().await;
+                rat_logger::debug!("[L2] L2 缓存命中: {}", key);
+                Ok(Some((value, flags, cas)))
+            }
+            StoredRead::Missing => {
+                self.record_miss().await;
+                rat_logger::debug!("[L2] L2 缓存未命中: {}", key);
+                Ok(None)
+            }
         }
     }
 
-    /// 设置缓存值（带 TTL）
-    pub async fn set_with_ttl(&self, key: &str, value: Bytes, ttl_seconds: u64) -> CacheResult<()> {
-        self.set(key.to_string(), value, Some(ttl_seconds)).await
+    /// 设置缓存值（标志为 0，测试用）
+    #[cfg(test)]
+    pub async fn set(&self, key: String, value: Bytes, ttl_seconds: Option<u64>) -> CacheResult<()> {
+        self.set_with_flags(key, value, ttl_seconds, 0).await
     }
 
-    /// 设置缓存值
-    pub async fn set(&self, key: String, value: Bytes, ttl_seconds: Option<u64>) -> CacheResult<()> {
+    /// 设置缓存值及其标志
+    pub async fn set_with_flags(&self, key: String, value: Bytes, ttl_seconds: Option<u64>, flags: u32) -> CacheResult<()> {
+        self.set_many_with_flags(vec![(key, value, ttl_seconds, flags)]).await
+    }
+
+    /// 设置缓存值及其标志，`compression` 覆盖全局压缩决定（见 [`Compressor::compress_with`]）
+    pub async fn set_with_compression(&self, key: String, value: Bytes, ttl_seconds: Option<u64>, flags: u32, compression: Option<bool>) -> CacheResult<()> {
+        self.write_batch(vec![(key, value, ttl_seconds, flags)], compression).await
+    }
+
+    /// 批量写入（键, 值, TTL, 标志），所有条目在一次后台任务中以一个批次写入
+    ///
+    /// 同一键出现多次时以最后一次为准
+    pub async fn set_many_with_flags(&self, entries: Vec<(String, Bytes, Option<u64>, u32)>) -> CacheResult<()> {
+        self.write_batch(entries, None).await
+    }
+
+    async fn write_batch(&self, entries: Vec<(String, Bytes, Option<u64>, u32)>, compression: Option<bool>) -> CacheResult<()> {
         let start_time = Instant::now();
 
+        let mut seen = HashSet::with_capacity(entries.len());
+        let mut entries: Vec<_> = entries.into_iter().rev().filter(|(key, ..)| seen.insert(key.clone())).collect();
+        entries.reverse();
+        if entries.is_empty() {
+            return Ok(());
+        }
+
         // 检查磁盘空间
-        self.check_disk_space(value.len()).await?;
+        self.check_disk_space(entries.iter().map(|(_, value, ..)| value.len()).sum()).await?;
+
+        let prepared = entries.into_iter()
+            .map(|(key, value, ttl_seconds, flags)| self.prepare_write(key, value, ttl_seconds, flags, compression))
+            .collect::<CacheResult<Vec<_>>>()?;
+        let prepared = Arc::new(prepared);
+
+        let db = Arc::clone(&self.db);
+        let layout = self.layout.clone();
+        let writes = Arc::clone(&prepared);
+
+        // 写入前标记非空，保证写入完成后的读取不会被跳过
+        self.known_empty.store(false, Ordering::Release);
+
+        // 在后台线程中执行 I/O 操作
+        let old_entries = self.run_storage_op(move || -> CacheResult<Vec<Option<EntrySize>>> {
+            let mut old_entries = Vec::with_capacity(writes.len());
+            let mut operations = Vec::with_capacity(writes.len() * 2);
+            for write in writes.iter() {
+                // 读取旧条目的大小（用于增量维护统计）
+                let old_entry = Self::read_entry_size(&db, &write.key, &layout)?;
+                operations.push(MelangeAdapter::insert_op(&layout.data_key(&write.key), &write.data));
+                match &write.metadata_bytes {
+                    Some(metadata_bytes) => {
+                        operations.push(MelangeAdapter::insert_op(&layout.metadata_key(&write.key), metadata_bytes));
+                    }
+                    // 单键写入，旧条目来自双键模式时清理其元数据键
+                    None if old_entry.is_some_and(|entry| entry.has_metadata_key) => {
+                        operations.push(MelangeAdapter::delete_op(&layout.metadata_key(&write.key)));
+                    }
+                    None => {}
+                }
+                old_entries.push(old_entry);
+            }
+
+            db.batch_write(operations)?;
+            Ok(old_entries)
+        }).await?;
+
+        for (write, old_entry) in prepared.iter().zip(old_entries) {
+            // 设置 TTL
+            if write.ttl_seconds.is_some() {
+                self.ttl_manager.add_key(write.key.clone(), write.ttl_seconds).await?;
+            }
 
+            // 更新统计
+            self.record_write().await;
+            self.disk_usage.fetch_add(write.data.len() as u64, Ordering::Relaxed);
+            match old_entry {
+                Some(old_entry) => self.sub_entry_size(old_entry, false),
+                None => {
+                    self.entry_count.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            self.logical_bytes.fetch_add(write.original_size as u64, Ordering::Relaxed);
+            self.record_compression(write.algorithm, write.original_size, write.compressed_size).await;
+
+            rat_logger::debug!("[L2] L2 缓存设置: {} ({}压缩)",
+                write.key, if write.is_compressed { "已" } else { "未" });
+        }
+
+        self.record_write_latency(start_time.elapsed()).await;
+        Ok(())
+    }
+
+    /// 压缩、编码并生成元数据，得到待写入的数据
+    fn prepare_write(&self, key: String, value: Bytes, ttl_seconds: Option<u64>, flags: u32, compression: Option<bool>) -> CacheResult<PreparedWrite> {
         // 压缩数据
-        let compression_result = self.compressor.compress(&value)?;
+        let compression_result = self.compressor.compress_with(&value, compression)?;
+
+        // 应用编解码链
+        let encoded = if self.codec_chain.is_empty() {
+            compression_result.compressed_data.clone()
+        } else {
+            Bytes::from(self.codec_chain.encode(&compression_result.compressed_data)?)
+        };
 
         // 创建元数据
+        let now = self.ttl_manager.clock().now();
         let metadata = StoredMetadata {
-            created_at: crate::types::current_timestamp(),
-            accessed_at: crate::types::current_timestamp(),
-            expires_at: if let Some(ttl) = ttl_seconds {
-                crate::types::current_timestamp() + ttl
-            } else {
-                0
+            created_at: now,
+            accessed_at: now,
+            // TTL 为 0 与 TTL 管理器一致，表示永不过期
+            expires_at: match ttl_seconds {
+                Some(ttl) if ttl > 0 => now + ttl,
+                _ => 0,
             },
             access_count: 1,
             original_size: value.len(),
             is_compressed: compression_result.is_compressed,
-            data_size: compression_result.compressed_data.len(),
+            data_size: encoded.len(),
+            codec_chain: self.codec_chain.descriptor(),
+            checksum: self.config.checksum_enabled.then(|| crc32fast::hash(&encoded)),
+            flags,
+            compression_algorithm: compression_result.algorithm,
+            cas: self.last_cas.fetch_add(1, Ordering::Relaxed) + 1,
         };
 
-        let db = Arc::clone(&self.db);
-        let key_clone = key.clone();
-        let data = compression_result.compressed_data.clone();
-
-        // 在后台线程中执行 I/O 操作
-        task::spawn_blocking(move || -> CacheResult<()> {
+        // 紧凑存储仅用于无 TTL、无标志且未配置编解码链的条目，其余条目保留独立的元数据键
+        let compact = self.config.compact_storage && ttl_seconds.is_none() && flags == 0 && self.codec_chain.is_empty();
+        let (data, metadata_bytes) = if compact {
+            (Bytes::from(Self::encode_compact_value(&metadata, &encoded)), None)
+        } else {
             // 序列化元数据
             let metadata_bytes = encode_to_vec(&metadata, bincode::config::standard())
-                .map_err(|e| CacheError::serialization_error(&format!("序列化元数据失败: {}", e)))?;
-
-            // 使用批量写入
-            let operations = vec![
-                MelangeAdapter::insert_op(&Self::make_data_key(&key_clone), &data),
-                MelangeAdapter::insert_op(&Self::make_metadata_key(&key_clone), &metadata_bytes),
-            ];
-
-            db.batch_write(operations)?;
-            Ok(())
-        }).await
-        .map_err(|e| CacheError::io_error(&format!("后台任务执行失败: {}", e)))??;
-
-        // 设置 TTL
-        if ttl_seconds.is_some() {
-            self.ttl_manager.add_key(key.clone(), ttl_seconds).await?;
-        }
-
-        // 更新统计
-        self.record_write().await;
-        self.disk_usage.fetch_add(compression_result.compressed_data.len() as u64, Ordering::Relaxed);
-
-        // 记录指标
-
-        if compression_result.is_compressed {
-            // 压缩统计已移除
-        }
+                .map_err(|e| CacheError::serialization_error(format!("序列化元数据失败: {}", e)))?;
+            (encoded, Some(metadata_bytes))
+        };
 
-        rat_logger::debug!("[L2] L2 缓存设置: {} ({}压缩)",
-            key, if compression_result.is_compressed { "已" } else { "未" });
+        Ok(PreparedWrite {
+            key,
+            data,
+            metadata_bytes,
+            ttl_seconds,
+            original_size: compression_result.original_size,
+            compressed_size: compression_result.compressed_size,
+            is_compressed: compression_result.is_compressed,
+            algorithm: compression_result.algorithm,
+        })
+    }
 
-        self.record_write_latency(start_time.elapsed()).await;
-        Ok(())
+    /// 同步刷新并 fsync 之前的所有写入，绕过智能 flush 的批处理
+    pub async fn sync(&self) -> CacheResult<()> {
+        let db = Arc::clone(&self.db);
+        self.run_blocking(move || db.flush()).await?
     }
 
     /// 删除缓存值
@@ -354,24 +1294,53 @@ impl L2Cache {
         Ok(deleted)
     }
 
+    /// 更新条目元数据中记录的过期时间（TTL 为 0 表示永不过期），返回条目是否有独立的元数据键
+    ///
+    /// 紧凑存储的条目不记录过期时间，只由 TTL 索引判断过期
+    pub async fn set_expiry(&self, key: &str, ttl_seconds: u64) -> CacheResult<bool> {
+        let db = Arc::clone(&self.db);
+        let metadata_key = self.layout.metadata_key(key);
+        let expires_at = match ttl_seconds {
+            0 => 0,
+            ttl => self.ttl_manager.clock().now() + ttl,
+        };
+
+        self.run_blocking(move || -> CacheResult<bool> {
+            let Some(metadata_bytes) = db.get(&metadata_key)? else {
+                return Ok(false);
+            };
+            let mut metadata = Self::decode_metadata(&metadata_bytes)?;
+            metadata.expires_at = expires_at;
+            let metadata_bytes = encode_to_vec(&metadata, bincode::config::standard())
+                .map_err(|e| CacheError::serialization_error(format!("序列化元数据失败: {}", e)))?;
+            db.put(&metadata_key, &metadata_bytes)?;
+            Ok(true)
+        }).await?
+    }
+
     /// 清空缓存
     pub async fn clear(&self) -> CacheResult<()> {
         let _start_time = Instant::now();
 
         let db = Arc::clone(&self.db);
+        let namespace = Arc::clone(&self.layout.namespace);
 
-        // 在后台线程中执行清空操作
+        // 在后台线程中执行清空操作，设置了 key_prefix 时只删除本命名空间的键
         task::spawn_blocking(move || -> CacheResult<()> {
-            db.clear()?;
+            if namespace.is_empty() {
+                db.clear()?;
+            } else {
+                db.delete_prefix(&namespace)?;
+            }
             Ok(())
         }).await
         .map_err(|e| CacheError::io_error(&format!("后台任务执行失败: {}", e)))??;
 
         // 重置统计
         self.disk_usage.store(0, Ordering::Relaxed);
-        let mut stats = self.stats.write().await;
-        stats.entry_count = 0;
-        drop(stats);
+        self.entry_count.store(0, Ordering::Relaxed);
+        self.logical_bytes.store(0, Ordering::Relaxed);
+        self.known_empty.store(true, Ordering::Release);
 
 
         rat_logger::debug!("[L2] L2 缓存已清空");
@@ -379,11 +1348,17 @@ impl L2Cache {
         Ok(())
     }
 
-    /// 压缩数据库（MelangeDB 版本）
+    /// 压缩数据库（MelangeDB 版本），逐个分区清理过期条目和孤立元数据
     pub async fn compact(&self) -> CacheResult<()> {
         let start_time = Instant::now();
 
-        // MelangeDB 可能不需要手动压缩，但我们仍然记录统计
+        let mut removed = 0;
+        for partition in 0..self.config.l2_partitions {
+            removed += self.compact_partition(partition).await?;
+            // 分区之间让出执行权，避免长时间占用运行时
+            task::yield_now().await;
+        }
+
         let mut stats = self.stats.write().await;
         stats.compactions += 1;
         drop(stats);
@@ -391,61 +1366,418 @@ impl L2Cache {
         // 重新计算磁盘使用量
         self.update_disk_usage_estimate().await;
 
-        rat_logger::debug!("[L2] L2 缓存压缩完成，耗时: {:.2}ms",
-            start_time.elapsed().as_millis());
+        rat_logger::debug!("[L2] L2 缓存压缩完成，清理 {} 个条目，耗时: {:.2}ms",
+            removed, start_time.elapsed().as_millis());
 
         Ok(())
     }
 
+    /// 压缩单个分区，返回清理的条目数（不影响其他分区的数据）
+    pub async fn compact_partition(&self, partition: usize) -> CacheResult<usize> {
+        let layout = self.layout.clone();
+        if partition >= layout.partitions {
+            return Err(CacheError::config_error(format!("分区 {} 超出范围（共 {} 个分区）", partition, layout.partitions)));
+        }
+
+        let db = Arc::clone(&self.db);
+        let now = self.ttl_manager.clock().now();
+
+        // 返回过期条目的原始键及其大小，以及清理的孤立元数据数
+        let (expired, orphans) = task::spawn_blocking(move || -> CacheResult<(Vec<(String, EntrySize)>, usize)> {
+            let metadata_prefix = layout.partition_prefix(key_prefixes::METADATA, partition);
+
+            let mut expired = Vec::new();
+            let mut orphans = 0;
+            let mut operations = Vec::new();
+            for (metadata_key, metadata_bytes) in db.prefix_iter(&metadata_prefix)? {
+                let user_key = layout.user_key(&metadata_key, key_prefixes::METADATA);
+                let mut data_key = layout.partition_prefix(key_prefixes::DATA, partition);
+                data_key.extend_from_slice(user_key);
+
+                let expired_size = Self::decode_metadata(&metadata_bytes).ok()
+                    .filter(|metadata| metadata.expires_at != 0 && metadata.expires_at <= now)
+                    .map(|metadata| EntrySize::from_metadata(&metadata));
+
+                if db.get(&data_key)?.is_none() {
+                    // 孤立元数据
+                    operations.push(MelangeAdapter::delete_op(&metadata_key));
+                    orphans += 1;
+                } else if let Some(size) = expired_size {
+                    operations.push(MelangeAdapter::delete_op(&data_key));
+                    operations.push(MelangeAdapter::delete_op(&metadata_key));
+                    expired.push((String::from_utf8_lossy(user_key).to_string(), size));
+                }
+            }
+
+            if !operations.is_empty() {
+                db.batch_write(operations)?;
+            }
+            Ok((expired, orphans))
+        }).await
+        .map_err(|e| CacheError::io_error(format!("后台任务执行失败: {}", e)))??;
+
+        for (key, size) in &expired {
+            self.sub_entry_size(*size, true);
+            self.ttl_manager.remove_key(key).await;
+        }
+
+        Ok(expired.len() + orphans)
+    }
+
     /// 获取统计信息
     pub async fn get_stats(&self) -> L2CacheStats {
         let mut stats = self.stats.read().await.clone();
         stats.estimated_disk_usage = self.disk_usage.load(Ordering::Relaxed);
+        stats.entry_count = self.entry_count.load(Ordering::Relaxed);
+        stats.logical_bytes = self.logical_bytes.load(Ordering::Relaxed);
+        if let Some(limiter) = &self.write_limiter {
+            limiter.fill_stats(&mut stats);
+        }
+        stats.spawn_blocking_queue_wait = self.queue_wait.snapshot();
+        stats.retries = self.retries.load(Ordering::Relaxed);
+        stats.retries_exhausted = self.retries_exhausted.load(Ordering::Relaxed);
         stats
     }
 
+    /// 在后台线程池中执行读写任务，瞬时故障（见 [`CacheError::is_transient`]）按
+    /// `l2_max_retries` 和 `l2_retry_backoff_ms` 指数退避重试，其他错误直接返回
+    async fn run_storage_op<R, F>(&self, op: F) -> CacheResult<R>
+    where
+        F: Fn() -> CacheResult<R> + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        let op = Arc::new(op);
+        let mut attempt = 0;
+        loop {
+            #[cfg(test)]
+            let injected = self.injected_failures.lock().pop_front();
+            #[cfg(not(test))]
+            let injected: Option<CacheError> = None;
+
+            let result = match injected {
+                Some(e) => Err(e),
+                None => {
+                    let op = Arc::clone(&op);
+                    self.run_blocking(move || op()).await.and_then(|result| result)
+                }
+            };
+            match result {
+                Err(e) if e.is_transient() && attempt < self.config.l2_max_retries => {
+                    let backoff_ms = self.config.l2_retry_backoff_ms.saturating_mul(1 << attempt.min(16));
+                    attempt += 1;
+                    self.retries.fetch_add(1, Ordering::Relaxed);
+                    rat_logger::warn!("[L2] 存储操作失败，{}ms 后第 {} 次重试: {}", backoff_ms, attempt, e);
+                    tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                }
+                Err(e) => {
+                    if attempt > 0 && e.is_transient() {
+                        self.retries_exhausted.fetch_add(1, Ordering::Relaxed);
+                    }
+                    return Err(e);
+                }
+                ok => return ok,
+            }
+        }
+    }
+
+    /// 让接下来的读写依次返回给定的错误（测试用）
+    #[cfg(test)]
+    pub(crate) fn inject_failures(&self, errors: impl IntoIterator<Item = CacheError>) {
+        self.injected_failures.lock().extend(errors);
+    }
+
+    /// 在后台线程池中执行读写任务，记录任务从提交到开始执行的排队时间
+    ///
+    /// 配置了 `blocking_queue_max_wait_ms` 时，超时仍未开始执行的任务被取消并返回 `CacheError::Busy`；
+    /// 已开始执行的任务总会等待其完成
+    async fn run_blocking<R, F>(&self, f: F) -> CacheResult<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let queued_at = Instant::now();
+        let state = Arc::new(AtomicU8::new(BLOCKING_TASK_PENDING));
+        let task_state = Arc::clone(&state);
+        let metrics = Arc::clone(&self.queue_wait);
+        let warn_threshold = Duration::from_millis(self.config.blocking_queue_warn_ms);
+
+        let mut handle = task::spawn_blocking(move || {
+            if task_state
+                .compare_exchange(BLOCKING_TASK_PENDING, BLOCKING_TASK_RUNNING, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+            {
+                return None;
+            }
+            metrics.record(queued_at.elapsed(), warn_threshold);
+            Some(f())
+        });
+
+        let joined = match self.config.blocking_queue_max_wait_ms {
+            Some(max_wait_ms) => match tokio::time::timeout(Duration::from_millis(max_wait_ms), &mut handle).await {
+                Ok(joined) => joined,
+                Err(_) if state
+                    .compare_exchange(BLOCKING_TASK_PENDING, BLOCKING_TASK_CANCELLED, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok() =>
+                {
+                    self.queue_wait.busy_rejections.fetch_add(1, Ordering::Relaxed);
+                    rat_logger::warn!("[L2] 后台线程池排队超过 {}ms，拒绝本次操作", max_wait_ms);
+                    return Err(CacheError::busy(max_wait_ms));
+                }
+                // 任务已开始执行，等待其完成
+                Err(_) => handle.await,
+            },
+            None => handle.await,
+        };
+
+        joined
+            .map_err(|e| CacheError::io_error(format!("后台任务执行失败: {}", e)))?
+            .ok_or_else(|| CacheError::busy(queued_at.elapsed().as_millis() as u64))
+    }
+
+    /// 申请写入带宽预算，超出 `l2_max_write_bytes_per_sec` 时返回 false，
+    /// 调用方应放弃本次 L2 写入；`force` 为 true 时总是放行（如持久写入）
+    pub fn try_reserve_write(&self, bytes: usize, force: bool) -> bool {
+        match &self.write_limiter {
+            Some(limiter) => limiter.try_acquire(bytes, force),
+            None => true,
+        }
+    }
+
     /// 检查是否包含键
     pub async fn contains_key(&self, key: &str) -> CacheResult<bool> {
         let db = Arc::clone(&self.db);
         let key_str = key.to_string();
+        let layout = self.layout.clone();
+
+        let exists = self.run_storage_op(move || -> CacheResult<bool> {
+            let data_key = layout.data_key(&key_str);
+            let result = db.get(&data_key)?;
+            Ok(result.is_some())
+        }).await?;
+
+        Ok(exists)
+    }
+
+    /// 获取所有键
+    pub async fn keys(&self) -> CacheResult<Vec<String>> {
+        let db = Arc::clone(&self.db);
+        let layout = self.layout.clone();
+
+        let keys = task::spawn_blocking(move || -> CacheResult<Vec<String>> {
+            let data_prefix = layout.type_prefix(key_prefixes::DATA);
+            let results = db.prefix_iter(&data_prefix)?;
+
+            let mut keys = Vec::new();
+            for (key, _) in results {
+                if key.starts_with(&data_prefix) {
+                    let original_key = String::from_utf8_lossy(layout.user_key(&key, key_prefixes::DATA)).to_string();
+                    keys.push(original_key);
+                }
+            }
+
+            Ok(keys)
+        }).await
+        .map_err(|e| CacheError::io_error(&format!("后台任务执行失败: {}", e)))??;
+
+        Ok(keys)
+    }
+
+    /// 按前缀扫描单个分区中以 `prefix` 开头的键
+    pub async fn keys_with_prefix(&self, prefix: &str, partition: usize) -> CacheResult<Vec<String>> {
+        let layout = self.layout.clone();
+        if partition >= layout.partitions {
+            return Err(CacheError::config_error(format!("分区 {} 超出范围（共 {} 个分区）", partition, layout.partitions)));
+        }
+
+        let db = Arc::clone(&self.db);
+        let prefix = prefix.to_string();
+        task::spawn_blocking(move || -> CacheResult<Vec<String>> {
+            let mut scan_prefix = layout.partition_prefix(key_prefixes::DATA, partition);
+            scan_prefix.extend_from_slice(prefix.as_bytes());
+
+            let keys = db.prefix_iter(&scan_prefix)?
+                .into_iter()
+                .map(|(key, _)| String::from_utf8_lossy(layout.user_key(&key, key_prefixes::DATA)).to_string())
+                .collect();
+            Ok(keys)
+        }).await
+        .map_err(|e| CacheError::io_error(format!("后台任务执行失败: {}", e)))?
+    }
+
+    /// L2 分区数量
+    pub fn partition_count(&self) -> usize {
+        self.layout.partitions
+    }
+
+    /// 扫描元数据（最多 [`HOT_KEYS_SCAN_LIMIT`] 条），按访问次数取前 N 个未过期的键
+    pub async fn top_accessed_keys(&self, top_n: usize) -> CacheResult<Vec<(String, u64)>> {
+        if top_n == 0 {
+            return Ok(Vec::new());
+        }
+        let db = Arc::clone(&self.db);
+        let layout = self.layout.clone();
+        let now = self.ttl_manager.clock().now();
+
+        task::spawn_blocking(move || -> CacheResult<Vec<(String, u64)>> {
+            let mut heap = BinaryHeap::with_capacity(top_n + 1);
+            for (metadata_key, metadata_bytes) in db.prefix_iter_limited(&layout.type_prefix(key_prefixes::METADATA), HOT_KEYS_SCAN_LIMIT)? {
+                let Ok(metadata) = Self::decode_metadata(&metadata_bytes) else {
+                    continue;
+                };
+                if metadata.expires_at != 0 && metadata.expires_at <= now {
+                    continue;
+                }
+                let count = metadata.access_count;
+                if heap.len() == top_n && heap.peek().is_some_and(|Reverse((min, _))| count <= *min) {
+                    continue;
+                }
+                let key = String::from_utf8_lossy(layout.user_key(&metadata_key, key_prefixes::METADATA)).to_string();
+                heap.push(Reverse((count, key)));
+                if heap.len() > top_n {
+                    heap.pop();
+                }
+            }
+            Ok(heap.into_sorted_vec().into_iter().map(|Reverse((count, key))| (key, count)).collect())
+        }).await
+        .map_err(|e| CacheError::io_error(format!("后台任务执行失败: {}", e)))?
+    }
+
+    /// 批量读取键在 L2 中的访问次数（不存在的键为 0）
+    pub async fn access_counts(&self, keys: Vec<String>) -> CacheResult<Vec<u64>> {
+        let db = Arc::clone(&self.db);
+        let layout = self.layout.clone();
+
+        task::spawn_blocking(move || -> CacheResult<Vec<u64>> {
+            keys.iter()
+                .map(|key| {
+                    let metadata_bytes = db.get(&layout.metadata_key(key))?;
+                    Ok(metadata_bytes
+                        .and_then(|bytes| Self::decode_metadata(&bytes).ok())
+                        .map_or(0, |metadata| metadata.access_count))
+                })
+                .collect()
+        }).await
+        .map_err(|e| CacheError::io_error(format!("后台任务执行失败: {}", e)))?
+    }
+
+    /// 将 TTL 索引保存到数据目录，供下次启动直接加载（需开启 `persist_ttl_index`，应在关闭时调用）
+    pub async fn save_ttl_index(&self) -> CacheResult<()> {
+        if !self.config.persist_ttl_index {
+            return Ok(());
+        }
+
+        let db = Arc::clone(&self.db);
+        let generation_key = self.layout.generation_key();
+        let path = self.ttl_index_path();
+        let index = TtlIndexFile {
+            generation: self.generation,
+            entries: self.ttl_manager.export_expire_times(),
+        };
+
+        let saved = task::spawn_blocking(move || -> CacheResult<usize> {
+            // 清空数据库会删除代数记录，先写回并落盘，保证索引文件不比数据新
+            db.put(&generation_key, &index.generation.to_le_bytes())?;
+            db.flush()?;
+
+            let bytes = encode_to_vec(&index, bincode::config::standard())
+                .map_err(|e| CacheError::serialization_error(format!("序列化 TTL 索引失败: {}", e)))?;
+            // 先写临时文件再重命名，避免留下不完整的索引文件
+            let temp_path = path.with_extension("tmp");
+            std::fs::write(&temp_path, bytes)?;
+            std::fs::rename(&temp_path, &path)?;
+            Ok(index.entries.len())
+        }).await
+        .map_err(|e| CacheError::io_error(format!("后台任务执行失败: {}", e)))??;
+
+        rat_logger::debug!("[L2] TTL 索引已保存: {} 个键", saved);
+        Ok(())
+    }
+
+    /// 重建 TTL 索引：优先加载代数匹配的索引文件，否则扫描全部元数据
+    async fn restore_ttl_index(&self, previous_generation: u64) {
+        let path = self.ttl_index_path();
+        let sidecar = if self.config.persist_ttl_index {
+            Self::read_ttl_index_file(&path, previous_generation)
+        } else {
+            None
+        };
+        // 索引文件只使用一次，之后的异常退出会回退为扫描元数据
+        let _ = std::fs::remove_file(&path);
+
+        let (source, entries) = match sidecar {
+            Some(entries) => (TtlIndexSource::Sidecar, entries),
+            None => match self.scan_expire_times().await {
+                Ok(entries) => (TtlIndexSource::MetadataScan, entries),
+                Err(e) => {
+                    rat_logger::warn!("[L2] 扫描元数据重建 TTL 索引失败: {}", e);
+                    return;
+                }
+            },
+        };
 
-        let exists = task::spawn_blocking(move || -> CacheResult<bool> {
-            let data_key = Self::make_data_key(&key_str);
-            let result = db.get(&data_key)?;
-            Ok(result.is_some())
-        }).await
-        .map_err(|e| CacheError::io_error(&format!("后台任务执行失败: {}", e)))??;
+        let restored = self.ttl_manager.restore_expire_times(entries);
+        rat_logger::debug!("[L2] TTL 索引已重建（{:?}），恢复 {} 个键", source, restored);
+        self.stats.write().await.ttl_index_source = source;
+    }
 
-        Ok(exists)
+    /// 读取索引文件，文件不存在、损坏或代数不匹配时返回 None
+    fn read_ttl_index_file(path: &std::path::Path, expected_generation: u64) -> Option<Vec<(String, u64)>> {
+        let bytes = std::fs::read(path).ok()?;
+        match decode_from_slice::<TtlIndexFile, _>(&bytes, bincode::config::standard()) {
+            Ok((index, _)) if index.generation == expected_generation => Some(index.entries),
+            Ok((index, _)) => {
+                rat_logger::warn!("[L2] TTL 索引文件已失效（代数 {}，期望 {}），改为扫描元数据",
+                    index.generation, expected_generation);
+                None
+            }
+            Err(e) => {
+                rat_logger::warn!("[L2] TTL 索引文件损坏，改为扫描元数据: {}", e);
+                None
+            }
+        }
     }
 
-    /// 获取所有键
-    pub async fn keys(&self) -> CacheResult<Vec<String>> {
+    /// 扫描全部元数据，收集未过期键的过期时间
+    async fn scan_expire_times(&self) -> CacheResult<Vec<(String, u64)>> {
         let db = Arc::clone(&self.db);
-
-        let keys = task::spawn_blocking(move || -> CacheResult<Vec<String>> {
-            let data_prefix = key_prefixes::DATA;
-            let results = db.prefix_iter(data_prefix)?;
-
-            let mut keys = Vec::new();
-            for (key, _) in results {
-                if key.starts_with(data_prefix) {
-                    let original_key = String::from_utf8_lossy(&key[data_prefix.len()..]).to_string();
-                    keys.push(original_key);
+        let layout = self.layout.clone();
+        let now = self.ttl_manager.clock().now();
+
+        task::spawn_blocking(move || -> CacheResult<Vec<(String, u64)>> {
+            let mut entries = Vec::new();
+            for (metadata_key, metadata_bytes) in db.prefix_iter(&layout.type_prefix(key_prefixes::METADATA))? {
+                let Ok(metadata) = Self::decode_metadata(&metadata_bytes) else {
+                    continue;
+                };
+                if metadata.expires_at > now {
+                    let key = String::from_utf8_lossy(layout.user_key(&metadata_key, key_prefixes::METADATA)).to_string();
+                    entries.push((key, metadata.expires_at));
                 }
             }
-
-            Ok(keys)
+            Ok(entries)
         }).await
-        .map_err(|e| CacheError::io_error(&format!("后台任务执行失败: {}", e)))??;
+        .map_err(|e| CacheError::io_error(format!("后台任务执行失败: {}", e)))?
+    }
 
-        Ok(keys)
+    /// TTL 索引文件路径，设置了 `key_prefix` 时按命名空间区分
+    fn ttl_index_path(&self) -> PathBuf {
+        if self.layout.namespace.is_empty() {
+            self.data_dir.join(TTL_INDEX_FILE)
+        } else {
+            self.data_dir.join(format!("ttl_index_{:016x}.bin", fxhash::hash64(&self.layout.namespace[..])))
+        }
+    }
+
+    /// 读取数据库记录的 L2 代数，没有记录时为 0
+    fn read_generation(db: &MelangeAdapter, layout: &KeyLayout) -> CacheResult<u64> {
+        Ok(db.get(&layout.generation_key())?
+            .and_then(|bytes| bytes.try_into().ok())
+            .map_or(0, u64::from_le_bytes))
     }
 
     /// 获取缓存大小
     pub async fn len(&self) -> CacheResult<usize> {
-        let stats = self.stats.read().await;
-        Ok(stats.entry_count as usize)
+        Ok(self.entry_count.load(Ordering::Relaxed) as usize)
     }
 
     /// 检查缓存是否为空
@@ -458,15 +1790,18 @@ impl L2Cache {
     async fn delete_internal(&self, key: &str) -> CacheResult<bool> {
         let db = Arc::clone(&self.db);
         let key_str = key.to_string();
+        let layout = self.layout.clone();
 
-        let deleted = task::spawn_blocking(move || -> CacheResult<bool> {
-            let data_key = Self::make_data_key(&key_str);
-            let metadata_key = Self::make_metadata_key(&key_str);
+        let deleted = self.run_storage_op(move || -> CacheResult<Option<EntrySize>> {
+            let data_key = layout.data_key(&key_str);
+            let metadata_key = layout.metadata_key(&key_str);
 
             // 检查键是否存在
             let exists = db.get(&data_key)?;
 
             if exists.is_some() {
+                let entry = Self::read_entry_size(&db, &key_str, &layout)?;
+
                 // 删除数据和元数据
                 let operations = vec![
                     MelangeAdapter::delete_op(&data_key),
@@ -474,28 +1809,29 @@ impl L2Cache {
                 ];
 
                 db.batch_write(operations)?;
-                Ok(true)
+                Ok(Some(entry.unwrap_or_default()))
             } else {
-                Ok(false)
+                Ok(None)
             }
-        }).await
-        .map_err(|e| CacheError::io_error(&format!("后台任务执行失败: {}", e)))??;
+        }).await?;
 
-        if deleted {
+        if let Some(entry) = deleted {
+            self.sub_entry_size(entry, true);
             // 移除 TTL
             self.ttl_manager.remove_key(key).await;
         }
 
-        Ok(deleted)
+        Ok(deleted.is_some())
     }
 
     /// 异步更新元数据
     async fn update_metadata_async(&self, key: &str, metadata: StoredMetadata) {
         let db = Arc::clone(&self.db);
         let key_str = key.to_string();
+        let layout = self.layout.clone();
 
         let _ = task::spawn_blocking(move || -> CacheResult<()> {
-            let metadata_key = Self::make_metadata_key(&key_str);
+            let metadata_key = layout.metadata_key(&key_str);
             let metadata_bytes = encode_to_vec(&metadata, bincode::config::standard())
                 .map_err(|e| CacheError::serialization_error(&format!("序列化元数据失败: {}", e)))?;
 
@@ -514,51 +1850,218 @@ impl L2Cache {
         Ok(())
     }
 
-    /// 更新磁盘使用量估算
-    async fn update_disk_usage_estimate(&self) {
+    /// 全量扫描重新计算磁盘使用量、条目数和逻辑字节数，返回扫描到的条目数
+    ///
+    /// 开销与数据量成正比，只在启动和 [`compact`](Self::compact) 时执行，统计查询只读取增量维护的计数
+    async fn update_disk_usage_estimate(&self) -> Option<u64> {
+        self.stats.write().await.full_scans += 1;
         let db = Arc::clone(&self.db);
+        let data_prefix = self.layout.type_prefix(key_prefixes::DATA);
+        let metadata_prefix = self.layout.type_prefix(key_prefixes::METADATA);
 
-        let _ = task::spawn_blocking(move || -> CacheResult<(u64, u64)> {
-            let data_prefix = key_prefixes::DATA;
-            let results = db.prefix_iter(data_prefix)?;
+        task::spawn_blocking(move || -> CacheResult<(u64, u64, u64)> {
+            let results = db.prefix_iter(&data_prefix)?;
 
             let mut total_size = 0u64;
             let mut entry_count = 0u64;
 
-            for (_, value) in results {
+            // 从元数据累计逻辑字节数
+            let mut logical_size = 0u64;
+            let mut keys_with_metadata = HashSet::new();
+            for (metadata_key, metadata_bytes) in db.prefix_iter(&metadata_prefix)? {
+                if let Ok(metadata) = Self::decode_metadata(&metadata_bytes) {
+                    logical_size += metadata.original_size as u64;
+                }
+                keys_with_metadata.insert(metadata_key[metadata_prefix.len()..].to_vec());
+            }
+
+            for (data_key, value) in results {
                 total_size += value.len() as u64;
                 entry_count += 1;
+
+                // 紧凑存储条目没有独立元数据键，从内联头部读取
+                if keys_with_metadata.contains(&data_key[data_prefix.len()..]) {
+                    continue;
+                }
+                if let Ok((metadata, _)) = Self::decode_compact_value(&value) {
+                    logical_size += metadata.original_size as u64;
+                }
             }
 
-            Ok((total_size, entry_count))
+            Ok((total_size, entry_count, logical_size))
         }).await
-        .map(|result| {
-            if let Ok((size, count)) = result {
-                self.disk_usage.store(size, Ordering::Relaxed);
+        .ok()
+        .and_then(|result| result.ok())
+        .map(|(size, count, logical_size)| {
+            self.disk_usage.store(size, Ordering::Relaxed);
+            self.entry_count.store(count, Ordering::Relaxed);
+            self.logical_bytes.store(logical_size, Ordering::Relaxed);
+            count
+        })
+    }
 
-                let stats_clone = Arc::clone(&self.stats);
-                tokio::spawn(async move {
-                    let mut stats = stats_clone.write().await;
-                    stats.entry_count = count;
-                });
+    /// 读取条目的元数据（紧凑存储时从数据头部解码）
+    fn read_stored_metadata(db: &MelangeAdapter, key: &str, layout: &KeyLayout) -> CacheResult<Option<StoredMetadata>> {
+        if let Some(metadata_bytes) = db.get(&layout.metadata_key(key))? {
+            return Self::decode_metadata(&metadata_bytes).map(Some);
+        }
+        match db.get(&layout.data_key(key))? {
+            Some(value) => Ok(Some(Self::decode_compact_value(&value)?.0)),
+            None => Ok(None),
+        }
+    }
+
+    /// 读取条目元数据中记录的原始大小，以及条目是否有独立的元数据键
+    fn read_entry_size(db: &MelangeAdapter, key: &str, layout: &KeyLayout) -> CacheResult<Option<EntrySize>> {
+        if let Some(metadata_bytes) = db.get(&layout.metadata_key(key))? {
+            let metadata = Self::decode_metadata(&metadata_bytes)?;
+            return Ok(Some(EntrySize::from_metadata(&metadata)));
+        }
+
+        // 没有元数据键时按紧凑存储格式读取
+        match db.get(&layout.data_key(key))? {
+            Some(value) => Ok(Some(EntrySize {
+                original_size: Self::decode_compact_value(&value)?.0.original_size,
+                stored_size: value.len(),
+                has_metadata_key: false,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// 编码紧凑存储值（元数据头 + 数据）
+    fn encode_compact_value(metadata: &StoredMetadata, data: &[u8]) -> Vec<u8> {
+        let mut value = Vec::with_capacity(compact_header::LEN + compact_header::CHECKSUM_LEN + compact_header::CAS_LEN + data.len());
+        let mut flags = match metadata.compression_algorithm {
+            CompressionAlgorithm::None => 0,
+            CompressionAlgorithm::Snappy => compact_header::FLAG_COMPRESSED | compact_header::FLAG_SNAPPY,
+            _ => compact_header::FLAG_COMPRESSED,
+        };
+        if metadata.checksum.is_some() {
+            flags |= compact_header::FLAG_CHECKSUM;
+        }
+        if metadata.cas != 0 {
+            flags |= compact_header::FLAG_CAS;
+        }
+        value.push(flags);
+        value.extend_from_slice(&(metadata.original_size as u64).to_le_bytes());
+        value.extend_from_slice(&metadata.created_at.to_le_bytes());
+        if let Some(checksum) = metadata.checksum {
+            value.extend_from_slice(&checksum.to_le_bytes());
+        }
+        if metadata.cas != 0 {
+            value.extend_from_slice(&metadata.cas.to_le_bytes());
+        }
+        value.extend_from_slice(data);
+        value
+    }
+
+    /// 解码紧凑存储值，返回元数据和数据部分
+    fn decode_compact_value(value: &[u8]) -> CacheResult<(StoredMetadata, &[u8])> {
+        if value.len() < compact_header::LEN {
+            return Err(CacheError::serialization_error("紧凑存储头部长度不足"));
+        }
+
+        let flags = value[0];
+        let original_size = u64::from_le_bytes(value[1..9].try_into().unwrap()) as usize;
+        let created_at = u64::from_le_bytes(value[9..17].try_into().unwrap());
+        let (checksum, payload) = if flags & compact_header::FLAG_CHECKSUM != 0 {
+            let payload_start = compact_header::LEN + compact_header::CHECKSUM_LEN;
+            if value.len() < payload_start {
+                return Err(CacheError::serialization_error("紧凑存储校验和长度不足"));
             }
-        });
+            let checksum = u32::from_le_bytes(value[compact_header::LEN..payload_start].try_into().unwrap());
+            (Some(checksum), &value[payload_start..])
+        } else {
+            (None, &value[compact_header::LEN..])
+        };
+        let (cas, payload) = if flags & compact_header::FLAG_CAS != 0 {
+            if payload.len() < compact_header::CAS_LEN {
+                return Err(CacheError::serialization_error("紧凑存储 CAS 令牌长度不足"));
+            }
+            let (cas, payload) = payload.split_at(compact_header::CAS_LEN);
+            (u64::from_le_bytes(cas.try_into().unwrap()), payload)
+        } else {
+            (0, payload)
+        };
+
+        let metadata = StoredMetadata {
+            created_at,
+            accessed_at: created_at,
+            expires_at: 0,
+            access_count: 0,
+            original_size,
+            is_compressed: flags & compact_header::FLAG_COMPRESSED != 0,
+            data_size: payload.len(),
+            codec_chain: Vec::new(),
+            checksum,
+            flags: 0,
+            compression_algorithm: match (flags & compact_header::FLAG_COMPRESSED != 0, flags & compact_header::FLAG_SNAPPY != 0) {
+                (false, _) => CompressionAlgorithm::None,
+                (true, false) => CompressionAlgorithm::Lz4,
+                (true, true) => CompressionAlgorithm::Snappy,
+            },
+            cas,
+        };
+        Ok((metadata, payload))
     }
 
-    /// 构造数据键
-    fn make_data_key(key: &str) -> Vec<u8> {
-        let mut data_key = Vec::with_capacity(key_prefixes::DATA.len() + key.len());
-        data_key.extend_from_slice(key_prefixes::DATA);
-        data_key.extend_from_slice(key.as_bytes());
-        data_key
+    /// 采样写回时每次计入的访问次数（采样率的倒数）
+    fn sampled_access_increment(sampling: f64) -> u64 {
+        if sampling >= 1.0 {
+            1
+        } else {
+            ((1.0 / sampling).round() as u64).max(1)
+        }
     }
 
-    /// 构造元数据键
-    fn make_metadata_key(key: &str) -> Vec<u8> {
-        let mut metadata_key = Vec::with_capacity(key_prefixes::METADATA.len() + key.len());
-        metadata_key.extend_from_slice(key_prefixes::METADATA);
-        metadata_key.extend_from_slice(key.as_bytes());
-        metadata_key
+    /// 校验存储数据的校验和（未记录校验和时跳过）
+    fn verify_checksum(key: &str, data: &[u8], expected: Option<u32>) -> CacheResult<()> {
+        if let Some(expected) = expected {
+            let actual = crc32fast::hash(data);
+            if actual != expected {
+                return Err(CacheError::corruption(
+                    key,
+                    format!("校验和不匹配，期望: {:#010x}, 实际: {:#010x}", expected, actual),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 反序列化元数据，兼容之前版本写入的旧格式
+    fn decode_metadata(metadata_bytes: &[u8]) -> CacheResult<StoredMetadata> {
+        // 旧格式是新格式的前缀，按从新到旧的顺序尝试
+        match decode_from_slice::<StoredMetadata, _>(metadata_bytes, bincode::config::standard()) {
+            Ok((metadata, _)) => Ok(metadata),
+            Err(e) => decode_from_slice::<TokenlessStoredMetadata, _>(metadata_bytes, bincode::config::standard())
+                .map(|(tokenless, _)| tokenless.into())
+                .or_else(|_| decode_from_slice::<UntaggedStoredMetadata, _>(metadata_bytes, bincode::config::standard())
+                    .map(|(untagged, _)| untagged.into()))
+                .or_else(|_| decode_from_slice::<LegacyStoredMetadata, _>(metadata_bytes, bincode::config::standard())
+                    .map(|(legacy, _)| legacy.into()))
+                .map_err(|_| CacheError::serialization_error(format!("反序列化元数据失败: {}", e))),
+        }
+    }
+
+    /// 扣减逻辑字节数（不低于 0）
+    fn sub_logical_bytes(&self, size: u64) {
+        Self::saturating_sub(&self.logical_bytes, size);
+    }
+
+    /// 扣减被覆盖或移除的条目占用的统计，`removed` 为 true 时条目数同时减一
+    fn sub_entry_size(&self, entry: EntrySize, removed: bool) {
+        self.sub_logical_bytes(entry.original_size as u64);
+        Self::saturating_sub(&self.disk_usage, entry.stored_size as u64);
+        if removed {
+            Self::saturating_sub(&self.entry_count, 1);
+        }
+    }
+
+    fn saturating_sub(counter: &AtomicU64, value: u64) {
+        let _ = counter.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            Some(current.saturating_sub(value))
+        });
     }
 
     /// 记录命中
@@ -575,12 +2078,29 @@ impl L2Cache {
         stats.reads += 1;
     }
 
+    /// 记录因 L2 为空而跳过的读取
+    async fn record_skipped_read(&self) {
+        let mut stats = self.stats.write().await;
+        stats.skipped_reads += 1;
+        stats.misses += 1;
+        stats.reads += 1;
+    }
+
     /// 记录写入
     async fn record_write(&self) {
         let mut stats = self.stats.write().await;
         stats.writes += 1;
     }
 
+    /// 记录按算法分类的压缩统计
+    async fn record_compression(&self, algorithm: CompressionAlgorithm, original_size: usize, stored_size: usize) {
+        let mut stats = self.stats.write().await;
+        let entry = stats.compression_by_algorithm.entry(algorithm).or_default();
+        entry.entries += 1;
+        entry.original_bytes += original_size as u64;
+        entry.stored_bytes += stored_size as u64;
+    }
+
     /// 记录删除
     async fn record_delete(&self) {
         let mut stats = self.stats.write().await;
@@ -623,24 +2143,39 @@ impl L2CacheStats {
 
     /// 格式化统计信息
     pub fn format(&self) -> String {
+        let mut algorithms: Vec<_> = self.compression_by_algorithm.iter().collect();
+        algorithms.sort_by_key(|(algorithm, _)| format!("{:?}", algorithm));
+        let breakdown: String = algorithms
+            .iter()
+            .map(|(algorithm, stats)| format!(
+                "\n压缩算法 {:?}: {} 条 ({} -> {} bytes, 比率: {:.2})",
+                algorithm, stats.entries, stats.original_bytes, stats.stored_bytes, stats.compression_ratio()
+            ))
+            .collect();
+
         format!(
             "L2 缓存统计 (MelangeDB):\n\
              条目数: {}\n\
              磁盘使用: {} bytes\n\
-             读取: {} 次 (命中: {}, 未命中: {}, 命中率: {:.1}%)\n\
-             写入: {} 次\n\
+             逻辑数据量: {} bytes\n\
+             读取: {} 次 (命中: {}, 未命中: {}, 空跳过: {}, 命中率: {:.1}%)\n\
+             写入: {} 次 (限流: {} 次, 速率: {:.0} B/s)\n\
              删除: {} 次\n\
-             压缩: {} 次\n\
+             重试: {} 次 (耗尽: {} 次)\n\
+             压缩: {} 次 (全量扫描: {} 次)\n\
              平均读取延迟: {:.2}ms\n\
-             平均写入延迟: {:.2}ms",
+             平均写入延迟: {:.2}ms{}",
             self.entry_count,
             self.estimated_disk_usage,
-            self.reads, self.hits, self.misses, self.hit_rate() * 100.0,
-            self.writes,
+            self.logical_bytes,
+            self.reads, self.hits, self.misses, self.skipped_reads, self.hit_rate() * 100.0,
+            self.writes, self.throttled_writes, self.write_rate_bytes_per_sec,
             self.deletes,
-            self.compactions,
+            self.retries, self.retries_exhausted,
+            self.compactions, self.full_scans,
             self.avg_read_latency_ms,
-            self.avg_write_latency_ms
+            self.avg_write_latency_ms,
+            breakdown
         )
     }
 }
@@ -654,9 +2189,16 @@ mod tests {
         use tempfile::TempDir;
 
     async fn create_test_cache() -> (L2Cache, TempDir) {
+        create_test_cache_with(|_| {}, CodecChain::default()).await
+    }
+
+    async fn create_test_cache_with(
+        configure_l2: impl FnOnce(&mut L2Config),
+        codec_chain: CodecChain,
+    ) -> (L2Cache, TempDir) {
         let temp_dir = TempDir::new().unwrap();
 
-        let l2_config = L2Config {
+        let mut l2_config = L2Config {
             enable_l2_cache: true,
             data_dir: Some(temp_dir.path().to_path_buf()),
             max_disk_size: 10 * 1024 * 1024, // 10MB
@@ -665,6 +2207,7 @@ mod tests {
             block_cache_size: 512 * 1024,    // 512KB
             background_threads: 2,
             clear_on_startup: false,
+            confirm_clear_on_startup: false,
             enable_lz4: true,
             compression_threshold: 128,
             compression_max_threshold: 1024 * 1024,
@@ -682,8 +2225,28 @@ mod tests {
             l2_write_strategy: "write_through".to_string(),
             l2_write_threshold: 1024,
             l2_write_ttl_threshold: 300,
+            l2_write_eviction_rate_low: 1.0,
+            l2_write_eviction_rate_high: 100.0,
+            ttl_demotion_enabled: false,
+            ttl_demotion_ratio: 0.1,
+            ttl_demotion_grace_seconds: 60,
+            compact_storage: false,
+            skip_reads_when_empty: true,
+            checksum_enabled: true,
+            evict_corrupted_entries: true,
+            metadata_update_sampling: 1.0,
+            l2_partitions: 1,
+            degrade_to_l1_on_l2_failure: false,
+            l2_max_write_bytes_per_sec: None,
+            blocking_queue_warn_ms: 100,
+            blocking_queue_max_wait_ms: None,
+            persist_ttl_index: true,
+            value_compression_algorithm: CompressionAlgorithm::Lz4,
+            key_prefix: String::new(),
+            l2_max_retries: 2,
+            l2_retry_backoff_ms: 10,
         };
-
+        configure_l2(&mut l2_config);
 
         let ttl_config = TtlConfig {
             expire_seconds: Some(60),
@@ -691,16 +2254,356 @@ mod tests {
             max_cleanup_entries: 100,
             lazy_expiration: true,
             active_expiration: false, // 测试中禁用主动过期
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
         };
 
         let compressor = Compressor::new_from_l2_config(&l2_config);
         let ttl_manager = Arc::new(TtlManager::new(ttl_config).await.unwrap());
 
-        let cache = L2Cache::new(l2_config, compressor, ttl_manager).await.unwrap();
+        let cache = L2Cache::new(l2_config, compressor, Arc::new(codec_chain), ttl_manager).await.unwrap();
 
         (cache, temp_dir)
     }
 
+    #[tokio::test]
+    async fn test_clear_on_startup_requires_confirmation() {
+        let data_dir = TempDir::new().unwrap();
+        let mut captured = None;
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.data_dir = Some(data_dir.path().to_path_buf());
+            captured = Some(l2.clone());
+        }, CodecChain::default()).await;
+        cache.set("key".to_string(), Bytes::from("value"), None).await.unwrap();
+        cache.sync().await.unwrap();
+        drop(cache);
+
+        let marker = data_dir.path().join("marker");
+        std::fs::write(&marker, b"keep").unwrap();
+
+        let mut l2_config = captured.unwrap();
+        l2_config.clear_on_startup = true;
+        let ttl_config = TtlConfig {
+            expire_seconds: Some(60),
+            cleanup_interval: 60,
+            max_cleanup_entries: 100,
+            lazy_expiration: true,
+            active_expiration: false,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
+        };
+        let ttl_manager = Arc::new(TtlManager::new(ttl_config).await.unwrap());
+
+        // 未确认时拒绝启动且不删除数据
+        let result = L2Cache::new(
+            l2_config.clone(),
+            Compressor::new_from_l2_config(&l2_config),
+            Arc::new(CodecChain::default()),
+            ttl_manager.clone(),
+        ).await;
+        assert!(matches!(result, Err(CacheError::ConfigError { .. })));
+        assert!(marker.exists());
+
+        l2_config.confirm_clear_on_startup = true;
+        let cache = L2Cache::new(
+            l2_config.clone(),
+            Compressor::new_from_l2_config(&l2_config),
+            Arc::new(CodecChain::default()),
+            ttl_manager,
+        ).await.unwrap();
+        assert!(!marker.exists());
+        assert!(cache.get("key").await.unwrap().is_none());
+    }
+
+    #[cfg(feature = "snappy")]
+    #[tokio::test]
+    async fn test_snappy_cross_codec_reads() {
+        async fn reopen(l2_config: &L2Config) -> L2Cache {
+            let ttl_config = TtlConfig {
+                expire_seconds: Some(60),
+                cleanup_interval: 60,
+                max_cleanup_entries: 100,
+                lazy_expiration: true,
+                active_expiration: false,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
+            };
+            L2Cache::new(
+                l2_config.clone(),
+                Compressor::new_from_l2_config(l2_config),
+                Arc::new(CodecChain::default()),
+                Arc::new(TtlManager::new(ttl_config).await.unwrap()),
+            ).await.unwrap()
+        }
+
+        let data_dir = TempDir::new().unwrap();
+        let mut captured = None;
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.data_dir = Some(data_dir.path().to_path_buf());
+            captured = Some(l2.clone());
+        }, CodecChain::default()).await;
+        let mut l2_config = captured.unwrap();
+        let value = Bytes::from("mixed codec value ".repeat(50));
+
+        // 默认 LZ4 写入
+        cache.set("lz4".to_string(), value.clone(), None).await.unwrap();
+        cache.sync().await.unwrap();
+        drop(cache);
+
+        // 改为 Snappy 后重新打开，双键（带 TTL）和紧凑存储各写入一条
+        l2_config.value_compression_algorithm = CompressionAlgorithm::Snappy;
+        l2_config.compact_storage = true;
+        let cache = reopen(&l2_config).await;
+        cache.set("snappy".to_string(), value.clone(), Some(3600)).await.unwrap();
+        cache.set("snappy_compact".to_string(), value.clone(), None).await.unwrap();
+        assert_eq!(cache.get("lz4").await.unwrap(), Some(value.clone()));
+        assert_eq!(cache.get("snappy").await.unwrap(), Some(value.clone()));
+        assert_eq!(cache.get("snappy_compact").await.unwrap(), Some(value.clone()));
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.compression_by_algorithm[&CompressionAlgorithm::Snappy].entries, 2);
+        cache.sync().await.unwrap();
+        drop(cache);
+
+        // 切回 LZ4 后 Snappy 数据仍可读取（含流式读取）
+        l2_config.value_compression_algorithm = CompressionAlgorithm::Lz4;
+        let cache = reopen(&l2_config).await;
+        for key in ["lz4", "snappy", "snappy_compact"] {
+            assert_eq!(cache.get(key).await.unwrap(), Some(value.clone()), "{}", key);
+        }
+        let (mut stream, _) = cache.get_stream("snappy").await.unwrap().unwrap();
+        let mut streamed = Vec::new();
+        while let Some(chunk) = stream.next_chunk().await {
+            streamed.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(streamed, value);
+    }
+
+    #[tokio::test]
+    async fn test_key_prefix_isolation() {
+        let data_dir = TempDir::new().unwrap();
+        let open = |key_prefix: &'static str| {
+            let data_dir = data_dir.path().to_path_buf();
+            create_test_cache_with(move |l2| {
+                l2.data_dir = Some(data_dir);
+                l2.key_prefix = key_prefix.to_string();
+            }, CodecChain::default())
+        };
+
+        let (cache_a, _temp_a) = open("a/").await;
+        cache_a.set("shared".to_string(), Bytes::from("from a"), None).await.unwrap();
+        cache_a.set("only_a".to_string(), Bytes::from("a"), Some(3600)).await.unwrap();
+        cache_a.save_ttl_index().await.unwrap();
+        drop(cache_a);
+
+        // 同一目录下另一个前缀的实例看不到 a/ 的数据
+        let (cache_b, _temp_b) = open("b/").await;
+        assert!(cache_b.is_empty().await.unwrap());
+        assert_eq!(cache_b.get("shared").await.unwrap(), None);
+        cache_b.set("shared".to_string(), Bytes::from("from b"), None).await.unwrap();
+        assert_eq!(cache_b.keys().await.unwrap(), vec!["shared".to_string()]);
+        assert!(cache_b.top_accessed_keys(10).await.unwrap().iter().all(|(key, _)| key == "shared"));
+
+        // 清空只删除本命名空间的键
+        cache_b.clear().await.unwrap();
+        assert_eq!(cache_b.get("shared").await.unwrap(), None);
+        cache_b.sync().await.unwrap();
+        drop(cache_b);
+
+        let (cache_a, _temp_a) = open("a/").await;
+        assert_eq!(cache_a.get_stats().await.ttl_index_source, TtlIndexSource::Sidecar);
+        assert_eq!(cache_a.get("shared").await.unwrap(), Some(Bytes::from("from a")));
+        assert!(cache_a.ttl_manager.get_ttl("only_a").await.is_some());
+        let mut keys = cache_a.keys().await.unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["only_a".to_string(), "shared".to_string()]);
+        assert!(cache_a.db.prefix_iter(key_prefixes::DATA).unwrap().is_empty());
+        assert_eq!(cache_a.db.prefix_iter(b"a/d:").unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stored_expiry_without_ttl_index() {
+        let clock = Arc::new(crate::clock::MockClock::new(1_000));
+        let mut captured = None;
+        let (_, temp_dir) = create_test_cache_with(|l2| captured = Some(l2.clone()), CodecChain::default()).await;
+        let l2_config = captured.unwrap();
+        let ttl_config = TtlConfig {
+            expire_seconds: Some(60),
+            cleanup_interval: 60,
+            max_cleanup_entries: 100,
+            lazy_expiration: true,
+            active_expiration: false,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
+        };
+        let ttl_manager = Arc::new(TtlManager::with_clock(ttl_config, clock.clone()).await.unwrap());
+        let cache = L2Cache::new(
+            l2_config.clone(),
+            Compressor::new_from_l2_config(&l2_config),
+            Arc::new(CodecChain::default()),
+            Arc::clone(&ttl_manager),
+        ).await.unwrap();
+
+        cache.set("short".to_string(), Bytes::from("value"), Some(10)).await.unwrap();
+        cache.set("forever".to_string(), Bytes::from("value"), Some(0)).await.unwrap();
+
+        // 模拟 TTL 索引丢失该键
+        ttl_manager.remove_key("short").await;
+        clock.advance(11);
+        assert!(!ttl_manager.is_expired("short").await);
+
+        assert_eq!(cache.get("short").await.unwrap(), None);
+        assert_eq!(cache.get("forever").await.unwrap(), Some(Bytes::from("value")));
+
+        // 过期条目已被惰性删除
+        assert!(!cache.contains_key("short").await.unwrap());
+        drop(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_get_many_with_flags() {
+        let (cache, temp_dir) = create_test_cache().await;
+        cache.set_with_flags("a".to_string(), Bytes::from("1"), None, 7).await.unwrap();
+        cache.set("b".to_string(), Bytes::from("2"), None).await.unwrap();
+
+        // 整批在一次后台任务中读取，瞬时故障时整批重试
+        cache.inject_failures([CacheError::melange_db_error("transient")]);
+        let keys = vec!["a".to_string(), "missing".to_string(), "b".to_string()];
+        let values = cache.get_many_with_flags(&keys).await.unwrap();
+        assert_eq!(values, vec![Some((Bytes::from("1"), 7)), None, Some((Bytes::from("2"), 0))]);
+
+        let stats = cache.get_stats().await;
+        assert_eq!((stats.reads, stats.hits, stats.misses), (3, 2, 1));
+        assert_eq!(stats.retries, 1);
+        drop(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_transient_error_retry() {
+        let (cache, temp_dir) = create_test_cache_with(|l2| {
+            l2.l2_max_retries = 2;
+            l2.l2_retry_backoff_ms = 1;
+        }, CodecChain::default()).await;
+        let transient = || CacheError::melange_db_error("transient");
+
+        // 瞬时故障在重试次数内恢复，调用方看不到错误
+        cache.inject_failures([transient(), transient()]);
+        cache.set("key".to_string(), Bytes::from("value"), None).await.unwrap();
+        cache.inject_failures([transient()]);
+        assert_eq!(cache.get("key").await.unwrap(), Some(Bytes::from("value")));
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.retries, 3);
+        assert_eq!(stats.retries_exhausted, 0);
+
+        // 永久性错误不重试
+        cache.inject_failures([CacheError::corruption("key", "bad")]);
+        assert!(cache.contains_key("key").await.is_err());
+        assert_eq!(cache.get_stats().await.retries, 3);
+
+        // 超过重试次数后返回最后一次的错误
+        cache.inject_failures((0..3).map(|_| transient()));
+        assert!(cache.delete("key").await.unwrap_err().is_transient());
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.retries, 5);
+        assert_eq!(stats.retries_exhausted, 1);
+        assert!(cache.contains_key("key").await.unwrap());
+        drop(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_incremental_disk_usage() {
+        let (cache, temp_dir) = create_test_cache_with(|_| {}, CodecChain::default()).await;
+        assert_eq!(cache.get_stats().await.full_scans, 1);
+
+        for i in 0..10 {
+            cache.set(format!("key_{}", i), Bytes::from(vec![b'a' + i as u8; 200 + i * 10]), None).await.unwrap();
+        }
+        cache.set("key_0".to_string(), Bytes::from("short"), None).await.unwrap();
+        cache.delete("key_1").await.unwrap();
+        cache.delete("missing").await.unwrap();
+
+        // 统计查询只读取增量维护的计数，不触发全量扫描
+        for _ in 0..5 {
+            cache.get_stats().await;
+        }
+        let incremental = cache.get_stats().await;
+        assert_eq!(incremental.full_scans, 1);
+        assert_eq!(incremental.entry_count, 9);
+        assert_eq!(cache.len().await.unwrap(), 9);
+
+        // 压缩时全量扫描的结果与增量维护的一致
+        cache.compact().await.unwrap();
+        let scanned = cache.get_stats().await;
+        assert_eq!(scanned.full_scans, 2);
+        assert_eq!(scanned.entry_count, incremental.entry_count);
+        assert_eq!(scanned.estimated_disk_usage, incremental.estimated_disk_usage);
+        assert_eq!(scanned.logical_bytes, incremental.logical_bytes);
+        drop(temp_dir);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_index_persistence() {
+        async fn reopen(l2_config: &L2Config) -> L2Cache {
+            let ttl_config = TtlConfig {
+                expire_seconds: Some(60),
+                cleanup_interval: 60,
+                max_cleanup_entries: 100,
+                lazy_expiration: true,
+                active_expiration: false,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
+            };
+            L2Cache::new(
+                l2_config.clone(),
+                Compressor::new_from_l2_config(l2_config),
+                Arc::new(CodecChain::default()),
+                Arc::new(TtlManager::new(ttl_config).await.unwrap()),
+            ).await.unwrap()
+        }
+
+        let data_dir = TempDir::new().unwrap();
+        let mut captured = None;
+        let (cache, _temp_dir) = create_test_cache_with(|l2| {
+            l2.data_dir = Some(data_dir.path().to_path_buf());
+            captured = Some(l2.clone());
+        }, CodecChain::default()).await;
+        let l2_config = captured.unwrap();
+        let sidecar = data_dir.path().join(TTL_INDEX_FILE);
+
+        // 首次启动扫描元数据
+        assert_eq!(cache.get_stats().await.ttl_index_source, TtlIndexSource::MetadataScan);
+        cache.set("ttl_key".to_string(), Bytes::from("value"), Some(3600)).await.unwrap();
+        cache.set("plain_key".to_string(), Bytes::from("value"), None).await.unwrap();
+
+        // 正常关闭：加载索引文件
+        cache.save_ttl_index().await.unwrap();
+        assert!(sidecar.exists());
+        let saved_sidecar = std::fs::read(&sidecar).unwrap();
+        drop(cache);
+
+        let cache = reopen(&l2_config).await;
+        assert_eq!(cache.get_stats().await.ttl_index_source, TtlIndexSource::Sidecar);
+        assert!(!sidecar.exists());
+        let ttl = cache.ttl_manager.get_ttl("ttl_key").await.unwrap();
+        assert!(ttl > 3500 && ttl <= 3600);
+        assert!(cache.ttl_manager.get_ttl("plain_key").await.is_none());
+        cache.sync().await.unwrap();
+
+        // 异常退出（未保存索引）：回退为扫描元数据
+        drop(cache);
+        let cache = reopen(&l2_config).await;
+        assert_eq!(cache.get_stats().await.ttl_index_source, TtlIndexSource::MetadataScan);
+        let ttl = cache.ttl_manager.get_ttl("ttl_key").await.unwrap();
+        assert!(ttl > 3500 && ttl <= 3600);
+        drop(cache);
+
+        // 过期的索引文件（代数不匹配）被丢弃
+        std::fs::write(&sidecar, &saved_sidecar).unwrap();
+        let cache = reopen(&l2_config).await;
+        assert_eq!(cache.get_stats().await.ttl_index_source, TtlIndexSource::MetadataScan);
+        assert!(!sidecar.exists());
+        assert!(cache.ttl_manager.get_ttl("ttl_key").await.is_some());
+    }
+
     #[tokio::test]
     async fn test_cache_creation() {
         let (cache, _temp_dir) = create_test_cache().await;
@@ -735,6 +2638,234 @@ mod tests {
         assert!(!cache.contains_key(&key).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_logical_bytes() {
+        let (cache, _temp_dir) = create_test_cache().await;
+        let large = Bytes::from("abcdefgh".repeat(256));
+
+        cache.set("a".to_string(), large.clone(), None).await.unwrap();
+        cache.set("b".to_string(), Bytes::from("tiny"), None).await.unwrap();
+        assert_eq!(cache.get_stats().await.logical_bytes, large.len() as u64 + 4);
+
+        // 覆盖写入只计算新值
+        cache.set("a".to_string(), Bytes::from("short"), None).await.unwrap();
+        assert_eq!(cache.get_stats().await.logical_bytes, 9);
+
+        cache.delete("b").await.unwrap();
+        assert_eq!(cache.get_stats().await.logical_bytes, 5);
+    }
+
+    #[tokio::test]
+    async fn test_compact_storage() {
+        let (cache, _temp_dir) = create_test_cache_with(|config| config.compact_storage = true, CodecChain::default()).await;
+        let small = Bytes::from("tiny");
+        let large = Bytes::from("abcdefgh".repeat(256));
+
+        // 无 TTL 的条目只写入数据键
+        cache.set("small".to_string(), small.clone(), None).await.unwrap();
+        cache.set("large".to_string(), large.clone(), None).await.unwrap();
+        assert!(cache.db.prefix_iter(key_prefixes::METADATA).unwrap().is_empty());
+        assert_eq!(cache.get("small").await.unwrap(), Some(small.clone()));
+        assert_eq!(cache.get("large").await.unwrap(), Some(large.clone()));
+        assert_eq!(cache.get_stats().await.logical_bytes, large.len() as u64 + 4);
+
+        // 带 TTL 的条目保留独立元数据键
+        cache.set("small".to_string(), small.clone(), Some(60)).await.unwrap();
+        assert_eq!(cache.db.prefix_iter(key_prefixes::METADATA).unwrap().len(), 1);
+
+        // 覆盖为无 TTL 后清理旧的元数据键
+        cache.set("small".to_string(), small.clone(), None).await.unwrap();
+        assert!(cache.db.prefix_iter(key_prefixes::METADATA).unwrap().is_empty());
+        assert_eq!(cache.get("small").await.unwrap(), Some(small));
+
+        assert!(cache.delete("large").await.unwrap());
+        assert_eq!(cache.get_stats().await.logical_bytes, 4);
+    }
+
+    #[tokio::test]
+    async fn test_cas_tokens() {
+        let (cache, _temp_dir) = create_test_cache_with(|config| config.compact_storage = true, CodecChain::default()).await;
+        cache.set("compact".to_string(), Bytes::from("a"), None).await.unwrap();
+        cache.set("tracked".to_string(), Bytes::from("b"), Some(60)).await.unwrap();
+        let compact = cache.cas_token("compact").await.unwrap().unwrap();
+        let tracked = cache.cas_token("tracked").await.unwrap().unwrap();
+        assert_eq!(compact >> CAS_GENERATION_SHIFT, cache.generation);
+        assert!(tracked > compact);
+        assert_eq!(cache.get_with_cas("compact").await.unwrap(), Some((Bytes::from("a"), 0, compact)));
+        assert_eq!(cache.get_with_cas("tracked").await.unwrap(), Some((Bytes::from("b"), 0, tracked)));
+
+        // 每次写入签发新的令牌
+        cache.set("compact".to_string(), Bytes::from("a"), None).await.unwrap();
+        assert!(cache.cas_token("compact").await.unwrap().unwrap() > tracked);
+        assert_eq!(cache.cas_token("missing").await.unwrap(), None);
+
+        // 记录令牌之前写入的数据没有令牌
+        let data_key = cache.layout.data_key("compact");
+        let stored = cache.db.get(&data_key).unwrap().unwrap();
+        let (mut metadata, payload) = L2Cache::decode_compact_value(&stored).unwrap();
+        metadata.cas = 0;
+        cache.db.put(&data_key, &L2Cache::encode_compact_value(&metadata, payload)).unwrap();
+        assert_eq!(cache.cas_token("compact").await.unwrap(), None);
+        assert_eq!(cache.get_with_cas("compact").await.unwrap(), Some((Bytes::from("a"), 0, 0)));
+    }
+
+    #[tokio::test]
+    async fn test_skip_reads_when_empty() {
+        let (cache, _temp_dir) = create_test_cache().await;
+
+        // 绕过 set 直接写入数据库：已知为空时读取不会访问数据库
+        cache.db.put(&cache.layout.data_key("raw"), b"raw").unwrap();
+        assert_eq!(cache.get("raw").await.unwrap(), None);
+        assert_eq!(cache.get("missing").await.unwrap(), None);
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.skipped_reads, 2);
+        assert_eq!(stats.misses, 2);
+
+        // 首次写入后恢复正常读取
+        let value = Bytes::from("value");
+        cache.set("key".to_string(), value.clone(), None).await.unwrap();
+        assert_eq!(cache.get("key").await.unwrap(), Some(value));
+        assert_eq!(cache.get("missing").await.unwrap(), None);
+        assert_eq!(cache.get_stats().await.skipped_reads, 2);
+
+        // 清空后重新跳过读取
+        cache.clear().await.unwrap();
+        assert_eq!(cache.get("key").await.unwrap(), None);
+        assert_eq!(cache.get_stats().await.skipped_reads, 3);
+    }
+
+    #[tokio::test]
+    async fn test_checksum_detects_corruption() {
+        for compact_storage in [false, true] {
+            let (cache, _temp_dir) = create_test_cache_with(
+                |config| config.compact_storage = compact_storage,
+                CodecChain::default(),
+            ).await;
+            cache.set("key".to_string(), Bytes::from("abcdefgh".repeat(256)), None).await.unwrap();
+
+            // 翻转存储数据的最后一个字节
+            let data_key = cache.layout.data_key("key");
+            let mut data = cache.db.get(&data_key).unwrap().unwrap().to_vec();
+            *data.last_mut().unwrap() ^= 0xff;
+            cache.db.put(&data_key, &data).unwrap();
+
+            assert!(matches!(cache.get("key").await, Err(CacheError::Corruption { .. })));
+
+            // 损坏的条目被删除
+            assert!(!cache.contains_key("key").await.unwrap());
+            assert_eq!(cache.get("key").await.unwrap(), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_update_sampling() {
+        let (cache, _temp_dir) = create_test_cache_with(
+            |config| config.metadata_update_sampling = 0.5,
+            CodecChain::default(),
+        ).await;
+        cache.set("key".to_string(), Bytes::from("value"), None).await.unwrap();
+
+        for _ in 0..1000 {
+            assert!(cache.get("key").await.unwrap().is_some());
+        }
+
+        // 约一半的命中跳过写回，写回的访问计数按 2 倍计入
+        let skipped = cache.get_stats().await.skipped_metadata_updates;
+        assert!((350..=650).contains(&skipped), "skipped: {}", skipped);
+
+        let metadata_bytes = cache.db.get(&cache.layout.metadata_key("key")).unwrap().unwrap();
+        let metadata = L2Cache::decode_metadata(&metadata_bytes).unwrap();
+        assert_eq!(metadata.access_count, 1 + (1000 - skipped) * 2);
+    }
+
+    #[tokio::test]
+    async fn test_partition_routing() {
+        let (cache, _temp_dir) = create_test_cache_with(|config| config.l2_partitions = 4, CodecChain::default()).await;
+
+        for i in 0..20 {
+            let key = format!("key_{}", i);
+            cache.set(key.clone(), Bytes::from(format!("value_{}", i)), None).await.unwrap();
+
+            // 数据键位于所属分区的前缀下
+            let partition = cache.layout.partition_of(&key).unwrap() as usize;
+            let prefix = cache.layout.partition_prefix(key_prefixes::DATA, partition);
+            let data_key = cache.layout.data_key(&key);
+            assert!(data_key.starts_with(&prefix));
+            assert!(cache.db.prefix_iter(&prefix).unwrap().iter().any(|(stored, _)| *stored == data_key));
+        }
+
+        let mut keys = cache.keys().await.unwrap();
+        keys.sort();
+        let mut expected: Vec<_> = (0..20).map(|i| format!("key_{}", i)).collect();
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+
+    #[tokio::test]
+    async fn test_compact_partition_keeps_other_partitions() {
+        let (cache, _temp_dir) = create_test_cache_with(|config| config.l2_partitions = 4, CodecChain::default()).await;
+
+        for i in 0..20 {
+            cache.set(format!("key_{}", i), Bytes::from(format!("value_{}", i)), None).await.unwrap();
+        }
+
+        // 在每个分区写入一条孤立元数据
+        let orphans: Vec<String> = (0..4)
+            .map(|partition| (0..).map(|i| format!("orphan_{}", i))
+                .find(|key| cache.layout.partition_of(key) == Some(partition))
+                .unwrap())
+            .collect();
+        for orphan in &orphans {
+            cache.db.put(&cache.layout.metadata_key(orphan), b"orphan").unwrap();
+        }
+
+        assert_eq!(cache.compact_partition(1).await.unwrap(), 1);
+        assert!(cache.compact_partition(4).await.is_err());
+
+        // 仅清理目标分区，其他分区的孤立元数据和所有数据保留
+        for (partition, orphan) in orphans.iter().enumerate() {
+            let exists = cache.db.get(&cache.layout.metadata_key(orphan)).unwrap().is_some();
+            assert_eq!(exists, partition != 1);
+        }
+        for i in 0..20 {
+            let value = cache.get(&format!("key_{}", i)).await.unwrap();
+            assert_eq!(value, Some(Bytes::from(format!("value_{}", i))));
+        }
+
+        // 整体压缩清理剩余分区
+        cache.compact().await.unwrap();
+        for orphan in &orphans {
+            assert!(cache.db.get(&cache.layout.metadata_key(orphan)).unwrap().is_none());
+        }
+        assert_eq!(cache.keys().await.unwrap().len(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_codec_chain() {
+        let codec_chain = CodecChain::new(vec![
+            Box::new(crate::codec::Lz4Codec),
+            Box::new(crate::codec::Crc32Codec),
+        ]).unwrap();
+        let (cache, _temp_dir) = create_test_cache_with(|config| {
+            config.compact_storage = true;
+            // 关闭 L2 自身的校验，验证编解码链中的校验
+            config.checksum_enabled = false;
+        }, codec_chain).await;
+        let value = Bytes::from("abcdefgh".repeat(256));
+
+        // 配置编解码链时不使用紧凑存储，链描述记录在元数据中
+        cache.set("key".to_string(), value.clone(), None).await.unwrap();
+        assert_eq!(cache.db.prefix_iter(key_prefixes::METADATA).unwrap().len(), 1);
+        assert_eq!(cache.get("key").await.unwrap(), Some(value));
+
+        // 篡改存储的数据后读取时校验失败
+        let data_key = cache.layout.data_key("key");
+        let mut data = cache.db.get(&data_key).unwrap().unwrap().to_vec();
+        data[0] ^= 0xff;
+        cache.db.put(&data_key, &data).unwrap();
+        assert!(matches!(cache.get("key").await, Err(CacheError::ChecksumMismatch { .. })));
+    }
+
     #[tokio::test]
     async fn test_clear() {
         let (cache, _temp_dir) = create_test_cache().await;
@@ -806,6 +2937,90 @@ mod tests {
         assert!(stats.deletes > 0);
     }
 
+    #[test]
+    fn test_blocking_queue_wait() {
+        // 只有一个阻塞线程，占用后 L2 读写必然排队
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .max_blocking_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let (cache, _temp_dir) = create_test_cache_with(|l2| {
+                l2.blocking_queue_warn_ms = 10;
+                l2.blocking_queue_max_wait_ms = Some(50);
+            }, CodecChain::default()).await;
+            cache.set("key".to_string(), Bytes::from("value"), None).await.unwrap();
+
+            let blocked = Arc::new(std::sync::Barrier::new(2));
+            let blocker = {
+                let blocked = blocked.clone();
+                tokio::task::spawn_blocking(move || { blocked.wait(); })
+            };
+
+            // 排队超时的读取快速失败，且被取消的任务不会再执行
+            let result = cache.get("key").await;
+            assert!(result.unwrap_err().is_busy());
+
+            // 排队未超时的读取正常完成，并计入慢排队
+            let release = {
+                let blocked = blocked.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    blocked.wait();
+                })
+            };
+            assert_eq!(cache.get("key").await.unwrap(), Some(Bytes::from("value")));
+            release.await.unwrap();
+            blocker.await.unwrap();
+
+            let stats = cache.get_stats().await.spawn_blocking_queue_wait;
+            assert_eq!(stats.busy_rejections, 1);
+            assert!(stats.slow_waits >= 1);
+            assert!(stats.max_wait_us >= 10_000);
+        });
+    }
+
+    #[test]
+    fn test_write_rate_limiter() {
+        let limiter = WriteRateLimiter::new(1000);
+
+        // 桶满时允许单次超出容量的写入，之后预算为负
+        assert!(limiter.try_acquire(1500, false));
+        assert!(!limiter.try_acquire(10, false));
+        // 强制写入总是放行
+        assert!(limiter.try_acquire(10, true));
+
+        let mut stats = L2CacheStats::default();
+        limiter.fill_stats(&mut stats);
+        assert_eq!(stats.throttled_writes, 1);
+        assert!(!stats.write_throttled);
+    }
+
+    #[tokio::test]
+    async fn test_compression_breakdown_stats() {
+        let (cache, _temp_dir) = create_test_cache().await;
+
+        // 小于压缩阈值，不压缩
+        cache.set("small".to_string(), Bytes::from("tiny"), None).await.unwrap();
+        // 高度可压缩的数据，使用 LZ4
+        let large = Bytes::from("abcdefgh".repeat(256));
+        cache.set("large".to_string(), large.clone(), None).await.unwrap();
+
+        let stats = cache.get_stats().await;
+        let none = &stats.compression_by_algorithm[&CompressionAlgorithm::None];
+        assert_eq!(none.entries, 1);
+        assert_eq!(none.original_bytes, 4);
+        assert_eq!(none.compression_ratio(), 1.0);
+
+        let lz4 = &stats.compression_by_algorithm[&CompressionAlgorithm::Lz4];
+        assert_eq!(lz4.entries, 1);
+        assert_eq!(lz4.original_bytes, large.len() as u64);
+        assert!(lz4.compression_ratio() < 1.0);
+        assert!(!stats.compression_by_algorithm.contains_key(&CompressionAlgorithm::Zstd));
+    }
+
     #[tokio::test]
     async fn test_compression_algorithms() {
         let temp_dir = TempDir::new().unwrap();
@@ -820,6 +3035,7 @@ mod tests {
                 block_cache_size: 512 * 1024,
                 background_threads: 2,
                 clear_on_startup: false,
+                confirm_clear_on_startup: false,
                 enable_lz4: enable_lz4,
                 compression_threshold: 128,
                 compression_max_threshold: 1024 * 1024,
@@ -837,6 +3053,26 @@ mod tests {
                 l2_write_strategy: "write_through".to_string(),
                 l2_write_threshold: 1024,
                 l2_write_ttl_threshold: 300,
+                l2_write_eviction_rate_low: 1.0,
+                l2_write_eviction_rate_high: 100.0,
+                ttl_demotion_enabled: false,
+                ttl_demotion_ratio: 0.1,
+                ttl_demotion_grace_seconds: 60,
+                compact_storage: false,
+                skip_reads_when_empty: true,
+                checksum_enabled: true,
+                evict_corrupted_entries: true,
+                metadata_update_sampling: 1.0,
+                l2_partitions: 1,
+                degrade_to_l1_on_l2_failure: false,
+                l2_max_write_bytes_per_sec: None,
+                blocking_queue_warn_ms: 100,
+                blocking_queue_max_wait_ms: None,
+                persist_ttl_index: true,
+                value_compression_algorithm: CompressionAlgorithm::Lz4,
+                key_prefix: String::new(),
+                l2_max_retries: 2,
+                l2_retry_backoff_ms: 10,
             };
 
             let logging_config = LoggingConfig {
@@ -859,12 +3095,14 @@ mod tests {
                 max_cleanup_entries: 100,
                 lazy_expiration: true,
                 active_expiration: false,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
             };
 
             let compressor = Compressor::new_from_l2_config(&l2_config);
             let ttl_manager = Arc::new(TtlManager::new(ttl_config).await.unwrap());
 
-            let cache = L2Cache::new(l2_config, compressor, ttl_manager).await.unwrap();
+            let cache = L2Cache::new(l2_config, compressor, Arc::new(CodecChain::default()), ttl_manager).await.unwrap();
 
             let key = "compression_test";
             let value = Bytes::from("this is a test value for compression");
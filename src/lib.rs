@@ -30,8 +30,14 @@
 
 // 核心模块
 pub mod cache;
+pub mod clock;
+pub mod codec;
 pub mod config;
 pub mod error;
+pub mod events;
+pub mod key_stats;
+pub mod simulator;
+pub mod transaction;
 pub mod types;
 
 // 公开模块
@@ -39,25 +45,41 @@ pub mod logging;
 pub mod streaming_protocol;
 
 // 内部模块
+mod arc_policy;
 mod compression;
+mod frequency_sketch;
 mod l1_cache;
+mod lru_shards;
 #[cfg(feature = "melange-storage")]
 mod l2_cache;
 #[cfg(feature = "melange-storage")]
 mod melange_adapter;
+mod tags;
+mod tasks;
 mod ttl;
 
 
 // 重新导出主要类型
-pub use cache::{RatMemCache, RatMemCacheBuilder, CacheOptions};
+pub use cache::{RatMemCache, RatMemCacheBuilder, CacheOptions, CacheEntry, CacheStats, CombinedCacheStats, L1DefragReport, RuntimeInfo, OperationMetric, OperationStats, MetricsSnapshot, L1MetricsSnapshot, L2MetricsSnapshot};
 
+pub use clock::{Clock, MockClock, SystemClock};
+pub use codec::{CodecChain, ValueCodec, Lz4Codec, Crc32Codec};
+pub use compression::CompressionAlgorithm;
+#[cfg(feature = "zstd-codec")]
+pub use codec::ZstdCodec;
+#[cfg(feature = "aes-gcm-codec")]
+pub use codec::AesGcmCodec;
 pub use error::{CacheError, CacheResult};
-pub use types::{CacheValue, EvictionStrategy, CacheLayer, CacheOperation};
+pub use events::{CacheEvent, CacheEventStream, EventDelivery, EventStream, EvictionEvent, EvictionEventStream, EvictionListener, EvictionReason, L2HealthEvent, L2HealthListener};
+pub use key_stats::KeyStats;
+pub use simulator::{CacheSimulator, SimulationReport, TraceEvent, TraceOp};
+pub use transaction::TxOp;
+pub use types::{CacheKey, CacheKeyRef, CacheValue, CacheValueBuilder, CasResult, EvictionStrategy, CacheLayer, CacheOperation, SetOutcome};
 
 // 重新导出配置类型
 pub use config::{
-    CacheConfig, CacheConfigBuilder,
-    L1Config, TtlConfig,
+    CacheConfig, CacheConfigBuilder, Preset,
+    L1Config, L1SetFailurePolicy, TtlConfig,
     PerformanceConfig, LoggingConfig
 };
 #[cfg(feature = "melange-storage")]
@@ -65,12 +87,12 @@ pub use config::{L2Config, CacheWarmupStrategy};
 
 // 重新导出 MelangeDB 相关类型
 #[cfg(feature = "melange-storage")]
-pub use melange_adapter::{MelangeAdapter, MelangeConfig, CompressionAlgorithm, BatchOperation};
+pub use melange_adapter::{MelangeAdapter, MelangeConfig, BatchOperation};
 
 // 重新导出统计类型
-pub use l1_cache::L1CacheStats;
+pub use l1_cache::{L1CacheStats, AccessPatternReport};
 #[cfg(feature = "melange-storage")]
-pub use l2_cache::L2CacheStats;
+pub use l2_cache::{L2CacheStats, AlgorithmCompressionStats, QueueWaitStats, TtlIndexSource, ValueStream};
 pub use ttl::TtlStats;
 
 // 版本信息
@@ -106,6 +128,8 @@ mod tests {
                 max_memory: 1024 * 1024 * 1024, // 1GB
                 max_entries: 100_000,
                 eviction_strategy: EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
             })
             .l2_config(L2Config {
                 enable_l2_cache: true,
@@ -120,6 +144,7 @@ mod tests {
                 compression_level: 6,
                 background_threads: 2,
                 clear_on_startup: false,
+                confirm_clear_on_startup: false,
                 cache_size_mb: 256,
                 max_file_size_mb: 512,
                 smart_flush_enabled: true,
@@ -133,6 +158,26 @@ mod tests {
                 l2_write_strategy: "write_through".to_string(),
                 l2_write_threshold: 1024,
                 l2_write_ttl_threshold: 300,
+                l2_write_eviction_rate_low: 1.0,
+                l2_write_eviction_rate_high: 100.0,
+                ttl_demotion_enabled: false,
+                ttl_demotion_ratio: 0.1,
+                ttl_demotion_grace_seconds: 60,
+                compact_storage: false,
+                skip_reads_when_empty: true,
+                checksum_enabled: true,
+                evict_corrupted_entries: true,
+                metadata_update_sampling: 1.0,
+                l2_partitions: 1,
+                degrade_to_l1_on_l2_failure: false,
+                l2_max_write_bytes_per_sec: None,
+                blocking_queue_warn_ms: 100,
+                blocking_queue_max_wait_ms: None,
+                persist_ttl_index: true,
+                value_compression_algorithm: CompressionAlgorithm::Lz4,
+                key_prefix: String::new(),
+                l2_max_retries: 2,
+                l2_retry_backoff_ms: 10,
             })
             .ttl_config(TtlConfig {
                 expire_seconds: Some(60),
@@ -140,6 +185,8 @@ mod tests {
                 max_cleanup_entries: 100,
                 lazy_expiration: true,
                 active_expiration: false,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
             })
                         .performance_config(PerformanceConfig {
                 worker_threads: 4,
@@ -148,6 +195,9 @@ mod tests {
                 batch_size: 100,
                 enable_warmup: false,
                 large_value_threshold: 10240, // 10KB
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 0,
+                max_tagged_keys: 100_000,
             })
             .logging_config(LoggingConfig {
                 level: "debug".to_string(),
@@ -188,6 +238,8 @@ mod tests {
                 max_memory: 1024 * 1024 * 1024, // 1GB
                 max_entries: 100_000,
                 eviction_strategy: EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
             })
             .l2_config(L2Config {
                 enable_l2_cache: true,
@@ -202,6 +254,7 @@ mod tests {
                 compression_level: 6,
                 background_threads: 2,
                 clear_on_startup: false,
+                confirm_clear_on_startup: false,
                 cache_size_mb: 256,
                 max_file_size_mb: 512,
                 smart_flush_enabled: true,
@@ -215,6 +268,26 @@ mod tests {
                 l2_write_strategy: "write_through".to_string(),
                 l2_write_threshold: 1024,
                 l2_write_ttl_threshold: 300,
+                l2_write_eviction_rate_low: 1.0,
+                l2_write_eviction_rate_high: 100.0,
+                ttl_demotion_enabled: false,
+                ttl_demotion_ratio: 0.1,
+                ttl_demotion_grace_seconds: 60,
+                compact_storage: false,
+                skip_reads_when_empty: true,
+                checksum_enabled: true,
+                evict_corrupted_entries: true,
+                metadata_update_sampling: 1.0,
+                l2_partitions: 1,
+                degrade_to_l1_on_l2_failure: false,
+                l2_max_write_bytes_per_sec: None,
+                blocking_queue_warn_ms: 100,
+                blocking_queue_max_wait_ms: None,
+                persist_ttl_index: true,
+                value_compression_algorithm: CompressionAlgorithm::Lz4,
+                key_prefix: String::new(),
+                l2_max_retries: 2,
+                l2_retry_backoff_ms: 10,
             })
             .ttl_config(TtlConfig {
                 expire_seconds: Some(60),
@@ -222,6 +295,8 @@ mod tests {
                 max_cleanup_entries: 100,
                 lazy_expiration: true,
                 active_expiration: false,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
             })
                         .performance_config(PerformanceConfig {
                 worker_threads: 4,
@@ -230,6 +305,9 @@ mod tests {
                 batch_size: 100,
                 enable_warmup: false,
                 large_value_threshold: 10240, // 10KB
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 0,
+                max_tagged_keys: 100_000,
             })
             .logging_config(LoggingConfig {
                 level: "debug".to_string(),
@@ -257,6 +335,7 @@ mod tests {
             force_l2: true,
             skip_l1: false,
             enable_compression: Some(true),
+            ..Default::default()
         };
         
         cache.set_with_options(key.clone(), value.clone(), &options).await.unwrap();
@@ -278,6 +357,8 @@ mod tests {
                 max_memory: 1024 * 1024 * 1024, // 1GB
                 max_entries: 100_000,
                 eviction_strategy: EvictionStrategy::Lru,
+                l1_min_retention_ms: 0,
+                order_compaction_factor: 2,
             })
             .l2_config(L2Config {
                 enable_l2_cache: true,
@@ -292,6 +373,7 @@ mod tests {
                 compression_level: 6,
                 background_threads: 2,
                 clear_on_startup: false,
+                confirm_clear_on_startup: false,
                 cache_size_mb: 256,
                 max_file_size_mb: 512,
                 smart_flush_enabled: true,
@@ -305,6 +387,26 @@ mod tests {
                 l2_write_strategy: "write_through".to_string(),
                 l2_write_threshold: 1024,
                 l2_write_ttl_threshold: 300,
+                l2_write_eviction_rate_low: 1.0,
+                l2_write_eviction_rate_high: 100.0,
+                ttl_demotion_enabled: false,
+                ttl_demotion_ratio: 0.1,
+                ttl_demotion_grace_seconds: 60,
+                compact_storage: false,
+                skip_reads_when_empty: true,
+                checksum_enabled: true,
+                evict_corrupted_entries: true,
+                metadata_update_sampling: 1.0,
+                l2_partitions: 1,
+                degrade_to_l1_on_l2_failure: false,
+                l2_max_write_bytes_per_sec: None,
+                blocking_queue_warn_ms: 100,
+                blocking_queue_max_wait_ms: None,
+                persist_ttl_index: true,
+                value_compression_algorithm: CompressionAlgorithm::Lz4,
+                key_prefix: String::new(),
+                l2_max_retries: 2,
+                l2_retry_backoff_ms: 10,
             })
             .ttl_config(TtlConfig {
                 expire_seconds: Some(60),
@@ -312,6 +414,8 @@ mod tests {
                 max_cleanup_entries: 100,
                 lazy_expiration: true,
                 active_expiration: false,
+                cleanup_channel_capacity: 10_000,
+                sliding_expiration: false,
             })
                         .performance_config(PerformanceConfig {
                 worker_threads: 4,
@@ -320,6 +424,9 @@ mod tests {
                 batch_size: 100,
                 enable_warmup: false,
                 large_value_threshold: 10240, // 10KB
+                key_stats_top_n: 0,
+                absolute_max_value_bytes: 0,
+                max_tagged_keys: 100_000,
             })
             .logging_config(LoggingConfig {
                 level: "debug".to_string(),
@@ -0,0 +1,274 @@
+//! 分片 LRU 访问顺序
+//!
+//! 键按哈希分到多个分片，每个分片用独立的锁保护一个双向链表（节点存放在数组中，空闲槽位复用）
+//! 和键到节点的索引，访问与删除都是 O(1)，不同分片上的访问互不阻塞。每次访问从全局计数器
+//! 取一个序号，选择驱逐候选者时比较各分片队首的序号，取最久未访问的键。
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 分片数量
+const SHARD_COUNT: usize = 16;
+/// 空链接
+const NIL: usize = usize::MAX;
+
+#[derive(Debug)]
+struct Node {
+    key: String,
+    /// 最近一次访问的全局序号
+    seq: u64,
+    prev: usize,
+    next: usize,
+}
+
+/// 每个键在链表节点和索引项中占用的固定字节数（不含键数据）
+pub(crate) const KEY_RECORD_SIZE: usize = std::mem::size_of::<Node>() + std::mem::size_of::<(String, usize)>();
+
+/// 单个分片：队首为最久未访问的键
+#[derive(Debug)]
+struct Shard {
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    index: HashMap<String, usize>,
+    head: usize,
+    tail: usize,
+}
+
+impl Default for Shard {
+    fn default() -> Self {
+        Self { nodes: Vec::new(), free: Vec::new(), index: HashMap::new(), head: NIL, tail: NIL }
+    }
+}
+
+impl Shard {
+    /// 将键移到队尾（不存在时插入）
+    fn touch(&mut self, key: &str, seq: u64) {
+        if let Some(&slot) = self.index.get(key) {
+            self.unlink(slot);
+            self.nodes[slot].seq = seq;
+            self.link_back(slot);
+            return;
+        }
+
+        let node = Node { key: key.to_string(), seq, prev: NIL, next: NIL };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.nodes[slot] = node;
+                slot
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+        self.index.insert(key.to_string(), slot);
+        self.link_back(slot);
+    }
+
+    fn remove(&mut self, key: &str) -> bool {
+        let Some(slot) = self.index.remove(key) else {
+            return false;
+        };
+        self.unlink(slot);
+        self.nodes[slot].key = String::new();
+        self.free.push(slot);
+        true
+    }
+
+    /// 从队首起第一个满足条件的键及其序号
+    fn find_front(&self, mut predicate: impl FnMut(&str) -> bool) -> Option<(u64, &str)> {
+        let mut slot = self.head;
+        while slot != NIL {
+            let node = &self.nodes[slot];
+            if predicate(&node.key) {
+                return Some((node.seq, &node.key));
+            }
+            slot = node.next;
+        }
+        None
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let (prev, next) = (self.nodes[slot].prev, self.nodes[slot].next);
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+
+    fn link_back(&mut self, slot: usize) {
+        self.nodes[slot].prev = self.tail;
+        self.nodes[slot].next = NIL;
+        match self.tail {
+            NIL => self.head = slot,
+            tail => self.nodes[tail].next = slot,
+        }
+        self.tail = slot;
+    }
+
+    /// 重建节点数组，去掉空闲槽位并收缩容量
+    fn shrink_to_fit(&mut self) {
+        let mut keys = Vec::with_capacity(self.index.len());
+        let mut slot = self.head;
+        while slot != NIL {
+            let node = &mut self.nodes[slot];
+            keys.push((std::mem::take(&mut node.key), node.seq));
+            slot = node.next;
+        }
+
+        *self = Self::default();
+        self.nodes.reserve_exact(keys.len());
+        for (key, seq) in keys {
+            self.touch(&key, seq);
+        }
+        self.index.shrink_to_fit();
+    }
+}
+
+/// 分片 LRU 顺序
+#[derive(Debug)]
+pub(crate) struct ShardedLruOrder {
+    shards: Box<[Mutex<Shard>]>,
+    next_seq: AtomicU64,
+}
+
+impl ShardedLruOrder {
+    pub(crate) fn new() -> Self {
+        Self {
+            shards: (0..SHARD_COUNT).map(|_| Mutex::new(Shard::default())).collect(),
+            next_seq: AtomicU64::new(0),
+        }
+    }
+
+    fn shard(&self, key: &str) -> &Mutex<Shard> {
+        &self.shards[fxhash::hash64(key) as usize % self.shards.len()]
+    }
+
+    /// 记录一次访问，将键移到所在分片的队尾
+    pub(crate) fn touch(&self, key: &str) {
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed) + 1;
+        self.shard(key).lock().touch(key, seq);
+    }
+
+    pub(crate) fn remove(&self, key: &str) {
+        self.shard(key).lock().remove(key);
+    }
+
+    /// 键数量（测试用）
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.lock().index.len()).sum()
+    }
+
+    pub(crate) fn clear(&self) {
+        for shard in self.shards.iter() {
+            *shard.lock() = Shard::default();
+        }
+    }
+
+    pub(crate) fn shrink_to_fit(&self) {
+        for shard in self.shards.iter() {
+            shard.lock().shrink_to_fit();
+        }
+    }
+
+    /// 各分片中第一个满足条件的键里最久未访问的
+    fn oldest(&self, mut predicate: impl FnMut(&str) -> bool) -> Option<(usize, String)> {
+        let mut oldest: Option<(u64, usize, String)> = None;
+        for (index, shard) in self.shards.iter().enumerate() {
+            let shard = shard.lock();
+            if let Some((seq, key)) = shard.find_front(&mut predicate)
+                && oldest.as_ref().is_none_or(|(oldest_seq, _, _)| seq < *oldest_seq)
+            {
+                oldest = Some((seq, index, key.to_string()));
+            }
+        }
+        oldest.map(|(_, index, key)| (index, key))
+    }
+
+    /// 最久未访问且 `is_retained` 为 false 的键，全部为 true 时取最久未访问的键
+    fn candidate(&self, is_retained: impl Fn(&str) -> bool) -> Option<(usize, String)> {
+        self.oldest(|key| !is_retained(key)).or_else(|| self.oldest(|_| true))
+    }
+
+    /// 查看驱逐候选者，不移除
+    pub(crate) fn peek_oldest(&self, is_retained: impl Fn(&str) -> bool) -> Option<String> {
+        self.candidate(is_retained).map(|(_, key)| key)
+    }
+
+    /// 取出驱逐候选者；选出后到加锁移除前被其他任务删除时重新选择
+    pub(crate) fn pop_oldest(&self, is_retained: impl Fn(&str) -> bool) -> Option<String> {
+        loop {
+            let (index, key) = self.candidate(&is_retained)?;
+            if self.shards[index].lock().remove(&key) {
+                return Some(key);
+            }
+        }
+    }
+
+    /// 按最近访问时间排列的所有键，最久未访问的在前
+    pub(crate) fn keys_by_recency(&self) -> Vec<String> {
+        let mut keys: Vec<(u64, String)> = Vec::new();
+        for shard in self.shards.iter() {
+            let shard = shard.lock();
+            keys.extend(shard.index.values().map(|&slot| (shard.nodes[slot].seq, shard.nodes[slot].key.clone())));
+        }
+        keys.sort_unstable_by_key(|(seq, _)| *seq);
+        keys.into_iter().map(|(_, key)| key).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oldest_across_shards() {
+        let order = ShardedLruOrder::new();
+        for i in 0..100 {
+            order.touch(&format!("key_{}", i));
+        }
+        order.touch("key_0");
+        order.remove("key_1");
+        assert_eq!(order.len(), 99);
+
+        assert_eq!(order.pop_oldest(|_| false), Some("key_2".to_string()));
+        assert_eq!(order.peek_oldest(|key| key == "key_3"), Some("key_4".to_string()));
+        let keys = order.keys_by_recency();
+        assert_eq!(keys.first().map(String::as_str), Some("key_3"));
+        assert_eq!(keys.last().map(String::as_str), Some("key_0"));
+
+        // 全部在保留期内时取最久未访问的键
+        assert_eq!(order.pop_oldest(|_| true), Some("key_3".to_string()));
+    }
+
+    #[test]
+    fn test_slot_reuse_and_shrink() {
+        let order = ShardedLruOrder::new();
+        for round in 0..3 {
+            for i in 0..50 {
+                order.touch(&format!("key_{}", i));
+            }
+            for i in 0..50 {
+                if i % 2 == round % 2 {
+                    order.remove(&format!("key_{}", i));
+                }
+            }
+        }
+        let slots: usize = order.shards.iter().map(|shard| shard.lock().nodes.len()).sum();
+        assert!(slots <= 50);
+
+        let before = order.keys_by_recency();
+        order.shrink_to_fit();
+        let slots: usize = order.shards.iter().map(|shard| shard.lock().nodes.len()).sum();
+        assert_eq!(slots, order.len());
+        assert_eq!(order.keys_by_recency(), before);
+        order.clear();
+        assert_eq!((order.len(), order.pop_oldest(|_| false)), (0, None));
+    }
+}
@@ -7,19 +7,7 @@ use std::path::Path;
 use std::sync::Arc;
 use bytes::Bytes;
 
-/// 压缩算法枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-pub enum CompressionAlgorithm {
-    None,
-    Lz4,
-    Zstd,
-}
-
-impl Default for CompressionAlgorithm {
-    fn default() -> Self {
-        CompressionAlgorithm::Lz4
-    }
-}
+pub use crate::compression::CompressionAlgorithm;
 
 /// 缓存预热策略
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -206,7 +194,10 @@ trait DatabaseBackend: Send + Sync + std::fmt::Debug {
     fn delete(&self, key: &[u8]) -> CacheResult<()>;
     fn batch_write(&self, operations: &[BatchOperation]) -> CacheResult<()>;
     fn prefix_iter(&self, prefix: &[u8]) -> CacheResult<Vec<(Vec<u8>, Vec<u8>)>>;
+    fn prefix_iter_limited(&self, prefix: &[u8], limit: usize) -> CacheResult<Vec<(Vec<u8>, Vec<u8>)>>;
+    fn delete_prefix(&self, prefix: &[u8]) -> CacheResult<usize>;
     fn clear(&self) -> CacheResult<()>;
+    fn flush(&self) -> CacheResult<()>;
     fn get_statistics(&self) -> CacheResult<DatabaseStats>;
 }
 
@@ -281,15 +272,39 @@ impl DatabaseBackend for MelangeBackend {
         Ok(results)
     }
 
-    fn clear(&self) -> CacheResult<()> {
-        // 获取所有键并删除
-        let all_keys = self.prefix_iter(&[])?;
-        let operations: Vec<BatchOperation> = all_keys
-            .into_iter()
-            .map(|(key, _)| BatchOperation::Remove { key })
-            .collect();
+    fn prefix_iter_limited(&self, prefix: &[u8], limit: usize) -> CacheResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        let mut results = Vec::new();
+        for item in self.db.scan_prefix(prefix).take(limit) {
+            let (key, value) = item
+                .map_err(|e| CacheError::melange_db_error(format!("迭代失败: {}", e)))?;
+            results.push((key.to_vec(), value.to_vec()));
+        }
+
+        Ok(results)
+    }
+
+    fn delete_prefix(&self, prefix: &[u8]) -> CacheResult<usize> {
+        let mut operations = Vec::new();
+        for item in self.db.scan_prefix(prefix) {
+            let (key, _) = item
+                .map_err(|e| CacheError::melange_db_error(format!("迭代失败: {}", e)))?;
+            operations.push(BatchOperation::Remove { key: key.to_vec() });
+        }
 
+        let deleted = operations.len();
         self.batch_write(&operations)?;
+        Ok(deleted)
+    }
+
+    fn clear(&self) -> CacheResult<()> {
+        // 删除所有键
+        self.delete_prefix(&[])?;
+        Ok(())
+    }
+
+    fn flush(&self) -> CacheResult<()> {
+        self.db.flush()
+            .map_err(|e| CacheError::melange_db_error(format!("同步落盘失败: {}", e)))?;
         Ok(())
     }
 
@@ -323,7 +338,7 @@ impl MelangeAdapter {
         path: P,
         config: MelangeConfig,
     ) -> CacheResult<Self> {
-        let melange_config = create_melange_config(&config);
+        let melange_config = create_melange_config(&config)?;
 
         let db = melange_config.path(path).open()
             .map_err(|e| CacheError::melange_db_error(&format!("打开 MelangeDB 失败: {}", e)))?;
@@ -361,11 +376,26 @@ impl MelangeAdapter {
         self.db.backend.prefix_iter(prefix)
     }
 
+    /// 前缀扫描，最多返回 `limit` 条，不遍历整个数据库
+    pub fn prefix_iter_limited(&self, prefix: &[u8], limit: usize) -> CacheResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        self.db.backend.prefix_iter_limited(prefix, limit)
+    }
+
+    /// 删除带有指定前缀的所有键，返回删除的键数
+    pub fn delete_prefix(&self, prefix: &[u8]) -> CacheResult<usize> {
+        self.db.backend.delete_prefix(prefix)
+    }
+
     /// 清空数据库
     pub fn clear(&self) -> CacheResult<()> {
         self.db.backend.clear()
     }
 
+    /// 同步刷新所有脏数据并 fsync，成功返回后之前的写入在崩溃后可恢复
+    pub fn flush(&self) -> CacheResult<()> {
+        self.db.backend.flush()
+    }
+
     /// 获取统计信息
     pub fn get_statistics(&self) -> CacheResult<DatabaseStats> {
         self.db.backend.get_statistics()
@@ -392,15 +422,19 @@ impl MelangeAdapter {
     }
 }
 
-// 辅助函数：创建 MelangeDB 配置
-fn create_melange_config(config: &MelangeConfig) -> melange_db::Config {
+// 辅助函数：创建 MelangeDB 配置，页压缩不支持 Snappy 时返回配置错误
+fn create_melange_config(config: &MelangeConfig) -> CacheResult<melange_db::Config> {
+    let compression_algorithm = match config.compression_algorithm {
+        CompressionAlgorithm::None => melange_db::CompressionAlgorithm::None,
+        CompressionAlgorithm::Lz4 => melange_db::CompressionAlgorithm::Lz4,
+        CompressionAlgorithm::Zstd => melange_db::CompressionAlgorithm::Zstd,
+        CompressionAlgorithm::Snappy => {
+            return Err(CacheError::config_error("MelangeDB 页压缩不支持 Snappy（Snappy 仅用于值压缩）"));
+        }
+    };
     let mut melange_config = melange_db::Config::new()
         .cache_capacity_bytes(config.cache_size_mb * 1024 * 1024)
-        .compression_algorithm(match config.compression_algorithm {
-            CompressionAlgorithm::None => melange_db::CompressionAlgorithm::None,
-            CompressionAlgorithm::Lz4 => melange_db::CompressionAlgorithm::Lz4,
-            CompressionAlgorithm::Zstd => melange_db::CompressionAlgorithm::Zstd,
-        })
+        .compression_algorithm(compression_algorithm)
         .flush_every_ms(None); // 禁用传统自动flush，使用智能flush
 
     // 仅在ZSTD压缩时设置压缩级别
@@ -426,7 +460,7 @@ fn create_melange_config(config: &MelangeConfig) -> melange_db::Config {
         CacheWarmupStrategy::Full => melange_db::CacheWarmupStrategy::Full,
     };
 
-    melange_config
+    Ok(melange_config)
 }
 
 // 便捷函数：直接操作 Bytes 类型
@@ -543,5 +577,9 @@ mod tests {
             let retrieved = adapter.get(key).unwrap();
             assert_eq!(retrieved, Some(value.to_vec()));
         }
+
+        // 页压缩不支持 Snappy
+        let config = MelangeConfig::default().with_compression(CompressionAlgorithm::Snappy);
+        assert!(matches!(MelangeAdapter::new(temp_dir.path(), config), Err(CacheError::ConfigError { .. })));
     }
 }
\ No newline at end of file
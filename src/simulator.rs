@@ -0,0 +1,339 @@
+//! 容量规划模拟模块
+//!
+//! 将记录的访问轨迹回放到按给定限制和驱逐策略配置的 L1 缓存上，
+//! 复用 L1 的容量检查与驱逐候选选择逻辑，使用模拟时钟推进时间，不涉及磁盘或网络 I/O。
+
+use crate::clock::{Clock, MockClock};
+use crate::compression::Compressor;
+use crate::config::{L1Config, TtlConfig};
+use crate::error::CacheResult;
+use crate::l1_cache::L1Cache;
+use crate::ttl::TtlManager;
+use bytes::Bytes;
+use std::sync::Arc;
+
+/// 轨迹中的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceOp {
+    /// 读取
+    Get,
+    /// 写入指定大小的值
+    Set { size: usize, ttl_seconds: Option<u64> },
+    /// 删除
+    Delete,
+}
+
+/// 访问轨迹事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEvent {
+    /// 事件时间戳（秒），用于推进模拟时钟
+    pub timestamp: u64,
+    /// 访问的键
+    pub key: String,
+    /// 操作
+    pub op: TraceOp,
+}
+
+impl TraceEvent {
+    /// 创建读取事件
+    pub fn get(timestamp: u64, key: impl Into<String>) -> Self {
+        Self { timestamp, key: key.into(), op: TraceOp::Get }
+    }
+
+    /// 创建写入事件
+    pub fn set(timestamp: u64, key: impl Into<String>, size: usize, ttl_seconds: Option<u64>) -> Self {
+        Self { timestamp, key: key.into(), op: TraceOp::Set { size, ttl_seconds } }
+    }
+
+    /// 创建删除事件
+    pub fn delete(timestamp: u64, key: impl Into<String>) -> Self {
+        Self { timestamp, key: key.into(), op: TraceOp::Delete }
+    }
+}
+
+/// 模拟结果
+#[derive(Debug, Clone, Default)]
+pub struct SimulationReport {
+    /// 回放的事件数
+    pub operations: u64,
+    /// 读取次数
+    pub gets: u64,
+    /// 命中次数
+    pub hits: u64,
+    /// 未命中次数
+    pub misses: u64,
+    /// 写入次数
+    pub sets: u64,
+    /// 因超出容量被拒绝的写入次数
+    pub rejected_sets: u64,
+    /// 删除次数
+    pub deletes: u64,
+    /// 驱逐次数
+    pub evictions: u64,
+    /// 驱逐的总字节数
+    pub evicted_bytes: u64,
+    /// 峰值内存使用量（字节）
+    pub peak_memory_usage: usize,
+    /// 回放结束时的内存使用量（字节）
+    pub final_memory_usage: usize,
+    /// 每个事件后内存使用量的平均值（字节）
+    pub avg_memory_usage: f64,
+    /// 峰值条目数
+    pub peak_entry_count: usize,
+    /// 回放结束时的条目数
+    pub final_entry_count: usize,
+}
+
+impl SimulationReport {
+    /// 计算命中率
+    pub fn hit_rate(&self) -> f64 {
+        if self.gets == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / self.gets as f64
+    }
+
+    /// 格式化模拟结果
+    pub fn format(&self) -> String {
+        format!(
+            "容量规划模拟结果:\n\
+             事件数: {}\n\
+             读取: {} 次 (命中: {}, 未命中: {}, 命中率: {:.1}%)\n\
+             写入: {} 次 (拒绝: {})\n\
+             删除: {} 次\n\
+             驱逐: {} 次 ({} bytes)\n\
+             内存使用: 峰值 {} bytes, 平均 {:.0} bytes, 结束 {} bytes\n\
+             条目数: 峰值 {}, 结束 {}",
+            self.operations,
+            self.gets, self.hits, self.misses, self.hit_rate() * 100.0,
+            self.sets, self.rejected_sets,
+            self.deletes,
+            self.evictions, self.evicted_bytes,
+            self.peak_memory_usage, self.avg_memory_usage, self.final_memory_usage,
+            self.peak_entry_count, self.final_entry_count
+        )
+    }
+}
+
+/// 容量规划模拟器
+#[derive(Debug, Clone)]
+pub struct CacheSimulator {
+    config: L1Config,
+}
+
+impl CacheSimulator {
+    /// 以给定的 L1 限制和驱逐策略创建模拟器
+    pub fn new(config: L1Config) -> Self {
+        Self { config }
+    }
+
+    /// 回放访问轨迹
+    ///
+    /// 每次回放使用全新的缓存实例。过期按惰性方式处理（访问时检查），与关闭主动过期时的行为一致。
+    pub async fn run<I>(&self, trace: I) -> CacheResult<SimulationReport>
+    where
+        I: IntoIterator<Item = TraceEvent>,
+    {
+        let clock = Arc::new(MockClock::new(0));
+        let ttl_config = TtlConfig {
+            expire_seconds: None,
+            cleanup_interval: 60,
+            max_cleanup_entries: 1000,
+            lazy_expiration: true,
+            active_expiration: false,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
+        };
+        let ttl_manager = Arc::new(TtlManager::with_clock(ttl_config, clock.clone()).await?);
+        let l1_cache = L1Cache::new(self.config.clone(), Compressor::new_disabled(), ttl_manager).await?;
+
+        let mut report = SimulationReport::default();
+        let mut memory_sum = 0u128;
+
+        for event in trace {
+            if event.timestamp > clock.now() {
+                clock.set(event.timestamp);
+            }
+
+            match event.op {
+                TraceOp::Get => {
+                    report.gets += 1;
+                    if l1_cache.get_with_flags(&event.key).await?.is_some() {
+                        report.hits += 1;
+                    } else {
+                        report.misses += 1;
+                    }
+                }
+                TraceOp::Set { size, ttl_seconds } => {
+                    report.sets += 1;
+                    // 超过内存上限的值无法放入缓存
+                    let stored = l1_cache.set_with_flags(event.key, Bytes::from(vec![0u8; size]), ttl_seconds, 0).await.is_ok();
+                    if !stored {
+                        report.rejected_sets += 1;
+                    }
+                }
+                TraceOp::Delete => {
+                    report.deletes += 1;
+                    l1_cache.delete(&event.key).await?;
+                }
+            }
+
+            let stats = l1_cache.get_stats().await;
+            report.operations += 1;
+            report.peak_memory_usage = report.peak_memory_usage.max(stats.memory_usage);
+            report.peak_entry_count = report.peak_entry_count.max(stats.entry_count);
+            memory_sum += stats.memory_usage as u128;
+        }
+
+        let stats = l1_cache.get_stats().await;
+        report.evictions = stats.eviction_stats.total_evictions();
+        report.evicted_bytes = stats.eviction_stats.evicted_bytes();
+        report.final_memory_usage = stats.memory_usage;
+        report.final_entry_count = stats.entry_count;
+        if report.operations > 0 {
+            report.avg_memory_usage = (memory_sum / report.operations as u128) as f64;
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::l1_cache::ENTRY_OVERHEAD;
+    use crate::types::EvictionStrategy;
+
+    /// 每个 "key_N" 条目计入的字节数
+    const ENTRY: usize = 100 + "key_0".len() + ENTRY_OVERHEAD;
+
+    fn simulator(max_memory: usize, eviction_strategy: EvictionStrategy) -> CacheSimulator {
+        CacheSimulator::new(L1Config {
+            max_memory,
+            max_entries: 1000,
+            eviction_strategy,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
+        })
+    }
+
+    /// 写入 10 个 100 字节的键，再反复读取前 3 个键
+    fn trace() -> Vec<TraceEvent> {
+        let mut trace: Vec<_> = (0..10).map(|i| TraceEvent::set(i, format!("key_{}", i), 100, None)).collect();
+        for round in 0..5 {
+            for i in 0..3 {
+                trace.push(TraceEvent::get(10 + round, format!("key_{}", i)));
+            }
+        }
+        trace
+    }
+
+    #[tokio::test]
+    async fn test_simulation_with_enough_capacity() {
+        let report = simulator(100 * ENTRY, EvictionStrategy::Lru).run(trace()).await.unwrap();
+
+        assert_eq!(report.operations, 25);
+        assert_eq!((report.gets, report.hits, report.misses), (15, 15, 0));
+        assert_eq!(report.evictions, 0);
+        assert_eq!(report.peak_memory_usage, 10 * ENTRY);
+        assert_eq!(report.final_entry_count, 10);
+        assert_eq!(report.hit_rate(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_simulation_compares_limits() {
+        // 仅能容纳 5 个键，FIFO 淘汰最早写入的热点键
+        let report = simulator(5 * ENTRY, EvictionStrategy::Fifo).run(trace()).await.unwrap();
+        assert_eq!(report.evictions, 5);
+        assert_eq!(report.evicted_bytes, 5 * ENTRY as u64);
+        assert_eq!(report.peak_memory_usage, 5 * ENTRY);
+        assert_eq!(report.hits, 0);
+
+        // 超过内存上限的值被拒绝
+        let report = simulator(5 * ENTRY, EvictionStrategy::Lru)
+            .run(vec![TraceEvent::set(0, "huge", 5 * ENTRY, None)])
+            .await
+            .unwrap();
+        assert_eq!(report.rejected_sets, 1);
+        assert_eq!(report.final_entry_count, 0);
+    }
+
+    /// Zipf 分布（s = 1）的读取轨迹，未命中时写入；每隔一段插入一次只访问一次的顺序扫描
+    fn zipf_trace_with_scans(keys: usize, requests: usize) -> Vec<TraceEvent> {
+        let weights: Vec<f64> = (1..=keys).map(|rank| 1.0 / rank as f64).collect();
+        let total: f64 = weights.iter().sum();
+        let cdf: Vec<f64> = weights.iter()
+            .scan(0.0, |acc, weight| {
+                *acc += weight / total;
+                Some(*acc)
+            })
+            .collect();
+
+        // 固定种子的 xorshift，保证轨迹可复现
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut trace = Vec::new();
+        for i in 0..requests {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let sample = (state >> 11) as f64 / (1u64 << 53) as f64;
+            let key = format!("hot_{}", cdf.partition_point(|&p| p < sample));
+            trace.push(TraceEvent::get(0, key.clone()));
+            trace.push(TraceEvent::set(0, key, 10, None));
+
+            if i % 2_000 == 1_999 {
+                for j in 0..150 {
+                    let key = format!("scan_{}_{}", i, j);
+                    trace.push(TraceEvent::get(0, key.clone()));
+                    trace.push(TraceEvent::set(0, key, 10, None));
+                }
+            }
+        }
+        trace
+    }
+
+    /// 以 200 个条目的容量回放 Zipf 轨迹
+    async fn run_zipf_trace(eviction_strategy: EvictionStrategy) -> SimulationReport {
+        CacheSimulator::new(L1Config {
+            max_memory: 1024 * 1024,
+            max_entries: 200,
+            eviction_strategy,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
+        })
+        .run(zipf_trace_with_scans(2_000, 20_000))
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_arc_beats_lru_on_zipf_with_scans() {
+        let lru = run_zipf_trace(EvictionStrategy::Lru).await;
+        let arc = run_zipf_trace(EvictionStrategy::Arc).await;
+        assert_eq!(arc.gets, lru.gets);
+        assert!(arc.final_entry_count <= 200);
+        assert!(arc.hit_rate() > lru.hit_rate());
+    }
+
+    #[tokio::test]
+    async fn test_tinylfu_beats_lru_on_zipf_with_scans() {
+        let lru = run_zipf_trace(EvictionStrategy::Lru).await;
+        let tinylfu = run_zipf_trace(EvictionStrategy::TinyLfu).await;
+        assert!(tinylfu.final_entry_count <= 200);
+        assert!(tinylfu.hit_rate() > lru.hit_rate());
+    }
+
+    #[tokio::test]
+    async fn test_simulation_expires_by_trace_time() {
+        let trace = vec![
+            TraceEvent::set(100, "short", 10, Some(5)),
+            TraceEvent::get(103, "short"),
+            TraceEvent::get(106, "short"),
+            TraceEvent::delete(107, "short"),
+        ];
+        let report = simulator(1000, EvictionStrategy::Lru).run(trace).await.unwrap();
+
+        assert_eq!((report.hits, report.misses, report.deletes), (1, 1, 1));
+        assert_eq!(report.final_memory_usage, 0);
+    }
+}
@@ -4,16 +4,73 @@
  * 扩展memcached协议，支持大值数据的流式传输
  */
 
+use crate::codec::{Lz4Codec, ValueCodec};
+use crate::error::{CacheError, CacheResult};
 use bytes::Bytes;
 use std::collections::HashMap;
 
+/// 数据块压缩算法
+///
+/// 在开始帧中以算法字节协商，之后每个数据块独立压缩，接收方逐块解压，不影响流式传输
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StreamCompression {
+    /// 不压缩
+    #[default]
+    None,
+    /// LZ4 块压缩
+    Lz4,
+}
+
+impl StreamCompression {
+    /// 开始帧中使用的算法字节
+    pub fn as_byte(self) -> u8 {
+        match self {
+            StreamCompression::None => 0,
+            StreamCompression::Lz4 => 1,
+        }
+    }
+
+    /// 从算法字节解析，未知算法返回 None
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(StreamCompression::None),
+            1 => Some(StreamCompression::Lz4),
+            _ => None,
+        }
+    }
+
+    /// 压缩单个数据块
+    pub fn compress(self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        match self {
+            StreamCompression::None => Ok(data.to_vec()),
+            StreamCompression::Lz4 => Lz4Codec.encode(data),
+        }
+    }
+
+    /// 解压单个数据块
+    pub fn decompress(self, data: &[u8]) -> CacheResult<Vec<u8>> {
+        match self {
+            StreamCompression::None => Ok(data.to_vec()),
+            StreamCompression::Lz4 => Lz4Codec.decode(data),
+        }
+    }
+
+    /// 解析可选的算法字段，缺省时为不压缩
+    fn parse_field(field: Option<&&str>) -> Option<Self> {
+        match field {
+            Some(field) => field.parse().ok().and_then(Self::from_byte),
+            None => Some(StreamCompression::None),
+        }
+    }
+}
+
 /// 流式命令类型
 #[derive(Debug, Clone, PartialEq)]
 pub enum StreamingCommand {
     /// 流式GET请求
     StreamingGet { key: String, chunk_size: Option<usize> },
     /// 分块SET开始
-    SetBegin { key: String, total_size: usize, chunk_count: usize, flags: u32, exptime: u32 },
+    SetBegin { key: String, total_size: usize, chunk_count: usize, flags: u32, exptime: u32, compression: StreamCompression },
     /// 分块SET数据
     SetData { key: String, chunk_number: usize, data: Bytes },
     /// 分块SET结束
@@ -24,7 +81,7 @@ pub enum StreamingCommand {
 #[derive(Debug, Clone)]
 pub enum StreamingResponse {
     /// 流开始
-    StreamBegin { key: String, total_size: usize, chunk_count: usize },
+    StreamBegin { key: String, total_size: usize, chunk_count: usize, compression: StreamCompression },
     /// 数据块
     StreamData { key: String, chunk_number: usize, data: Bytes },
     /// 流结束
@@ -46,6 +103,7 @@ struct PendingSetOperation {
     chunk_count: usize,
     flags: u32,
     exptime: u32,
+    compression: StreamCompression,
     received_chunks: HashMap<usize, Bytes>,
 }
 
@@ -80,17 +138,19 @@ impl StreamingParser {
                     let chunk_count = parts[3].parse().unwrap_or(0);
                     let flags = parts[4].parse().unwrap_or(0);
                     let exptime = parts.get(5).and_then(|s| s.parse().ok()).unwrap_or(0);
+                    let compression = StreamCompression::parse_field(parts.get(6))?;
 
                     let pending_op = PendingSetOperation {
                         total_size,
                         chunk_count,
                         flags,
                         exptime,
+                        compression,
                         received_chunks: HashMap::new(),
                     };
                     self.pending_sets.insert(key.clone(), pending_op);
 
-                    Some(StreamingCommand::SetBegin { key, total_size, chunk_count, flags, exptime })
+                    Some(StreamingCommand::SetBegin { key, total_size, chunk_count, flags, exptime, compression })
                 } else {
                     None
                 }
@@ -137,15 +197,42 @@ impl StreamingParser {
         None
     }
 
-    /// 添加数据块
+    /// 添加数据块（按开始帧协商的算法解压），键不存在或解压失败时返回 false
     pub fn add_chunk(&mut self, key: String, chunk_number: usize, data: Bytes) -> bool {
         if let Some(pending_op) = self.pending_sets.get_mut(&key) {
-            pending_op.received_chunks.insert(chunk_number, data);
-            true
+            match pending_op.compression.decompress(&data) {
+                Ok(chunk) => {
+                    pending_op.received_chunks.insert(chunk_number, Bytes::from(chunk));
+                    true
+                }
+                Err(_) => false,
+            }
         } else {
             false
         }
     }
+
+    /// 解析服务器返回的流开始帧（`STREAM_BEGIN <key> <total_size> <chunk_count> [algorithm]`）
+    pub fn parse_stream_begin(line: &str) -> Option<StreamingResponse> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 4 || parts[0] != "STREAM_BEGIN" {
+            return None;
+        }
+        let key = parts[1].to_string();
+        let total_size = parts[2].parse().ok()?;
+        let chunk_count = parts[3].parse().ok()?;
+        match StreamCompression::parse_field(parts.get(4)) {
+            Some(compression) => Some(StreamingResponse::StreamBegin { key, total_size, chunk_count, compression }),
+            None => Some(StreamingResponse::Error(format!("不支持的压缩算法: {}", parts[4]))),
+        }
+    }
+
+    /// 按开始帧协商的算法解压收到的数据块
+    pub fn decode_stream_data(compression: StreamCompression, data: &[u8]) -> CacheResult<Bytes> {
+        compression.decompress(data)
+            .map(Bytes::from)
+            .map_err(|e| CacheError::compression_error(format!("数据块解压失败: {}", e)))
+    }
 }
 
 impl Default for StreamingParser {
@@ -163,11 +250,28 @@ impl StreamingFormatter {
         format!("STREAM_BEGIN {} {} {}\r\n", key, total_size, chunk_count).into_bytes()
     }
 
+    /// 格式化带压缩算法的流开始响应，不压缩时与 [`Self::format_stream_begin`] 相同
+    pub fn format_stream_begin_with_compression(key: &str, total_size: usize, chunk_count: usize, compression: StreamCompression) -> Vec<u8> {
+        match compression {
+            StreamCompression::None => Self::format_stream_begin(key, total_size, chunk_count),
+            _ => format!("STREAM_BEGIN {} {} {} {}\r\n", key, total_size, chunk_count, compression.as_byte()).into_bytes(),
+        }
+    }
+
     /// 格式化数据块响应
     pub fn format_stream_data(key: &str, chunk_number: usize, data: &[u8]) -> Vec<u8> {
         format!("STREAM_DATA {} {} {}\r\n", key, chunk_number, data.len()).into_bytes()
     }
 
+    /// 格式化完整的数据块帧（头部、按算法压缩后的数据和结尾换行），头部中的长度为压缩后长度
+    pub fn format_stream_data_frame(key: &str, chunk_number: usize, data: &[u8], compression: StreamCompression) -> CacheResult<Vec<u8>> {
+        let payload = compression.compress(data)?;
+        let mut frame = Self::format_stream_data(key, chunk_number, &payload);
+        frame.extend_from_slice(&payload);
+        frame.extend_from_slice(b"\r\n");
+        Ok(frame)
+    }
+
     /// 格式化流结束响应
     pub fn format_stream_end(key: &str) -> Vec<u8> {
         format!("STREAM_END {}\r\n", key).into_bytes()
@@ -179,6 +283,111 @@ impl StreamingFormatter {
     }
 }
 
+/// `dump` / `load` 迁移流中的单个条目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpEntry {
+    /// 键的原始字节
+    pub key: Bytes,
+    /// 值
+    pub value: Bytes,
+    /// 客户端标志
+    pub flags: u32,
+    /// 导出时的剩余 TTL（秒），0 表示永不过期
+    pub ttl_seconds: u64,
+}
+
+/// `dump` / `load` 迁移流的编解码（非标准 memcached 扩展）
+///
+/// `dump` 的响应由若干批次和一个结束行组成：
+///
+/// ```text
+/// DUMP_BATCH <count> <bytes>\r\n
+/// <bytes 字节的条目帧>\r\n
+/// ...
+/// DUMP_END <total>\r\n
+/// ```
+///
+/// 每个条目帧依次为（整数均为大端序）：
+///
+/// | 字段 | 长度 |
+/// |------|------|
+/// | 键长度 | u16 |
+/// | 键 | 键长度字节 |
+/// | 客户端标志 | u32 |
+/// | 剩余 TTL（秒，0 表示永不过期） | u64 |
+/// | 值长度 | u32 |
+/// | 值 | 值长度字节 |
+///
+/// 批次数据原样作为 `load <bytes>\r\n<bytes 字节的条目帧>\r\n` 的数据块即可导入，
+/// 成功时返回 `LOADED <count>\r\n`
+pub struct DumpCodec;
+
+impl DumpCodec {
+    /// 条目帧中除键和值以外的固定长度
+    pub const FRAME_OVERHEAD: usize = 2 + 4 + 8 + 4;
+
+    /// 将条目帧追加到缓冲区，键超过 u16 或值超过 u32 可表示的长度时返回错误
+    pub fn encode_entry(entry: &DumpEntry, buf: &mut Vec<u8>) -> CacheResult<()> {
+        let key_len = u16::try_from(entry.key.len())
+            .map_err(|_| CacheError::invalid_value(format!("键过长，无法导出: {} bytes", entry.key.len())))?;
+        let value_len = u32::try_from(entry.value.len())
+            .map_err(|_| CacheError::invalid_value(format!("值过大，无法导出: {} bytes", entry.value.len())))?;
+        buf.reserve(Self::FRAME_OVERHEAD + entry.key.len() + entry.value.len());
+        buf.extend_from_slice(&key_len.to_be_bytes());
+        buf.extend_from_slice(&entry.key);
+        buf.extend_from_slice(&entry.flags.to_be_bytes());
+        buf.extend_from_slice(&entry.ttl_seconds.to_be_bytes());
+        buf.extend_from_slice(&value_len.to_be_bytes());
+        buf.extend_from_slice(&entry.value);
+        Ok(())
+    }
+
+    /// 解码一个批次内的全部条目帧，帧不完整或有多余字节时返回错误
+    pub fn decode_entries(data: &Bytes) -> CacheResult<Vec<DumpEntry>> {
+        fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> CacheResult<&'a [u8]> {
+            let field = data.get(*pos..*pos + len)
+                .ok_or_else(|| CacheError::serialization_error(format!("条目帧在偏移 {} 处被截断", *pos)))?;
+            *pos += len;
+            Ok(field)
+        }
+
+        let mut entries = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let key_len = u16::from_be_bytes(take(data, &mut pos, 2)?.try_into().unwrap()) as usize;
+            let key_start = pos;
+            take(data, &mut pos, key_len)?;
+            let key = data.slice(key_start..pos);
+            let flags = u32::from_be_bytes(take(data, &mut pos, 4)?.try_into().unwrap());
+            let ttl_seconds = u64::from_be_bytes(take(data, &mut pos, 8)?.try_into().unwrap());
+            let value_len = u32::from_be_bytes(take(data, &mut pos, 4)?.try_into().unwrap()) as usize;
+            let value_start = pos;
+            take(data, &mut pos, value_len)?;
+            entries.push(DumpEntry { key, value: data.slice(value_start..pos), flags, ttl_seconds });
+        }
+        Ok(entries)
+    }
+
+    /// 格式化批次头
+    pub fn format_batch_header(count: usize, bytes: usize) -> Vec<u8> {
+        format!("DUMP_BATCH {} {}\r\n", count, bytes).into_bytes()
+    }
+
+    /// 解析批次头（`DUMP_BATCH <count> <bytes>`），返回条目数和数据块字节数
+    pub fn parse_batch_header(line: &str) -> Option<(usize, usize)> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() != 3 || parts[0] != "DUMP_BATCH" {
+            return None;
+        }
+        Some((parts[1].parse().ok()?, parts[2].parse().ok()?))
+    }
+
+    /// 格式化结束行
+    pub fn format_end(total: u64) -> Vec<u8> {
+        format!("DUMP_END {}\r\n", total).into_bytes()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,8 +413,43 @@ mod tests {
             total_size: 50000,
             chunk_count: 13,
             flags: 0,
-            exptime: 300
+            exptime: 300,
+            compression: StreamCompression::None,
         }));
+
+        // 未知压缩算法的开始命令无效
+        assert_eq!(parser.parse_command("set_begin other_key 100 1 0 0 9", None), None);
+    }
+
+    #[test]
+    fn test_compressed_chunks_round_trip() {
+        let chunk = b"large cached document ".repeat(64);
+
+        let begin = StreamingFormatter::format_stream_begin_with_compression("doc", chunk.len(), 1, StreamCompression::Lz4);
+        assert_eq!(String::from_utf8_lossy(&begin), format!("STREAM_BEGIN doc {} 1 1\r\n", chunk.len()));
+        let compression = match StreamingParser::parse_stream_begin(&String::from_utf8_lossy(&begin)) {
+            Some(StreamingResponse::StreamBegin { compression, .. }) => compression,
+            other => panic!("unexpected begin frame: {:?}", other),
+        };
+        assert_eq!(compression, StreamCompression::Lz4);
+
+        // 每个数据块独立压缩，头部长度为压缩后长度
+        let frame = StreamingFormatter::format_stream_data_frame("doc", 0, &chunk, compression).unwrap();
+        let header_end = frame.windows(2).position(|w| w == b"\r\n").unwrap();
+        let header = String::from_utf8_lossy(&frame[..header_end]).to_string();
+        let payload_len: usize = header.rsplit(' ').next().unwrap().parse().unwrap();
+        assert!(payload_len < chunk.len());
+        let payload = &frame[header_end + 2..header_end + 2 + payload_len];
+        assert_eq!(StreamingParser::decode_stream_data(compression, payload).unwrap(), Bytes::from(chunk.clone()));
+
+        // 分块 SET 按开始命令协商的算法逐块解压
+        let mut parser = StreamingParser::new();
+        parser.parse_command(&format!("set_begin doc {} 1 0 0 1", chunk.len()), None).unwrap();
+        assert!(parser.add_chunk("doc".to_string(), 0, Bytes::copy_from_slice(payload)));
+        let (_, assembled) = parser.check_set_complete("doc").unwrap();
+        assert_eq!(assembled, chunk);
+
+        assert!(matches!(StreamingParser::parse_stream_begin("STREAM_BEGIN doc 10 1 7"), Some(StreamingResponse::Error(_))));
     }
 
     #[test]
@@ -219,4 +463,25 @@ mod tests {
         let end = StreamingFormatter::format_stream_end("test_key");
         assert_eq!(String::from_utf8_lossy(&end), "STREAM_END test_key\r\n");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_dump_entries_round_trip() {
+        let entries = vec![
+            DumpEntry { key: Bytes::from_static(b"key\xff"), value: Bytes::from_static(b"hello"), flags: 42, ttl_seconds: 300 },
+            DumpEntry { key: Bytes::from_static(b"empty"), value: Bytes::new(), flags: 0, ttl_seconds: 0 },
+        ];
+        let mut buf = Vec::new();
+        for entry in &entries {
+            DumpCodec::encode_entry(entry, &mut buf).unwrap();
+        }
+        assert_eq!(buf.len(), 2 * DumpCodec::FRAME_OVERHEAD + 4 + 5 + 5);
+        assert_eq!(DumpCodec::decode_entries(&Bytes::from(buf.clone())).unwrap(), entries);
+
+        // 截断的帧整体无效
+        assert!(DumpCodec::decode_entries(&Bytes::copy_from_slice(&buf[..buf.len() - 1])).is_err());
+
+        let header = DumpCodec::format_batch_header(2, buf.len());
+        assert_eq!(DumpCodec::parse_batch_header(String::from_utf8_lossy(&header).trim_end()), Some((2, buf.len())));
+        assert_eq!(DumpCodec::parse_batch_header("DUMP_END 2"), None);
+    }
+}
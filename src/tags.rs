@@ -0,0 +1,122 @@
+//! 标签索引模块
+//!
+//! 维护标签到键的二级索引，用于按标签批量失效与键形态无关的一组条目。
+//! 索引只保存在内存中：写入时更新，删除、过期和清空时移除，重启后不会恢复。
+//! 带标签的键数有上限，达到上限后为新键设置标签的写入会被拒绝，而不是静默丢弃标签导致失效遗漏。
+
+use crate::error::{CacheError, CacheResult};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Default)]
+struct TagIndexInner {
+    /// 标签 -> 键
+    tags: HashMap<String, HashSet<String>>,
+    /// 键 -> 标签
+    keys: HashMap<String, Vec<String>>,
+}
+
+impl TagIndexInner {
+    fn remove_key(&mut self, key: &str) {
+        let Some(tags) = self.keys.remove(key) else {
+            return;
+        };
+        for tag in tags {
+            if let Some(keys) = self.tags.get_mut(&tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.tags.remove(&tag);
+                }
+            }
+        }
+    }
+}
+
+/// 标签到键的二级索引
+#[derive(Debug)]
+pub(crate) struct TagIndex {
+    inner: Mutex<TagIndexInner>,
+    /// 带标签的键数，为 0 时未带标签的写入和删除无需加锁
+    len: AtomicUsize,
+    max_keys: usize,
+}
+
+impl TagIndex {
+    pub(crate) fn new(max_keys: usize) -> Self {
+        Self {
+            inner: Mutex::new(TagIndexInner::default()),
+            len: AtomicUsize::new(0),
+            max_keys,
+        }
+    }
+
+    /// 用新的标签替换键原有的标签，`tags` 为空时移除键
+    pub(crate) fn update(&self, key: &str, tags: &[String]) -> CacheResult<()> {
+        if tags.is_empty() {
+            self.remove(key);
+            return Ok(());
+        }
+        let mut inner = self.inner.lock();
+        if !inner.keys.contains_key(key) && inner.keys.len() >= self.max_keys {
+            return Err(CacheError::tag_index_full(self.max_keys));
+        }
+
+        inner.remove_key(key);
+        let mut unique = Vec::with_capacity(tags.len());
+        for tag in tags {
+            if !unique.contains(tag) {
+                unique.push(tag.clone());
+            }
+        }
+        for tag in &unique {
+            inner.tags.entry(tag.clone()).or_default().insert(key.to_string());
+        }
+        inner.keys.insert(key.to_string(), unique);
+        self.len.store(inner.keys.len(), Ordering::Release);
+        Ok(())
+    }
+
+    /// 键当前的标签
+    pub(crate) fn tags(&self, key: &str) -> Vec<String> {
+        if self.len.load(Ordering::Acquire) == 0 {
+            return Vec::new();
+        }
+        self.inner.lock().keys.get(key).cloned().unwrap_or_default()
+    }
+
+    /// 移除键的所有标签
+    pub(crate) fn remove(&self, key: &str) {
+        if self.len.load(Ordering::Acquire) == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        inner.remove_key(key);
+        self.len.store(inner.keys.len(), Ordering::Release);
+    }
+
+    /// 取出带有该标签的所有键，并从索引中移除这些键
+    pub(crate) fn take(&self, tag: &str) -> Vec<String> {
+        let mut inner = self.inner.lock();
+        let Some(keys) = inner.tags.remove(tag) else {
+            return Vec::new();
+        };
+        for key in &keys {
+            inner.remove_key(key);
+        }
+        self.len.store(inner.keys.len(), Ordering::Release);
+        keys.into_iter().collect()
+    }
+
+    /// 带标签的键数
+    pub(crate) fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn clear(&self) {
+        let mut inner = self.inner.lock();
+        inner.tags.clear();
+        inner.keys.clear();
+        self.len.store(0, Ordering::Release);
+    }
+}
@@ -0,0 +1,67 @@
+//! 后台任务登记
+//!
+//! 记录缓存启动的后台任务，关闭时通知循环任务退出并等待全部结束，避免任务泄漏
+
+use parking_lot::Mutex;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// 关闭时等待单个后台任务退出的最长时间，超时后中止该任务
+const SHUTDOWN_JOIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 后台任务登记表
+#[derive(Debug)]
+pub(crate) struct BackgroundTasks {
+    handles: Mutex<Vec<JoinHandle<()>>>,
+    /// 关闭信号，变为 true 后循环任务应退出
+    stop: watch::Sender<bool>,
+}
+
+impl BackgroundTasks {
+    pub(crate) fn new() -> Self {
+        Self {
+            handles: Mutex::new(Vec::new()),
+            stop: watch::channel(false).0,
+        }
+    }
+
+    /// 启动并登记后台任务，顺带清理已结束任务的句柄；关闭后不再启动新任务
+    pub(crate) fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let mut handles = self.handles.lock();
+        if *self.stop.borrow() {
+            return;
+        }
+        handles.retain(|handle| !handle.is_finished());
+        handles.push(tokio::spawn(task));
+    }
+
+    /// 获取关闭信号，循环任务在 `wait_for(|stopped| *stopped)` 返回后退出
+    pub(crate) fn stop_signal(&self) -> watch::Receiver<bool> {
+        self.stop.subscribe()
+    }
+
+    /// 仍在运行的任务数量
+    pub(crate) fn count(&self) -> usize {
+        self.handles.lock().iter().filter(|handle| !handle.is_finished()).count()
+    }
+
+    /// 发出关闭信号并等待所有任务结束
+    pub(crate) async fn shutdown(&self) {
+        let handles = {
+            let mut handles = self.handles.lock();
+            self.stop.send_replace(true);
+            std::mem::take(&mut *handles)
+        };
+        for mut handle in handles {
+            if tokio::time::timeout(SHUTDOWN_JOIN_TIMEOUT, &mut handle).await.is_err() {
+                rat_logger::warn!("[CACHE] 后台任务未在 {:?} 内退出，已中止", SHUTDOWN_JOIN_TIMEOUT);
+                handle.abort();
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! 多键事务模块
+//!
+//! 提供作用于少量键的全有或全无写入（类似受限的 MULTI/EXEC）。
+//! 键按哈希映射到固定数量的锁分片：普通写入持有分片读锁，事务按分片序号升序获取写锁，
+//! 因此多个事务之间不会死锁，事务执行期间涉及的键也不会被普通写入修改。
+
+use bytes::Bytes;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// 锁分片数量
+const KEY_LOCK_STRIPES: usize = 256;
+
+/// 事务中的单个操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxOp {
+    /// 键的当前版本（见 [`crate::CacheEntry::version`]）等于 `version` 时写入，键不存在或版本不同时整个事务放弃
+    SetIfVersion {
+        key: String,
+        value: Bytes,
+        version: u64,
+        /// TTL（秒），None 时使用默认 TTL
+        ttl_seconds: Option<u64>,
+    },
+    /// 无条件写入
+    Set {
+        key: String,
+        value: Bytes,
+        /// TTL（秒），None 时使用默认 TTL
+        ttl_seconds: Option<u64>,
+    },
+    /// 删除键
+    Delete { key: String },
+}
+
+impl TxOp {
+    /// 操作涉及的键
+    pub fn key(&self) -> &str {
+        match self {
+            TxOp::SetIfVersion { key, .. } | TxOp::Set { key, .. } | TxOp::Delete { key } => key,
+        }
+    }
+}
+
+/// 计算值的版本号（值内容与客户端标志的 64 位指纹）
+pub(crate) fn value_version(value: &[u8], flags: u32) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    flags.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 事务开始前键的状态，用于出错时回滚
+#[derive(Debug)]
+pub(crate) struct TxSnapshot {
+    pub(crate) value: Bytes,
+    pub(crate) flags: u32,
+    /// 剩余 TTL（秒），None 表示永不过期
+    pub(crate) ttl_seconds: Option<u64>,
+    pub(crate) tags: Vec<String>,
+}
+
+/// 按键分片的读写锁
+#[derive(Debug)]
+pub(crate) struct KeyLocks {
+    stripes: Vec<RwLock<()>>,
+}
+
+impl KeyLocks {
+    pub(crate) fn new() -> Self {
+        Self {
+            stripes: (0..KEY_LOCK_STRIPES).map(|_| RwLock::new(())).collect(),
+        }
+    }
+
+    fn stripe_of(&self, key: &str) -> usize {
+        fxhash::hash64(key) as usize % self.stripes.len()
+    }
+
+    /// 普通写入持有的共享锁
+    pub(crate) async fn read(&self, key: &str) -> RwLockReadGuard<'_, ()> {
+        self.stripes[self.stripe_of(key)].read().await
+    }
+
+    /// 按分片序号升序获取所有键的共享锁，用于批量写入
+    pub(crate) async fn read_many<'a>(&self, keys: impl Iterator<Item = &'a str>) -> Vec<RwLockReadGuard<'_, ()>> {
+        let mut guards = Vec::new();
+        for stripe in self.sorted_stripes(keys) {
+            guards.push(self.stripes[stripe].read().await);
+        }
+        guards
+    }
+
+    /// 按分片序号升序获取所有键的独占锁
+    pub(crate) async fn write_many<'a>(&self, keys: impl Iterator<Item = &'a str>) -> Vec<RwLockWriteGuard<'_, ()>> {
+        let mut guards = Vec::new();
+        for stripe in self.sorted_stripes(keys) {
+            guards.push(self.stripes[stripe].write().await);
+        }
+        guards
+    }
+
+    /// 去重后按升序排列的分片序号，同一分片只加锁一次，避免排队中的写锁导致自身死锁
+    fn sorted_stripes<'a>(&self, keys: impl Iterator<Item = &'a str>) -> Vec<usize> {
+        let mut stripes: Vec<usize> = keys.map(|key| self.stripe_of(key)).collect();
+        stripes.sort_unstable();
+        stripes.dedup();
+        stripes
+    }
+}
@@ -2,18 +2,25 @@
 //!
 //! 提供过期时间管理、惰性过期和主动过期清理功能
 
+use crate::clock::{system_clock, SharedClock};
 use crate::config::TtlConfig;
 use crate::error::{CacheError, CacheResult};
+use crate::events::{CacheEvent, EventBroadcaster};
 use crate::types::current_timestamp;
 use crate::ttl_log;
 use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
-use tokio::sync::{RwLock, Mutex};
+use parking_lot::{Mutex, RwLock};
 use tokio::time::interval;
-use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::task::JoinHandle;
 
 /// TTL 管理器
+///
+/// 过期索引与统计使用同步锁，临界区很短且不跨越 await；只有跨越 await 的键锁使用异步锁
 #[derive(Debug)]
 pub struct TtlManager {
     config: Arc<TtlConfig>,
@@ -21,12 +28,27 @@ pub struct TtlManager {
     expiry_index: Arc<RwLock<BTreeMap<u64, HashSet<String>>>>,
     /// 键到过期时间的映射 (key -> expire_time)
     key_expiry: Arc<RwLock<std::collections::HashMap<String, u64>>>,
-    /// 清理任务通道
-    cleanup_sender: UnboundedSender<CleanupCommand>,
+    /// 键到总 TTL 时长的映射 (key -> ttl_seconds)
+    key_ttl: Arc<RwLock<std::collections::HashMap<String, u64>>>,
+    /// 清理任务通道（有界）
+    cleanup_sender: Sender<CleanupCommand>,
+    /// 因通道已满而丢弃的清理命令数
+    dropped_commands: Arc<AtomicU64>,
     /// 统计信息
     stats: Arc<Mutex<TtlStats>>,
+    /// 时间源
+    clock: SharedClock,
+    /// 按键哈希分段的锁，串行化同一键的 TTL 修改
+    key_locks: Arc<Vec<tokio::sync::Mutex<()>>>,
+    /// 主动清理任务句柄（未开启主动过期或已停止时为 None）
+    cleanup_task: Mutex<Option<JoinHandle<()>>>,
+    /// 键空间事件广播器（未开启键空间事件时为 None）
+    events: Option<Arc<EventBroadcaster<CacheEvent>>>,
 }
 
+/// 键锁分段数量
+const KEY_LOCK_STRIPES: usize = 64;
+
 /// 清理命令
 #[derive(Debug, Clone)]
 enum CleanupCommand {
@@ -57,19 +79,43 @@ pub struct TtlStats {
     pub avg_cleanup_time_ms: f64,
     /// 当前管理的键数量
     pub managed_keys: u64,
+    /// 清理通道中待处理的命令数
+    pub cleanup_queue_depth: usize,
+    /// 因清理通道已满而丢弃的命令数
+    pub cleanup_commands_dropped: u64,
 }
 
 impl TtlManager {
     /// 创建新的 TTL 管理器
     pub async fn new(config: TtlConfig) -> CacheResult<Self> {
-        let (cleanup_sender, cleanup_receiver) = unbounded_channel();
+        Self::with_clock(config, system_clock()).await
+    }
+
+    /// 使用指定时钟创建 TTL 管理器
+    pub async fn with_clock(config: TtlConfig, clock: SharedClock) -> CacheResult<Self> {
+        Self::with_events(config, clock, None).await
+    }
+
+    /// 创建 TTL 管理器，主动清理时向 `events` 发布过期事件
+    pub(crate) async fn with_events(
+        config: TtlConfig,
+        clock: SharedClock,
+        events: Option<Arc<EventBroadcaster<CacheEvent>>>,
+    ) -> CacheResult<Self> {
+        let (cleanup_sender, cleanup_receiver) = channel(config.cleanup_channel_capacity.max(1));
 
         let manager = Self {
             config: Arc::new(config),
             expiry_index: Arc::new(RwLock::new(BTreeMap::new())),
             key_expiry: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            key_ttl: Arc::new(RwLock::new(std::collections::HashMap::new())),
             cleanup_sender,
+            dropped_commands: Arc::new(AtomicU64::new(0)),
             stats: Arc::new(Mutex::new(TtlStats::default())),
+            clock,
+            key_locks: Arc::new((0..KEY_LOCK_STRIPES).map(|_| tokio::sync::Mutex::new(())).collect()),
+            cleanup_task: Mutex::new(None),
+            events,
         };
 
         // 启动清理任务
@@ -83,60 +129,83 @@ impl TtlManager {
 
     /// 添加键的过期时间
     pub async fn add_key(&self, key: String, ttl_seconds: Option<u64>) -> CacheResult<u64> {
-        let expire_time = if let Some(ttl) = ttl_seconds {
-            // 使用传入的TTL参数
-            if ttl == 0 {
-                // TTL为0表示永不过期
-                return Ok(0);
-            }
-            current_timestamp() + ttl
-        } else if let Some(expire) = self.config.expire_seconds {
-            // 使用配置中设置的过期时间
-            if expire == 0 {
-                // 配置为0表示永不过期
-                return Ok(0);
-            }
-            current_timestamp() + expire
-        } else {
-            // 配置中没有设置过期时间，永不过期
+        let Some(expire_time) = self.resolve_expire_time(ttl_seconds) else {
             return Ok(0);
         };
 
-        // 更新索引
-        self.update_key_expiry(key.clone(), expire_time).await;
-
-        // 发送清理命令
-        if let Err(e) = self.cleanup_sender.send(CleanupCommand::AddKey {
-            key: key.clone(),
-            expire_time,
-        }) {
-            ttl_log!( warn, "发送清理命令失败: {}", e);
-        }
-
-        ttl_log!( debug, "添加键 {} 的过期时间: {}", key, expire_time);
+        let _guard = self.key_lock(&key).lock().await;
+        self.set_expire_time(key, expire_time);
         Ok(expire_time)
     }
 
     /// 移除键的过期时间
     pub async fn remove_key(&self, key: &str) {
-        self.remove_key_expiry(key).await;
-        
-        if let Err(e) = self.cleanup_sender.send(CleanupCommand::RemoveKey {
-            key: key.to_string(),
-        }) {
-            ttl_log!( warn, "发送移除命令失败: {}", e);
-        }
-
-        ttl_log!( debug, "移除键 {} 的过期时间", key);
+        let _guard = self.key_lock(key).lock().await;
+        self.clear_expire_time(key);
     }
 
     /// 更新键的过期时间
     pub async fn update_key(&self, key: String, ttl_seconds: Option<u64>) -> CacheResult<u64> {
+        let _guard = self.key_lock(&key).lock().await;
+
         // 先移除旧的过期时间
-        self.remove_key_expiry(&key).await;
+        self.remove_key_expiry(&key);
 
         // 添加新的过期时间
-        self.add_key(key, ttl_seconds).await
+        let Some(expire_time) = self.resolve_expire_time(ttl_seconds) else {
+            return Ok(0);
+        };
+        self.set_expire_time(key, expire_time);
+        Ok(expire_time)
+    }
+
+    /// 在键锁内读取剩余 TTL 并按 `f` 的结果原子地修改
+    ///
+    /// 键不存在、永不过期或已过期时不调用 `f`，返回 None。
+    /// `f` 返回 None 时保持不变并返回当前剩余 TTL；返回 Some(0) 时改为永不过期并返回 None；
+    /// 其余情况设置新的 TTL 并返回它。
+    pub async fn update_ttl_atomic<F>(&self, key: &str, f: F) -> CacheResult<Option<u64>>
+    where
+        F: FnOnce(u64) -> Option<u64>,
+    {
+        let _guard = self.key_lock(key).lock().await;
+
+        let current = match self.get_ttl(key).await {
+            Some(remaining) if remaining > 0 => remaining,
+            _ => return Ok(None),
+        };
+
+        match f(current) {
+            None => Ok(Some(current)),
+            Some(0) => {
+                self.clear_expire_time(key);
+                Ok(None)
+            }
+            Some(ttl) => {
+                self.set_expire_time(key.to_string(), self.clock.now() + ttl);
+                Ok(Some(ttl))
+            }
+        }
+    }
+
+    /// 滑动过期：按键的 TTL 时长从当前时间重新计算过期时间，返回滑动使用的 TTL
+    ///
+    /// 未开启 `sliding_expiration`、键永不过期或已过期时不做修改并返回 None；
+    /// 同一秒内的重复访问不会改变过期时间，同样返回 None
+    pub async fn touch_on_access(&self, key: &str) -> Option<u64> {
+        if !self.config.sliding_expiration {
+            return None;
+        }
+        let _guard = self.key_lock(key).lock().await;
+
+        let now = self.clock.now();
+        let expire_time = self.key_expiry.read().get(key).copied()?;
+        let ttl = self.key_ttl.read().get(key).copied()?;
+        if expire_time <= now || now + ttl <= expire_time {
+            return None;
+        }
+        self.set_expire_time(key.to_string(), now + ttl);
+        Some(ttl)
     }
 
     /// 检查键是否过期（惰性过期）
@@ -145,34 +214,34 @@ impl TtlManager {
             return false;
         }
 
-        let key_expiry = self.key_expiry.read().await;
-        if let Some(&expire_time) = key_expiry.get(key) {
-            if expire_time > 0 && current_timestamp() >= expire_time {
-                drop(key_expiry);
-                
-                // 记录惰性过期
-                let mut stats = self.stats.lock().await;
-                stats.lazy_expired += 1;
-                stats.total_expired += 1;
-                drop(stats);
-                
-                ttl_log!( debug, "键 {} 已过期（惰性检查）", key);
-                return true;
-            }
+        let key_expiry = self.key_expiry.read();
+        if let Some(&expire_time) = key_expiry.get(key)
+            && expire_time > 0 && self.clock.now() >= expire_time
+        {
+            drop(key_expiry);
+
+            // 记录惰性过期
+            let mut stats = self.stats.lock();
+            stats.lazy_expired += 1;
+            stats.total_expired += 1;
+            drop(stats);
+
+            ttl_log!( debug, "键 {} 已过期（惰性检查）", key);
+            return true;
         }
         false
     }
 
     /// 获取键的剩余 TTL（秒）
     pub async fn get_ttl(&self, key: &str) -> Option<u64> {
-        let key_expiry = self.key_expiry.read().await;
+        let key_expiry = self.key_expiry.read();
         if let Some(&expire_time) = key_expiry.get(key) {
             if expire_time == 0 {
                 // 永不过期
                 return None;
             }
             
-            let current = current_timestamp();
+            let current = self.clock.now();
             if current >= expire_time {
                 // 已过期
                 return Some(0);
@@ -185,8 +254,8 @@ impl TtlManager {
 
     /// 获取所有过期的键
     pub async fn get_expired_keys(&self, limit: usize) -> Vec<String> {
-        let current_time = current_timestamp();
-        let expiry_index = self.expiry_index.read().await;
+        let current_time = self.clock.now();
+        let expiry_index = self.expiry_index.read();
         
         let mut expired_keys = Vec::new();
         
@@ -206,64 +275,196 @@ impl TtlManager {
         expired_keys
     }
 
+    /// 获取即将过期的键（剩余 TTL 不超过总 TTL 的指定比例，且尚未过期）
+    ///
+    /// 返回 (键, 剩余秒数)，按过期时间从早到晚排列
+    #[cfg(feature = "melange-storage")]
+    pub async fn get_keys_near_expiry(&self, remaining_ratio: f64, limit: usize) -> Vec<(String, u64)> {
+        let current_time = self.clock.now();
+        let expiry_index = self.expiry_index.read();
+        let key_ttl = self.key_ttl.read();
+
+        let mut near_expiry = Vec::new();
+
+        for (&expire_time, keys) in expiry_index.range(current_time + 1..) {
+            let remaining = expire_time - current_time;
+            for key in keys {
+                if near_expiry.len() >= limit {
+                    return near_expiry;
+                }
+                if key_ttl.get(key).is_some_and(|&ttl| remaining as f64 <= ttl as f64 * remaining_ratio) {
+                    near_expiry.push((key.clone(), remaining));
+                }
+            }
+        }
+
+        near_expiry
+    }
+
+    /// 获取时间源
+    pub fn clock(&self) -> SharedClock {
+        Arc::clone(&self.clock)
+    }
+
     /// 强制清理过期键
     pub async fn force_cleanup(&self) {
-        if let Err(e) = self.cleanup_sender.send(CleanupCommand::ForceCleanup) {
+        if let Err(e) = self.cleanup_sender.send(CleanupCommand::ForceCleanup).await {
             ttl_log!( warn, "发送强制清理命令失败: {}", e);
         }
     }
 
+    /// 收缩过期索引的多余容量
+    pub fn shrink_to_fit(&self) {
+        for keys in self.expiry_index.write().values_mut() {
+            keys.shrink_to_fit();
+        }
+        self.key_expiry.write().shrink_to_fit();
+        self.key_ttl.write().shrink_to_fit();
+    }
+
+    /// 导出所有键的过期时间（Unix 时间戳，秒），用于持久化 TTL 索引
+    #[cfg(feature = "melange-storage")]
+    pub fn export_expire_times(&self) -> Vec<(String, u64)> {
+        self.key_expiry.read().iter().map(|(key, &expire_time)| (key.clone(), expire_time)).collect()
+    }
+
+    /// 批量恢复键的过期时间，跳过已过期的键，返回恢复的键数量
+    ///
+    /// 用于启动时重建索引，不获取键锁
+    #[cfg(feature = "melange-storage")]
+    pub fn restore_expire_times(&self, entries: impl IntoIterator<Item = (String, u64)>) -> usize {
+        let now = self.clock.now();
+        let mut restored = 0;
+        for (key, expire_time) in entries {
+            if expire_time > now {
+                self.update_key_expiry(key, expire_time);
+                restored += 1;
+            }
+        }
+        restored
+    }
+
     /// 获取统计信息
     pub async fn get_stats(&self) -> TtlStats {
-        let mut stats = self.stats.lock().await;
+        let mut stats = self.stats.lock();
         
         // 更新当前管理的键数量
-        let key_expiry = self.key_expiry.read().await;
+        let key_expiry = self.key_expiry.read();
         stats.managed_keys = key_expiry.len() as u64;
         drop(key_expiry);
+
+        stats.cleanup_queue_depth = self.cleanup_sender.max_capacity() - self.cleanup_sender.capacity();
+        stats.cleanup_commands_dropped = self.dropped_commands.load(Ordering::Relaxed);
         
         stats.clone()
     }
 
     /// 重置统计信息
     pub async fn reset_stats(&self) {
-        let mut stats = self.stats.lock().await;
+        let mut stats = self.stats.lock();
         *stats = TtlStats::default();
         ttl_log!( info, "TTL 统计信息已重置");
     }
 
-    /// 停止 TTL 管理器
+    /// 停止 TTL 管理器，等待清理任务退出
     pub async fn stop(&self) {
-        if let Err(e) = self.cleanup_sender.send(CleanupCommand::Stop) {
+        let Some(cleanup_task) = self.cleanup_task.lock().take() else {
+            return;
+        };
+        if let Err(e) = self.cleanup_sender.send(CleanupCommand::Stop).await {
             ttl_log!( warn, "发送停止命令失败: {}", e);
         }
+        let _ = cleanup_task.await;
         ttl_log!( info, "TTL 管理器已停止");
     }
 
+    /// 主动清理任务是否仍在运行
+    pub fn is_cleanup_task_running(&self) -> bool {
+        self.cleanup_task.lock().as_ref().is_some_and(|task| !task.is_finished())
+    }
+
+    /// 解析过期时间，None 表示永不过期（TTL 或配置为 0 同样表示永不过期）
+    fn resolve_expire_time(&self, ttl_seconds: Option<u64>) -> Option<u64> {
+        let ttl = ttl_seconds.or(self.config.expire_seconds)?;
+        if ttl == 0 {
+            return None;
+        }
+        Some(self.clock.now() + ttl)
+    }
+
+    /// 获取键对应的分段锁
+    fn key_lock(&self, key: &str) -> &tokio::sync::Mutex<()> {
+        &self.key_locks[fxhash::hash(key) % KEY_LOCK_STRIPES]
+    }
+
+    /// 写入过期时间并通知清理任务（调用方需持有键锁）
+    fn set_expire_time(&self, key: String, expire_time: u64) {
+        // 更新索引
+        self.update_key_expiry(key.clone(), expire_time);
+
+        // 发送清理命令
+        self.try_send_command(CleanupCommand::AddKey {
+            key: key.clone(),
+            expire_time,
+        });
+
+        ttl_log!( debug, "添加键 {} 的过期时间: {}", key, expire_time);
+    }
+
+    /// 移除过期时间并通知清理任务（调用方需持有键锁）
+    fn clear_expire_time(&self, key: &str) {
+        self.remove_key_expiry(key);
+
+        self.try_send_command(CleanupCommand::RemoveKey {
+            key: key.to_string(),
+        });
+
+        ttl_log!( debug, "移除键 {} 的过期时间", key);
+    }
+
+    /// 非阻塞发送清理命令，通道已满时丢弃并计数
+    ///
+    /// 过期索引已在调用方更新，丢弃命令不影响惰性过期和定时清理
+    fn try_send_command(&self, command: CleanupCommand) {
+        match self.cleanup_sender.try_send(command) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.dropped_commands.fetch_add(1, Ordering::Relaxed);
+                ttl_log!( debug, "清理通道已满，丢弃命令");
+            }
+            Err(e) => {
+                ttl_log!( warn, "发送清理命令失败: {}", e);
+            }
+        }
+    }
+
     /// 更新键的过期时间索引
-    async fn update_key_expiry(&self, key: String, expire_time: u64) {
+    fn update_key_expiry(&self, key: String, expire_time: u64) {
         // 移除旧的索引
-        self.remove_key_expiry(&key).await;
+        self.remove_key_expiry(&key);
         
         // 添加新的索引
-        let mut expiry_index = self.expiry_index.write().await;
-        let mut key_expiry = self.key_expiry.write().await;
+        let mut expiry_index = self.expiry_index.write();
+        let mut key_expiry = self.key_expiry.write();
+        let mut key_ttl = self.key_ttl.write();
         
         expiry_index.entry(expire_time)
-            .or_insert_with(HashSet::new)
+            .or_default()
             .insert(key.clone());
         
+        key_ttl.insert(key.clone(), expire_time.saturating_sub(self.clock.now()));
         key_expiry.insert(key, expire_time);
     }
 
     /// 移除键的过期时间索引
-    async fn remove_key_expiry(&self, key: &str) {
-        let mut key_expiry = self.key_expiry.write().await;
+    fn remove_key_expiry(&self, key: &str) {
+        let mut key_expiry = self.key_expiry.write();
         
         if let Some(old_expire_time) = key_expiry.remove(key) {
             drop(key_expiry);
+            self.key_ttl.write().remove(key);
             
-            let mut expiry_index = self.expiry_index.write().await;
+            let mut expiry_index = self.expiry_index.write();
             if let Some(keys) = expiry_index.get_mut(&old_expire_time) {
                 keys.remove(key);
                 if keys.is_empty() {
@@ -274,13 +475,16 @@ impl TtlManager {
     }
 
     /// 启动清理任务
-    async fn start_cleanup_task(&self, mut cleanup_receiver: UnboundedReceiver<CleanupCommand>) {
+    async fn start_cleanup_task(&self, mut cleanup_receiver: Receiver<CleanupCommand>) {
         let config = Arc::clone(&self.config);
         let expiry_index = Arc::clone(&self.expiry_index);
         let key_expiry = Arc::clone(&self.key_expiry);
+        let key_ttl = Arc::clone(&self.key_ttl);
         let stats = Arc::clone(&self.stats);
+        let clock = Arc::clone(&self.clock);
+        let events = self.events.clone();
         
-        tokio::spawn(async move {
+        let cleanup_task = tokio::spawn(async move {
             let mut cleanup_interval = interval(Duration::from_secs(config.cleanup_interval));
             
             ttl_log!( info, "TTL 清理任务已启动，间隔: {}秒", config.cleanup_interval);
@@ -293,8 +497,11 @@ impl TtlManager {
                             &config,
                             &expiry_index,
                             &key_expiry,
+                            &key_ttl,
                             &stats,
-                        ).await;
+                            &clock,
+                            events.as_deref(),
+                        );
                     }
                     
                     // 处理清理命令
@@ -305,8 +512,11 @@ impl TtlManager {
                                     &config,
                                     &expiry_index,
                                     &key_expiry,
+                                    &key_ttl,
                                     &stats,
-                                ).await;
+                                    &clock,
+                                    events.as_deref(),
+                                );
                             }
                             Some(CleanupCommand::Stop) => {
                                 ttl_log!( info, "TTL 清理任务已停止");
@@ -324,17 +534,21 @@ impl TtlManager {
                 }
             }
         });
+        *self.cleanup_task.lock() = Some(cleanup_task);
     }
 
     /// 执行清理操作
-    async fn perform_cleanup(
+    fn perform_cleanup(
         config: &TtlConfig,
         expiry_index: &Arc<RwLock<BTreeMap<u64, HashSet<String>>>>,
         key_expiry: &Arc<RwLock<std::collections::HashMap<String, u64>>>,
+        key_ttl: &Arc<RwLock<std::collections::HashMap<String, u64>>>,
         stats: &Arc<Mutex<TtlStats>>,
+        clock: &SharedClock,
+        events: Option<&EventBroadcaster<CacheEvent>>,
     ) {
         let start_time = Instant::now();
-        let current_time = current_timestamp();
+        let current_time = clock.now();
         
         ttl_log!( debug, "开始 TTL 清理任务");
         
@@ -342,7 +556,7 @@ impl TtlManager {
         
         // 收集过期的键
         {
-            let expiry_index_guard = expiry_index.read().await;
+            let expiry_index_guard = expiry_index.read();
             
             for (&expire_time, keys) in expiry_index_guard.iter() {
                 if expire_time > current_time {
@@ -364,24 +578,32 @@ impl TtlManager {
         
         // 清理过期的键
         if !expired_keys.is_empty() {
-            let mut expiry_index_guard = expiry_index.write().await;
-            let mut key_expiry_guard = key_expiry.write().await;
+            let mut expiry_index_guard = expiry_index.write();
+            let mut key_expiry_guard = key_expiry.write();
+            let mut key_ttl_guard = key_ttl.write();
             
             for key in &expired_keys {
-                if let Some(expire_time) = key_expiry_guard.remove(key) {
-                    if let Some(keys) = expiry_index_guard.get_mut(&expire_time) {
-                        keys.remove(key);
-                        if keys.is_empty() {
-                            expiry_index_guard.remove(&expire_time);
-                        }
+                key_ttl_guard.remove(key);
+                if let Some(expire_time) = key_expiry_guard.remove(key)
+                    && let Some(keys) = expiry_index_guard.get_mut(&expire_time)
+                {
+                    keys.remove(key);
+                    if keys.is_empty() {
+                        expiry_index_guard.remove(&expire_time);
                     }
                 }
             }
         }
+
+        if let Some(events) = events.filter(|events| events.has_subscribers()) {
+            for key in &expired_keys {
+                events.publish(CacheEvent::Expired { key: key.clone() });
+            }
+        }
         
         // 更新统计信息
         let cleanup_duration = start_time.elapsed();
-        let mut stats_guard = stats.lock().await;
+        let mut stats_guard = stats.lock();
         stats_guard.cleanup_runs += 1;
         stats_guard.active_expired += expired_keys.len() as u64;
         stats_guard.total_expired += expired_keys.len() as u64;
@@ -420,7 +642,7 @@ impl Drop for TtlManager {
         // 在销毁时尝试停止清理任务
         if self.config.active_expiration {
             // 忽略发送错误，因为清理任务可能已经停止
-            let _ = self.cleanup_sender.send(CleanupCommand::Stop);
+            let _ = self.cleanup_sender.try_send(CleanupCommand::Stop);
         }
     }
 }
@@ -465,6 +687,7 @@ pub mod utils {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
     use crate::config::TtlConfig;
     use tokio::time::{sleep, Duration};
 
@@ -475,6 +698,8 @@ mod tests {
             max_cleanup_entries: 100,
             lazy_expiration: true,
             active_expiration: true,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
         }
     }
 
@@ -498,6 +723,26 @@ mod tests {
         assert!(ttl.unwrap() <= 30);
     }
 
+    #[tokio::test]
+    async fn test_cleanup_channel_backpressure() {
+        let mut ttl_config = create_test_config();
+        ttl_config.cleanup_channel_capacity = 2;
+        let clock = Arc::new(MockClock::new(1_000));
+        let manager = TtlManager::with_clock(ttl_config, clock).await.unwrap();
+
+        // 清理任务尚未运行，超出容量的命令被丢弃
+        for i in 0..5 {
+            manager.add_key(format!("key_{}", i), Some(10)).await.unwrap();
+        }
+        let stats = manager.get_stats().await;
+        assert_eq!(stats.cleanup_queue_depth, 2);
+        assert_eq!(stats.cleanup_commands_dropped, 3);
+
+        // 丢弃命令不影响过期判断
+        assert_eq!(stats.managed_keys, 5);
+        assert_eq!(manager.get_ttl("key_4").await, Some(10));
+    }
+
     #[tokio::test]
     async fn test_key_expiration() {
         let mut ttl_config = create_test_config();
@@ -548,6 +793,124 @@ mod tests {
         assert!(new_ttl > old_ttl);
     }
 
+    #[tokio::test]
+    async fn test_expiration_with_mock_clock() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let manager = TtlManager::with_clock(create_test_config(), clock.clone()).await.unwrap();
+
+        let expire_time = manager.add_key("mock_key".to_string(), Some(10)).await.unwrap();
+        assert_eq!(expire_time, 1_010);
+        assert_eq!(manager.get_ttl("mock_key").await, Some(10));
+
+        // 到期前一秒仍然有效
+        clock.advance(9);
+        assert!(!manager.is_expired("mock_key").await);
+        assert_eq!(manager.get_ttl("mock_key").await, Some(1));
+
+        // 恰好到达过期时间即视为过期
+        clock.advance(1);
+        assert!(manager.is_expired("mock_key").await);
+        assert_eq!(manager.get_ttl("mock_key").await, Some(0));
+        assert_eq!(manager.get_expired_keys(10).await, vec!["mock_key".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_per_key_ttl_overrides_default() {
+        let mut ttl_config = create_test_config();
+        ttl_config.expire_seconds = Some(100);
+        let clock = Arc::new(MockClock::new(1_000));
+        let manager = TtlManager::with_clock(ttl_config, clock.clone()).await.unwrap();
+
+        // 显式 TTL 各自生效，None 才使用全局默认值
+        manager.add_key("short".to_string(), Some(5)).await.unwrap();
+        manager.add_key("long".to_string(), Some(20)).await.unwrap();
+        manager.add_key("default".to_string(), None).await.unwrap();
+
+        clock.advance(5);
+        assert!(manager.is_expired("short").await);
+        assert!(!manager.is_expired("long").await);
+
+        clock.advance(15);
+        assert!(manager.is_expired("long").await);
+        assert_eq!(manager.get_ttl("default").await, Some(80));
+    }
+
+    #[tokio::test]
+    async fn test_touch_on_access() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let manager = TtlManager::with_clock(create_test_config(), clock.clone()).await.unwrap();
+        manager.add_key("key".to_string(), Some(10)).await.unwrap();
+        clock.advance(5);
+        assert_eq!(manager.touch_on_access("key").await, None);
+        assert_eq!(manager.get_ttl("key").await, Some(5));
+
+        let mut ttl_config = create_test_config();
+        ttl_config.sliding_expiration = true;
+        let manager = TtlManager::with_clock(ttl_config, clock.clone()).await.unwrap();
+        manager.add_key("key".to_string(), Some(10)).await.unwrap();
+
+        // 按原始 TTL 时长重新计算过期时间，而不是剩余时间
+        clock.advance(6);
+        assert_eq!(manager.touch_on_access("key").await, Some(10));
+        assert_eq!(manager.get_ttl("key").await, Some(10));
+        assert_eq!(manager.touch_on_access("key").await, None);
+
+        // 永不过期和已过期的键不受影响
+        assert_eq!(manager.touch_on_access("missing").await, None);
+        clock.advance(10);
+        assert_eq!(manager.touch_on_access("key").await, None);
+        assert!(manager.is_expired("key").await);
+    }
+
+    #[tokio::test]
+    async fn test_active_cleanup_publishes_expired() {
+        let mut ttl_config = create_test_config();
+        ttl_config.cleanup_interval = 3600;
+        let clock = Arc::new(MockClock::new(1_000));
+        let events = Arc::new(EventBroadcaster::new(16).unwrap());
+        let manager = TtlManager::with_events(ttl_config, clock.clone(), Some(Arc::clone(&events))).await.unwrap();
+        let mut stream = events.subscribe();
+
+        manager.add_key("short".to_string(), Some(5)).await.unwrap();
+        manager.add_key("long".to_string(), Some(50)).await.unwrap();
+        clock.advance(10);
+        manager.force_cleanup().await;
+
+        assert_eq!(stream.next_event().await, Some(CacheEvent::Expired { key: "short".to_string() }));
+        assert!(manager.get_ttl("long").await.is_some());
+        manager.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_update_ttl_atomic() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let manager = Arc::new(TtlManager::with_clock(create_test_config(), clock).await.unwrap());
+
+        // 无 TTL 的键不调用闭包
+        assert_eq!(manager.update_ttl_atomic("missing", |_| panic!("不应调用")).await.unwrap(), None);
+
+        manager.add_key("key".to_string(), Some(10)).await.unwrap();
+        assert_eq!(manager.update_ttl_atomic("key", |_| None).await.unwrap(), Some(10));
+
+        // 并发续期不丢失更新
+        let handles: Vec<_> = (0..50)
+            .map(|_| {
+                let manager = Arc::clone(&manager);
+                tokio::spawn(async move {
+                    manager.update_ttl_atomic("key", |remaining| Some(remaining + 1)).await.unwrap()
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(manager.get_ttl("key").await, Some(60));
+
+        // 返回 0 改为永不过期
+        assert_eq!(manager.update_ttl_atomic("key", |_| Some(0)).await.unwrap(), None);
+        assert_eq!(manager.get_ttl("key").await, None);
+    }
+
     #[test]
     fn test_format_remaining_time() {
         assert_eq!(utils::format_remaining_time(0), "已过期");
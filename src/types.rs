@@ -2,8 +2,10 @@
 //!
 //! 定义缓存系统中使用的核心数据结构
 
+use crate::error::{CacheError, CacheResult};
 use chrono;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// 缓存值包装器，包含数据和元数据
@@ -25,6 +27,12 @@ pub struct CacheValue {
     pub original_size: usize,
     /// 压缩后大小
     pub compressed_size: usize,
+    /// 客户端标志（Memcached flags），默认 0
+    #[serde(default)]
+    pub flags: u32,
+    /// CAS 令牌，写入 L1 时分配，同一键每次写入都会得到更大的值；0 表示未分配
+    #[serde(default)]
+    pub cas: u64,
 }
 
 impl CacheValue {
@@ -41,6 +49,8 @@ impl CacheValue {
             is_compressed: compressed,
             original_size,
             compressed_size: size,
+            flags: 0,
+            cas: 0,
         }
     }
 
@@ -113,6 +123,112 @@ impl CacheValue {
     pub fn memory_size(&self) -> usize {
         self.data.len() + std::mem::size_of::<Self>()
     }
+
+    /// 创建构建器，按给定的元数据构造缓存值（如从其他系统导入，无需重新压缩）
+    pub fn builder() -> CacheValueBuilder {
+        CacheValueBuilder::default()
+    }
+}
+
+/// [`CacheValue`] 构建器
+///
+/// 未设置的时间戳默认为当前时间，访问次数默认为 1；`build()` 时校验元数据的一致性
+#[derive(Debug, Clone, Default)]
+pub struct CacheValueBuilder {
+    data: Vec<u8>,
+    compressed: bool,
+    original_size: Option<usize>,
+    flags: u32,
+    created_at: Option<u64>,
+    last_accessed: Option<u64>,
+    expires_at: Option<u64>,
+    access_count: Option<u64>,
+}
+
+impl CacheValueBuilder {
+    /// 实际存储的数据（已压缩时为压缩后的数据）
+    pub fn data(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.data = data.into();
+        self
+    }
+
+    /// 数据是否已压缩，已压缩时必须设置原始大小
+    pub fn compressed(mut self, compressed: bool) -> Self {
+        self.compressed = compressed;
+        self
+    }
+
+    /// 原始数据大小（压缩前），未压缩时必须等于数据长度
+    pub fn original_size(mut self, original_size: usize) -> Self {
+        self.original_size = Some(original_size);
+        self
+    }
+
+    /// 客户端标志
+    pub fn flags(mut self, flags: u32) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// 创建时间戳（Unix 时间戳，秒）
+    pub fn created_at(mut self, created_at: u64) -> Self {
+        self.created_at = Some(created_at);
+        self
+    }
+
+    /// 最后访问时间戳（Unix 时间戳，秒），默认等于创建时间
+    pub fn last_accessed(mut self, last_accessed: u64) -> Self {
+        self.last_accessed = Some(last_accessed);
+        self
+    }
+
+    /// 过期时间戳（Unix 时间戳，秒），必须晚于创建时间
+    pub fn expires_at(mut self, expires_at: u64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// 访问次数
+    pub fn access_count(mut self, access_count: u64) -> Self {
+        self.access_count = Some(access_count);
+        self
+    }
+
+    /// 校验元数据并构造缓存值
+    pub fn build(self) -> CacheResult<CacheValue> {
+        let original_size = match (self.compressed, self.original_size) {
+            (true, None) => return Err(CacheError::invalid_value("压缩数据必须指定原始大小")),
+            (false, Some(size)) if size != self.data.len() => {
+                return Err(CacheError::invalid_value(format!(
+                    "未压缩数据的原始大小 {} 与数据长度 {} 不一致", size, self.data.len()
+                )));
+            }
+            (_, size) => size.unwrap_or(self.data.len()),
+        };
+
+        let created_at = self.created_at.unwrap_or_else(current_timestamp);
+        let last_accessed = self.last_accessed.unwrap_or(created_at);
+        if last_accessed < created_at {
+            return Err(CacheError::invalid_value("最后访问时间早于创建时间"));
+        }
+        if self.expires_at.is_some_and(|expires_at| expires_at <= created_at) {
+            return Err(CacheError::invalid_value("过期时间必须晚于创建时间"));
+        }
+
+        let compressed_size = self.data.len();
+        Ok(CacheValue {
+            data: self.data,
+            created_at,
+            last_accessed,
+            expires_at: self.expires_at,
+            access_count: self.access_count.unwrap_or(1),
+            is_compressed: self.compressed,
+            original_size,
+            compressed_size,
+            flags: self.flags,
+            cas: 0,
+        })
+    }
 }
 
 /// 缓存策略枚举
@@ -128,6 +244,10 @@ pub enum EvictionStrategy {
     LruLfu,
     /// 基于 TTL 的策略
     TtlBased,
+    /// 自适应替换缓存（Adaptive Replacement Cache），按影子列表的命中在近期性与频率之间自适应
+    Arc,
+    /// LRU 驱逐 + TinyLFU 准入：需要驱逐时，新键的估计访问频率高于驱逐候选者才写入（没有 W-TinyLFU 的窗口区）
+    TinyLfu,
 }
 
 /// 缓存层级枚举
@@ -152,9 +272,149 @@ pub enum CacheOperation {
     Clear,
     /// 过期清理操作
     Expire,
+    /// 刷新存在键的过期时间（touch）
+    Touch,
+    /// 直接修改或移除 TTL
+    Ttl,
+}
+
+/// 单次写入的结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SetOutcome {
+    /// 本次写入为腾出空间而驱逐的 L1 条目数
+    pub evicted_count: u64,
+    /// 本次写入驱逐的字节数
+    pub evicted_bytes: u64,
+    /// TinyLFU 准入判断拒绝了本次写入，值没有进入 L1（仍按写入策略写入 L2，未启用 L2 时没有被存储）
+    pub admission_rejected: bool,
+}
+
+impl SetOutcome {
+    /// 本次写入是否触发了驱逐
+    pub fn caused_eviction(&self) -> bool {
+        self.evicted_count > 0
+    }
+
+    /// 累加另一次写入的驱逐量
+    pub(crate) fn merge(&mut self, other: SetOutcome) {
+        self.evicted_count += other.evicted_count;
+        self.evicted_bytes += other.evicted_bytes;
+        self.admission_rejected |= other.admission_rejected;
+    }
+}
+
+/// 比较并写入（CAS）的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CasResult {
+    /// 令牌匹配，已写入
+    Stored,
+    /// 键存在但令牌不匹配（期间被修改过），未写入
+    Exists,
+    /// 键不存在或已过期
+    NotFound,
+}
+
+/// 键的最大长度（字符数，与 Memcached 协议一致）
+pub const MAX_KEY_LENGTH: usize = 250;
+
+/// 经过校验的缓存键
+///
+/// 构造时检查长度与字符集：非空、不超过 [`MAX_KEY_LENGTH`] 个字符、不含空格和控制字符。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CacheKey(String);
+
+impl CacheKey {
+    /// 校验并创建键
+    pub fn new(key: impl Into<String>) -> CacheResult<Self> {
+        let key = key.into();
+        Self::validate(&key)?;
+        Ok(Self(key))
+    }
+
+    /// 校验键是否符合规则
+    pub fn validate(key: &str) -> CacheResult<()> {
+        if key.is_empty() {
+            return Err(CacheError::invalid_key(key, "键不能为空"));
+        }
+        if key.chars().count() > MAX_KEY_LENGTH {
+            return Err(CacheError::invalid_key(key, format!("键长度超过 {} 个字符", MAX_KEY_LENGTH)));
+        }
+        if key.chars().any(|c| c == ' ' || c.is_control()) {
+            return Err(CacheError::invalid_key(key, "键不能包含空格或控制字符"));
+        }
+        Ok(())
+    }
+
+    /// 键的字符串形式
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 转换为字符串
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl std::fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for CacheKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// 缓存 API 接受的键参数，可由 [`CacheKey`] 或原始字符串转换而来
+///
+/// 原始字符串不做校验，保持与基于字符串的 API 一致。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKeyRef<'a>(Cow<'a, str>);
+
+impl CacheKeyRef<'_> {
+    /// 键的字符串形式
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 转换为字符串
+    pub fn into_string(self) -> String {
+        self.0.into_owned()
+    }
+}
+
+impl<'a> From<&'a str> for CacheKeyRef<'a> {
+    fn from(key: &'a str) -> Self {
+        Self(Cow::Borrowed(key))
+    }
 }
 
+impl<'a> From<&'a String> for CacheKeyRef<'a> {
+    fn from(key: &'a String) -> Self {
+        Self(Cow::Borrowed(key))
+    }
+}
+
+impl From<String> for CacheKeyRef<'_> {
+    fn from(key: String) -> Self {
+        Self(Cow::Owned(key))
+    }
+}
+
+impl<'a> From<&'a CacheKey> for CacheKeyRef<'a> {
+    fn from(key: &'a CacheKey) -> Self {
+        Self(Cow::Borrowed(&key.0))
+    }
+}
 
+impl From<CacheKey> for CacheKeyRef<'_> {
+    fn from(key: CacheKey) -> Self {
+        Self(Cow::Owned(key.0))
+    }
+}
 
 /// 获取当前 Unix 时间戳（秒）
 pub fn current_timestamp() -> u64 {
@@ -172,4 +432,40 @@ pub fn duration_to_seconds(duration: Duration) -> u64 {
 /// 从秒数创建 Duration
 pub fn seconds_to_duration(seconds: u64) -> Duration {
     Duration::from_secs(seconds)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_value_builder() {
+        let value = CacheValue::builder()
+            .data(vec![1, 2, 3])
+            .compressed(true)
+            .original_size(100)
+            .flags(7)
+            .created_at(1_000)
+            .expires_at(2_000)
+            .build()
+            .unwrap();
+        assert!(value.is_compressed);
+        assert_eq!((value.original_size, value.compressed_size, value.flags), (100, 3, 7));
+        assert_eq!((value.created_at, value.last_accessed, value.access_count), (1_000, 1_000, 1));
+        assert_eq!(value.expires_at, Some(2_000));
+
+        let plain = CacheValue::builder().data(b"abc".to_vec()).build().unwrap();
+        assert_eq!(plain.original_size, 3);
+
+        // 违反不变量时拒绝构造
+        let invalid = [
+            CacheValue::builder().data(vec![1]).compressed(true),
+            CacheValue::builder().data(vec![1]).original_size(2),
+            CacheValue::builder().created_at(1_000).last_accessed(999),
+            CacheValue::builder().created_at(1_000).expires_at(1_000),
+        ];
+        for builder in invalid {
+            assert!(matches!(builder.build(), Err(CacheError::InvalidValue { .. })));
+        }
+    }
+}
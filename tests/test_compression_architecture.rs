@@ -29,6 +29,8 @@ async fn test_compression_architecture() {
             max_memory: 1024 * 1024 * 1024, // 1GB
             max_entries: 100000,
             eviction_strategy: rat_memcache::EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
         })
         .l2_config(L2Config {
             enable_l2_cache: true,
@@ -39,6 +41,7 @@ async fn test_compression_architecture() {
             block_cache_size: 32 * 1024 * 1024, // 32MB
             background_threads: 4,
             clear_on_startup: false,
+            confirm_clear_on_startup: false,
             enable_lz4: true,
             compression_threshold,
             compression_max_threshold,
@@ -56,6 +59,26 @@ async fn test_compression_architecture() {
             l2_write_strategy: "write_through".to_string(),
             l2_write_threshold: 1024,
             l2_write_ttl_threshold: 300,
+            l2_write_eviction_rate_low: 1.0,
+            l2_write_eviction_rate_high: 100.0,
+            ttl_demotion_enabled: false,
+            ttl_demotion_ratio: 0.1,
+            ttl_demotion_grace_seconds: 60,
+            compact_storage: false,
+            skip_reads_when_empty: true,
+            checksum_enabled: true,
+            evict_corrupted_entries: true,
+            metadata_update_sampling: 1.0,
+            l2_partitions: 1,
+            degrade_to_l1_on_l2_failure: false,
+            l2_max_write_bytes_per_sec: None,
+            blocking_queue_warn_ms: 100,
+            blocking_queue_max_wait_ms: None,
+            persist_ttl_index: true,
+            value_compression_algorithm: CompressionAlgorithm::Lz4,
+            key_prefix: String::new(),
+            l2_max_retries: 2,
+            l2_retry_backoff_ms: 10,
         })
         .performance_config(PerformanceConfig {
             worker_threads: 4,
@@ -64,6 +87,9 @@ async fn test_compression_architecture() {
             batch_size: 100,
             enable_warmup: true,
             large_value_threshold,
+            key_stats_top_n: 0,
+            absolute_max_value_bytes: 0,
+            max_tagged_keys: 100_000,
         })
         .ttl_config(TtlConfig {
             expire_seconds: Some(3600),
@@ -71,6 +97,8 @@ async fn test_compression_architecture() {
             max_cleanup_entries: 1000,
             lazy_expiration: true,
             active_expiration: true,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
         })
         .logging_config(LoggingConfig {
             level: "DEBUG".to_string(),
@@ -180,6 +208,8 @@ async fn test_compression_disabled() {
             max_memory: 1024 * 1024 * 1024,
             max_entries: 100000,
             eviction_strategy: rat_memcache::EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
         })
         .l2_config(L2Config {
             enable_l2_cache: true,
@@ -190,6 +220,7 @@ async fn test_compression_disabled() {
             block_cache_size: 32 * 1024 * 1024,
             background_threads: 4,
             clear_on_startup: false,
+            confirm_clear_on_startup: false,
             enable_lz4: false, // 禁用压缩
             compression_threshold: 128,
             compression_max_threshold: 1024 * 1024,
@@ -207,6 +238,26 @@ async fn test_compression_disabled() {
             l2_write_strategy: "write_through".to_string(),
             l2_write_threshold: 1024,
             l2_write_ttl_threshold: 300,
+            l2_write_eviction_rate_low: 1.0,
+            l2_write_eviction_rate_high: 100.0,
+            ttl_demotion_enabled: false,
+            ttl_demotion_ratio: 0.1,
+            ttl_demotion_grace_seconds: 60,
+            compact_storage: false,
+            skip_reads_when_empty: true,
+            checksum_enabled: true,
+            evict_corrupted_entries: true,
+            metadata_update_sampling: 1.0,
+            l2_partitions: 1,
+            degrade_to_l1_on_l2_failure: false,
+            l2_max_write_bytes_per_sec: None,
+            blocking_queue_warn_ms: 100,
+            blocking_queue_max_wait_ms: None,
+            persist_ttl_index: true,
+            value_compression_algorithm: CompressionAlgorithm::Lz4,
+            key_prefix: String::new(),
+            l2_max_retries: 2,
+            l2_retry_backoff_ms: 10,
         })
         .performance_config(PerformanceConfig {
             worker_threads: 4,
@@ -215,6 +266,9 @@ async fn test_compression_disabled() {
             batch_size: 100,
             enable_warmup: true,
             large_value_threshold: 10240,
+            key_stats_top_n: 0,
+            absolute_max_value_bytes: 0,
+            max_tagged_keys: 100_000,
         })
         .ttl_config(TtlConfig {
             expire_seconds: None,
@@ -222,6 +276,8 @@ async fn test_compression_disabled() {
             max_cleanup_entries: 100,
             lazy_expiration: true,
             active_expiration: true,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
         })
         .logging_config(LoggingConfig {
             level: "debug".to_string(),
@@ -24,6 +24,8 @@ async fn test_large_value_functionality() {
             max_memory: 1024 * 1024,
             max_entries: 1000,
             eviction_strategy: EvictionStrategy::Lru,
+            l1_min_retention_ms: 0,
+            order_compaction_factor: 2,
         })
         .l2_config(L2Config {
             enable_l2_cache: true,
@@ -38,6 +40,7 @@ async fn test_large_value_functionality() {
             compression_level: 3,
             background_threads: 2,
             clear_on_startup: true,
+            confirm_clear_on_startup: true,
             cache_size_mb: 64,
             max_file_size_mb: 256,
             smart_flush_enabled: false,
@@ -51,6 +54,26 @@ async fn test_large_value_functionality() {
             l2_write_strategy: "write_through".to_string(),
             l2_write_threshold: 1024,
             l2_write_ttl_threshold: 300,
+            l2_write_eviction_rate_low: 1.0,
+            l2_write_eviction_rate_high: 100.0,
+            ttl_demotion_enabled: false,
+            ttl_demotion_ratio: 0.1,
+            ttl_demotion_grace_seconds: 60,
+            compact_storage: false,
+            skip_reads_when_empty: true,
+            checksum_enabled: true,
+            evict_corrupted_entries: true,
+            metadata_update_sampling: 1.0,
+            l2_partitions: 1,
+            degrade_to_l1_on_l2_failure: false,
+            l2_max_write_bytes_per_sec: None,
+            blocking_queue_warn_ms: 100,
+            blocking_queue_max_wait_ms: None,
+            persist_ttl_index: true,
+            value_compression_algorithm: CompressionAlgorithm::Lz4,
+            key_prefix: String::new(),
+            l2_max_retries: 2,
+            l2_retry_backoff_ms: 10,
         })
         .performance_config(PerformanceConfig {
             worker_threads: 2,
@@ -59,6 +82,9 @@ async fn test_large_value_functionality() {
             batch_size: 100,
             enable_warmup: false,
             large_value_threshold: 1024, // 1KB阈值
+            key_stats_top_n: 0,
+            absolute_max_value_bytes: 0,
+            max_tagged_keys: 100_000,
         })
         .ttl_config(TtlConfig {
             expire_seconds: Some(3600),
@@ -66,6 +92,8 @@ async fn test_large_value_functionality() {
             max_cleanup_entries: 1000,
             lazy_expiration: true,
             active_expiration: true,
+            cleanup_channel_capacity: 10_000,
+            sliding_expiration: false,
         })
         .logging_config(LoggingConfig {
             level: "INFO".to_string(),